@@ -66,6 +66,8 @@ pub struct App {
     pub(crate) change_summary_result_rx: Receiver<ChangeSummaryResult>,
     pub(crate) pending_change_summary_results: Vec<ChangeSummaryResult>,
     pub(crate) change_summary_worker: Option<std::thread::JoinHandle<()>>,
+    pub(crate) session_action_result_tx: Sender<SessionActionResult>,
+    pub(crate) session_action_result_rx: Receiver<SessionActionResult>,
     pub detail_focus: DetailFocus,
     pub detail_scroll_offset: usize,
     pub log_scroll_offset: usize,
@@ -77,6 +79,8 @@ pub struct App {
     pub session_subagent_cache: Arc<Mutex<HashMap<Uuid, Vec<SubagentTodoSummary>>>>,
     pub session_title_cache: Arc<Mutex<HashMap<String, String>>>,
     pub session_message_cache: Arc<Mutex<HashMap<Uuid, Vec<SessionMessageItem>>>>,
+    pub session_permission_cache: Arc<Mutex<HashMap<Uuid, Vec<SessionPermissionRequest>>>>,
+    pub session_usage_cache: Arc<Mutex<HashMap<Uuid, TokenUsage>>>,
     pub todo_visualization_mode: TodoVisualizationMode,
     pub keybindings: Keybindings,
     pub settings: crate::settings::Settings,
@@ -110,6 +114,21 @@ pub(crate) fn load_project_detail(
     })
 }
 
+fn resolve_theme(
+    cli_theme_override: Option<ThemePreset>,
+    settings: &crate::settings::Settings,
+) -> Theme {
+    let env_theme = std::env::var("OPENCODE_KANBAN_THEME")
+        .ok()
+        .and_then(|value| ThemePreset::from_str(&value).ok());
+    let settings_theme = ThemePreset::from_str(&settings.theme).ok();
+    let effective_theme = cli_theme_override
+        .or(env_theme)
+        .or(settings_theme)
+        .unwrap_or_default();
+    Theme::resolve(effective_theme, &settings.custom_theme)
+}
+
 impl App {
     fn status_poller_caches(&self) -> polling::StatusPollerCaches {
         polling::StatusPollerCaches {
@@ -117,6 +136,8 @@ impl App {
             session_subagent_cache: Arc::clone(&self.session_subagent_cache),
             session_title_cache: Arc::clone(&self.session_title_cache),
             session_message_cache: Arc::clone(&self.session_message_cache),
+            session_permission_cache: Arc::clone(&self.session_permission_cache),
+            session_usage_cache: Arc::clone(&self.session_usage_cache),
         }
     }
 
@@ -151,33 +172,80 @@ impl App {
         let db_path = default_db_path()?;
         let db = Database::open(&db_path)?;
         let server_manager = ensure_server_ready();
-        let poller_stop = Arc::new(AtomicBool::new(false));
-        let session_todo_cache = Arc::new(Mutex::new(HashMap::new()));
-        let session_subagent_cache = Arc::new(Mutex::new(HashMap::new()));
-        let session_title_cache = Arc::new(Mutex::new(HashMap::new()));
-        let session_message_cache = Arc::new(Mutex::new(HashMap::new()));
         let settings = crate::settings::Settings::load();
+        let theme = resolve_theme(cli_theme_override, &settings);
+
+        let mut app = Self::from_parts(db, settings, theme, server_manager);
+
+        app.refresh_data()?;
+        app.refresh_projects()?;
+
+        if let Some(name) = project_name {
+            if let Some(idx) = app.project_list.iter().position(|p| p.name == name) {
+                app.selected_project_index = idx;
+                if let Some(project) = app.project_list.get(idx) {
+                    app.switch_project(project.path.clone())?;
+                    app.current_view = View::Board;
+                }
+            } else {
+                anyhow::bail!("project '{}' not found", name);
+            }
+        }
+
+        app.reconcile_startup_with_runtime(&RealRecoveryRuntime)?;
+        app.refresh_data()?;
+
+        app.poller_thread = Some(polling::spawn_status_poller(
+            db_path,
+            Arc::clone(&app.poller_stop),
+            app.status_poller_caches(),
+            app.settings.poll_interval_ms,
+            app.task_completion_notification_config(),
+            app.current_project_slug_for_tmux(),
+        ));
+        Ok(app)
+    }
+
+    /// Builds a board-only app for one project without starting the OpenCode
+    /// server, the status poller, or startup reconciliation. Used to render
+    /// snapshots of the UI outside a terminal.
+    pub fn snapshot(
+        db: Database,
+        project_path: PathBuf,
+        cli_theme_override: Option<ThemePreset>,
+        view_mode: ViewMode,
+        viewport: (u16, u16),
+    ) -> Result<Self> {
+        let settings = crate::settings::Settings::load();
+        let theme = resolve_theme(cli_theme_override, &settings);
+
+        let mut app = Self::from_parts(db, settings, theme, OpenCodeServerManager::new());
+        app.current_project_path = Some(project_path);
+        app.current_view = View::Board;
+        app.view_mode = view_mode;
+        app.viewport = viewport;
+        app.refresh_data()?;
+        Ok(app)
+    }
+
+    fn from_parts(
+        db: Database,
+        settings: crate::settings::Settings,
+        theme: Theme,
+        server_manager: OpenCodeServerManager,
+    ) -> Self {
         let (change_summary_request_tx, change_summary_result_rx, change_summary_worker) =
             spawn_change_summary_worker();
-        let env_theme = std::env::var("OPENCODE_KANBAN_THEME")
-            .ok()
-            .and_then(|value| ThemePreset::from_str(&value).ok());
-        let settings_theme = ThemePreset::from_str(&settings.theme).ok();
-        let effective_theme = cli_theme_override
-            .or(env_theme)
-            .or(settings_theme)
-            .unwrap_or_default();
-
+        let (session_action_result_tx, session_action_result_rx) = mpsc::channel();
         let todo_visualization_mode = std::env::var("OPENCODE_KANBAN_TODO_VISUALIZATION")
             .ok()
             .and_then(|value| TodoVisualizationMode::from_str(&value).ok())
             .unwrap_or(TodoVisualizationMode::Checklist);
-        let default_view_mode = default_view_mode(&settings);
 
-        let mut app = Self {
+        Self {
             should_quit: false,
             pulse_phase: 0,
-            theme: Theme::resolve(effective_theme, &settings.custom_theme),
+            theme,
             layout_epoch: 0,
             viewport: (80, 24),
             last_mouse_event: None,
@@ -202,9 +270,9 @@ impl App {
             selected_project_index: 0,
             project_list_state: ListState::default(),
             _server_manager: server_manager,
-            poller_stop,
+            poller_stop: Arc::new(AtomicBool::new(false)),
             poller_thread: None,
-            view_mode: default_view_mode,
+            view_mode: default_view_mode(&settings),
             side_panel_width: settings.side_panel_width,
             side_panel_selected_row: 0,
             archive_selected_index: 0,
@@ -220,6 +288,8 @@ impl App {
             change_summary_result_rx,
             pending_change_summary_results: Vec::new(),
             change_summary_worker: Some(change_summary_worker),
+            session_action_result_tx,
+            session_action_result_rx,
             detail_focus: DetailFocus::List,
             detail_scroll_offset: 0,
             log_scroll_offset: 0,
@@ -227,10 +297,12 @@ impl App {
             log_expanded: false,
             log_expanded_scroll_offset: 0,
             log_expanded_entries: HashSet::new(),
-            session_todo_cache,
-            session_subagent_cache,
-            session_title_cache,
-            session_message_cache,
+            session_todo_cache: Arc::new(Mutex::new(HashMap::new())),
+            session_subagent_cache: Arc::new(Mutex::new(HashMap::new())),
+            session_title_cache: Arc::new(Mutex::new(HashMap::new())),
+            session_message_cache: Arc::new(Mutex::new(HashMap::new())),
+            session_permission_cache: Arc::new(Mutex::new(HashMap::new())),
+            session_usage_cache: Arc::new(Mutex::new(HashMap::new())),
             todo_visualization_mode,
             keybindings: Keybindings::load(),
             settings,
@@ -240,35 +312,7 @@ impl App {
             project_detail_cache: None,
             last_click: None,
             pending_gg_at: None,
-        };
-
-        app.refresh_data()?;
-        app.refresh_projects()?;
-
-        if let Some(name) = project_name {
-            if let Some(idx) = app.project_list.iter().position(|p| p.name == name) {
-                app.selected_project_index = idx;
-                if let Some(project) = app.project_list.get(idx) {
-                    app.switch_project(project.path.clone())?;
-                    app.current_view = View::Board;
-                }
-            } else {
-                anyhow::bail!("project '{}' not found", name);
-            }
         }
-
-        app.reconcile_startup_with_runtime(&RealRecoveryRuntime)?;
-        app.refresh_data()?;
-
-        app.poller_thread = Some(polling::spawn_status_poller(
-            db_path,
-            Arc::clone(&app.poller_stop),
-            app.status_poller_caches(),
-            app.settings.poll_interval_ms,
-            app.task_completion_notification_config(),
-            app.current_project_slug_for_tmux(),
-        ));
-        Ok(app)
     }
 
     pub fn should_quit(&self) -> bool {
@@ -308,6 +352,22 @@ impl App {
             .and_then(|cache| cache.get(session_id).cloned())
     }
 
+    pub fn session_permissions(&self, task_id: Uuid) -> Vec<SessionPermissionRequest> {
+        self.session_permission_cache
+            .lock()
+            .ok()
+            .and_then(|cache| cache.get(&task_id).cloned())
+            .unwrap_or_default()
+    }
+
+    /// Tokens and cost of the task's sessions, subagents included.
+    pub fn session_usage(&self, task_id: Uuid) -> Option<TokenUsage> {
+        self.session_usage_cache
+            .lock()
+            .ok()
+            .and_then(|cache| cache.get(&task_id).copied())
+    }
+
     pub fn session_messages(&self, task_id: Uuid) -> Vec<SessionMessageItem> {
         self.session_message_cache
             .lock()
@@ -322,15 +382,29 @@ impl App {
         let mut lines = Vec::new();
 
         for message in messages.iter().rev() {
+            let role = log_role_label(message.role.as_deref());
+            let timestamp = log_time_label(message.timestamp.as_deref());
+
+            if !message.parts.is_empty() {
+                for part in message.parts.iter().rev() {
+                    let (kind, summary, details) = log_part_entry(part);
+                    if summary.is_empty() {
+                        lines.push(format!("> [{kind}] {role:<9} {timestamp}"));
+                    } else {
+                        lines.push(format!("> [{kind}] {role:<9} {timestamp} {summary}"));
+                    }
+                    lines.extend(details.iter().map(|detail| format!("  {detail}")));
+                    lines.push(String::new());
+                }
+                continue;
+            }
+
             let content = message.content.trim();
             if content.is_empty() {
                 continue;
             }
 
             let kind = log_kind_label(message.message_type.as_deref());
-            let role = log_role_label(message.role.as_deref());
-            let timestamp = log_time_label(message.timestamp.as_deref());
-
             lines.push(format!("> [{kind}] {role:<9} {timestamp}"));
 
             for line in content.lines() {
@@ -427,6 +501,12 @@ impl App {
         if let Ok(mut cache) = self.session_message_cache.lock() {
             cache.retain(|task_id, _| self.tasks.iter().any(|task| task.id == *task_id));
         }
+        if let Ok(mut cache) = self.session_permission_cache.lock() {
+            cache.retain(|task_id, _| self.tasks.iter().any(|task| task.id == *task_id));
+        }
+        if let Ok(mut cache) = self.session_usage_cache.lock() {
+            cache.retain(|task_id, _| self.tasks.iter().any(|task| task.id == *task_id));
+        }
         self.change_summary_cache
             .retain(|key, _| self.tasks.iter().any(|task| task.id == key.task_id));
         self.change_summary_in_flight
@@ -544,6 +624,12 @@ impl App {
         if let Ok(mut cache) = self.session_message_cache.lock() {
             cache.clear();
         }
+        if let Ok(mut cache) = self.session_permission_cache.lock() {
+            cache.clear();
+        }
+        if let Ok(mut cache) = self.session_usage_cache.lock() {
+            cache.clear();
+        }
         self.log_expanded_entries.clear();
         self.refresh_data()?;
 
@@ -741,6 +827,115 @@ impl App {
         self.apply_cached_change_summary();
     }
 
+    /// Drops an answered request right away instead of waiting for the
+    /// poller, and puts the task back to running once nothing is pending.
+    fn resolve_permission_request(&mut self, task_id: Uuid, permission_id: &str) -> Result<()> {
+        let still_waiting = match self.session_permission_cache.lock() {
+            Ok(mut cache) => {
+                if let Some(pending) = cache.get_mut(&task_id) {
+                    pending.retain(|permission| permission.id != permission_id);
+                    if pending.is_empty() {
+                        cache.remove(&task_id);
+                    }
+                }
+                cache.contains_key(&task_id)
+            }
+            Err(_) => false,
+        };
+        let awaiting_approval = self.tasks.iter().any(|task| {
+            task.id == task_id && task.tmux_status == Status::AwaitingApproval.as_str()
+        });
+        if !still_waiting && awaiting_approval {
+            self.db
+                .update_task_status(task_id, Status::Running.as_str())?;
+            self.refresh_data()?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn drain_session_action_results(&mut self) -> Result<()> {
+        while let Ok(result) = self.session_action_result_rx.try_recv() {
+            let title = &result.task_title;
+            self.footer_notice = Some(match (&result.action, result.outcome) {
+                (SessionAction::Prompt { .. }, Ok(())) => format!("  ✓ Prompt sent to '{title}'  "),
+                (SessionAction::Abort, Ok(())) => {
+                    self.db
+                        .update_task_status(result.task_id, Status::Idle.as_str())?;
+                    self.refresh_data()?;
+                    format!("  ✓ Interrupted '{title}'  ")
+                }
+                (SessionAction::Prompt { .. }, Err(err)) => {
+                    warn!("quick prompt to '{title}' failed: {err}");
+                    format!(" Prompt to '{title}' failed: {err} ")
+                }
+                (SessionAction::Abort, Err(err)) => {
+                    warn!("interrupting '{title}' failed: {err}");
+                    format!(" Interrupting '{title}' failed: {err} ")
+                }
+                (
+                    SessionAction::ReplyPermission {
+                        permission_id,
+                        permission_title,
+                        reply,
+                    },
+                    Ok(()),
+                ) => {
+                    self.resolve_permission_request(result.task_id, permission_id)?;
+                    let verb = match reply {
+                        PermissionReply::Reject => "Denied",
+                        PermissionReply::Once | PermissionReply::Always => "Approved",
+                    };
+                    format!("  ✓ {verb} '{permission_title}' for '{title}'  ")
+                }
+                (SessionAction::ReplyPermission { .. }, Err(err)) => {
+                    warn!("answering permission request for '{title}' failed: {err}");
+                    format!(" Answering permission request for '{title}' failed: {err} ")
+                }
+                (SessionAction::Revert { .. } | SessionAction::Unrevert { .. }, Ok(())) => {
+                    let delta = match result.revert {
+                        Some(report) => self.apply_revert_report(result.task_id, report),
+                        None => change_delta_label(None, None),
+                    };
+                    if matches!(result.action, SessionAction::Revert { .. }) {
+                        format!("  ✓ Reverted last turn of '{title}': {delta}  ")
+                    } else {
+                        format!("  ✓ Restored reverted turn of '{title}': {delta}  ")
+                    }
+                }
+                (SessionAction::Revert { .. }, Err(err)) => {
+                    warn!("reverting last turn of '{title}' failed: {err}");
+                    format!(" Reverting last turn of '{title}' failed: {err} ")
+                }
+                (SessionAction::Unrevert { .. }, Err(err)) => {
+                    warn!("restoring reverted turn of '{title}' failed: {err}");
+                    format!(" Restoring reverted turn of '{title}' failed: {err} ")
+                }
+            });
+        }
+        Ok(())
+    }
+
+    /// Shows the reverted session's messages and worktree changes right away
+    /// instead of waiting for the next poll; returns the change delta label.
+    fn apply_revert_report(&mut self, task_id: Uuid, report: RevertReport) -> String {
+        let label = change_delta_label(report.before.as_ref(), report.after.as_ref());
+        if let Some(messages) = report.messages
+            && let Ok(mut cache) = self.session_message_cache.lock()
+        {
+            cache.insert(task_id, messages);
+        }
+        let key = self
+            .tasks
+            .iter()
+            .find(|task| task.id == task_id)
+            .and_then(|task| self.task_change_summary_key(task));
+        if let (Some(key), Some(after)) = (key, report.after) {
+            self.change_summary_cache.insert(key, Ok(after));
+            self.apply_cached_change_summary();
+        }
+        label
+    }
+
     pub(crate) fn update_current_change_summary_for_task(&mut self, task: Option<&Task>) {
         let Some(task) = task else {
             self.clear_current_change_summary();
@@ -14,10 +14,11 @@ use super::state::{
     ActiveDialog, ArchiveTaskDialogState, CategoryColorDialogState, CategoryColorField,
     CategoryInputDialogState, CategoryInputField, ConfirmCancelField, ConfirmQuitDialogState,
     DeleteCategoryDialogState, DeleteTaskDialogState, DeleteTaskField, EditTaskDialogState,
-    EditTaskField, NewProjectDialogState, NewProjectField, NewTaskDialogState, NewTaskField,
-    RenameProjectDialogState, RenameProjectField, RenameRepoDialogState, RenameRepoField,
-    RepoPickerTarget, RepoSuggestionItem, RepoSuggestionKind, WorktreeNotFoundDialogState,
-    WorktreeNotFoundField,
+    EditTaskField, ForkTaskDialogState, ForkTaskField, GcDialogState, NewProjectDialogState,
+    NewProjectField, NewTaskDialogState, NewTaskField, PermissionApprovalDialogState,
+    PermissionReplyField, QuickPromptDialogState, RenameProjectDialogState, RenameProjectField,
+    RenameRepoDialogState, RenameRepoField, RepoPickerTarget, RepoSuggestionItem,
+    RepoSuggestionKind, WorktreeNotFoundDialogState, WorktreeNotFoundField,
 };
 
 /// Handle key events when a dialog is active
@@ -52,6 +53,9 @@ pub fn handle_dialog_key(
         ActiveDialog::RenameRepo(state) => {
             handle_rename_repo_dialog_key(state, key, &mut follow_up);
         }
+        ActiveDialog::QuickPrompt(state) => {
+            handle_quick_prompt_dialog_key(state, key, &mut follow_up);
+        }
         ActiveDialog::DeleteRepo(_) => match key.code {
             KeyCode::Esc => follow_up = Some(Message::DismissDialog),
             KeyCode::Enter => follow_up = Some(Message::ConfirmDeleteRepo),
@@ -78,9 +82,27 @@ pub fn handle_dialog_key(
         ActiveDialog::ArchiveTask(state) => {
             handle_archive_task_dialog_key(state, key, &mut follow_up);
         }
+        ActiveDialog::ForkTask(state) => {
+            handle_fork_task_dialog_key(state, key, &mut follow_up);
+        }
+        ActiveDialog::InterruptSession(state) => {
+            handle_confirm_cancel_dialog_key(
+                &mut state.focused_field,
+                key,
+                Message::ConfirmInterruptSession,
+                Message::DismissDialog,
+                &mut follow_up,
+            );
+        }
+        ActiveDialog::PermissionApproval(state) => {
+            handle_permission_approval_dialog_key(state, key, &mut follow_up);
+        }
         ActiveDialog::ConfirmQuit(state) => {
             handle_confirm_quit_dialog_key(state, key, &mut follow_up);
         }
+        ActiveDialog::Gc(state) => {
+            handle_gc_dialog_key(state, key, &mut follow_up);
+        }
         ActiveDialog::WorktreeNotFound(state) => {
             handle_worktree_not_found_dialog_key(state, key, &mut follow_up);
         }
@@ -198,6 +220,9 @@ fn handle_new_task_dialog_key(
             NewTaskField::UseExistingDirectory,
             NewTaskField::ExistingDirectory,
             NewTaskField::Title,
+            NewTaskField::Model,
+            NewTaskField::Agent,
+            NewTaskField::Prompt,
             NewTaskField::Create,
             NewTaskField::Cancel,
         ]
@@ -208,6 +233,9 @@ fn handle_new_task_dialog_key(
             NewTaskField::Branch,
             NewTaskField::Base,
             NewTaskField::Title,
+            NewTaskField::Model,
+            NewTaskField::Agent,
+            NewTaskField::Prompt,
             NewTaskField::EnsureBaseUpToDate,
             NewTaskField::Create,
             NewTaskField::Cancel,
@@ -243,20 +271,16 @@ fn handle_new_task_dialog_key(
         KeyCode::Right if state.focused_field == NewTaskField::UseExistingDirectory => {
             state.use_existing_directory = true;
         }
-        KeyCode::Left if state.focused_field == NewTaskField::Repo => {
-            if !repos.is_empty() {
-                state.repo_idx = state.repo_idx.saturating_sub(1);
-                if let Some(repo) = repos.get(state.repo_idx) {
-                    state.base_input = repo_default_base(repo);
-                }
+        KeyCode::Left if state.focused_field == NewTaskField::Repo && !repos.is_empty() => {
+            state.repo_idx = state.repo_idx.saturating_sub(1);
+            if let Some(repo) = repos.get(state.repo_idx) {
+                state.base_input = repo_default_base(repo);
             }
         }
-        KeyCode::Right if state.focused_field == NewTaskField::Repo => {
-            if !repos.is_empty() {
-                state.repo_idx = (state.repo_idx + 1).min(repos.len() - 1);
-                if let Some(repo) = repos.get(state.repo_idx) {
-                    state.base_input = repo_default_base(repo);
-                }
+        KeyCode::Right if state.focused_field == NewTaskField::Repo && !repos.is_empty() => {
+            state.repo_idx = (state.repo_idx + 1).min(repos.len() - 1);
+            if let Some(repo) = repos.get(state.repo_idx) {
+                state.base_input = repo_default_base(repo);
             }
         }
         KeyCode::Left if state.focused_field == NewTaskField::Create => {
@@ -291,8 +315,31 @@ fn handle_new_task_dialog_key(
             NewTaskField::Title => {
                 state.title_input.pop();
             }
+            NewTaskField::Model => {
+                state.model_input.pop();
+            }
+            NewTaskField::Agent => {
+                state.agent_input.pop();
+            }
+            NewTaskField::Prompt => {
+                state.prompt_input.pop();
+            }
             _ => {}
         },
+        KeyCode::Enter
+            if state.focused_field == NewTaskField::Prompt
+                && key
+                    .modifiers
+                    .intersects(KeyModifiers::ALT | KeyModifiers::SHIFT) =>
+        {
+            state.prompt_input.push('\n');
+        }
+        KeyCode::Char('j')
+            if state.focused_field == NewTaskField::Prompt
+                && key.modifiers.contains(KeyModifiers::CONTROL) =>
+        {
+            state.prompt_input.push('\n');
+        }
         KeyCode::Enter => {
             if state.focused_field == NewTaskField::Repo {
                 open_repo_picker(state, RepoPickerTarget::Repo, repos, db);
@@ -311,6 +358,9 @@ fn handle_new_task_dialog_key(
             NewTaskField::Branch => state.branch_input.push(ch),
             NewTaskField::Base => state.base_input.push(ch),
             NewTaskField::Title => state.title_input.push(ch),
+            NewTaskField::Model => state.model_input.push(ch),
+            NewTaskField::Agent => state.agent_input.push(ch),
+            NewTaskField::Prompt => state.prompt_input.push(ch),
             _ => {}
         },
         _ => {}
@@ -700,10 +750,8 @@ fn handle_new_project_dialog_key(
         KeyCode::Right if state.focused_field == NewProjectField::Cancel => {
             state.focused_field = NewProjectField::Create;
         }
-        KeyCode::Backspace => {
-            if state.focused_field == NewProjectField::Name {
-                state.name_input.pop();
-            }
+        KeyCode::Backspace if state.focused_field == NewProjectField::Name => {
+            state.name_input.pop();
         }
         KeyCode::Enter => {
             *follow_up = Some(match state.focused_field {
@@ -711,10 +759,8 @@ fn handle_new_project_dialog_key(
                 _ => Message::CreateProject,
             });
         }
-        KeyCode::Char(ch) => {
-            if state.focused_field == NewProjectField::Name {
-                state.name_input.push(ch);
-            }
+        KeyCode::Char(ch) if state.focused_field == NewProjectField::Name => {
+            state.name_input.push(ch);
         }
         _ => {}
     }
@@ -760,10 +806,8 @@ fn handle_category_input_dialog_key(
         KeyCode::Right if state.focused_field == CategoryInputField::Cancel => {
             state.focused_field = CategoryInputField::Confirm;
         }
-        KeyCode::Backspace => {
-            if state.focused_field == CategoryInputField::Name {
-                state.name_input.pop();
-            }
+        KeyCode::Backspace if state.focused_field == CategoryInputField::Name => {
+            state.name_input.pop();
         }
         KeyCode::Enter => {
             *follow_up = Some(match state.focused_field {
@@ -771,10 +815,8 @@ fn handle_category_input_dialog_key(
                 _ => Message::SubmitCategoryInput,
             });
         }
-        KeyCode::Char(ch) => {
-            if state.focused_field == CategoryInputField::Name {
-                state.name_input.push(ch);
-            }
+        KeyCode::Char(ch) if state.focused_field == CategoryInputField::Name => {
+            state.name_input.push(ch);
         }
         _ => {}
     }
@@ -967,10 +1009,8 @@ fn handle_edit_task_dialog_key(
         KeyCode::Right if state.focused_field == EditTaskField::Cancel => {
             state.focused_field = EditTaskField::Save;
         }
-        KeyCode::Backspace => {
-            if state.focused_field == EditTaskField::Title {
-                state.title_input.pop();
-            }
+        KeyCode::Backspace if state.focused_field == EditTaskField::Title => {
+            state.title_input.pop();
         }
         KeyCode::Enter => {
             *follow_up = Some(match state.focused_field {
@@ -978,11 +1018,74 @@ fn handle_edit_task_dialog_key(
                 _ => Message::ConfirmEditTask,
             });
         }
-        KeyCode::Char(ch) => {
-            if state.focused_field == EditTaskField::Title {
-                state.title_input.push(ch);
+        KeyCode::Char(ch) if state.focused_field == EditTaskField::Title => {
+            state.title_input.push(ch);
+        }
+        _ => {}
+    }
+}
+
+fn handle_fork_task_dialog_key(
+    state: &mut ForkTaskDialogState,
+    key: KeyEvent,
+    follow_up: &mut Option<Message>,
+) {
+    let fields = [
+        ForkTaskField::Branch,
+        ForkTaskField::Title,
+        ForkTaskField::Fork,
+        ForkTaskField::Cancel,
+    ];
+
+    let mut focus_index = fields
+        .iter()
+        .position(|field| *field == state.focused_field)
+        .unwrap_or(0);
+
+    let move_focus = |current: usize, delta: isize| -> usize {
+        let len = fields.len() as isize;
+        let next = (current as isize + delta).rem_euclid(len);
+        next as usize
+    };
+
+    match key.code {
+        KeyCode::Esc => {
+            *follow_up = Some(Message::DismissDialog);
+        }
+        KeyCode::Tab | KeyCode::Down => {
+            focus_index = move_focus(focus_index, 1);
+            state.focused_field = fields[focus_index];
+        }
+        KeyCode::BackTab | KeyCode::Up => {
+            focus_index = move_focus(focus_index, -1);
+            state.focused_field = fields[focus_index];
+        }
+        KeyCode::Left if state.focused_field == ForkTaskField::Fork => {
+            state.focused_field = ForkTaskField::Cancel;
+        }
+        KeyCode::Right if state.focused_field == ForkTaskField::Cancel => {
+            state.focused_field = ForkTaskField::Fork;
+        }
+        KeyCode::Backspace => match state.focused_field {
+            ForkTaskField::Branch => {
+                state.branch_input.pop();
+            }
+            ForkTaskField::Title => {
+                state.title_input.pop();
             }
+            _ => {}
+        },
+        KeyCode::Enter => {
+            *follow_up = Some(match state.focused_field {
+                ForkTaskField::Cancel => Message::DismissDialog,
+                _ => Message::ConfirmForkTask,
+            });
         }
+        KeyCode::Char(ch) => match state.focused_field {
+            ForkTaskField::Branch => state.branch_input.push(ch),
+            ForkTaskField::Title => state.title_input.push(ch),
+            _ => {}
+        },
         _ => {}
     }
 }
@@ -1019,6 +1122,43 @@ fn handle_worktree_not_found_dialog_key(
     }
 }
 
+fn handle_permission_approval_dialog_key(
+    state: &mut PermissionApprovalDialogState,
+    key: KeyEvent,
+    follow_up: &mut Option<Message>,
+) {
+    match key.code {
+        KeyCode::Esc => *follow_up = Some(Message::DismissDialog),
+        KeyCode::Left | KeyCode::Char('h') => {
+            state.focused_field = match state.focused_field {
+                PermissionReplyField::Approve => PermissionReplyField::Deny,
+                PermissionReplyField::AlwaysApprove => PermissionReplyField::Approve,
+                PermissionReplyField::Deny => PermissionReplyField::AlwaysApprove,
+            };
+        }
+        KeyCode::Right | KeyCode::Char('l') | KeyCode::Tab => {
+            state.focused_field = match state.focused_field {
+                PermissionReplyField::Approve => PermissionReplyField::AlwaysApprove,
+                PermissionReplyField::AlwaysApprove => PermissionReplyField::Deny,
+                PermissionReplyField::Deny => PermissionReplyField::Approve,
+            };
+        }
+        KeyCode::Enter => *follow_up = Some(Message::ReplyPermission(state.focused_field)),
+        KeyCode::Char('y') => {
+            *follow_up = Some(Message::ReplyPermission(PermissionReplyField::Approve));
+        }
+        KeyCode::Char('a') => {
+            *follow_up = Some(Message::ReplyPermission(
+                PermissionReplyField::AlwaysApprove,
+            ));
+        }
+        KeyCode::Char('n') => {
+            *follow_up = Some(Message::ReplyPermission(PermissionReplyField::Deny));
+        }
+        _ => {}
+    }
+}
+
 fn handle_archive_task_dialog_key(
     state: &mut ArchiveTaskDialogState,
     key: KeyEvent,
@@ -1047,6 +1187,20 @@ fn handle_confirm_quit_dialog_key(
     );
 }
 
+fn handle_gc_dialog_key(state: &mut GcDialogState, key: KeyEvent, follow_up: &mut Option<Message>) {
+    if key.code == KeyCode::Char('a') {
+        *follow_up = Some(Message::ToggleGcAdopt);
+        return;
+    }
+    handle_confirm_cancel_dialog_key(
+        &mut state.focused_field,
+        key,
+        Message::ConfirmGc,
+        Message::DismissDialog,
+        follow_up,
+    );
+}
+
 fn toggle_confirm_cancel_field(field: &mut ConfirmCancelField) {
     *field = match *field {
         ConfirmCancelField::Confirm => ConfirmCancelField::Cancel,
@@ -1127,10 +1281,8 @@ fn handle_rename_project_dialog_key(
         KeyCode::Right if state.focused_field == RenameProjectField::Cancel => {
             state.focused_field = RenameProjectField::Confirm;
         }
-        KeyCode::Backspace => {
-            if state.focused_field == RenameProjectField::Name {
-                state.name_input.pop();
-            }
+        KeyCode::Backspace if state.focused_field == RenameProjectField::Name => {
+            state.name_input.pop();
         }
         KeyCode::Enter => {
             *follow_up = Some(match state.focused_field {
@@ -1138,10 +1290,8 @@ fn handle_rename_project_dialog_key(
                 _ => Message::ConfirmRenameProject,
             });
         }
-        KeyCode::Char(ch) => {
-            if state.focused_field == RenameProjectField::Name {
-                state.name_input.push(ch);
-            }
+        KeyCode::Char(ch) if state.focused_field == RenameProjectField::Name => {
+            state.name_input.push(ch);
         }
         _ => {}
     }
@@ -1187,10 +1337,8 @@ fn handle_rename_repo_dialog_key(
         KeyCode::Right if state.focused_field == RenameRepoField::Cancel => {
             state.focused_field = RenameRepoField::Confirm;
         }
-        KeyCode::Backspace => {
-            if state.focused_field == RenameRepoField::Name {
-                state.name_input.pop();
-            }
+        KeyCode::Backspace if state.focused_field == RenameRepoField::Name => {
+            state.name_input.pop();
         }
         KeyCode::Enter => {
             *follow_up = Some(match state.focused_field {
@@ -1198,10 +1346,46 @@ fn handle_rename_repo_dialog_key(
                 _ => Message::ConfirmRenameRepo,
             });
         }
-        KeyCode::Char(ch) => {
-            if state.focused_field == RenameRepoField::Name {
-                state.name_input.push(ch);
+        KeyCode::Char(ch) if state.focused_field == RenameRepoField::Name => {
+            state.name_input.push(ch);
+        }
+        _ => {}
+    }
+}
+
+fn handle_quick_prompt_dialog_key(
+    state: &mut QuickPromptDialogState,
+    key: KeyEvent,
+    follow_up: &mut Option<Message>,
+) {
+    match key.code {
+        KeyCode::Esc => {
+            *follow_up = Some(Message::DismissDialog);
+        }
+        KeyCode::Enter => {
+            *follow_up = Some(Message::SubmitQuickPrompt);
+        }
+        KeyCode::Up if !state.history.is_empty() => {
+            let next = state
+                .history_index
+                .map_or(0, |index| (index + 1).min(state.history.len() - 1));
+            *follow_up = Some(Message::UseQuickPromptHistory(next));
+        }
+        KeyCode::Down => match state.history_index {
+            Some(0) => {
+                state.history_index = None;
+                state.prompt_input.clear();
+            }
+            Some(index) => {
+                *follow_up = Some(Message::UseQuickPromptHistory(index - 1));
             }
+            None => {}
+        },
+        KeyCode::Backspace => {
+            state.prompt_input.pop();
+        }
+        KeyCode::Char(ch) => {
+            state.prompt_input.push(ch);
         }
         _ => {}
     }
@@ -1274,6 +1458,9 @@ mod tests {
             branch_input: String::new(),
             base_input: "main".to_string(),
             title_input: String::new(),
+            model_input: String::new(),
+            agent_input: String::new(),
+            prompt_input: String::new(),
             ensure_base_up_to_date: true,
             loading_message: None,
             focused_field: NewTaskField::Repo,
@@ -1290,6 +1477,9 @@ mod tests {
             branch_input: String::new(),
             base_input: "main".to_string(),
             title_input: String::new(),
+            model_input: String::new(),
+            agent_input: String::new(),
+            prompt_input: String::new(),
             ensure_base_up_to_date: true,
             loading_message: None,
             focused_field: NewTaskField::ExistingDirectory,
@@ -1320,6 +1510,59 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn model_and_agent_fields_follow_title_in_tab_order() -> Result<()> {
+        let db = Database::open(":memory:")?;
+        let mut repos = Vec::new();
+        let mut state = repo_focused_state();
+        state.focused_field = NewTaskField::Title;
+        let mut follow_up = None;
+
+        let tab = KeyEvent::new(KeyCode::Tab, KeyModifiers::empty());
+        handle_new_task_dialog_key(&mut state, tab, &mut repos, &db, &mut follow_up);
+        assert_eq!(state.focused_field, NewTaskField::Model);
+        for ch in "openai/gpt-5".chars() {
+            let key = KeyEvent::new(KeyCode::Char(ch), KeyModifiers::empty());
+            handle_new_task_dialog_key(&mut state, key, &mut repos, &db, &mut follow_up);
+        }
+        handle_new_task_dialog_key(&mut state, tab, &mut repos, &db, &mut follow_up);
+        assert_eq!(state.focused_field, NewTaskField::Agent);
+        for ch in "plan".chars() {
+            let key = KeyEvent::new(KeyCode::Char(ch), KeyModifiers::empty());
+            handle_new_task_dialog_key(&mut state, key, &mut repos, &db, &mut follow_up);
+        }
+        handle_new_task_dialog_key(&mut state, tab, &mut repos, &db, &mut follow_up);
+
+        assert_eq!(state.model_input, "openai/gpt-5");
+        assert_eq!(state.agent_input, "plan");
+        assert_eq!(state.focused_field, NewTaskField::Prompt);
+        Ok(())
+    }
+
+    #[test]
+    fn prompt_field_accepts_newlines_and_enter_creates() -> Result<()> {
+        let db = Database::open(":memory:")?;
+        let mut repos = Vec::new();
+        let mut state = repo_focused_state();
+        state.focused_field = NewTaskField::Prompt;
+        let mut follow_up = None;
+
+        for key in [
+            KeyEvent::new(KeyCode::Char('a'), KeyModifiers::empty()),
+            KeyEvent::new(KeyCode::Enter, KeyModifiers::ALT),
+            KeyEvent::new(KeyCode::Char('b'), KeyModifiers::empty()),
+            KeyEvent::new(KeyCode::Char('j'), KeyModifiers::CONTROL),
+        ] {
+            handle_new_task_dialog_key(&mut state, key, &mut repos, &db, &mut follow_up);
+        }
+        assert_eq!(state.prompt_input, "a\nb\n");
+        assert!(follow_up.is_none());
+
+        handle_new_task_dialog_key(&mut state, key_enter(), &mut repos, &db, &mut follow_up);
+        assert_eq!(follow_up, Some(Message::CreateTask));
+        Ok(())
+    }
+
     #[test]
     fn picker_enter_selects_highlighted_repo() -> Result<()> {
         let db = Database::open(":memory:")?;
@@ -1715,6 +1958,44 @@ mod tests {
         assert_eq!(follow_up, Some(Message::DismissDialog));
     }
 
+    #[test]
+    fn fork_task_keys_edit_focused_input_and_confirm() {
+        let mut state = ForkTaskDialogState {
+            task_id: Uuid::new_v4(),
+            task_title: "Ship it".to_string(),
+            branch_input: "feature/ship-fork".to_string(),
+            title_input: "Ship it (fork)".to_string(),
+            focused_field: ForkTaskField::Branch,
+        };
+        let mut follow_up = None;
+
+        handle_fork_task_dialog_key(
+            &mut state,
+            KeyEvent::new(KeyCode::Char('2'), KeyModifiers::empty()),
+            &mut follow_up,
+        );
+        handle_fork_task_dialog_key(
+            &mut state,
+            KeyEvent::new(KeyCode::Tab, KeyModifiers::empty()),
+            &mut follow_up,
+        );
+        handle_fork_task_dialog_key(
+            &mut state,
+            KeyEvent::new(KeyCode::Backspace, KeyModifiers::empty()),
+            &mut follow_up,
+        );
+        assert_eq!(state.branch_input, "feature/ship-fork2");
+        assert_eq!(state.title_input, "Ship it (fork");
+        assert_eq!(follow_up, None);
+
+        handle_fork_task_dialog_key(&mut state, key_enter(), &mut follow_up);
+        assert_eq!(follow_up, Some(Message::ConfirmForkTask));
+
+        state.focused_field = ForkTaskField::Cancel;
+        handle_fork_task_dialog_key(&mut state, key_enter(), &mut follow_up);
+        assert_eq!(follow_up, Some(Message::DismissDialog));
+    }
+
     #[test]
     fn edit_task_backspace_edits_title_only_when_title_focused() {
         let mut state = edit_task_state(EditTaskField::Title);
@@ -1803,6 +2084,30 @@ mod tests {
         assert_eq!(follow_up, Some(Message::ConfirmQuit));
     }
 
+    #[test]
+    fn test_handle_gc_dialog_key() {
+        let mut state = GcDialogState {
+            report: crate::app::workflows::GcReport::default(),
+            adopt_worktrees: false,
+            focused_field: ConfirmCancelField::Confirm,
+        };
+        let mut follow_up = None;
+
+        handle_gc_dialog_key(
+            &mut state,
+            KeyEvent::new(KeyCode::Char('a'), KeyModifiers::empty()),
+            &mut follow_up,
+        );
+        assert_eq!(follow_up, Some(Message::ToggleGcAdopt));
+
+        handle_gc_dialog_key(
+            &mut state,
+            KeyEvent::new(KeyCode::Enter, KeyModifiers::empty()),
+            &mut follow_up,
+        );
+        assert_eq!(follow_up, Some(Message::ConfirmGc));
+    }
+
     #[test]
     fn test_handle_confirm_cancel_dialog_key() {
         let mut field = ConfirmCancelField::Confirm;
@@ -1901,6 +2206,107 @@ mod tests {
         assert_eq!(follow_up, Some(Message::DismissDialog));
     }
 
+    #[test]
+    fn test_handle_quick_prompt_dialog_key_walks_history() {
+        let mut state = QuickPromptDialogState {
+            task_id: Uuid::new_v4(),
+            task_title: "Task".to_string(),
+            session_id: "ses_1".to_string(),
+            prompt_input: String::new(),
+            history: vec!["newest".to_string(), "older".to_string()],
+            history_index: Some(1),
+        };
+        let mut follow_up = None;
+
+        handle_quick_prompt_dialog_key(
+            &mut state,
+            KeyEvent::new(KeyCode::Up, KeyModifiers::empty()),
+            &mut follow_up,
+        );
+        assert_eq!(follow_up, Some(Message::UseQuickPromptHistory(1)));
+
+        follow_up = None;
+        handle_quick_prompt_dialog_key(
+            &mut state,
+            KeyEvent::new(KeyCode::Down, KeyModifiers::empty()),
+            &mut follow_up,
+        );
+        assert_eq!(follow_up, Some(Message::UseQuickPromptHistory(0)));
+
+        follow_up = None;
+        state.history_index = Some(0);
+        state.prompt_input = "newest".to_string();
+        handle_quick_prompt_dialog_key(
+            &mut state,
+            KeyEvent::new(KeyCode::Down, KeyModifiers::empty()),
+            &mut follow_up,
+        );
+        assert_eq!(follow_up, None);
+        assert_eq!(state.history_index, None);
+        assert!(state.prompt_input.is_empty());
+
+        handle_quick_prompt_dialog_key(
+            &mut state,
+            KeyEvent::new(KeyCode::Char('m'), KeyModifiers::empty()),
+            &mut follow_up,
+        );
+        handle_quick_prompt_dialog_key(
+            &mut state,
+            KeyEvent::new(KeyCode::Enter, KeyModifiers::empty()),
+            &mut follow_up,
+        );
+        assert_eq!(state.prompt_input, "m");
+        assert_eq!(follow_up, Some(Message::SubmitQuickPrompt));
+    }
+
+    #[test]
+    fn test_handle_permission_approval_dialog_key() {
+        let mut state = PermissionApprovalDialogState {
+            task_id: Uuid::new_v4(),
+            task_title: "Test".to_string(),
+            permission: crate::types::SessionPermissionRequest {
+                id: "per_1".to_string(),
+                session_id: "ses_1".to_string(),
+                tool: "bash".to_string(),
+                title: "git push".to_string(),
+                arguments: vec!["git push".to_string()],
+            },
+            pending_count: 1,
+            focused_field: PermissionReplyField::Approve,
+        };
+        let mut follow_up = None;
+
+        handle_permission_approval_dialog_key(
+            &mut state,
+            KeyEvent::new(KeyCode::Left, KeyModifiers::empty()),
+            &mut follow_up,
+        );
+        assert_eq!(state.focused_field, PermissionReplyField::Deny);
+        assert_eq!(follow_up, None);
+
+        handle_permission_approval_dialog_key(
+            &mut state,
+            KeyEvent::new(KeyCode::Enter, KeyModifiers::empty()),
+            &mut follow_up,
+        );
+        assert_eq!(
+            follow_up,
+            Some(Message::ReplyPermission(PermissionReplyField::Deny))
+        );
+
+        handle_permission_approval_dialog_key(
+            &mut state,
+            KeyEvent::new(KeyCode::Char('a'), KeyModifiers::empty()),
+            &mut follow_up,
+        );
+        assert_eq!(
+            follow_up,
+            Some(Message::ReplyPermission(
+                PermissionReplyField::AlwaysApprove
+            ))
+        );
+    }
+
     #[test]
     fn test_handle_worktree_not_found_dialog_key() {
         let mut state = WorktreeNotFoundDialogState {
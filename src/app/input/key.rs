@@ -86,15 +86,11 @@ impl App {
                 KeyAction::OpenArchiveView => {
                     self.update(Message::OpenArchiveView)?;
                 }
-                KeyAction::ProjectNext => {
-                    if self.current_view == View::Board {
-                        self.update(Message::SwitchToNextProject)?;
-                    }
+                KeyAction::ProjectNext if self.current_view == View::Board => {
+                    self.update(Message::SwitchToNextProject)?;
                 }
-                KeyAction::ProjectPrev => {
-                    if self.current_view == View::Board {
-                        self.update(Message::SwitchToPrevProject)?;
-                    }
+                KeyAction::ProjectPrev if self.current_view == View::Board => {
+                    self.update(Message::SwitchToPrevProject)?;
                 }
                 _ => {}
             }
@@ -116,11 +112,9 @@ impl App {
                     self.cycle_detail_focus();
                     return Ok(());
                 }
-                KeyCode::Enter | KeyCode::Char('e') => {
-                    if self.detail_focus == DetailFocus::Log {
-                        self.toggle_selected_log_entry(false);
-                        return Ok(());
-                    }
+                KeyCode::Enter | KeyCode::Char('e') if self.detail_focus == DetailFocus::Log => {
+                    self.toggle_selected_log_entry(false);
+                    return Ok(());
                 }
                 KeyCode::Char('f') => {
                     if self.detail_focus == DetailFocus::Log {
@@ -338,6 +332,24 @@ impl App {
                 KeyAction::OpenInWeb => {
                     self.update(Message::OpenSelectedTaskInWeb)?;
                 }
+                KeyAction::QuickPrompt => {
+                    self.update(Message::OpenQuickPromptDialog)?;
+                }
+                KeyAction::InterruptSession => {
+                    self.update(Message::OpenInterruptSessionDialog)?;
+                }
+                KeyAction::ForkTask => {
+                    self.update(Message::OpenForkTaskDialog)?;
+                }
+                KeyAction::RevertLastTurn => {
+                    self.update(Message::RevertLastTurn)?;
+                }
+                KeyAction::UnrevertLastTurn => {
+                    self.update(Message::UnrevertLastTurn)?;
+                }
+                KeyAction::ApprovePermission => {
+                    self.update(Message::OpenPermissionApprovalDialog)?;
+                }
                 KeyAction::CycleTodoVisualization => {
                     self.update(Message::CycleTodoVisualization)?;
                 }
@@ -351,10 +363,8 @@ impl App {
                         self.update(Message::DismissDialog)?;
                     }
                 }
-                KeyAction::ToggleCategoryEditMode => {
-                    if self.active_dialog == ActiveDialog::None {
-                        self.category_edit_mode = !self.category_edit_mode;
-                    }
+                KeyAction::ToggleCategoryEditMode if self.active_dialog == ActiveDialog::None => {
+                    self.category_edit_mode = !self.category_edit_mode;
                 }
                 _ => {}
             }
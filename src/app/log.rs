@@ -1,5 +1,7 @@
 use chrono::{DateTime, Local, Utc};
 
+use crate::types::{SessionMessagePart, ToolCallStatus};
+
 pub(crate) fn log_kind_label(raw: Option<&str>) -> String {
     let normalized = raw.unwrap_or("text").trim().to_ascii_lowercase();
     let value = match normalized.as_str() {
@@ -25,6 +27,58 @@ pub(crate) fn log_kind_label(raw: Option<&str>) -> String {
     }
 }
 
+/// Kind label, header summary and detail lines for one typed message part.
+pub(crate) fn log_part_entry(part: &SessionMessagePart) -> (&'static str, String, Vec<String>) {
+    match part {
+        SessionMessagePart::Text { text } => ("SAY", String::new(), text_lines(text)),
+        SessionMessagePart::Reasoning { text } => ("THINK", String::new(), text_lines(text)),
+        SessionMessagePart::Tool(call) => {
+            let mut summary = format!("{} · {}", call.name, call.status.as_str());
+            if let Some((_, value)) = call.arguments.first() {
+                summary.push_str(" · ");
+                summary.push_str(value);
+            }
+            let kind = if call.status == ToolCallStatus::Failed {
+                "TOOL!"
+            } else {
+                "TOOL"
+            };
+
+            let mut details = call
+                .arguments
+                .iter()
+                .map(|(key, value)| format!("{key}: {value}"))
+                .collect::<Vec<_>>();
+            if let Some(error) = &call.error {
+                details.push(format!("error: {error}"));
+            }
+            if let Some(output) = &call.output {
+                details.extend(text_lines(output));
+            }
+            (kind, summary, details)
+        }
+        SessionMessagePart::FileEdit {
+            path,
+            additions,
+            deletions,
+            diff,
+        } => (
+            "EDIT",
+            format!("{path} +{additions} -{deletions}"),
+            diff.clone(),
+        ),
+        SessionMessagePart::Error { message } => ("ERROR", message.clone(), Vec::new()),
+    }
+}
+
+fn text_lines(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
 pub(crate) fn log_role_label(raw: Option<&str>) -> String {
     let value = raw
         .map(str::trim)
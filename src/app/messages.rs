@@ -5,8 +5,9 @@ use std::path::PathBuf;
 use crossterm::event::{KeyEvent, MouseEvent};
 
 use super::state::{
-    CategoryInputField, DeleteTaskField, DetailFocus, EditTaskField, NewProjectField, NewTaskField,
-    RenameProjectField, RenameRepoField, SettingsSection,
+    CategoryInputField, DeleteTaskField, DetailFocus, EditTaskField, ForkTaskField,
+    NewProjectField, NewTaskField, PermissionReplyField, RenameProjectField, RenameRepoField,
+    SettingsSection,
 };
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -23,6 +24,17 @@ pub enum Message {
     AttachSelectedTask,
     OpenSelectedTaskInNewTerminal,
     OpenSelectedTaskInWeb,
+    OpenQuickPromptDialog,
+    SubmitQuickPrompt,
+    UseQuickPromptHistory(usize),
+    OpenInterruptSessionDialog,
+    ConfirmInterruptSession,
+    OpenForkTaskDialog,
+    RevertLastTurn,
+    UnrevertLastTurn,
+    ConfirmForkTask,
+    OpenPermissionApprovalDialog,
+    ReplyPermission(PermissionReplyField),
     OpenNewTaskDialog,
     OpenCommandPalette,
     OpenTaskPalette,
@@ -75,6 +87,9 @@ pub enum Message {
     RepoUnavailableDismiss,
     ConfirmQuit,
     CancelQuit,
+    OpenGcDialog,
+    ToggleGcAdopt,
+    ConfirmGc,
     ExecuteCommand(String),
     CycleCategoryColor(usize),
     OpenCategoryColorDialog,
@@ -107,6 +122,7 @@ pub enum Message {
     FocusNewProjectField(NewProjectField),
     FocusDeleteTaskField(DeleteTaskField),
     FocusEditTaskField(EditTaskField),
+    FocusForkTaskField(ForkTaskField),
     ToggleDeleteTaskCheckbox(DeleteTaskField),
     FocusDialogButton(String),
     SelectProject(usize),
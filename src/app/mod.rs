@@ -35,7 +35,7 @@ use tuirealm::ratatui::widgets::{ListState, ScrollbarState};
 use uuid::Uuid;
 
 use self::interaction::{InteractionKind, InteractionMap};
-use self::log::{log_kind_label, log_role_label, log_time_label};
+use self::log::{log_kind_label, log_part_entry, log_role_label, log_time_label};
 pub use self::messages::Message;
 pub use self::state::{
     ActiveDialog, ArchiveTaskDialogState, CATEGORY_COLOR_PALETTE, CategoryColorDialogState,
@@ -43,11 +43,13 @@ pub use self::state::{
     ConfirmCancelField, ConfirmQuitDialogState, ContextMenuItem, ContextMenuState,
     DeleteCategoryDialogState, DeleteProjectDialogState, DeleteRepoDialogState,
     DeleteTaskDialogState, DeleteTaskField, DetailFocus, EditTaskDialogState, EditTaskField,
-    ErrorDialogState, MoveTaskDialogState, NewProjectDialogState, NewProjectField,
-    NewTaskDialogState, NewTaskField, RenameProjectDialogState, RenameProjectField,
-    RenameRepoDialogState, RenameRepoField, RepoPickerDialogState, RepoPickerTarget,
-    RepoSuggestionItem, RepoSuggestionKind, RepoUnavailableDialogState, SettingsSection,
-    SettingsViewState, TaskSearchMode, TaskSearchState, TodoVisualizationMode, View, ViewMode,
+    ErrorDialogState, ForkTaskDialogState, ForkTaskField, GcDialogState,
+    InterruptSessionDialogState, MoveTaskDialogState, NewProjectDialogState, NewProjectField,
+    NewTaskDialogState, NewTaskField, PermissionApprovalDialogState, PermissionReplyField,
+    QuickPromptDialogState, RenameProjectDialogState, RenameProjectField, RenameRepoDialogState,
+    RenameRepoField, RepoPickerDialogState, RepoPickerTarget, RepoSuggestionItem,
+    RepoSuggestionKind, RepoUnavailableDialogState, SettingsSection, SettingsViewState,
+    TaskSearchMode, TaskSearchState, TodoVisualizationMode, View, ViewMode,
     WorktreeNotFoundDialogState, WorktreeNotFoundField, category_color_label,
     normalize_category_color_key,
 };
@@ -59,21 +61,28 @@ use crate::git::{
     git_remove_worktree,
 };
 use crate::keybindings::Keybindings;
-use crate::opencode::{OpenCodeServerManager, Status, ensure_server_ready};
+use crate::opencode::status_server::{PermissionReply, ServerStatusConfig};
+use crate::opencode::{OpenCodeServerManager, ServerStatusProvider, Status, ensure_server_ready};
 use crate::projects::{self, ProjectInfo};
 use crate::theme::{Theme, ThemePreset};
 use crate::tmux::tmux_kill_session;
-use crate::types::{Category, Repo, SessionMessageItem, SessionTodoItem, Task};
+use crate::types::{
+    AgentSelection, Category, Repo, SessionMessageItem, SessionPermissionRequest,
+    SessionStatusError, SessionTodoItem, Task, TokenUsage,
+};
 
-use self::runtime::{RealCreateTaskRuntime, RealRecoveryRuntime, RecoveryRuntime};
+use self::runtime::{RealCreateTaskRuntime, RealGcRuntime, RealRecoveryRuntime, RecoveryRuntime};
 use self::state::AttachTaskResult;
 use self::workflows::{
-    attach_task_with_runtime, create_task_error_dialog_state, create_task_pipeline_with_runtime,
+    GcOptions, apply_gc, attach_task_with_runtime, create_task_error_dialog_state,
+    create_task_pipeline_with_runtime, fork_task_pipeline_with_runtime, open_other_project_dbs,
     open_task_in_new_terminal_with_runtime, rank_repos_for_query, reconcile_startup_tasks,
-    repo_selection_usage_map,
+    repo_selection_usage_map, scan_orphans,
 };
 
 const GG_SEQUENCE_TIMEOUT: Duration = Duration::from_millis(500);
+const QUICK_PROMPT_HISTORY_LIMIT: usize = 10;
+const SESSION_ACTION_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum SidePanelRow {
@@ -147,6 +156,175 @@ pub(crate) fn spawn_change_summary_worker() -> (
     (request_tx, result_rx, worker)
 }
 
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) enum SessionAction {
+    Prompt {
+        text: String,
+        selection: AgentSelection,
+    },
+    Abort,
+    ReplyPermission {
+        permission_id: String,
+        permission_title: String,
+        reply: PermissionReply,
+    },
+    /// Reverts the session to before its last assistant message.
+    Revert {
+        worktree_path: Option<PathBuf>,
+    },
+    Unrevert {
+        worktree_path: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug)]
+pub(crate) struct SessionActionResult {
+    pub(crate) task_id: Uuid,
+    pub(crate) task_title: String,
+    pub(crate) action: SessionAction,
+    pub(crate) outcome: Result<(), String>,
+    pub(crate) revert: Option<RevertReport>,
+}
+
+/// What a revert or unrevert left behind: the session's messages and the
+/// worktree's change summary before and after the request.
+#[derive(Debug)]
+pub(crate) struct RevertReport {
+    pub(crate) messages: Option<Vec<SessionMessageItem>>,
+    pub(crate) before: Option<GitChangeSummary>,
+    pub(crate) after: Option<GitChangeSummary>,
+}
+
+/// Runs one request against a task's OpenCode session on a short-lived
+/// thread so the UI never waits on the server; the outcome is picked up on
+/// the next tick.
+pub(crate) fn spawn_session_action(
+    task_id: Uuid,
+    task_title: String,
+    session_id: String,
+    action: SessionAction,
+    result_tx: Sender<SessionActionResult>,
+) {
+    thread::spawn(move || {
+        let provider = ServerStatusProvider::new(ServerStatusConfig {
+            request_timeout: SESSION_ACTION_REQUEST_TIMEOUT,
+            ..ServerStatusConfig::default()
+        });
+        let outcome = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|err| err.to_string())
+            .and_then(|runtime| {
+                runtime
+                    .block_on(async {
+                        match &action {
+                            SessionAction::Prompt { text, selection } => provider
+                                .send_session_prompt(&session_id, text, selection)
+                                .await
+                                .map(|()| None),
+                            SessionAction::Abort => {
+                                provider.abort_session(&session_id).await.map(|()| None)
+                            }
+                            SessionAction::ReplyPermission {
+                                permission_id,
+                                reply,
+                                ..
+                            } => provider
+                                .reply_permission(&session_id, permission_id, *reply)
+                                .await
+                                .map(|()| None),
+                            SessionAction::Revert { worktree_path } => {
+                                let messages = provider.fetch_session_messages(&session_id).await?;
+                                let message_id =
+                                    last_assistant_message_id(&messages).ok_or_else(|| {
+                                        SessionStatusError {
+                                            code: "NOTHING_TO_REVERT".to_string(),
+                                            message: "the session has no assistant turn to revert"
+                                                .to_string(),
+                                        }
+                                    })?;
+                                let before = worktree_change_summary(worktree_path.as_deref());
+                                provider.revert_session(&session_id, message_id).await?;
+                                Ok(Some(
+                                    revert_report(&provider, &session_id, worktree_path, before)
+                                        .await,
+                                ))
+                            }
+                            SessionAction::Unrevert { worktree_path } => {
+                                let before = worktree_change_summary(worktree_path.as_deref());
+                                provider.unrevert_session(&session_id).await?;
+                                Ok(Some(
+                                    revert_report(&provider, &session_id, worktree_path, before)
+                                        .await,
+                                ))
+                            }
+                        }
+                    })
+                    .map_err(|err| err.message)
+            });
+        let (outcome, revert) = match outcome {
+            Ok(revert) => (Ok(()), revert),
+            Err(err) => (Err(err), None),
+        };
+        let _ = result_tx.send(SessionActionResult {
+            task_id,
+            task_title,
+            action,
+            outcome,
+            revert,
+        });
+    });
+}
+
+/// The newest assistant message that can be reverted to.
+pub(crate) fn last_assistant_message_id(messages: &[SessionMessageItem]) -> Option<&str> {
+    messages
+        .iter()
+        .rev()
+        .filter(|message| message.role.as_deref() == Some("assistant"))
+        .find_map(|message| message.id.as_deref())
+}
+
+fn worktree_change_summary(worktree_path: Option<&Path>) -> Option<GitChangeSummary> {
+    worktree_path.and_then(|path| git_change_summary_against_nearest_ancestor(path).ok())
+}
+
+async fn revert_report(
+    provider: &ServerStatusProvider,
+    session_id: &str,
+    worktree_path: &Option<PathBuf>,
+    before: Option<GitChangeSummary>,
+) -> RevertReport {
+    RevertReport {
+        messages: provider.fetch_session_messages(session_id).await.ok(),
+        before,
+        after: worktree_change_summary(worktree_path.as_deref()),
+    }
+}
+
+/// How the worktree's change summary moved, e.g. `3 files +10 -2 → 1 file +1 -0`.
+pub(crate) fn change_delta_label(
+    before: Option<&GitChangeSummary>,
+    after: Option<&GitChangeSummary>,
+) -> String {
+    let short = |summary: &GitChangeSummary| {
+        let noun = if summary.files_changed == 1 {
+            "file"
+        } else {
+            "files"
+        };
+        format!(
+            "{} {noun} +{} -{}",
+            summary.files_changed, summary.insertions, summary.deletions
+        )
+    };
+    match (before, after) {
+        (Some(before), Some(after)) => format!("{} → {}", short(before), short(after)),
+        (None, Some(after)) => format!("now {}", short(after)),
+        _ => "change summary unavailable".to_string(),
+    }
+}
+
 impl App {
     fn move_category_left(&mut self) -> Result<()> {
         if self.categories.len() < 2 || self.focused_column == 0 {
@@ -437,6 +615,215 @@ impl App {
         Ok(())
     }
 
+    fn open_interrupt_session_dialog(&mut self) -> Result<()> {
+        if self.current_view != View::Board {
+            return Ok(());
+        }
+
+        let Some(task) = self.selected_task() else {
+            return Ok(());
+        };
+        let Some(session_id) = task
+            .opencode_session_id
+            .clone()
+            .filter(|session_id| !session_id.trim().is_empty())
+        else {
+            self.footer_notice = Some(format!(
+                " '{}' has no OpenCode session yet; nothing to interrupt ",
+                task.title
+            ));
+            return Ok(());
+        };
+
+        self.active_dialog = ActiveDialog::InterruptSession(InterruptSessionDialogState {
+            task_id: task.id,
+            task_title: task.title,
+            session_id,
+            focused_field: ConfirmCancelField::Cancel,
+        });
+        Ok(())
+    }
+
+    fn open_fork_task_dialog(&mut self) -> Result<()> {
+        if self.current_view != View::Board {
+            return Ok(());
+        }
+
+        let Some(task) = self.selected_task() else {
+            return Ok(());
+        };
+        if task.worktree_path.is_none() {
+            self.footer_notice = Some(format!(
+                " '{}' has no worktree yet; nothing to fork ",
+                task.title
+            ));
+            return Ok(());
+        }
+
+        self.active_dialog = ActiveDialog::ForkTask(ForkTaskDialogState {
+            task_id: task.id,
+            branch_input: format!("{}-fork", task.branch),
+            title_input: format!("{} (fork)", task.title),
+            task_title: task.title,
+            focused_field: ForkTaskField::Branch,
+        });
+        Ok(())
+    }
+
+    fn confirm_fork_task(&mut self) -> Result<()> {
+        let ActiveDialog::ForkTask(state) = self.active_dialog.clone() else {
+            return Ok(());
+        };
+
+        let source = self.db.get_task(state.task_id)?;
+        let Some(repo) = self.repo_for_task(&source) else {
+            self.active_dialog = ActiveDialog::Error(ErrorDialogState {
+                title: "Fork failed".to_string(),
+                detail: "The task's repository is no longer available.".to_string(),
+            });
+            return Ok(());
+        };
+
+        let project_slug = self.current_project_slug_for_tmux();
+        let result = fork_task_pipeline_with_runtime(
+            &self.db,
+            &source,
+            &repo,
+            &state,
+            project_slug.as_deref(),
+            &RealCreateTaskRuntime,
+        );
+
+        match result {
+            Ok(outcome) => {
+                self.footer_notice = Some(match outcome.warning {
+                    Some(warning) => format!(" Forked '{}', but {warning} ", state.task_title),
+                    None => format!("  ✓ Forked '{}'  ", state.task_title),
+                });
+                self.active_dialog = ActiveDialog::None;
+                self.refresh_data()?;
+                self.focus_task_by_id(outcome.task_id);
+            }
+            Err(err) => {
+                self.active_dialog = ActiveDialog::Error(create_task_error_dialog_state(&err));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn confirm_interrupt_session(&mut self) -> Result<()> {
+        let ActiveDialog::InterruptSession(state) = self.active_dialog.clone() else {
+            return Ok(());
+        };
+
+        spawn_session_action(
+            state.task_id,
+            state.task_title.clone(),
+            state.session_id,
+            SessionAction::Abort,
+            self.session_action_result_tx.clone(),
+        );
+        self.footer_notice = Some(format!("  Interrupting '{}'...  ", state.task_title));
+        self.active_dialog = ActiveDialog::None;
+        Ok(())
+    }
+
+    /// Reverts (or, with `undo`, restores) the selected task's last agent
+    /// turn in the background.
+    fn revert_last_turn(&mut self, undo: bool) -> Result<()> {
+        if self.current_view != View::Board {
+            return Ok(());
+        }
+
+        let Some(task) = self.selected_task() else {
+            return Ok(());
+        };
+        let Some(session_id) = task
+            .opencode_session_id
+            .clone()
+            .filter(|session_id| !session_id.trim().is_empty())
+        else {
+            self.footer_notice = Some(format!(
+                " '{}' has no OpenCode session yet; nothing to revert ",
+                task.title
+            ));
+            return Ok(());
+        };
+
+        let worktree_path = task.worktree_path.as_deref().map(PathBuf::from);
+        let (action, verb) = if undo {
+            (SessionAction::Unrevert { worktree_path }, "Restoring")
+        } else {
+            (SessionAction::Revert { worktree_path }, "Reverting")
+        };
+        spawn_session_action(
+            task.id,
+            task.title.clone(),
+            session_id,
+            action,
+            self.session_action_result_tx.clone(),
+        );
+        self.footer_notice = Some(format!("  {verb} last turn of '{}'...  ", task.title));
+        Ok(())
+    }
+
+    fn open_permission_approval_dialog(&mut self) -> Result<()> {
+        if self.current_view != View::Board {
+            return Ok(());
+        }
+
+        let Some(task) = self.selected_task() else {
+            return Ok(());
+        };
+        let pending = self.session_permissions(task.id);
+        let Some(permission) = pending.first().cloned() else {
+            self.footer_notice = Some(format!(
+                " '{}' has no pending permission requests ",
+                task.title
+            ));
+            return Ok(());
+        };
+
+        self.active_dialog = ActiveDialog::PermissionApproval(PermissionApprovalDialogState {
+            task_id: task.id,
+            task_title: task.title,
+            permission,
+            pending_count: pending.len(),
+            focused_field: PermissionReplyField::Approve,
+        });
+        Ok(())
+    }
+
+    fn reply_permission(&mut self, field: PermissionReplyField) -> Result<()> {
+        let ActiveDialog::PermissionApproval(state) = self.active_dialog.clone() else {
+            return Ok(());
+        };
+
+        let reply = match field {
+            PermissionReplyField::Approve => PermissionReply::Once,
+            PermissionReplyField::AlwaysApprove => PermissionReply::Always,
+            PermissionReplyField::Deny => PermissionReply::Reject,
+        };
+        spawn_session_action(
+            state.task_id,
+            state.task_title.clone(),
+            state.permission.session_id,
+            SessionAction::ReplyPermission {
+                permission_id: state.permission.id,
+                permission_title: state.permission.title,
+                reply,
+            },
+            self.session_action_result_tx.clone(),
+        );
+        self.footer_notice = Some(format!(
+            "  Answering permission request for '{}'...  ",
+            state.task_title
+        ));
+        self.active_dialog = ActiveDialog::None;
+        Ok(())
+    }
+
     fn confirm_category_input(&mut self) -> Result<()> {
         let ActiveDialog::CategoryInput(state) = self.active_dialog.clone() else {
             return Ok(());
@@ -613,6 +1000,69 @@ impl App {
         Ok(())
     }
 
+    fn open_gc_dialog(&mut self) -> Result<()> {
+        let project_slug = self.current_project_slug_for_tmux();
+        let other_projects = self
+            .current_project_path
+            .as_deref()
+            .map(open_other_project_dbs)
+            .unwrap_or_default();
+        let report = scan_orphans(
+            &self.db,
+            project_slug.as_deref(),
+            &other_projects,
+            &RealGcRuntime,
+        )?;
+
+        if report.is_empty() {
+            self.active_dialog = ActiveDialog::None;
+            self.footer_notice = Some("  Nothing to clean up  ".to_string());
+            return Ok(());
+        }
+
+        self.active_dialog = ActiveDialog::Gc(GcDialogState {
+            report,
+            adopt_worktrees: false,
+            focused_field: ConfirmCancelField::Cancel,
+        });
+        Ok(())
+    }
+
+    fn confirm_gc(&mut self) -> Result<()> {
+        let ActiveDialog::Gc(state) = self.active_dialog.clone() else {
+            return Ok(());
+        };
+
+        let options = GcOptions {
+            adopt_worktrees: state.adopt_worktrees,
+            ..GcOptions::default()
+        };
+        let outcomes = apply_gc(&self.db, &state.report, options, &RealGcRuntime);
+        self.refresh_data()?;
+
+        let failures = outcomes
+            .iter()
+            .filter_map(|outcome| {
+                let error = outcome.error.as_deref()?;
+                Some(format!(
+                    "{} {}: {error}",
+                    outcome.kind.as_str(),
+                    outcome.target
+                ))
+            })
+            .collect::<Vec<_>>();
+        if failures.is_empty() {
+            self.active_dialog = ActiveDialog::None;
+            self.footer_notice = Some(format!("  ✓ Cleaned up {} item(s)  ", outcomes.len()));
+        } else {
+            self.active_dialog = ActiveDialog::Error(ErrorDialogState {
+                title: "Cleanup incomplete".to_string(),
+                detail: failures.join("\n"),
+            });
+        }
+        Ok(())
+    }
+
     fn unarchive_selected_task(&mut self) -> Result<()> {
         if self.current_view != View::Archive {
             return Ok(());
@@ -844,6 +1294,22 @@ fn next_palette_color(current: Option<&str>) -> Option<String> {
     CATEGORY_COLOR_PALETTE[next_idx].map(str::to_string)
 }
 
+fn default_db_path() -> Result<PathBuf> {
+    let path = projects::get_project_path(projects::DEFAULT_PROJECT);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create data dir {}", parent.display()))?;
+    }
+    Ok(path)
+}
+
+pub fn point_in_rect(x: u16, y: u16, rect: Rect) -> bool {
+    x >= rect.x
+        && x < rect.x.saturating_add(rect.width)
+        && y >= rect.y
+        && y < rect.y.saturating_add(rect.height)
+}
+
 #[cfg(test)]
 mod tests {
     use super::interaction::InteractionLayer;
@@ -873,13 +1339,10 @@ mod tests {
     use crate::keybindings::{KeyAction, KeyContext, Keybindings};
     use crate::opencode::OpenCodeServerManager;
     use crate::tmux::PopupThemeStyle;
-    use crate::types::CommandFrequency;
+    use crate::types::{CommandFrequency, SessionMessagePart, SessionToolCall, ToolCallStatus};
 
     fn test_category(id: Uuid, name: &str, position: i64) -> Category {
-        let slug = name
-            .to_ascii_lowercase()
-            .replace(' ', "-")
-            .replace('_', "-");
+        let slug = name.to_ascii_lowercase().replace([' ', '_'], "-");
         Category {
             id,
             slug,
@@ -905,6 +1368,10 @@ mod tests {
             status_fetched_at: None,
             status_error: None,
             opencode_session_id: None,
+            model: None,
+            agent: None,
+            forked_from: None,
+            backend: None,
             attach_overlay_shown: false,
             needs_inspection: false,
             archived: false,
@@ -990,6 +1457,7 @@ mod tests {
             _session_name: &str,
             _working_dir: &Path,
             _command: &str,
+            _env: &[(String, String)],
         ) -> Result<()> {
             self.create_session_calls
                 .set(self.create_session_calls.get() + 1);
@@ -1204,6 +1672,9 @@ mod tests {
             branch_input: String::new(),
             base_input: String::new(),
             title_input: String::new(),
+            model_input: String::new(),
+            agent_input: String::new(),
+            prompt_input: String::new(),
             ensure_base_up_to_date: true,
             loading_message: None,
             focused_field: NewTaskField::Repo,
@@ -1239,6 +1710,7 @@ mod tests {
         let task = db.add_task(repo.id, "feature/category-edit-tests", "Task", ids[1])?;
         let (change_summary_request_tx, change_summary_result_rx, change_summary_worker) =
             spawn_change_summary_worker();
+        let (session_action_result_tx, session_action_result_rx) = mpsc::channel();
 
         let mut app = App {
             should_quit: false,
@@ -1286,6 +1758,8 @@ mod tests {
             change_summary_result_rx,
             pending_change_summary_results: Vec::new(),
             change_summary_worker: Some(change_summary_worker),
+            session_action_result_tx,
+            session_action_result_rx,
             detail_focus: DetailFocus::List,
             detail_scroll_offset: 0,
             log_scroll_offset: 0,
@@ -1297,6 +1771,8 @@ mod tests {
             session_subagent_cache: Arc::new(Mutex::new(HashMap::new())),
             session_title_cache: Arc::new(Mutex::new(HashMap::new())),
             session_message_cache: Arc::new(Mutex::new(HashMap::new())),
+            session_permission_cache: Arc::new(Mutex::new(HashMap::new())),
+            session_usage_cache: Arc::new(Mutex::new(HashMap::new())),
             todo_visualization_mode: TodoVisualizationMode::Checklist,
             keybindings: Keybindings::load(),
             settings: crate::settings::Settings::load(),
@@ -1500,8 +1976,8 @@ mod tests {
         for idx in 0..7 {
             app.db.add_task(
                 repo_id,
-                &format!("feature/half-page-{idx}"),
-                &format!("Half Page {idx}"),
+                format!("feature/half-page-{idx}"),
+                format!("Half Page {idx}"),
                 category_ids[1],
             )?;
         }
@@ -1526,8 +2002,8 @@ mod tests {
         for idx in 0..4 {
             app.db.add_task(
                 repo_id,
-                &format!("feature/g-jump-{idx}"),
-                &format!("Jump {idx}"),
+                format!("feature/g-jump-{idx}"),
+                format!("Jump {idx}"),
                 category_ids[1],
             )?;
         }
@@ -1616,6 +2092,186 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn interrupting_unbound_task_only_shows_notice() -> Result<()> {
+        let (mut app, _repo_dir, _task_id, _category_ids) = test_app_with_middle_task()?;
+
+        app.handle_key(key_char('i'))?;
+
+        assert_eq!(app.active_dialog, ActiveDialog::None);
+        assert!(
+            app.footer_notice
+                .as_deref()
+                .is_some_and(|notice| notice.contains("nothing to interrupt"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn successful_interrupt_marks_task_idle_on_next_drain() -> Result<()> {
+        let (mut app, _repo_dir, task_id, _category_ids) = test_app_with_middle_task()?;
+        app.db.update_task_status(task_id, "running")?;
+        app.refresh_data()?;
+
+        app.session_action_result_tx
+            .send(SessionActionResult {
+                task_id,
+                task_title: "Task".to_string(),
+                action: SessionAction::Abort,
+                outcome: Ok(()),
+                revert: None,
+            })
+            .expect("channel should be open");
+        app.drain_session_action_results()?;
+
+        let task = app.selected_task().expect("expected selected task");
+        assert_eq!(task.tmux_status, "idle");
+        assert_eq!(
+            app.footer_notice.as_deref(),
+            Some("  ✓ Interrupted 'Task'  ")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn successful_revert_shows_change_delta_and_refreshes_log() -> Result<()> {
+        let (mut app, _repo_dir, task_id, _category_ids) = test_app_with_middle_task()?;
+        let summary = |files_changed, insertions, deletions| GitChangeSummary {
+            base_ref: "main".to_string(),
+            commits_ahead: 0,
+            files_changed,
+            insertions,
+            deletions,
+            top_files: Vec::new(),
+        };
+        let messages = vec![SessionMessageItem {
+            id: Some("msg_user".to_string()),
+            message_type: Some("text".to_string()),
+            role: Some("user".to_string()),
+            content: "try again".to_string(),
+            timestamp: None,
+            parts: Vec::new(),
+        }];
+
+        app.session_action_result_tx
+            .send(SessionActionResult {
+                task_id,
+                task_title: "Task".to_string(),
+                action: SessionAction::Revert {
+                    worktree_path: None,
+                },
+                outcome: Ok(()),
+                revert: Some(RevertReport {
+                    messages: Some(messages.clone()),
+                    before: Some(summary(3, 10, 2)),
+                    after: Some(summary(1, 1, 0)),
+                }),
+            })
+            .expect("channel should be open");
+        app.drain_session_action_results()?;
+
+        assert_eq!(
+            app.footer_notice.as_deref(),
+            Some("  ✓ Reverted last turn of 'Task': 3 files +10 -2 → 1 file +1 -0  ")
+        );
+        assert_eq!(app.session_messages(task_id), messages);
+        let task = app.selected_task().expect("expected selected task");
+        let key = app.task_change_summary_key(&task).expect("summary key");
+        assert_eq!(
+            app.change_summary_cache.get(&key),
+            Some(&Ok(summary(1, 1, 0)))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn last_assistant_message_id_skips_user_and_unidentified_messages() {
+        let message = |id: Option<&str>, role: &str| SessionMessageItem {
+            id: id.map(str::to_string),
+            message_type: Some("text".to_string()),
+            role: Some(role.to_string()),
+            content: "text".to_string(),
+            timestamp: None,
+            parts: Vec::new(),
+        };
+        let messages = vec![
+            message(Some("msg_1"), "assistant"),
+            message(None, "assistant"),
+            message(Some("msg_3"), "user"),
+        ];
+        assert_eq!(last_assistant_message_id(&messages), Some("msg_1"));
+        assert_eq!(last_assistant_message_id(&messages[1..]), None);
+        assert_eq!(change_delta_label(None, None), "change summary unavailable");
+    }
+
+    #[test]
+    fn approving_last_permission_request_puts_task_back_to_running() -> Result<()> {
+        let (mut app, _repo_dir, task_id, _category_ids) = test_app_with_middle_task()?;
+        app.db
+            .update_task_status(task_id, Status::AwaitingApproval.as_str())?;
+        app.refresh_data()?;
+        app.session_permission_cache
+            .lock()
+            .expect("permission cache lock")
+            .insert(
+                task_id,
+                vec![SessionPermissionRequest {
+                    id: "per_1".to_string(),
+                    session_id: "ses_1".to_string(),
+                    tool: "bash".to_string(),
+                    title: "git push".to_string(),
+                    arguments: vec!["git push".to_string()],
+                }],
+            );
+
+        app.handle_key(key_char('y'))?;
+        let ActiveDialog::PermissionApproval(state) = &app.active_dialog else {
+            panic!("expected permission approval dialog");
+        };
+        assert_eq!(state.permission.id, "per_1");
+
+        app.session_action_result_tx
+            .send(SessionActionResult {
+                task_id,
+                task_title: "Task".to_string(),
+                action: SessionAction::ReplyPermission {
+                    permission_id: "per_1".to_string(),
+                    permission_title: "git push".to_string(),
+                    reply: PermissionReply::Once,
+                },
+                outcome: Ok(()),
+                revert: None,
+            })
+            .expect("channel should be open");
+        app.drain_session_action_results()?;
+
+        assert!(app.session_permissions(task_id).is_empty());
+        assert_eq!(
+            app.db.get_task(task_id)?.tmux_status,
+            Status::Running.as_str()
+        );
+        assert_eq!(
+            app.footer_notice.as_deref(),
+            Some("  ✓ Approved 'git push' for 'Task'  ")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn permission_dialog_without_pending_requests_only_shows_notice() -> Result<()> {
+        let (mut app, _repo_dir, _task_id, _category_ids) = test_app_with_middle_task()?;
+
+        app.handle_key(key_char('y'))?;
+
+        assert_eq!(app.active_dialog, ActiveDialog::None);
+        assert!(
+            app.footer_notice
+                .as_deref()
+                .is_some_and(|notice| notice.contains("no pending permission requests"))
+        );
+        Ok(())
+    }
+
     #[test]
     fn moving_task_right_keeps_focus_on_moved_task_in_kanban() -> Result<()> {
         let (mut app, _repo_dir, task_id, [_todo_id, _in_progress_id, done_id]) =
@@ -2343,6 +2999,9 @@ mod tests {
             branch_input: String::new(),
             base_input: String::new(),
             title_input: String::new(),
+            model_input: String::new(),
+            agent_input: String::new(),
+            prompt_input: String::new(),
             ensure_base_up_to_date: true,
             loading_message: None,
             focused_field: NewTaskField::Repo,
@@ -2361,6 +3020,9 @@ mod tests {
             branch_input: String::new(),
             base_input: String::new(),
             title_input: String::new(),
+            model_input: String::new(),
+            agent_input: String::new(),
+            prompt_input: String::new(),
             ensure_base_up_to_date: true,
             loading_message: None,
             focused_field: NewTaskField::Repo,
@@ -2443,10 +3105,12 @@ mod tests {
             messages.insert(
                 task_id,
                 vec![SessionMessageItem {
+                    id: None,
                     role: Some("assistant".to_string()),
                     content: "hello".to_string(),
                     timestamp: Some("2024-01-01T10:00:00Z".to_string()),
                     message_type: Some("text".to_string()),
+                    parts: Vec::new(),
                 }],
             );
         }
@@ -2604,6 +3268,14 @@ mod tests {
             Status::Running.as_str()
         );
 
+        app.db.update_task_status(task_id, Status::Error.as_str())?;
+        app.refresh_data()?;
+        reconcile_startup_tasks(&app.db, &app.tasks, &app.repos, &existing_session)?;
+        assert_eq!(
+            app.db.get_task(task_id)?.tmux_status,
+            Status::Error.as_str()
+        );
+
         app.db
             .update_task_status(task_id, Status::Running.as_str())?;
         app.refresh_data()?;
@@ -2766,6 +3438,44 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn log_buffer_lists_typed_parts_newest_first() {
+        let message = SessionMessageItem {
+            id: None,
+            message_type: Some("text".to_string()),
+            role: Some("assistant".to_string()),
+            content: "done".to_string(),
+            timestamp: None,
+            parts: vec![
+                SessionMessagePart::Tool(SessionToolCall {
+                    name: "bash".to_string(),
+                    status: ToolCallStatus::Failed,
+                    arguments: vec![("command".to_string(), "cargo test".to_string())],
+                    output: None,
+                    error: Some("exit 101".to_string()),
+                }),
+                SessionMessagePart::FileEdit {
+                    path: "src/lib.rs".to_string(),
+                    additions: 1,
+                    deletions: 1,
+                    diff: vec!["-old".to_string(), "+new".to_string()],
+                },
+                SessionMessagePart::Text {
+                    text: "done".to_string(),
+                },
+            ],
+        };
+
+        let log = App::build_log_buffer_from_messages(&[message]).expect("log should render");
+
+        assert_eq!(
+            log,
+            "> [SAY] ASSISTANT --:--:--\n  done\n\n\
+             > [EDIT] ASSISTANT --:--:-- src/lib.rs +1 -1\n  -old\n  +new\n\n\
+             > [TOOL!] ASSISTANT --:--:-- bash · failed · cargo test\n  command: cargo test\n  error: exit 101"
+        );
+    }
+
     #[test]
     fn key_shortcuts_cover_help_side_panel_and_expanded_log_paths() -> Result<()> {
         let (mut app, _repo_dir, _task_id, _category_ids) = test_app_with_middle_task()?;
@@ -3135,19 +3845,3 @@ mod tests {
         Ok(())
     }
 }
-
-fn default_db_path() -> Result<PathBuf> {
-    let path = projects::get_project_path(projects::DEFAULT_PROJECT);
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent)
-            .with_context(|| format!("failed to create data dir {}", parent.display()))?;
-    }
-    Ok(path)
-}
-
-pub fn point_in_rect(x: u16, y: u16, rect: Rect) -> bool {
-    x >= rect.x
-        && x < rect.x.saturating_add(rect.width)
-        && y >= rect.y
-        && y < rect.y.saturating_add(rect.height)
-}
@@ -6,19 +6,29 @@ use std::path::Path;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, Instant, SystemTime};
 
 use chrono::{DateTime, Utc};
+use tokio::sync::Notify;
 use tokio::task::JoinHandle;
 use tracing::debug;
 use uuid::Uuid;
 
 use super::SubagentTodoSummary;
+use crate::backend::{
+    AgentBackend, AgentBackendKind, backend_for_kind, backend_for_task, task_backend_kind,
+};
 use crate::db::Database;
-use crate::notification::{TaskCompletionNotificationConfig, notify_task_completion};
-use crate::opencode::status_server::SessionStatusMatch;
-use crate::opencode::{ServerStatusProvider, Status};
-use crate::types::{SessionMessageItem, SessionState, SessionStatusSource, SessionTodoItem};
+use crate::notification::{
+    TaskCompletionNotificationConfig, notify_task_attention, notify_task_completion,
+};
+use crate::opencode::events::ServerEvent;
+use crate::opencode::status_server::{SessionMessageLog, SessionStatusMatch, status_detail_error};
+use crate::opencode::{PaneStatusProvider, ServerStatusProvider, Status, StatusProvider};
+use crate::types::{
+    ModelUsage, SessionMessageItem, SessionPermissionRequest, SessionState, SessionStatus,
+    SessionStatusError, SessionStatusSource, SessionTodoItem, Task, TokenUsage,
+};
 
 #[derive(Clone)]
 pub struct StatusPollerCaches {
@@ -26,6 +36,9 @@ pub struct StatusPollerCaches {
     pub session_subagent_cache: Arc<Mutex<HashMap<Uuid, Vec<SubagentTodoSummary>>>>,
     pub session_title_cache: Arc<Mutex<HashMap<String, String>>>,
     pub session_message_cache: Arc<Mutex<HashMap<Uuid, Vec<SessionMessageItem>>>>,
+    pub session_permission_cache: Arc<Mutex<HashMap<Uuid, Vec<SessionPermissionRequest>>>>,
+    /// Token usage and cost per task, including its subagent sessions.
+    pub session_usage_cache: Arc<Mutex<HashMap<Uuid, TokenUsage>>>,
 }
 
 /// Spawn a background task that polls task status from the OpenCode server
@@ -53,276 +66,419 @@ pub fn spawn_status_poller(
             }
         };
 
-        while !stop.load(Ordering::Relaxed) {
-            let tasks = match db.list_tasks_async().await {
-                Ok(tasks) => tasks,
-                Err(_) => {
-                    interruptible_sleep(Duration::from_millis(poll_interval_ms), &stop).await;
-                    continue;
-                }
-            };
-            if tasks.is_empty() {
-                interruptible_sleep(Duration::from_millis(poll_interval_ms), &stop).await;
-                continue;
-            }
-
-            let repo_paths: HashMap<Uuid, String> = match db.list_repos_async().await {
-                Ok(repos) => repos.into_iter().map(|repo| (repo.id, repo.path)).collect(),
-                Err(err) => {
-                    tracing::warn!(error = %err, "failed to list repos for status poller");
-                    interruptible_sleep(Duration::from_millis(poll_interval_ms), &stop).await;
-                    continue;
-                }
-            };
-            let fetched_at = SystemTime::now();
-            let mut next_todo_cache: HashMap<Uuid, Vec<SessionTodoItem>> = caches
-                .session_todo_cache
-                .lock()
-                .ok()
-                .map(|cache| cache.clone())
-                .unwrap_or_default();
-            let mut next_subagent_cache: HashMap<Uuid, Vec<SubagentTodoSummary>> = caches
-                .session_subagent_cache
-                .lock()
-                .ok()
-                .map(|cache| cache.clone())
-                .unwrap_or_default();
-            let mut next_message_cache: HashMap<Uuid, Vec<SessionMessageItem>> = caches
-                .session_message_cache
-                .lock()
-                .ok()
-                .map(|cache| cache.clone())
-                .unwrap_or_default();
-            let mut next_title_cache: HashMap<String, String> = caches
-                .session_title_cache
-                .lock()
-                .ok()
-                .map(|cache| cache.clone())
-                .unwrap_or_default();
+        let stream = EventStreamState::default();
 
-            debug!(
+        // Both halves share this task, so aborting the poller's handle also
+        // closes the event stream.
+        tokio::join!(
+            poll_status_loop(
+                &db,
+                &server_provider,
+                &stop,
+                &caches,
                 poll_interval_ms,
-                task_count = tasks.len(),
-                "status/todo poll cycle started"
-            );
+                notification_config,
+                &stream,
+            ),
+            run_event_subscriber(
+                &db,
+                &server_provider,
+                &stop,
+                &caches,
+                notification_config,
+                &stream,
+            ),
+        );
+    })
+}
 
-            for task in &tasks {
-                if stop.load(Ordering::Relaxed) {
-                    break;
-                }
+/// Full status sync of every task. Runs every `poll_interval_ms` while the
+/// event stream is down, and only as a backfill while it is connected.
+async fn poll_status_loop(
+    db: &Database,
+    server_provider: &ServerStatusProvider,
+    stop: &AtomicBool,
+    caches: &StatusPollerCaches,
+    poll_interval_ms: u64,
+    notification_config: TaskCompletionNotificationConfig,
+    stream: &EventStreamState,
+) {
+    let pane_provider = Arc::new(PaneStatusProvider::default());
+    let mut session_usage = SessionUsageLedger::load(db).await;
+    session_usage.publish(caches);
+
+    while !stop.load(Ordering::Relaxed) {
+        let tasks = match db.list_tasks_async().await {
+            Ok(tasks) => tasks,
+            Err(_) => {
+                interruptible_sleep(Duration::from_millis(poll_interval_ms), stop).await;
+                continue;
+            }
+        };
+        stream.index_sessions(&tasks);
+        pane_provider.begin_cycle();
+        if tasks.is_empty() {
+            interruptible_sleep(Duration::from_millis(poll_interval_ms), stop).await;
+            continue;
+        }
 
-                next_subagent_cache.remove(&task.id);
+        let repo_paths: HashMap<Uuid, String> = match db.list_repos_async().await {
+            Ok(repos) => repos.into_iter().map(|repo| (repo.id, repo.path)).collect(),
+            Err(err) => {
+                tracing::warn!(error = %err, "failed to list repos for status poller");
+                interruptible_sleep(Duration::from_millis(poll_interval_ms), stop).await;
+                continue;
+            }
+        };
+        let fetched_at = SystemTime::now();
+        debug!(
+            poll_interval_ms,
+            task_count = tasks.len(),
+            "status/todo poll cycle started"
+        );
 
-                let repo_available = repo_paths
-                    .get(&task.repo_id)
-                    .map(|path| Path::new(path).exists())
-                    .unwrap_or(false);
+        for task in &tasks {
+            if stop.load(Ordering::Relaxed) {
+                break;
+            }
 
-                let mut todo_session_id = task.opencode_session_id.clone();
+            let mut subagent_summaries = Vec::new();
+
+            // Backends without sessions have nothing on the server to sync;
+            // their own provider decides the status.
+            if task_backend_kind(task) != AgentBackendKind::OpenCode {
+                let backend = backend_for_task(task);
+                let idle = SessionStatus {
+                    state: SessionState::Idle,
+                    source: SessionStatusSource::None,
+                    fetched_at,
+                    error: None,
+                };
+                let status = match backend.status_key(task) {
+                    // Providers such as the shell backend's shell out to `ps`.
+                    Some(key) => tokio::task::spawn_blocking(move || {
+                        backend.status_provider().get_status(&key)
+                    })
+                    .await
+                    .unwrap_or(idle),
+                    None => idle,
+                };
+                set_cached(&caches.session_subagent_cache, task.id, None);
+                set_cached(&caches.session_todo_cache, task.id, None);
+                set_cached(&caches.session_message_cache, task.id, None);
+                set_cached(&caches.session_permission_cache, task.id, None);
+                apply_provider_status(db, task, status, notification_config).await;
+                continue;
+            }
 
-                if !repo_available {
-                    if task.tmux_status != Status::Idle.as_str() {
-                        let _ = db
-                            .update_task_status_async(task.id, Status::Idle.as_str())
-                            .await;
-                        if should_mark_needs_inspection(
-                            task.tmux_status.as_str(),
-                            Status::Idle.as_str(),
-                        ) {
-                            let _ = db.update_task_needs_inspection_async(task.id, true).await;
-                        }
+            let backend: Arc<dyn AgentBackend> = Arc::from(backend_for_task(task));
+            let repo_available = repo_paths
+                .get(&task.repo_id)
+                .map(|path| Path::new(path).exists())
+                .unwrap_or(false);
+
+            let mut todo_session_id = task.opencode_session_id.clone();
+
+            if !repo_available {
+                set_cached(&caches.session_permission_cache, task.id, None);
+                if task.tmux_status != Status::Idle.as_str() {
+                    let _ = db
+                        .update_task_status_async(task.id, Status::Idle.as_str())
+                        .await;
+                    if should_mark_needs_inspection(
+                        task.tmux_status.as_str(),
+                        Status::Idle.as_str(),
+                    ) {
+                        let _ = db.update_task_needs_inspection_async(task.id, true).await;
                     }
-                    debug!(
-                        task_id = %task.id,
-                        session_id = ?todo_session_id,
-                        "repository unavailable; still attempting todo fetch"
-                    );
                 }
+                debug!(
+                    task_id = %task.id,
+                    session_id = ?todo_session_id,
+                    "repository unavailable; still attempting todo fetch"
+                );
+            }
 
-                if repo_available {
-                    if let Some(worktree_path) = task.worktree_path.as_deref() {
-                        debug!("Fetching status for task {} at {}", task.id, worktree_path);
-                        let mut bound_session_id = task.opencode_session_id.clone();
-                        let task_session_records = match server_provider
-                            .list_all_session_records(Some(worktree_path))
-                            .await
-                        {
-                            Ok(records) => {
-                                debug!(
-                                    task_id = %task.id,
-                                    worktree_path,
-                                    session_records = ?records,
-                                    "fetched session records for task directory"
-                                );
-                                Some(records)
-                            }
-                            Err(err) => {
-                                tracing::error!(
-                                    task_id = %task.id,
-                                    worktree_path,
-                                    "failed to fetch session records for task directory: {:?}",
-                                    err
-                                );
-                                None
-                            }
-                        };
-                        let complete_session_parent_map =
-                            task_session_records.as_ref().map(|records| {
-                                records
-                                    .iter()
-                                    .map(|record| {
-                                        (
-                                            record.session_id.clone(),
-                                            record.parent_session_id.clone(),
-                                        )
-                                    })
-                                    .collect::<HashMap<_, _>>()
-                            });
-                        if let Some(records) = task_session_records.as_ref() {
-                            for record in records {
-                                if let Some(title) = record.title.as_ref() {
-                                    next_title_cache
-                                        .insert(record.session_id.clone(), title.clone());
-                                }
+            if repo_available {
+                if let Some(worktree_path) = task.worktree_path.as_deref() {
+                    debug!("Fetching status for task {} at {}", task.id, worktree_path);
+                    let mut bound_session_id = task.opencode_session_id.clone();
+                    let task_session_records = match server_provider
+                        .list_all_session_records(Some(worktree_path))
+                        .await
+                    {
+                        Ok(records) => {
+                            debug!(
+                                task_id = %task.id,
+                                worktree_path,
+                                session_records = ?records,
+                                "fetched session records for task directory"
+                            );
+                            Some(records)
+                        }
+                        Err(err) => {
+                            tracing::error!(
+                                task_id = %task.id,
+                                worktree_path,
+                                "failed to fetch session records for task directory: {:?}",
+                                err
+                            );
+                            None
+                        }
+                    };
+                    let complete_session_parent_map =
+                        task_session_records.as_ref().map(|records| {
+                            records
+                                .iter()
+                                .map(|record| {
+                                    (record.session_id.clone(), record.parent_session_id.clone())
+                                })
+                                .collect::<HashMap<_, _>>()
+                        });
+                    if let Some(records) = task_session_records.as_ref()
+                        && let Ok(mut cache) = caches.session_title_cache.lock()
+                    {
+                        for record in records {
+                            if let Some(title) = record.title.as_ref() {
+                                cache.insert(record.session_id.clone(), title.clone());
                             }
                         }
+                    }
 
-                        match server_provider
-                            .fetch_status_matches(fetched_at, Some(worktree_path))
-                            .await
-                        {
-                            Ok(statuses) => {
-                                let selected_status_match = select_status_match(
-                                    statuses.clone(),
-                                    complete_session_parent_map.as_ref(),
+                    match server_provider
+                        .list_pending_permissions(Some(worktree_path))
+                        .await
+                    {
+                        Ok(Some(permissions)) if permissions.is_empty() => {
+                            set_cached(&caches.session_permission_cache, task.id, None);
+                        }
+                        Ok(Some(permissions)) => {
+                            set_cached(
+                                &caches.session_permission_cache,
+                                task.id,
+                                Some(permissions),
+                            );
+                        }
+                        Ok(None) => {}
+                        Err(err) => {
+                            debug!(
+                                task_id = %task.id,
+                                error = ?err,
+                                "permission fetch failed; keeping previous pending requests"
+                            );
+                        }
+                    }
+                    let awaiting_approval = caches
+                        .session_permission_cache
+                        .lock()
+                        .is_ok_and(|cache| cache.contains_key(&task.id));
+
+                    match server_provider
+                        .fetch_status_matches(fetched_at, Some(worktree_path))
+                        .await
+                    {
+                        Ok(statuses) => {
+                            let selected_status_match = select_status_match(
+                                statuses.clone(),
+                                complete_session_parent_map.as_ref(),
+                            );
+                            let root_session_id = selected_status_match
+                                .as_ref()
+                                .map(|status_match| status_match.session_id.clone());
+
+                            debug!("Got {} statuses for task {}", statuses.len(), task.id);
+                            if let Some(status_match) = selected_status_match {
+                                debug!(
+                                    "Task {} matched to session {} with status {:?}",
+                                    task.id, status_match.session_id, status_match.status.state
+                                );
+
+                                let next_state = next_session_state(
+                                    SessionState::from_raw_status(&task.tmux_status),
+                                    status_match.status.state,
+                                    awaiting_approval,
+                                    stream.connected.load(Ordering::Relaxed),
                                 );
-                                let root_session_id = selected_status_match
-                                    .as_ref()
-                                    .map(|status_match| status_match.session_id.clone());
-
-                                debug!("Got {} statuses for task {}", statuses.len(), task.id);
-                                if let Some(status_match) = selected_status_match {
-                                    debug!(
-                                        "Task {} matched to session {} with status {:?}",
-                                        task.id, status_match.session_id, status_match.status.state
-                                    );
-
-                                    if task.tmux_status != status_match.status.state.as_str() {
-                                        let next_status = status_match.status.state.as_str();
-                                        let _ =
-                                            db.update_task_status_async(task.id, next_status).await;
-                                        if should_mark_needs_inspection(
+                                if !next_state.is_active() {
+                                    set_cached(&caches.session_permission_cache, task.id, None);
+                                }
+                                if task.tmux_status != next_state.as_str() {
+                                    let next_status = next_state.as_str();
+                                    let _ = db.update_task_status_async(task.id, next_status).await;
+                                    if should_mark_needs_inspection(
+                                        task.tmux_status.as_str(),
+                                        next_status,
+                                    ) {
+                                        let _ = db
+                                            .update_task_needs_inspection_async(task.id, true)
+                                            .await;
+                                        if should_notify_root_session_completion(
                                             task.tmux_status.as_str(),
                                             next_status,
+                                            &status_match,
                                         ) {
-                                            let _ = db
-                                                .update_task_needs_inspection_async(task.id, true)
-                                                .await;
-                                            if should_notify_root_session_completion(
-                                                task.tmux_status.as_str(),
-                                                next_status,
-                                                &status_match,
-                                            ) {
-                                                notify_task_completion(task, notification_config);
-                                            }
+                                            notify_task_completion(task, notification_config);
                                         }
                                     }
+                                    notify_task_attention(task, next_state, notification_config);
+                                }
 
-                                    if task.status_source != SessionStatusSource::Server.as_str()
-                                        || task.status_error.is_some()
-                                    {
-                                        let _ = db
-                                            .update_task_status_metadata_async(
-                                                task.id,
-                                                SessionStatusSource::Server.as_str(),
-                                                Some(to_iso8601(fetched_at)),
-                                                None,
-                                            )
-                                            .await;
-                                    }
+                                let next_error = server_status_error(
+                                    task,
+                                    next_state,
+                                    status_match.status.error.as_ref(),
+                                );
+                                if task.status_source != SessionStatusSource::Server.as_str()
+                                    || task.status_error != next_error
+                                {
+                                    let _ = db
+                                        .update_task_status_metadata_async(
+                                            task.id,
+                                            SessionStatusSource::Server.as_str(),
+                                            Some(to_iso8601(fetched_at)),
+                                            next_error,
+                                        )
+                                        .await;
+                                }
 
-                                    if bound_session_id.as_deref()
-                                        != Some(status_match.session_id.as_str())
-                                    {
-                                        let _ = db
-                                            .update_task_session_binding_async(
-                                                task.id,
-                                                Some(status_match.session_id.clone()),
-                                            )
-                                            .await;
-                                    }
-                                    bound_session_id = Some(status_match.session_id);
-                                    todo_session_id = bound_session_id.clone();
-                                } else {
-                                    debug!(
-                                        "No active session for task {} - setting status to idle",
-                                        task.id
-                                    );
-                                    let missing_id = task
-                                        .tmux_session_name
-                                        .clone()
-                                        .unwrap_or_else(|| task.id.to_string());
-                                    let missing_error = format!("SESSION_NOT_FOUND:{missing_id}");
-
-                                    if task.tmux_status != Status::Idle.as_str() {
+                                if bound_session_id.as_deref()
+                                    != Some(status_match.session_id.as_str())
+                                {
+                                    let _ = db
+                                        .update_task_session_binding_async(
+                                            task.id,
+                                            Some(status_match.session_id.clone()),
+                                        )
+                                        .await;
+                                }
+                                bound_session_id = Some(status_match.session_id);
+                                todo_session_id = bound_session_id.clone();
+                            } else if task.tmux_status == SessionState::Error.as_str() {
+                                // A failed session goes idle and drops out of
+                                // `/session/status`; keep the failure on the
+                                // board until the next run starts.
+                                set_cached(&caches.session_permission_cache, task.id, None);
+                            } else {
+                                debug!(
+                                    "No active session for task {} - setting status to idle",
+                                    task.id
+                                );
+                                let missing_id = task
+                                    .tmux_session_name
+                                    .clone()
+                                    .unwrap_or_else(|| task.id.to_string());
+                                let missing_error = format!("SESSION_NOT_FOUND:{missing_id}");
+                                set_cached(&caches.session_permission_cache, task.id, None);
+
+                                if task.tmux_status != Status::Idle.as_str() {
+                                    let _ = db
+                                        .update_task_status_async(task.id, Status::Idle.as_str())
+                                        .await;
+                                    if should_mark_needs_inspection(
+                                        task.tmux_status.as_str(),
+                                        Status::Idle.as_str(),
+                                    ) {
                                         let _ = db
-                                            .update_task_status_async(
-                                                task.id,
-                                                Status::Idle.as_str(),
-                                            )
+                                            .update_task_needs_inspection_async(task.id, true)
                                             .await;
-                                        if should_mark_needs_inspection(
+                                        if should_notify_root_completion_without_status_match(
                                             task.tmux_status.as_str(),
                                             Status::Idle.as_str(),
+                                            task.opencode_session_id.as_deref(),
+                                            complete_session_parent_map.as_ref(),
                                         ) {
-                                            let _ = db
-                                                .update_task_needs_inspection_async(task.id, true)
-                                                .await;
-                                            if should_notify_root_completion_without_status_match(
-                                                task.tmux_status.as_str(),
-                                                Status::Idle.as_str(),
-                                                task.opencode_session_id.as_deref(),
-                                                complete_session_parent_map.as_ref(),
-                                            ) {
-                                                notify_task_completion(task, notification_config);
-                                            }
+                                            notify_task_completion(task, notification_config);
                                         }
                                     }
+                                }
 
-                                    if task.status_source != SessionStatusSource::None.as_str()
-                                        || task.status_error.as_deref()
-                                            != Some(missing_error.as_str())
-                                    {
-                                        let _ = db
-                                            .update_task_status_metadata_async(
-                                                task.id,
-                                                SessionStatusSource::None.as_str(),
-                                                Some(to_iso8601(fetched_at)),
-                                                Some(missing_error),
-                                            )
-                                            .await;
-                                    }
+                                if task.status_source != SessionStatusSource::None.as_str()
+                                    || task.status_error.as_deref() != Some(missing_error.as_str())
+                                {
+                                    let _ = db
+                                        .update_task_status_metadata_async(
+                                            task.id,
+                                            SessionStatusSource::None.as_str(),
+                                            Some(to_iso8601(fetched_at)),
+                                            Some(missing_error),
+                                        )
+                                        .await;
                                 }
+                            }
 
-                                if let Some(root_id) = root_session_id.as_deref() {
-                                    let subagent_session_ids = live_subagent_session_ids(
-                                        &statuses,
-                                        root_id,
-                                        complete_session_parent_map.as_ref(),
-                                    );
-                                    let summaries = build_subagent_todo_summaries(
-                                        &server_provider,
+                            if let Some(root_id) = root_session_id.as_deref() {
+                                let subagent_session_ids = live_subagent_session_ids(
+                                    &statuses,
+                                    root_id,
+                                    complete_session_parent_map.as_ref(),
+                                );
+                                let subagent_titles = caches
+                                    .session_title_cache
+                                    .lock()
+                                    .map(|cache| {
+                                        subagent_session_ids
+                                            .iter()
+                                            .filter_map(|id| {
+                                                Some((id.clone(), cache.get(id)?.clone()))
+                                            })
+                                            .collect::<HashMap<_, _>>()
+                                    })
+                                    .unwrap_or_default();
+                                subagent_summaries = build_subagent_todo_summaries(
+                                    &backend,
+                                    task.id,
+                                    &subagent_session_ids,
+                                    &subagent_titles,
+                                )
+                                .await;
+
+                                for session_id in session_usage.sessions_to_refresh(
+                                    root_id,
+                                    &subagent_session_ids,
+                                    complete_session_parent_map.as_ref(),
+                                ) {
+                                    if let Some(log) = fetch_task_messages(
+                                        &backend,
                                         task.id,
-                                        &subagent_session_ids,
-                                        &next_title_cache,
+                                        Some(session_id.as_str()),
                                     )
-                                    .await;
-                                    if !summaries.is_empty() {
-                                        next_subagent_cache.insert(task.id, summaries);
+                                    .await
+                                    {
+                                        session_usage
+                                            .record(db, task.id, &session_id, log.usage)
+                                            .await;
                                     }
                                 }
                             }
-                            Err(err) => {
+                        }
+                        Err(err) => {
+                            set_cached(&caches.session_permission_cache, task.id, None);
+                            // Without the server, the pane is the best view of
+                            // what the agent is doing.
+                            let pane_status = match task.tmux_session_name.clone() {
+                                Some(session_name) => {
+                                    let pane_provider = Arc::clone(&pane_provider);
+                                    tokio::task::spawn_blocking(move || {
+                                        pane_provider.get_status(&session_name)
+                                    })
+                                    .await
+                                    .ok()
+                                }
+                                None => None,
+                            }
+                            .filter(|status| status.error.is_none());
+                            if let Some(mut status) = pane_status {
+                                debug!(
+                                    task_id = %task.id,
+                                    error = ?err,
+                                    state = ?status.state,
+                                    "status fetch failed; using pane status"
+                                );
+                                // The pane only stands in for the server; keep
+                                // the reason it could not answer.
+                                status.error = Some(err);
+                                apply_provider_status(db, task, status, notification_config).await;
+                            } else {
                                 tracing::warn!(
                                     "Failed to fetch status for task {} - marking status idle: {:?}",
                                     task.id,
@@ -355,106 +511,475 @@ pub fn spawn_status_poller(
                                         )
                                         .await;
                                 }
-                                debug!(
-                                    task_id = %task.id,
-                                    session_id = ?todo_session_id,
-                                    "status fetch failed; still attempting todo fetch"
-                                );
                             }
+                            debug!(
+                                task_id = %task.id,
+                                session_id = ?todo_session_id,
+                                "status fetch failed; still attempting todo fetch"
+                            );
                         }
-                    } else {
-                        debug!(
-                            task_id = %task.id,
-                            session_id = ?todo_session_id,
-                            "task has no worktree path; still attempting todo fetch"
-                        );
                     }
+                } else {
+                    debug!(
+                        task_id = %task.id,
+                        session_id = ?todo_session_id,
+                        "task has no worktree path; still attempting todo fetch"
+                    );
                 }
+            }
 
-                if let Some(session_id) = todo_session_id.as_deref() {
-                    if let Some(todos) =
-                        fetch_task_todos(&server_provider, task.id, Some(session_id)).await
-                    {
-                        debug!(
-                            task_id = %task.id,
-                            session_id,
-                            todo_count = todos.len(),
-                            poll_interval_ms,
-                            "updated task todos from OpenCode server"
-                        );
-                        next_todo_cache.insert(task.id, todos);
-                    } else {
-                        debug!(
-                            task_id = %task.id,
-                            session_id,
-                            "todo fetch failed; preserving previous cached todos"
-                        );
-                    }
+            set_cached(
+                &caches.session_subagent_cache,
+                task.id,
+                (!subagent_summaries.is_empty()).then_some(subagent_summaries),
+            );
 
-                    if let Some(messages) =
-                        fetch_task_messages(&server_provider, task.id, Some(session_id)).await
-                    {
-                        debug!(
-                            task_id = %task.id,
-                            session_id,
-                            message_count = messages.len(),
-                            poll_interval_ms,
-                            "updated task messages from OpenCode server"
-                        );
-                        next_message_cache.insert(task.id, messages);
-                    } else {
-                        debug!(
-                            task_id = %task.id,
-                            session_id,
-                            "message fetch failed; preserving previous cached messages"
-                        );
-                    }
+            if let Some(session_id) = todo_session_id.as_deref() {
+                if let Some(todos) = fetch_task_todos(&backend, task.id, Some(session_id)).await {
+                    debug!(
+                        task_id = %task.id,
+                        session_id,
+                        todo_count = todos.len(),
+                        poll_interval_ms,
+                        "updated task todos from OpenCode server"
+                    );
+                    set_cached(&caches.session_todo_cache, task.id, Some(todos));
                 } else {
-                    next_todo_cache.remove(&task.id);
-                    next_message_cache.remove(&task.id);
                     debug!(
                         task_id = %task.id,
-                        "no bound session; removed cached todos and messages"
+                        session_id,
+                        "todo fetch failed; preserving previous cached todos"
                     );
                 }
 
-                if stop.load(Ordering::Relaxed) {
-                    break;
+                if let Some(log) = fetch_task_messages(&backend, task.id, Some(session_id)).await {
+                    debug!(
+                        task_id = %task.id,
+                        session_id,
+                        message_count = log.messages.len(),
+                        poll_interval_ms,
+                        "updated task messages from OpenCode server"
+                    );
+                    set_cached(&caches.session_message_cache, task.id, Some(log.messages));
+                    session_usage
+                        .record(db, task.id, session_id, log.usage)
+                        .await;
+                } else {
+                    debug!(
+                        task_id = %task.id,
+                        session_id,
+                        "message fetch failed; preserving previous cached messages"
+                    );
                 }
+            } else {
+                set_cached(&caches.session_todo_cache, task.id, None);
+                set_cached(&caches.session_message_cache, task.id, None);
+                debug!(
+                    task_id = %task.id,
+                    "no bound session; removed cached todos and messages"
+                );
             }
 
             if stop.load(Ordering::Relaxed) {
                 break;
             }
+        }
 
-            if let Ok(mut cache) = caches.session_todo_cache.lock() {
-                cache.clear();
-                cache.extend(next_todo_cache);
-            }
+        if stop.load(Ordering::Relaxed) {
+            break;
+        }
+
+        // The cycle may have bound new sessions.
+        if let Ok(tasks) = db.list_tasks_async().await {
+            stream.index_sessions(&tasks);
+        }
+        session_usage.finish_cycle();
+        session_usage.publish(caches);
+
+        debug!(
+            poll_interval_ms,
+            task_count = tasks.len(),
+            "status/todo poll cycle complete; sleeping until next cycle"
+        );
+        let interval = next_poll_interval(
+            Duration::from_millis(poll_interval_ms),
+            stream.connected.load(Ordering::Relaxed),
+        );
+        wait_for_next_cycle(interval, stop, &stream.backfill).await;
+    }
+}
+
+/// Slowest interval of the full sync while events keep the caches current.
+const EVENT_BACKFILL_INTERVAL: Duration = Duration::from_secs(30);
+const EVENT_RECONNECT_DELAY: Duration = Duration::from_secs(2);
+/// Streaming replies emit an event per token, so message lists are refetched
+/// at most this often per session.
+const MESSAGE_REFRESH_INTERVAL: Duration = Duration::from_millis(500);
+/// Subagents are never bound to a task, so events for sessions the board
+/// does not know trigger a full sync at most this often.
+const UNBOUND_SESSION_RETRY_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Default)]
+struct EventStreamState {
+    connected: AtomicBool,
+    /// Wakes the poll loop for an immediate full sync.
+    backfill: Notify,
+    /// Task of each bound session, rebuilt by every full sync so events
+    /// (one per streamed token) find their task without a database query.
+    session_tasks: Mutex<HashMap<String, Uuid>>,
+    board_worktrees: Mutex<HashSet<PathBuf>>,
+    unbound_retried_at: Mutex<Option<Instant>>,
+}
+
+impl EventStreamState {
+    fn index_sessions(&self, tasks: &[Task]) {
+        let index = tasks
+            .iter()
+            .filter_map(|task| Some((task.opencode_session_id.clone()?, task.id)))
+            .collect();
+        if let Ok(mut session_tasks) = self.session_tasks.lock() {
+            *session_tasks = index;
+        }
+        let worktrees = tasks
+            .iter()
+            .filter_map(|task| task.worktree_path.as_deref().map(PathBuf::from))
+            .collect();
+        if let Ok(mut board_worktrees) = self.board_worktrees.lock() {
+            *board_worktrees = worktrees;
+        }
+    }
+
+    fn is_board_worktree(&self, directory: &str) -> bool {
+        self.board_worktrees
+            .lock()
+            .is_ok_and(|worktrees| worktrees.contains(Path::new(directory)))
+    }
+
+    fn unbound_retry_due(&self) -> bool {
+        let Ok(mut retried_at) = self.unbound_retried_at.lock() else {
+            return false;
+        };
+        if retried_at.is_some_and(|at| at.elapsed() < UNBOUND_SESSION_RETRY_INTERVAL) {
+            return false;
+        }
+        *retried_at = Some(Instant::now());
+        true
+    }
 
-            if let Ok(mut cache) = caches.session_subagent_cache.lock() {
-                cache.clear();
-                cache.extend(next_subagent_cache);
+    fn task_for_session(&self, session_id: &str) -> Option<Uuid> {
+        self.session_tasks.lock().ok()?.get(session_id).copied()
+    }
+}
+
+fn next_poll_interval(poll_interval: Duration, stream_connected: bool) -> Duration {
+    if stream_connected {
+        poll_interval.max(EVENT_BACKFILL_INTERVAL)
+    } else {
+        poll_interval
+    }
+}
+
+async fn wait_for_next_cycle(duration: Duration, stop: &AtomicBool, backfill: &Notify) {
+    tokio::select! {
+        _ = interruptible_sleep(duration, stop) => {}
+        _ = backfill.notified() => {}
+    }
+}
+
+/// Applies OpenCode server events to tasks and caches as they arrive, and
+/// reconnects whenever the stream drops.
+async fn run_event_subscriber(
+    db: &Database,
+    server_provider: &ServerStatusProvider,
+    stop: &AtomicBool,
+    caches: &StatusPollerCaches,
+    notification_config: TaskCompletionNotificationConfig,
+    stream: &EventStreamState,
+) {
+    // The event stream only reports OpenCode sessions.
+    let backend: Arc<dyn AgentBackend> = Arc::from(backend_for_kind(AgentBackendKind::OpenCode));
+    let mut reconnecting = false;
+    while !stop.load(Ordering::Relaxed) {
+        match server_provider.open_event_stream().await {
+            Ok(mut events) => {
+                debug!("subscribed to OpenCode event stream");
+                stream.connected.store(true, Ordering::Relaxed);
+                if reconnecting {
+                    // Catch up on whatever changed while the stream was down.
+                    stream.backfill.notify_one();
+                }
+                reconnecting = true;
+
+                let mut stale_message_sessions = HashSet::new();
+                let mut refresh = tokio::time::interval(MESSAGE_REFRESH_INTERVAL);
+                while !stop.load(Ordering::Relaxed) {
+                    tokio::select! {
+                        next = events.next_event() => match next {
+                            Ok(Some(scoped)) => {
+                                apply_server_event(
+                                    db,
+                                    caches,
+                                    notification_config,
+                                    stream,
+                                    &mut stale_message_sessions,
+                                    scoped.directory.as_deref(),
+                                    scoped.event,
+                                )
+                                .await;
+                            }
+                            Ok(None) => {
+                                debug!("OpenCode event stream closed");
+                                break;
+                            }
+                            Err(err) => {
+                                debug!(error = ?err, "OpenCode event stream failed");
+                                break;
+                            }
+                        },
+                        _ = refresh.tick() => {
+                            refresh_session_messages(
+                                &backend,
+                                caches,
+                                stream,
+                                &mut stale_message_sessions,
+                            )
+                            .await;
+                        }
+                    }
+                }
+
+                stream.connected.store(false, Ordering::Relaxed);
+                stream.backfill.notify_one();
             }
+            Err(err) => {
+                debug!(error = ?err, "OpenCode event stream unavailable; polling instead");
+            }
+        }
+        interruptible_sleep(EVENT_RECONNECT_DELAY, stop).await;
+    }
+}
 
-            if let Ok(mut cache) = caches.session_title_cache.lock() {
-                cache.clear();
-                cache.extend(next_title_cache);
+async fn apply_server_event(
+    db: &Database,
+    caches: &StatusPollerCaches,
+    notification_config: TaskCompletionNotificationConfig,
+    stream: &EventStreamState,
+    stale_message_sessions: &mut HashSet<String>,
+    directory: Option<&str>,
+    event: ServerEvent,
+) {
+    if let ServerEvent::SessionUpdated {
+        session_id,
+        title: Some(title),
+    } = &event
+    {
+        if let Ok(mut cache) = caches.session_title_cache.lock() {
+            cache.insert(session_id.clone(), title.clone());
+        }
+        return;
+    }
+
+    let Some(session_id) = event.session_id().map(str::to_string) else {
+        return;
+    };
+    let mut task_id = stream.task_for_session(&session_id);
+    if task_id.is_none()
+        && matches!(
+            event,
+            ServerEvent::SessionStatus { .. } | ServerEvent::PermissionAsked(_)
+        )
+        && stream.unbound_retry_due()
+    {
+        // Tasks created since the last sync already know their session.
+        if let Ok(tasks) = db.list_tasks_async().await {
+            stream.index_sessions(&tasks);
+        }
+        task_id = stream.task_for_session(&session_id);
+        // Other new sessions and subagents in a board worktree are bound and
+        // summarized by a full sync. `/event` does not say where a session
+        // runs, so those always qualify.
+        if task_id.is_none()
+            && directory.is_none_or(|directory| stream.is_board_worktree(directory))
+        {
+            stream.backfill.notify_one();
+        }
+    }
+    let Some(task_id) = task_id else {
+        return;
+    };
+
+    let event = match event {
+        ServerEvent::TodoUpdated { todos, .. } => {
+            set_cached(&caches.session_todo_cache, task_id, Some(todos));
+            return;
+        }
+        ServerEvent::MessageUpdated { session_id } => {
+            stale_message_sessions.insert(session_id);
+            return;
+        }
+        event => event,
+    };
+    // Only status changes need the task itself.
+    let task = match db.get_task_async(task_id).await {
+        Ok(task) if task.opencode_session_id.as_deref() == Some(session_id.as_str()) => task,
+        Ok(_) => return,
+        Err(err) => {
+            tracing::warn!(error = %err, "failed to load task for server event");
+            return;
+        }
+    };
+
+    match event {
+        ServerEvent::SessionStatus { state, detail, .. } => {
+            let awaiting_approval = match caches.session_permission_cache.lock() {
+                Ok(mut cache) if !state.is_active() => {
+                    cache.remove(&task.id);
+                    false
+                }
+                Ok(cache) => cache.contains_key(&task.id),
+                Err(_) => false,
+            };
+            let next_state = next_session_state(
+                SessionState::from_raw_status(&task.tmux_status),
+                state,
+                awaiting_approval,
+                false,
+            );
+            let reported_error = status_detail_error(state, detail);
+            apply_session_state(
+                db,
+                &task,
+                next_state,
+                reported_error.as_ref(),
+                notification_config,
+            )
+            .await;
+        }
+        ServerEvent::PermissionAsked(permission) => {
+            if let Ok(mut cache) = caches.session_permission_cache.lock() {
+                let pending = cache.entry(task.id).or_default();
+                pending.retain(|existing| existing.id != permission.id);
+                pending.push(permission);
             }
+            apply_session_state(
+                db,
+                &task,
+                SessionState::AwaitingApproval,
+                None,
+                notification_config,
+            )
+            .await;
+        }
+        ServerEvent::PermissionReplied { permission_id, .. } => {
+            let still_waiting = match caches.session_permission_cache.lock() {
+                Ok(mut cache) => {
+                    if let Some(pending) = cache.get_mut(&task.id) {
+                        pending.retain(|existing| existing.id != permission_id);
+                        if pending.is_empty() {
+                            cache.remove(&task.id);
+                        }
+                    }
+                    cache.contains_key(&task.id)
+                }
+                Err(_) => false,
+            };
+            if !still_waiting && task.tmux_status == SessionState::AwaitingApproval.as_str() {
+                // The agent picks the tool call back up (or reacts to the
+                // denial) as soon as the reply lands.
+                apply_session_state(db, &task, SessionState::Running, None, notification_config)
+                    .await;
+            }
+        }
+        ServerEvent::TodoUpdated { .. }
+        | ServerEvent::MessageUpdated { .. }
+        | ServerEvent::Connected
+        | ServerEvent::SessionUpdated { .. }
+        | ServerEvent::Other => {}
+    }
+}
 
-            if let Ok(mut cache) = caches.session_message_cache.lock() {
-                cache.clear();
-                cache.extend(next_message_cache);
+async fn apply_session_state(
+    db: &Database,
+    task: &Task,
+    state: SessionState,
+    reported_error: Option<&SessionStatusError>,
+    notification_config: TaskCompletionNotificationConfig,
+) {
+    let next_status = state.as_str();
+    if task.tmux_status != next_status {
+        let _ = db.update_task_status_async(task.id, next_status).await;
+        // Bound sessions are always root sessions, so every running -> idle
+        // transition here is a completed task.
+        if should_mark_needs_inspection(task.tmux_status.as_str(), next_status) {
+            let _ = db.update_task_needs_inspection_async(task.id, true).await;
+            if state == SessionState::Idle {
+                notify_task_completion(task, notification_config);
             }
+        }
+        notify_task_attention(task, state, notification_config);
+    }
 
-            debug!(
-                poll_interval_ms,
-                task_count = tasks.len(),
-                "status/todo poll cycle complete; sleeping until next cycle"
-            );
-            interruptible_sleep(Duration::from_millis(poll_interval_ms), &stop).await;
+    let next_error = server_status_error(task, state, reported_error);
+    if task.status_source != SessionStatusSource::Server.as_str() || task.status_error != next_error
+    {
+        let _ = db
+            .update_task_status_metadata_async(
+                task.id,
+                SessionStatusSource::Server.as_str(),
+                Some(to_iso8601(SystemTime::now())),
+                next_error,
+            )
+            .await;
+    }
+}
+
+/// Applies a status reported by a `StatusProvider` other than the server's
+/// own poll: a non-OpenCode backend or the pane fallback.
+async fn apply_provider_status(
+    db: &Database,
+    task: &Task,
+    status: SessionStatus,
+    notification_config: TaskCompletionNotificationConfig,
+) {
+    let next_status = status.state.as_str();
+    if task.tmux_status != next_status {
+        let _ = db.update_task_status_async(task.id, next_status).await;
+        if should_mark_needs_inspection(task.tmux_status.as_str(), next_status) {
+            let _ = db.update_task_needs_inspection_async(task.id, true).await;
+            notify_task_completion(task, notification_config);
         }
-    })
+        notify_task_attention(task, status.state, notification_config);
+    }
+
+    let next_error = status
+        .error
+        .map(|err| format!("{}:{}", err.code, err.message));
+    if task.status_source != status.source.as_str() || task.status_error != next_error {
+        let _ = db
+            .update_task_status_metadata_async(
+                task.id,
+                status.source.as_str(),
+                Some(to_iso8601(status.fetched_at)),
+                next_error,
+            )
+            .await;
+    }
+}
+
+async fn refresh_session_messages(
+    backend: &Arc<dyn AgentBackend>,
+    caches: &StatusPollerCaches,
+    stream: &EventStreamState,
+    stale_message_sessions: &mut HashSet<String>,
+) {
+    for session_id in stale_message_sessions.drain() {
+        let Some(task_id) = stream.task_for_session(&session_id) else {
+            continue;
+        };
+        if let Some(log) = fetch_task_messages(backend, task_id, Some(session_id.as_str())).await {
+            set_cached(&caches.session_message_cache, task_id, Some(log.messages));
+        }
+    }
 }
 
 fn live_subagent_session_ids(
@@ -522,8 +1047,120 @@ fn is_descendant_of_session(
     }
 }
 
+/// Per-session token usage the poller has stored, so unchanged sessions are
+/// neither rewritten nor refetched.
+#[derive(Default)]
+struct SessionUsageLedger {
+    sessions: HashMap<String, (Uuid, Vec<ModelUsage>)>,
+    live_subagents: HashSet<String>,
+    previously_live_subagents: HashSet<String>,
+}
+
+impl SessionUsageLedger {
+    async fn load(db: &Database) -> Self {
+        let mut ledger = Self::default();
+        match db.list_session_usage_async().await {
+            Ok(records) => {
+                for record in records {
+                    ledger
+                        .sessions
+                        .entry(record.session_id)
+                        .or_insert_with(|| (record.task_id, Vec::new()))
+                        .1
+                        .push(ModelUsage {
+                            model: record.model,
+                            usage: record.usage,
+                        });
+                }
+            }
+            Err(err) => tracing::warn!(error = %err, "failed to load stored session usage"),
+        }
+        ledger
+    }
+
+    /// Subagent sessions under `root_session_id` whose usage may still grow:
+    /// running ones, ones that stopped since the last cycle, and ones never
+    /// fetched. Finished subagents keep their stored totals.
+    fn sessions_to_refresh(
+        &mut self,
+        root_session_id: &str,
+        live_subagent_ids: &[String],
+        complete_parent_map: Option<&HashMap<String, Option<String>>>,
+    ) -> Vec<String> {
+        self.live_subagents
+            .extend(live_subagent_ids.iter().cloned());
+        let mut ids = live_subagent_ids.to_vec();
+        if let Some(parent_map) = complete_parent_map {
+            ids.extend(
+                parent_map
+                    .keys()
+                    .filter(|session_id| {
+                        is_descendant_of_session(session_id, root_session_id, parent_map)
+                    })
+                    .filter(|session_id| {
+                        self.previously_live_subagents.contains(*session_id)
+                            || !self.sessions.contains_key(*session_id)
+                    })
+                    .cloned(),
+            );
+        }
+        ids.sort();
+        ids.dedup();
+        ids
+    }
+
+    async fn record(
+        &mut self,
+        db: &Database,
+        task_id: Uuid,
+        session_id: &str,
+        usage: Vec<ModelUsage>,
+    ) {
+        let unchanged = match self.sessions.get(session_id) {
+            Some((known_task_id, known_usage)) => {
+                *known_task_id == task_id && *known_usage == usage
+            }
+            None => false,
+        };
+        if unchanged {
+            return;
+        }
+        if let Err(err) = db
+            .replace_session_usage_async(task_id, session_id.to_string(), usage.clone())
+            .await
+        {
+            tracing::warn!(task_id = %task_id, session_id, error = %err, "failed to store session usage");
+            return;
+        }
+        self.sessions
+            .insert(session_id.to_string(), (task_id, usage));
+    }
+
+    fn finish_cycle(&mut self) {
+        self.previously_live_subagents = std::mem::take(&mut self.live_subagents);
+    }
+
+    fn task_totals(&self) -> HashMap<Uuid, TokenUsage> {
+        let mut totals: HashMap<Uuid, TokenUsage> = HashMap::new();
+        for (task_id, models) in self.sessions.values() {
+            let total = totals.entry(*task_id).or_default();
+            for model in models {
+                total.add(&model.usage);
+            }
+        }
+        totals.retain(|_, total| !total.is_empty());
+        totals
+    }
+
+    fn publish(&self, caches: &StatusPollerCaches) {
+        if let Ok(mut cache) = caches.session_usage_cache.lock() {
+            *cache = self.task_totals();
+        }
+    }
+}
+
 async fn build_subagent_todo_summaries(
-    server_provider: &ServerStatusProvider,
+    backend: &Arc<dyn AgentBackend>,
     task_id: Uuid,
     session_ids: &[String],
     session_titles: &HashMap<String, String>,
@@ -534,7 +1171,7 @@ async fn build_subagent_todo_summaries(
             .get(session_id)
             .cloned()
             .unwrap_or_else(|| "Untitled subagent".to_string());
-        let todo_summary = fetch_task_todos(server_provider, task_id, Some(session_id.as_str()))
+        let todo_summary = fetch_task_todos(backend, task_id, Some(session_id.as_str()))
             .await
             .and_then(|todos| {
                 if todos.is_empty() {
@@ -557,7 +1194,7 @@ async fn build_subagent_todo_summaries(
 }
 
 async fn fetch_task_todos(
-    server_provider: &ServerStatusProvider,
+    backend: &Arc<dyn AgentBackend>,
     task_id: Uuid,
     session_id: Option<&str>,
 ) -> Option<Vec<SessionTodoItem>> {
@@ -569,25 +1206,28 @@ async fn fetch_task_todos(
         return None;
     };
 
-    match server_provider.fetch_session_todo(session_id).await {
-        Ok(todos) => Some(todos),
-        Err(err) => {
+    let fetch_backend = Arc::clone(backend);
+    let fetch_session = session_id.to_string();
+    match tokio::task::spawn_blocking(move || fetch_backend.fetch_todos(&fetch_session)).await {
+        Ok(Ok(todos)) => Some(todos),
+        Ok(Err(err)) => {
             tracing::warn!(
-                "Failed to fetch todo list for task {} session {}: {:?}",
+                "Failed to fetch todo list for task {} session {}: {:#}",
                 task_id,
                 session_id,
                 err
             );
             None
         }
+        Err(_) => None,
     }
 }
 
 async fn fetch_task_messages(
-    server_provider: &ServerStatusProvider,
+    backend: &Arc<dyn AgentBackend>,
     task_id: Uuid,
     session_id: Option<&str>,
-) -> Option<Vec<SessionMessageItem>> {
+) -> Option<SessionMessageLog> {
     let Some(session_id) = session_id else {
         debug!(
             "Skipping message sync for task {} because no OpenCode session is bound",
@@ -596,17 +1236,36 @@ async fn fetch_task_messages(
         return None;
     };
 
-    match server_provider.fetch_session_messages(session_id).await {
-        Ok(messages) => Some(messages),
-        Err(err) => {
+    let fetch_backend = Arc::clone(backend);
+    let fetch_session = session_id.to_string();
+    match tokio::task::spawn_blocking(move || fetch_backend.fetch_messages(&fetch_session)).await {
+        Ok(Ok(log)) => Some(log),
+        Ok(Err(err)) => {
             tracing::warn!(
-                "Failed to fetch message list for task {} session {}: {:?}",
+                "Failed to fetch message list for task {} session {}: {:#}",
                 task_id,
                 session_id,
                 err
             );
             None
         }
+        Err(_) => None,
+    }
+}
+
+/// Stores or drops one task's entry. The poll cycle writes each entry as soon
+/// as it is fetched, so it never overwrites what the event stream stored for
+/// other tasks in the meantime.
+fn set_cached<V>(cache: &Mutex<HashMap<Uuid, V>>, task_id: Uuid, value: Option<V>) {
+    if let Ok(mut cache) = cache.lock() {
+        match value {
+            Some(value) => {
+                cache.insert(task_id, value);
+            }
+            None => {
+                cache.remove(&task_id);
+            }
+        }
     }
 }
 
@@ -696,9 +1355,56 @@ fn find_eldest_ancestor_recursive(
     }
 }
 
+/// State to record for a task when the server reports `reported` for its
+/// session.
+///
+/// The server reports a session blocked on a permission request or a
+/// question as busy, so a pending request wins over a busy report. While
+/// the event stream is up it announces when a question is answered, so a
+/// full sync must not flip the task back to running before that. A failed
+/// session goes idle right after the error; the failure stays until the
+/// next run.
+fn next_session_state(
+    previous: SessionState,
+    reported: SessionState,
+    awaiting_approval: bool,
+    events_connected: bool,
+) -> SessionState {
+    match reported {
+        SessionState::Idle if previous == SessionState::Error => SessionState::Error,
+        SessionState::Idle | SessionState::Error => reported,
+        _ if awaiting_approval => SessionState::AwaitingApproval,
+        SessionState::Running if previous == SessionState::AwaitingInput && events_connected => {
+            SessionState::AwaitingInput
+        }
+        _ => reported,
+    }
+}
+
+/// `status_error` for a task the server just reported on: the server's
+/// explanation while the session is failing or retrying, otherwise nothing.
+fn server_status_error(
+    task: &Task,
+    next_state: SessionState,
+    reported_error: Option<&SessionStatusError>,
+) -> Option<String> {
+    match reported_error {
+        Some(err) => Some(format!("{}:{}", err.code, err.message)),
+        None if next_state == SessionState::Error
+            && task.tmux_status == SessionState::Error.as_str() =>
+        {
+            task.status_error.clone()
+        }
+        None => None,
+    }
+}
+
 fn should_mark_needs_inspection(previous_status: &str, next_status: &str) -> bool {
-    SessionState::from_raw_status(previous_status) == SessionState::Running
-        && SessionState::from_raw_status(next_status) == SessionState::Idle
+    SessionState::from_raw_status(previous_status).is_active()
+        && matches!(
+            SessionState::from_raw_status(next_status),
+            SessionState::Idle | SessionState::Error
+        )
 }
 
 fn should_notify_root_session_completion(
@@ -706,7 +1412,9 @@ fn should_notify_root_session_completion(
     next_status: &str,
     status_match: &SessionStatusMatch,
 ) -> bool {
-    should_mark_needs_inspection(previous_status, next_status) && status_match.is_root_session()
+    should_mark_needs_inspection(previous_status, next_status)
+        && SessionState::from_raw_status(next_status) == SessionState::Idle
+        && status_match.is_root_session()
 }
 
 fn should_notify_root_completion_without_status_match(
@@ -732,6 +1440,7 @@ fn should_notify_root_completion_without_status_match(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::opencode::status_server::ServerStatusConfig;
     use crate::types::{SessionState, SessionStatus};
 
     fn status_match(session_id: &str, parent_session_id: Option<&str>) -> SessionStatusMatch {
@@ -964,6 +1673,40 @@ mod tests {
         assert_eq!(ids, vec!["subagent-1".to_string()]);
     }
 
+    #[test]
+    fn session_usage_ledger_refetches_only_growing_subagents() {
+        let task_id = Uuid::new_v4();
+        let mut ledger = SessionUsageLedger::default();
+        for session_id in ["done-subagent", "live-subagent"] {
+            ledger
+                .sessions
+                .insert(session_id.to_string(), (task_id, Vec::new()));
+        }
+        let mut complete_parent_map = HashMap::new();
+        for session_id in ["done-subagent", "live-subagent", "new-subagent"] {
+            complete_parent_map.insert(session_id.to_string(), Some("root-1".to_string()));
+        }
+        complete_parent_map.insert("other-root".to_string(), None);
+        let live = vec!["live-subagent".to_string()];
+
+        let ids = ledger.sessions_to_refresh("root-1", &live, Some(&complete_parent_map));
+        assert_eq!(ids, vec!["live-subagent", "new-subagent"]);
+
+        // One more fetch after a subagent stops picks up its final message.
+        ledger.finish_cycle();
+        let ids = ledger.sessions_to_refresh("root-1", &[], Some(&complete_parent_map));
+        assert_eq!(ids, vec!["live-subagent", "new-subagent"]);
+        ledger.finish_cycle();
+        ledger
+            .sessions
+            .insert("new-subagent".to_string(), (task_id, Vec::new()));
+        assert!(
+            ledger
+                .sessions_to_refresh("root-1", &[], Some(&complete_parent_map))
+                .is_empty()
+        );
+    }
+
     #[test]
     fn should_mark_needs_inspection_only_on_running_to_idle_transition() {
         assert!(should_mark_needs_inspection("running", "idle"));
@@ -971,6 +1714,42 @@ mod tests {
         assert!(!should_mark_needs_inspection("idle", "idle"));
         assert!(!should_mark_needs_inspection("running", "running"));
         assert!(!should_mark_needs_inspection("unknown", "idle"));
+        assert!(should_mark_needs_inspection("awaiting_approval", "idle"));
+        assert!(!should_mark_needs_inspection(
+            "running",
+            "awaiting_approval"
+        ));
+    }
+
+    #[test]
+    fn next_session_state_prefers_awaiting_approval_unless_stopped() {
+        use SessionState::*;
+        assert_eq!(
+            next_session_state(Running, Running, true, false),
+            AwaitingApproval
+        );
+        assert_eq!(next_session_state(Idle, Running, false, false), Running);
+        assert_eq!(
+            next_session_state(AwaitingApproval, Idle, true, false),
+            Idle
+        );
+        assert_eq!(next_session_state(Running, Error, true, false), Error);
+    }
+
+    #[test]
+    fn next_session_state_keeps_failures_and_questions_until_resolved() {
+        use SessionState::*;
+        assert_eq!(next_session_state(Error, Idle, false, true), Error);
+        assert_eq!(next_session_state(Error, Running, false, true), Running);
+        assert_eq!(
+            next_session_state(AwaitingInput, Running, false, true),
+            AwaitingInput
+        );
+        assert_eq!(
+            next_session_state(AwaitingInput, Running, false, false),
+            Running
+        );
+        assert_eq!(next_session_state(Running, Retrying, false, true), Retrying);
     }
 
     #[test]
@@ -1038,4 +1817,469 @@ mod tests {
             Some(&complete_parent_map)
         ));
     }
+
+    #[test]
+    fn next_poll_interval_backs_off_only_while_stream_is_connected() {
+        let poll_interval = Duration::from_millis(1000);
+        assert_eq!(next_poll_interval(poll_interval, false), poll_interval);
+        assert_eq!(
+            next_poll_interval(poll_interval, true),
+            EVENT_BACKFILL_INTERVAL
+        );
+        let slow = EVENT_BACKFILL_INTERVAL * 2;
+        assert_eq!(next_poll_interval(slow, true), slow);
+    }
+
+    #[tokio::test]
+    async fn apply_provider_status_marks_exited_process_for_inspection() {
+        let db = Database::open_async(":memory:")
+            .await
+            .expect("db should open");
+        let repo_dir = tempfile::TempDir::new().expect("temp repo");
+        let repo = db
+            .add_repo_async(repo_dir.path().to_path_buf())
+            .await
+            .expect("repo should save");
+        let category_id = db.list_categories_async().await.expect("categories")[0].id;
+        let task = db
+            .add_task_async(
+                repo.id,
+                "feature/shell".to_string(),
+                "Shell".to_string(),
+                category_id,
+            )
+            .await
+            .expect("task should save");
+        db.update_task_status_async(task.id, SessionState::Running.as_str())
+            .await
+            .expect("status should save");
+        let task = db.get_task_async(task.id).await.expect("task");
+        let notification_config = TaskCompletionNotificationConfig {
+            backend: crate::notification::NotificationBackend::None,
+            notification_display_duration_ms: 0,
+            sound: crate::notification::CompletionSoundConfig::default(),
+        };
+
+        apply_provider_status(
+            &db,
+            &task,
+            SessionStatus {
+                state: SessionState::Idle,
+                source: SessionStatusSource::Process,
+                fetched_at: SystemTime::now(),
+                error: None,
+            },
+            notification_config,
+        )
+        .await;
+
+        let updated = db.get_task_async(task.id).await.expect("task");
+        assert_eq!(updated.tmux_status, "idle");
+        assert!(updated.needs_inspection);
+        assert_eq!(updated.status_source, "process");
+        assert_eq!(updated.status_error, None);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn apply_server_event_backfills_only_for_new_board_sessions() {
+        let db = Database::open_async(":memory:")
+            .await
+            .expect("db should open");
+        let repo_dir = tempfile::TempDir::new().expect("temp repo");
+        let repo = db
+            .add_repo_async(repo_dir.path().to_path_buf())
+            .await
+            .expect("repo should save");
+        let category_id = db.list_categories_async().await.expect("categories")[0].id;
+        let task = db
+            .add_task_async(
+                repo.id,
+                "feature/events".to_string(),
+                "Events".to_string(),
+                category_id,
+            )
+            .await
+            .expect("task should save");
+        db.update_task_tmux_async(
+            task.id,
+            Some("ok-events".to_string()),
+            Some("/work/events".to_string()),
+        )
+        .await
+        .expect("worktree should save");
+
+        let caches = StatusPollerCaches {
+            session_todo_cache: Arc::default(),
+            session_subagent_cache: Arc::default(),
+            session_title_cache: Arc::default(),
+            session_message_cache: Arc::default(),
+            session_permission_cache: Arc::default(),
+            session_usage_cache: Arc::default(),
+        };
+        let notification_config = TaskCompletionNotificationConfig {
+            backend: crate::notification::NotificationBackend::None,
+            notification_display_duration_ms: 0,
+            sound: crate::notification::CompletionSoundConfig::default(),
+        };
+        let stream = EventStreamState::default();
+        stream.index_sessions(&db.list_tasks_async().await.expect("tasks"));
+        let mut stale = HashSet::new();
+        let running = |session_id: &str| ServerEvent::SessionStatus {
+            session_id: session_id.to_string(),
+            state: SessionState::Running,
+            detail: None,
+        };
+        let backfilled = || async {
+            tokio::time::timeout(Duration::from_millis(100), stream.backfill.notified())
+                .await
+                .is_ok()
+        };
+
+        apply_server_event(
+            &db,
+            &caches,
+            notification_config,
+            &stream,
+            &mut stale,
+            Some("/elsewhere"),
+            running("ses_other"),
+        )
+        .await;
+        assert!(
+            !backfilled().await,
+            "sessions outside the board are ignored"
+        );
+
+        *stream.unbound_retried_at.lock().expect("retry") = None;
+        apply_server_event(
+            &db,
+            &caches,
+            notification_config,
+            &stream,
+            &mut stale,
+            Some("/work/events/"),
+            running("ses_sub"),
+        )
+        .await;
+        assert!(backfilled().await, "new board sessions request a backfill");
+        apply_server_event(
+            &db,
+            &caches,
+            notification_config,
+            &stream,
+            &mut stale,
+            Some("/work/events"),
+            running("ses_sub"),
+        )
+        .await;
+        assert!(!backfilled().await, "backfills are throttled");
+
+        // A session bound since the last sync is picked up from the database.
+        db.update_task_session_binding_async(task.id, Some("ses_new".to_string()))
+            .await
+            .expect("binding should save");
+        *stream.unbound_retried_at.lock().expect("retry") = None;
+        apply_server_event(
+            &db,
+            &caches,
+            notification_config,
+            &stream,
+            &mut stale,
+            Some("/work/events"),
+            running("ses_new"),
+        )
+        .await;
+        assert!(!backfilled().await);
+        let updated = db.get_task_async(task.id).await.expect("task");
+        assert_eq!(updated.tmux_status, "running");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn apply_server_event_updates_bound_task_and_caches() {
+        let db = Database::open_async(":memory:")
+            .await
+            .expect("db should open");
+        let repo_dir = tempfile::TempDir::new().expect("temp repo");
+        let repo = db
+            .add_repo_async(repo_dir.path().to_path_buf())
+            .await
+            .expect("repo should save");
+        let category_id = db.list_categories_async().await.expect("categories")[0].id;
+        let task = db
+            .add_task_async(
+                repo.id,
+                "feature/events".to_string(),
+                "Events".to_string(),
+                category_id,
+            )
+            .await
+            .expect("task should save");
+        db.update_task_session_binding_async(task.id, Some("ses_root".to_string()))
+            .await
+            .expect("binding should save");
+
+        let caches = StatusPollerCaches {
+            session_todo_cache: Arc::default(),
+            session_subagent_cache: Arc::default(),
+            session_title_cache: Arc::default(),
+            session_message_cache: Arc::default(),
+            session_permission_cache: Arc::default(),
+            session_usage_cache: Arc::default(),
+        };
+        let notification_config = TaskCompletionNotificationConfig {
+            backend: crate::notification::NotificationBackend::None,
+            notification_display_duration_ms: 0,
+            sound: crate::notification::CompletionSoundConfig::default(),
+        };
+        let stream = EventStreamState::default();
+        stream.index_sessions(&db.list_tasks_async().await.expect("tasks"));
+        let mut stale = HashSet::new();
+
+        for event in [
+            ServerEvent::SessionStatus {
+                session_id: "ses_root".to_string(),
+                state: SessionState::Running,
+                detail: None,
+            },
+            ServerEvent::SessionStatus {
+                session_id: "ses_root".to_string(),
+                state: SessionState::Idle,
+                detail: None,
+            },
+            ServerEvent::TodoUpdated {
+                session_id: "ses_root".to_string(),
+                todos: vec![SessionTodoItem {
+                    content: "ship it".to_string(),
+                    completed: false,
+                }],
+            },
+            ServerEvent::SessionUpdated {
+                session_id: "ses_root".to_string(),
+                title: Some("Ship it".to_string()),
+            },
+            ServerEvent::MessageUpdated {
+                session_id: "ses_root".to_string(),
+            },
+        ] {
+            apply_server_event(
+                &db,
+                &caches,
+                notification_config,
+                &stream,
+                &mut stale,
+                None,
+                event,
+            )
+            .await;
+        }
+
+        let updated = db.get_task_async(task.id).await.expect("task");
+        assert_eq!(updated.tmux_status, "idle");
+        assert!(updated.needs_inspection);
+        assert_eq!(updated.status_source, "server");
+        assert_eq!(
+            caches.session_todo_cache.lock().expect("todos")[&task.id][0].content,
+            "ship it"
+        );
+        assert_eq!(
+            caches.session_title_cache.lock().expect("titles")["ses_root"],
+            "Ship it"
+        );
+        assert!(stale.contains("ses_root"));
+
+        apply_server_event(
+            &db,
+            &caches,
+            notification_config,
+            &stream,
+            &mut stale,
+            None,
+            ServerEvent::SessionStatus {
+                session_id: "ses_child".to_string(),
+                state: SessionState::Running,
+                detail: None,
+            },
+        )
+        .await;
+        tokio::time::timeout(Duration::from_secs(1), stream.backfill.notified())
+            .await
+            .expect("unknown sessions should request a backfill");
+
+        let permission = SessionPermissionRequest {
+            id: "per_1".to_string(),
+            session_id: "ses_root".to_string(),
+            tool: "bash".to_string(),
+            title: "git push".to_string(),
+            arguments: vec!["git push".to_string()],
+        };
+        for event in [
+            ServerEvent::PermissionAsked(permission.clone()),
+            ServerEvent::SessionStatus {
+                session_id: "ses_root".to_string(),
+                state: SessionState::Running,
+                detail: None,
+            },
+        ] {
+            apply_server_event(
+                &db,
+                &caches,
+                notification_config,
+                &stream,
+                &mut stale,
+                None,
+                event,
+            )
+            .await;
+        }
+        let updated = db.get_task_async(task.id).await.expect("task");
+        assert_eq!(updated.tmux_status, "awaiting_approval");
+        assert_eq!(
+            caches.session_permission_cache.lock().expect("permissions")[&task.id],
+            vec![permission]
+        );
+
+        apply_server_event(
+            &db,
+            &caches,
+            notification_config,
+            &stream,
+            &mut stale,
+            None,
+            ServerEvent::PermissionReplied {
+                session_id: "ses_root".to_string(),
+                permission_id: "per_1".to_string(),
+            },
+        )
+        .await;
+        let updated = db.get_task_async(task.id).await.expect("task");
+        assert_eq!(updated.tmux_status, "running");
+        assert!(
+            caches
+                .session_permission_cache
+                .lock()
+                .expect("permissions")
+                .is_empty()
+        );
+
+        for event in [
+            ServerEvent::SessionStatus {
+                session_id: "ses_root".to_string(),
+                state: SessionState::Error,
+                detail: Some("invalid api key".to_string()),
+            },
+            ServerEvent::SessionStatus {
+                session_id: "ses_root".to_string(),
+                state: SessionState::Idle,
+                detail: None,
+            },
+        ] {
+            apply_server_event(
+                &db,
+                &caches,
+                notification_config,
+                &stream,
+                &mut stale,
+                None,
+                event,
+            )
+            .await;
+        }
+        let updated = db.get_task_async(task.id).await.expect("task");
+        assert_eq!(updated.tmux_status, "error");
+        assert_eq!(
+            updated.status_error.as_deref(),
+            Some("SESSION_ERROR:invalid api key")
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn poll_cycle_leaves_cache_entries_of_other_tasks_alone() {
+        let db = Database::open_async(":memory:")
+            .await
+            .expect("db should open");
+        let repo_dir = tempfile::TempDir::new().expect("temp repo");
+        let repo = db
+            .add_repo_async(repo_dir.path().to_path_buf())
+            .await
+            .expect("repo should save");
+        let category_id = db.list_categories_async().await.expect("categories")[0].id;
+        let task = db
+            .add_task_async(
+                repo.id,
+                "feature/shell".to_string(),
+                "Shell".to_string(),
+                category_id,
+            )
+            .await
+            .expect("task should save");
+        db.update_task_backend_async(task.id, Some("shell".to_string()))
+            .await
+            .expect("backend should save");
+
+        let caches = StatusPollerCaches {
+            session_todo_cache: Arc::default(),
+            session_subagent_cache: Arc::default(),
+            session_title_cache: Arc::default(),
+            session_message_cache: Arc::default(),
+            session_permission_cache: Arc::default(),
+            session_usage_cache: Arc::default(),
+        };
+        // Written by the event stream for a task this cycle never visits.
+        let other_task_id = Uuid::new_v4();
+        let todo = |content: &str| SessionTodoItem {
+            content: content.to_string(),
+            completed: false,
+        };
+        {
+            let mut todos = caches.session_todo_cache.lock().expect("todos");
+            todos.insert(task.id, vec![todo("stale")]);
+            todos.insert(other_task_id, vec![todo("from events")]);
+        }
+        let notification_config = TaskCompletionNotificationConfig {
+            backend: crate::notification::NotificationBackend::None,
+            notification_display_duration_ms: 0,
+            sound: crate::notification::CompletionSoundConfig::default(),
+        };
+        let server_provider = ServerStatusProvider::new(ServerStatusConfig {
+            hostname: "127.0.0.1".to_string(),
+            port: 9,
+            request_timeout: Duration::from_millis(50),
+            auth: None,
+        });
+        let stream = EventStreamState::default();
+        let stop = AtomicBool::new(false);
+
+        tokio::join!(
+            poll_status_loop(
+                &db,
+                &server_provider,
+                &stop,
+                &caches,
+                10,
+                notification_config,
+                &stream,
+            ),
+            async {
+                let cleared = tokio::time::timeout(Duration::from_secs(5), async {
+                    while caches
+                        .session_todo_cache
+                        .lock()
+                        .expect("todos")
+                        .contains_key(&task.id)
+                    {
+                        tokio::time::sleep(Duration::from_millis(10)).await;
+                    }
+                })
+                .await;
+                // Let a few more full cycles run before stopping.
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                stop.store(true, Ordering::Relaxed);
+                cleared.expect("the poll should clear the shell task's todos");
+            },
+        );
+
+        let todos = caches.session_todo_cache.lock().expect("todos");
+        assert!(!todos.contains_key(&task.id));
+        assert_eq!(todos[&other_task_id][0].content, "from events");
+    }
 }
@@ -1,24 +1,43 @@
 //! Runtime traits and implementations for git/tmux operations
 
+use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, anyhow};
+use uuid::Uuid;
 
 use crate::git::{
-    git_check_branch_up_to_date, git_create_worktree, git_detect_default_branch, git_fetch,
-    git_is_valid_repo, git_remove_worktree,
+    GitWorktree, git_check_branch_up_to_date, git_copy_uncommitted_changes, git_create_worktree,
+    git_delete_branch, git_detect_default_branch, git_fetch, git_force_delete_branch,
+    git_head_commit, git_is_valid_repo, git_list_worktrees, git_remove_clean_worktree,
+    git_remove_worktree,
 };
+use crate::opencode::endpoint::ServerEndpoint;
+use crate::opencode::status_server::ServerStatusConfig;
+use crate::opencode::{ServerStatusProvider, block_on_opencode, wait_for_server_ready};
 use crate::tmux::{
     PopupThemeStyle, sanitize_session_name_for_project, tmux_create_session, tmux_kill_session,
-    tmux_open_session_in_new_terminal, tmux_session_exists, tmux_show_popup, tmux_switch_client,
+    tmux_list_project_sessions, tmux_list_sessions, tmux_open_session_in_new_terminal,
+    tmux_session_exists, tmux_show_popup, tmux_switch_client,
 };
+use crate::types::{AgentSelection, SessionMessageItem};
+
+/// Session creation and prompt submission can take longer than a status poll.
+const SESSION_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// Runtime trait for task recovery operations
 pub trait RecoveryRuntime {
     fn repo_exists(&self, path: &Path) -> bool;
     fn worktree_exists(&self, worktree_path: &Path) -> bool;
     fn session_exists(&self, session_name: &str) -> bool;
-    fn create_session(&self, session_name: &str, working_dir: &Path, command: &str) -> Result<()>;
+    fn create_session(
+        &self,
+        session_name: &str,
+        working_dir: &Path,
+        command: &str,
+        env: &[(String, String)],
+    ) -> Result<()>;
     fn switch_client(
         &self,
         session_name: &str,
@@ -51,8 +70,14 @@ impl RecoveryRuntime for RealRecoveryRuntime {
         tmux_session_exists(session_name)
     }
 
-    fn create_session(&self, session_name: &str, working_dir: &Path, command: &str) -> Result<()> {
-        tmux_create_session(session_name, working_dir, Some(command))
+    fn create_session(
+        &self,
+        session_name: &str,
+        working_dir: &Path,
+        command: &str,
+        env: &[(String, String)],
+    ) -> Result<()> {
+        tmux_create_session(session_name, working_dir, Some(command), env)
     }
 
     fn switch_client(
@@ -101,14 +126,38 @@ pub trait CreateTaskRuntime {
         base_ref: &str,
     ) -> Result<()>;
     fn git_remove_worktree(&self, repo_path: &Path, worktree_path: &Path) -> Result<()>;
+    /// Drops a branch this runtime just created, merged or not.
+    fn git_discard_branch(&self, repo_path: &Path, branch_name: &str) -> Result<()>;
+    fn git_head_commit(&self, worktree_path: &Path) -> Result<String>;
+    fn git_copy_uncommitted_changes(&self, source: &Path, target: &Path) -> Result<()>;
     fn tmux_session_exists(&self, session_name: &str) -> bool;
     fn tmux_create_session(
         &self,
         session_name: &str,
         working_dir: &Path,
         command: Option<&str>,
+        env: &[(String, String)],
     ) -> Result<()>;
     fn tmux_kill_session(&self, session_name: &str) -> Result<()>;
+    /// Blocks until the OpenCode server answers, starting it if needed.
+    fn opencode_wait_for_server(&self) -> Result<()>;
+    fn opencode_create_session(&self, worktree_path: &Path, title: &str) -> Result<String>;
+    fn opencode_send_prompt(
+        &self,
+        session_id: &str,
+        prompt: &str,
+        selection: &AgentSelection,
+    ) -> Result<()>;
+    /// Forks `session_id` into a new session rooted at `worktree_path`;
+    /// `None` when the server has no fork support.
+    fn opencode_fork_session(
+        &self,
+        session_id: &str,
+        worktree_path: &Path,
+    ) -> Result<Option<String>>;
+    fn opencode_seed_session(&self, session_id: &str, text: &str) -> Result<()>;
+    fn opencode_delete_session(&self, session_id: &str) -> Result<()>;
+    fn opencode_session_messages(&self, session_id: &str) -> Result<Vec<SessionMessageItem>>;
 }
 
 /// Real implementation of CreateTaskRuntime using actual git/tmux commands
@@ -211,6 +260,18 @@ impl CreateTaskRuntime for RealCreateTaskRuntime {
         git_remove_worktree(repo_path, worktree_path)
     }
 
+    fn git_discard_branch(&self, repo_path: &Path, branch_name: &str) -> Result<()> {
+        git_force_delete_branch(repo_path, branch_name)
+    }
+
+    fn git_head_commit(&self, worktree_path: &Path) -> Result<String> {
+        git_head_commit(worktree_path)
+    }
+
+    fn git_copy_uncommitted_changes(&self, source: &Path, target: &Path) -> Result<()> {
+        git_copy_uncommitted_changes(source, target)
+    }
+
     fn tmux_session_exists(&self, session_name: &str) -> bool {
         tmux_session_exists(session_name)
     }
@@ -220,13 +281,156 @@ impl CreateTaskRuntime for RealCreateTaskRuntime {
         session_name: &str,
         working_dir: &Path,
         command: Option<&str>,
+        env: &[(String, String)],
     ) -> Result<()> {
-        tmux_create_session(session_name, working_dir, command)
+        tmux_create_session(session_name, working_dir, command, env)
     }
 
     fn tmux_kill_session(&self, session_name: &str) -> Result<()> {
         tmux_kill_session(session_name)
     }
+
+    fn opencode_wait_for_server(&self) -> Result<()> {
+        wait_for_server_ready().map_err(|err| anyhow!("OpenCode server is not ready: {err}"))
+    }
+
+    fn opencode_create_session(&self, worktree_path: &Path, title: &str) -> Result<String> {
+        let directory = worktree_path.to_string_lossy();
+        block_on_opencode(session_request_provider().create_session(&directory, title))
+    }
+
+    fn opencode_send_prompt(
+        &self,
+        session_id: &str,
+        prompt: &str,
+        selection: &AgentSelection,
+    ) -> Result<()> {
+        block_on_opencode(
+            session_request_provider().send_session_prompt(session_id, prompt, selection),
+        )
+    }
+
+    fn opencode_fork_session(
+        &self,
+        session_id: &str,
+        worktree_path: &Path,
+    ) -> Result<Option<String>> {
+        let directory = worktree_path.to_string_lossy();
+        block_on_opencode(async {
+            match session_request_provider()
+                .fork_session(session_id, &directory)
+                .await
+            {
+                Ok(forked_id) => Ok(Some(forked_id)),
+                Err(err) if err.code == "SESSION_FORK_UNSUPPORTED" => Ok(None),
+                Err(err) => Err(err),
+            }
+        })
+    }
+
+    fn opencode_seed_session(&self, session_id: &str, text: &str) -> Result<()> {
+        block_on_opencode(session_request_provider().seed_session_context(session_id, text))
+    }
+
+    fn opencode_delete_session(&self, session_id: &str) -> Result<()> {
+        block_on_opencode(session_request_provider().delete_session(session_id))
+    }
+
+    fn opencode_session_messages(&self, session_id: &str) -> Result<Vec<SessionMessageItem>> {
+        block_on_opencode(session_request_provider().fetch_session_messages(session_id))
+    }
+}
+
+fn session_request_provider() -> ServerStatusProvider {
+    ServerStatusProvider::new(ServerStatusConfig {
+        request_timeout: SESSION_REQUEST_TIMEOUT,
+        ..ServerStatusConfig::default()
+    })
+}
+
+/// Runtime trait for orphan cleanup operations
+pub trait GcRuntime {
+    fn list_worktrees(&self, repo_path: &Path) -> Result<Vec<GitWorktree>>;
+    /// Sessions named for `project_slug`, or every `ok-*` session for the default project.
+    fn list_project_sessions(&self, project_slug: Option<&str>) -> Vec<String>;
+    fn path_exists(&self, path: &Path) -> bool;
+    /// Time since `path` was last modified; `None` when it cannot be read.
+    fn path_age(&self, path: &Path) -> Option<Duration>;
+    /// Removes a worktree; without `force` a worktree with uncommitted or
+    /// untracked changes is refused instead of discarded.
+    fn remove_worktree(&self, repo_path: &Path, worktree_path: &Path, force: bool) -> Result<()>;
+    fn delete_branch(&self, repo_path: &Path, branch_name: &str) -> Result<()>;
+    fn kill_session(&self, session_name: &str) -> Result<()>;
+}
+
+/// Real implementation of GcRuntime using actual git/tmux commands
+pub struct RealGcRuntime;
+
+impl GcRuntime for RealGcRuntime {
+    fn list_worktrees(&self, repo_path: &Path) -> Result<Vec<GitWorktree>> {
+        git_list_worktrees(repo_path)
+    }
+
+    fn list_project_sessions(&self, project_slug: Option<&str>) -> Vec<String> {
+        match project_slug {
+            Some(slug) => tmux_list_project_sessions(slug),
+            None => tmux_list_sessions()
+                .into_iter()
+                .map(|session| session.name)
+                .filter(|name| name.starts_with("ok-"))
+                .collect(),
+        }
+    }
+
+    fn path_exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+
+    fn path_age(&self, path: &Path) -> Option<Duration> {
+        let modified = fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()?;
+        SystemTime::now().duration_since(modified).ok()
+    }
+
+    fn remove_worktree(&self, repo_path: &Path, worktree_path: &Path, force: bool) -> Result<()> {
+        if force {
+            git_remove_worktree(repo_path, worktree_path)
+        } else {
+            git_remove_clean_worktree(repo_path, worktree_path)
+        }
+    }
+
+    fn delete_branch(&self, repo_path: &Path, branch_name: &str) -> Result<()> {
+        git_delete_branch(repo_path, branch_name)
+    }
+
+    fn kill_session(&self, session_name: &str) -> Result<()> {
+        tmux_kill_session(session_name)
+    }
+}
+
+/// Environment variable naming the task a tmux session was created for.
+pub const TASK_ID_ENV: &str = "OPENCODE_KANBAN_TASK_ID";
+/// Environment variable naming the project of that task.
+pub const PROJECT_ENV: &str = "OPENCODE_KANBAN_PROJECT";
+
+/// Environment injected into a task's tmux session so tools running inside it
+/// (such as `opencode-kanban mcp`) know which task they belong to, and so
+/// `opencode attach` can log in to a password-protected server.
+pub fn task_session_env(project: &str, task_id: Uuid) -> Vec<(String, String)> {
+    let mut env = vec![
+        (TASK_ID_ENV.to_string(), task_id.to_string()),
+        (PROJECT_ENV.to_string(), project.to_string()),
+    ];
+    if let Some(auth) = ServerEndpoint::current().auth {
+        env.extend(
+            auth.opencode_env()
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value)),
+        );
+    }
+    env
 }
 
 /// Generate next available tmux session name
@@ -324,7 +528,7 @@ mod tests {
 
     #[test]
     fn test_next_available_session_name_by_finds_first_available_suffix() {
-        let taken = vec![
+        let taken = [
             "ok-proj-repo-main",
             "ok-proj-repo-main-2",
             "ok-proj-repo-main-3",
@@ -343,7 +547,7 @@ mod tests {
 
     #[test]
     fn test_next_available_session_name_by_no_project_slug_taken() {
-        let taken = vec!["ok-myrepo-main", "ok-myrepo-main-2"];
+        let taken = ["ok-myrepo-main", "ok-myrepo-main-2"];
         let result = next_available_session_name_by(None, None, "myrepo", "main", |name| {
             taken.contains(&name)
         });
@@ -391,6 +595,7 @@ mod tests {
             _session_name: &str,
             _working_dir: &Path,
             _command: &str,
+            _env: &[(String, String)],
         ) -> Result<()> {
             Ok(())
         }
@@ -5,8 +5,10 @@ use std::str::FromStr;
 
 use uuid::Uuid;
 
+use super::workflows::GcReport;
 use crate::command_palette::CommandPaletteState;
 use crate::task_palette::TaskPaletteState;
+use crate::types::SessionPermissionRequest;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum NewTaskField {
@@ -16,6 +18,9 @@ pub enum NewTaskField {
     Base,
     ExistingDirectory,
     Title,
+    Model,
+    Agent,
+    Prompt,
     EnsureBaseUpToDate,
     Create,
     Cancel,
@@ -58,6 +63,11 @@ pub struct NewTaskDialogState {
     pub branch_input: String,
     pub base_input: String,
     pub title_input: String,
+    /// `provider/model`; empty keeps OpenCode's configured default.
+    pub model_input: String,
+    pub agent_input: String,
+    /// Sent to the new session once it exists; may span several lines.
+    pub prompt_input: String,
     pub ensure_base_up_to_date: bool,
     pub loading_message: Option<String>,
     pub focused_field: NewTaskField,
@@ -116,6 +126,17 @@ pub struct DeleteRepoDialogState {
     pub repo_name: String,
 }
 
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct QuickPromptDialogState {
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub session_id: String,
+    pub prompt_input: String,
+    /// Recently sent prompts, newest first.
+    pub history: Vec<String>,
+    pub history_index: Option<usize>,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct ErrorDialogState {
     pub title: String,
@@ -128,6 +149,13 @@ pub struct ConfirmQuitDialogState {
     pub focused_field: ConfirmCancelField,
 }
 
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GcDialogState {
+    pub report: GcReport,
+    pub adopt_worktrees: bool,
+    pub focused_field: ConfirmCancelField,
+}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum ConfirmCancelField {
     Confirm,
@@ -178,6 +206,48 @@ pub struct ArchiveTaskDialogState {
     pub focused_field: ConfirmCancelField,
 }
 
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ForkTaskField {
+    Branch,
+    Title,
+    Fork,
+    Cancel,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ForkTaskDialogState {
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub branch_input: String,
+    pub title_input: String,
+    pub focused_field: ForkTaskField,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct InterruptSessionDialogState {
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub session_id: String,
+    pub focused_field: ConfirmCancelField,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum PermissionReplyField {
+    Approve,
+    AlwaysApprove,
+    Deny,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PermissionApprovalDialogState {
+    pub task_id: Uuid,
+    pub task_title: String,
+    /// Oldest request still waiting; the rest are answered one at a time.
+    pub permission: SessionPermissionRequest,
+    pub pending_count: usize,
+    pub focused_field: PermissionReplyField,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct MoveTaskDialogState {
     pub category_idx: usize,
@@ -417,8 +487,13 @@ pub enum ActiveDialog {
     WorktreeNotFound(WorktreeNotFoundDialogState),
     RepoUnavailable(RepoUnavailableDialogState),
     ConfirmQuit(ConfirmQuitDialogState),
+    Gc(GcDialogState),
     RenameRepo(RenameRepoDialogState),
     DeleteRepo(DeleteRepoDialogState),
+    QuickPrompt(QuickPromptDialogState),
+    InterruptSession(InterruptSessionDialogState),
+    ForkTask(ForkTaskDialogState),
+    PermissionApproval(PermissionApprovalDialogState),
     Help,
 }
 
@@ -447,6 +522,12 @@ pub struct CreateTaskOutcome {
     pub warning: Option<String>,
 }
 
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ForkTaskOutcome {
+    pub task_id: Uuid,
+    pub warning: Option<String>,
+}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum DetailFocus {
     List,
@@ -9,6 +9,7 @@ impl App {
             Message::Tick => {
                 self.pulse_phase = (self.pulse_phase + 1) % 4;
                 self.refresh_data()?;
+                self.drain_session_action_results()?;
 
                 if self.view_mode == ViewMode::SidePanel {
                     let Some(task) = self.selected_task() else {
@@ -72,6 +73,77 @@ impl App {
                     tracing::error!("Failed to open session in browser: {}", e);
                 }
             }
+            Message::OpenQuickPromptDialog => {
+                if let Some(task) = self.selected_task() {
+                    match task
+                        .opencode_session_id
+                        .clone()
+                        .filter(|session_id| !session_id.trim().is_empty())
+                    {
+                        Some(session_id) => {
+                            let history = self
+                                .db
+                                .list_prompt_history(QUICK_PROMPT_HISTORY_LIMIT)
+                                .unwrap_or_else(|err| {
+                                    warn!("failed to load prompt history: {err:#}");
+                                    Vec::new()
+                                });
+                            self.active_dialog =
+                                ActiveDialog::QuickPrompt(QuickPromptDialogState {
+                                    task_id: task.id,
+                                    task_title: task.title.clone(),
+                                    session_id,
+                                    prompt_input: String::new(),
+                                    history,
+                                    history_index: None,
+                                });
+                        }
+                        None => {
+                            self.footer_notice = Some(format!(
+                                " '{}' has no OpenCode session yet; attach to it first ",
+                                task.title
+                            ));
+                        }
+                    }
+                }
+            }
+            Message::SubmitQuickPrompt => {
+                if let ActiveDialog::QuickPrompt(state) = &self.active_dialog {
+                    let prompt = state.prompt_input.trim().to_string();
+                    if !prompt.is_empty() {
+                        if let Err(err) = self.db.record_prompt_history(&prompt) {
+                            warn!("failed to record prompt history: {err:#}");
+                        }
+                        let selection = self
+                            .tasks
+                            .iter()
+                            .find(|task| task.id == state.task_id)
+                            .map(Task::agent_selection)
+                            .unwrap_or_default();
+                        spawn_session_action(
+                            state.task_id,
+                            state.task_title.clone(),
+                            state.session_id.clone(),
+                            SessionAction::Prompt {
+                                text: prompt,
+                                selection,
+                            },
+                            self.session_action_result_tx.clone(),
+                        );
+                        self.footer_notice =
+                            Some(format!("  Sending prompt to '{}'...  ", state.task_title));
+                        self.active_dialog = ActiveDialog::None;
+                    }
+                }
+            }
+            Message::UseQuickPromptHistory(index) => {
+                if let ActiveDialog::QuickPrompt(state) = &mut self.active_dialog
+                    && let Some(prompt) = state.history.get(index)
+                {
+                    state.prompt_input = prompt.clone();
+                    state.history_index = Some(index);
+                }
+            }
             Message::OpenNewTaskDialog => {
                 let usage = repo_selection_usage_map(&self.db);
                 let ranked_repo_indexes = rank_repos_for_query("", &self.repos, &usage);
@@ -90,6 +162,9 @@ impl App {
                     branch_input: String::new(),
                     base_input: default_base,
                     title_input: String::new(),
+                    model_input: String::new(),
+                    agent_input: String::new(),
+                    prompt_input: String::new(),
                     ensure_base_up_to_date: true,
                     loading_message: None,
                     focused_field: NewTaskField::UseExistingDirectory,
@@ -546,6 +621,13 @@ impl App {
             Message::CreateTask => self.confirm_new_task()?,
             Message::ConfirmQuit => self.should_quit = true,
             Message::CancelQuit => self.active_dialog = ActiveDialog::None,
+            Message::OpenGcDialog => self.open_gc_dialog()?,
+            Message::ToggleGcAdopt => {
+                if let ActiveDialog::Gc(state) = &mut self.active_dialog {
+                    state.adopt_worktrees = !state.adopt_worktrees;
+                }
+            }
+            Message::ConfirmGc => self.confirm_gc()?,
             Message::ExecuteCommand(command_id) => {
                 self.active_dialog = ActiveDialog::None;
 
@@ -589,6 +671,14 @@ impl App {
             Message::ConfirmDeleteTask => self.confirm_delete_task()?,
             Message::ConfirmEditTask => self.confirm_edit_task()?,
             Message::ConfirmArchiveTask => self.confirm_archive_task()?,
+            Message::OpenInterruptSessionDialog => self.open_interrupt_session_dialog()?,
+            Message::ConfirmInterruptSession => self.confirm_interrupt_session()?,
+            Message::OpenForkTaskDialog => self.open_fork_task_dialog()?,
+            Message::ConfirmForkTask => self.confirm_fork_task()?,
+            Message::RevertLastTurn => self.revert_last_turn(false)?,
+            Message::UnrevertLastTurn => self.revert_last_turn(true)?,
+            Message::OpenPermissionApprovalDialog => self.open_permission_approval_dialog()?,
+            Message::ReplyPermission(field) => self.reply_permission(field)?,
             Message::UnarchiveTask => self.unarchive_selected_task()?,
             Message::ArchiveSelectUp => {
                 self.archive_selected_index = self.archive_selected_index.saturating_sub(1);
@@ -931,6 +1021,11 @@ impl App {
                     state.focused_field = field;
                 }
             }
+            Message::FocusForkTaskField(field) => {
+                if let ActiveDialog::ForkTask(state) = &mut self.active_dialog {
+                    state.focused_field = field;
+                }
+            }
             Message::ToggleDeleteTaskCheckbox(field) => {
                 if let ActiveDialog::DeleteTask(state) = &mut self.active_dialog {
                     state.focused_field = field;
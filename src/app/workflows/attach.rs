@@ -5,10 +5,12 @@ use tracing::warn;
 use tuirealm::ratatui::style::Color;
 use uuid::Uuid;
 
-use crate::app::runtime::{RecoveryRuntime, next_available_session_name};
+use crate::app::runtime::{RecoveryRuntime, next_available_session_name, task_session_env};
 use crate::app::state::AttachTaskResult;
+use crate::backend::{AgentLaunch, backend_for_task};
 use crate::db::Database;
-use crate::opencode::{Status, opencode_attach_command};
+use crate::opencode::Status;
+use crate::projects::DEFAULT_PROJECT;
 use crate::theme::Theme;
 use crate::tmux::PopupThemeStyle;
 use crate::types::{Repo, SessionTodoItem, Task};
@@ -125,12 +127,15 @@ fn ensure_task_session_with_runtime(
         runtime,
     );
 
-    let command = opencode_attach_command(
-        task.opencode_session_id.as_deref(),
-        task.worktree_path.as_deref(),
-    );
+    let selection = task.agent_selection();
+    let command = backend_for_task(task).attach_command(&AgentLaunch {
+        session_id: task.opencode_session_id.as_deref(),
+        worktree_dir: task.worktree_path.as_deref(),
+        selection: &selection,
+    });
 
-    runtime.create_session(&session_name, worktree_path, &command)?;
+    let env = task_session_env(project_slug.unwrap_or(DEFAULT_PROJECT), task.id);
+    runtime.create_session(&session_name, worktree_path, &command, &env)?;
     db.update_task_tmux(
         task.id,
         Some(session_name.clone()),
@@ -9,17 +9,19 @@ use tracing::warn;
 use uuid::Uuid;
 
 use crate::app::runtime::{
-    CreateTaskRuntime, next_available_session_name_by, worktrees_root_for_repo,
+    CreateTaskRuntime, next_available_session_name_by, task_session_env, worktrees_root_for_repo,
 };
 use crate::app::state::{CreateTaskOutcome, NewTaskDialogState};
+use crate::backend::{AgentLaunch, default_backend};
 use crate::db::Database;
 use crate::git::derive_worktree_path;
 use crate::matching::{
     ascii_case_insensitive_subsequence, normalize_fuzzy_needle, recency_frequency_bonus,
     safe_fuzzy_indices,
 };
-use crate::opencode::{Status, opencode_attach_command};
-use crate::types::{CommandFrequency, Repo};
+use crate::opencode::Status;
+use crate::projects::DEFAULT_PROJECT;
+use crate::types::{AgentSelection, CommandFrequency, Repo};
 
 const REPO_SELECTION_USAGE_PREFIX: &str = "repo-selection:";
 const GENERATED_BRANCH_PREFIX: &str = "feature";
@@ -44,6 +46,8 @@ pub(crate) fn create_task_pipeline_with_runtime(
     project_slug: Option<&str>,
     runtime: &impl CreateTaskRuntime,
 ) -> Result<CreateTaskOutcome> {
+    let selection = AgentSelection::parse(&state.model_input, &state.agent_input)
+        .map_err(anyhow::Error::msg)?;
     let mut warning = None;
     let (repo, branch, repo_path, worktree_path, remove_worktree_on_failure) = if state
         .use_existing_directory
@@ -151,8 +155,11 @@ pub(crate) fn create_task_pipeline_with_runtime(
 
     let mut created_session_name: Option<String> = None;
     let mut created_task_id: Option<Uuid> = None;
+    let mut prompt_warning: Option<String> = None;
     let branch_name = branch.clone();
     let resolved_title = resolve_task_title(state.title_input.trim(), &branch_name);
+    let initial_prompt = state.prompt_input.trim();
+    let backend = default_backend();
 
     let mut operation = || -> Result<()> {
         let session_name =
@@ -160,17 +167,49 @@ pub(crate) fn create_task_pipeline_with_runtime(
                 runtime.tmux_session_exists(name)
             });
 
-        let command = opencode_attach_command(None, Some(worktree_path.to_string_lossy().as_ref()));
-
-        runtime
-            .tmux_create_session(&session_name, &worktree_path, Some(&command))
-            .context("tmux session creation failed")?;
-        created_session_name = Some(session_name.clone());
-
+        // The task is saved first so its id can be injected into the session.
         let task = db
             .add_task(repo.id, &branch_name, &resolved_title, todo_category_id)
             .context("failed to save task")?;
         created_task_id = Some(task.id);
+        if !selection.is_empty() {
+            db.update_task_agent_selection(task.id, selection.clone())
+                .context("failed to save task model and agent")?;
+        }
+        if let Some(kind) = backend.kind().task_value() {
+            db.update_task_backend(task.id, Some(kind))
+                .context("failed to save task backend")?;
+        }
+
+        let opencode_session_id = if initial_prompt.is_empty() {
+            None
+        } else if !backend.supports_sessions() {
+            prompt_warning = Some(format!(
+                "initial prompt not sent: the {} backend has no sessions",
+                backend.kind().as_str()
+            ));
+            None
+        } else {
+            match bind_initial_prompt_session(db, runtime, task.id, &worktree_path, &resolved_title)
+            {
+                Ok(session_id) => Some(session_id),
+                Err(err) => {
+                    prompt_warning = Some(format!("initial prompt not sent: {err:#}"));
+                    None
+                }
+            }
+        };
+        let command = backend.launch_command(&AgentLaunch {
+            session_id: opencode_session_id.as_deref(),
+            worktree_dir: Some(worktree_path.to_string_lossy().as_ref()),
+            selection: &selection,
+        });
+
+        let env = task_session_env(project_slug.unwrap_or(DEFAULT_PROJECT), task.id);
+        runtime
+            .tmux_create_session(&session_name, &worktree_path, Some(&command), &env)
+            .context("tmux session creation failed")?;
+        created_session_name = Some(session_name.clone());
 
         db.update_task_tmux(
             task.id,
@@ -181,6 +220,12 @@ pub(crate) fn create_task_pipeline_with_runtime(
         db.update_task_status(task.id, Status::Idle.as_str())
             .context("failed to save task runtime status")?;
 
+        if let Some(session_id) = opencode_session_id.as_deref()
+            && let Err(err) = runtime.opencode_send_prompt(session_id, initial_prompt, &selection)
+        {
+            prompt_warning = Some(format!("initial prompt not sent: {err:#}"));
+        }
+
         if let Err(err) = db.increment_command_usage(&repo_selection_command_id(repo.id)) {
             warn!(
                 error = %err,
@@ -205,9 +250,32 @@ pub(crate) fn create_task_pipeline_with_runtime(
         return Err(err);
     }
 
+    let warning = match (warning, prompt_warning) {
+        (Some(fetch), Some(prompt)) => Some(format!("{fetch}; {prompt}")),
+        (fetch, prompt) => fetch.or(prompt),
+    };
     Ok(CreateTaskOutcome { warning })
 }
 
+/// Creates the task's OpenCode session through the server and binds it, so
+/// the tmux session opens on it and an initial prompt can be sent right away.
+/// Waits for the server first; a freshly started one is not listening yet.
+pub(crate) fn bind_initial_prompt_session(
+    db: &Database,
+    runtime: &impl CreateTaskRuntime,
+    task_id: Uuid,
+    worktree_path: &Path,
+    title: &str,
+) -> Result<String> {
+    runtime.opencode_wait_for_server()?;
+    let session_id = runtime
+        .opencode_create_session(worktree_path, title)
+        .context("failed to create OpenCode session")?;
+    db.update_task_session_binding(task_id, Some(session_id.clone()))
+        .context("failed to bind OpenCode session")?;
+    Ok(session_id)
+}
+
 fn resolve_create_task_branch(branch_input: &str, title_input: &str) -> Result<String> {
     let branch = branch_input.trim();
     let title = title_input.trim();
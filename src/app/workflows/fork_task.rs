@@ -0,0 +1,635 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use uuid::Uuid;
+
+use crate::app::runtime::{
+    CreateTaskRuntime, next_available_session_name_by, task_session_env, worktrees_root_for_repo,
+};
+use crate::app::state::{ForkTaskDialogState, ForkTaskOutcome};
+use crate::backend::{AgentLaunch, backend_for_task};
+use crate::db::Database;
+use crate::git::derive_worktree_path;
+use crate::opencode::Status;
+use crate::projects::DEFAULT_PROJECT;
+use crate::types::{Repo, SessionMessageItem, Task};
+
+/// Messages from the source session carried into a seeded replacement.
+const SEED_MESSAGE_LIMIT: usize = 6;
+const SEED_MESSAGE_CHARS: usize = 600;
+
+/// Branches a task into a sibling: a new worktree at the source's `HEAD` with
+/// its uncommitted changes, and an OpenCode session that continues from the
+/// source session, either forked by the server or seeded with a summary.
+pub(crate) fn fork_task_pipeline_with_runtime(
+    db: &Database,
+    source: &Task,
+    repo: &Repo,
+    state: &ForkTaskDialogState,
+    project_slug: Option<&str>,
+    runtime: &impl CreateTaskRuntime,
+) -> Result<ForkTaskOutcome> {
+    let source_worktree = source
+        .worktree_path
+        .as_deref()
+        .map(PathBuf::from)
+        .filter(|path| path.exists())
+        .context("task has no worktree to fork from")?;
+    let repo_path = PathBuf::from(&repo.path);
+
+    let branch = state.branch_input.trim().to_string();
+    if branch.is_empty() {
+        anyhow::bail!("enter a branch for the fork");
+    }
+    runtime
+        .git_validate_branch(&repo_path, &branch)
+        .context("branch validation failed")?;
+    let title = match state.title_input.trim() {
+        "" => branch.clone(),
+        title => title.to_string(),
+    };
+
+    let head = runtime
+        .git_head_commit(&source_worktree)
+        .context("failed to read the task's HEAD")?;
+    let worktrees_root = worktrees_root_for_repo(&repo_path);
+    fs::create_dir_all(&worktrees_root).with_context(|| {
+        format!(
+            "failed to create worktree root {}",
+            worktrees_root.display()
+        )
+    })?;
+    let worktree_path = derive_worktree_path(&worktrees_root, &repo_path, &branch);
+    runtime
+        .git_create_worktree(&repo_path, &worktree_path, &branch, &head)
+        .context("worktree creation failed")?;
+
+    let mut created_session_name: Option<String> = None;
+    let mut created_opencode_session: Option<String> = None;
+    let mut created_task_id: Option<Uuid> = None;
+    let mut warning: Option<String> = None;
+    let selection = source.agent_selection();
+    let backend = backend_for_task(source);
+
+    let mut operation = || -> Result<Uuid> {
+        runtime
+            .git_copy_uncommitted_changes(&source_worktree, &worktree_path)
+            .context("failed to copy uncommitted changes")?;
+
+        let task = db
+            .add_task(repo.id, &branch, &title, source.category_id)
+            .context("failed to save task")?;
+        created_task_id = Some(task.id);
+        db.update_task_forked_from(task.id, source.id)
+            .context("failed to link fork to its source task")?;
+        if !selection.is_empty() {
+            db.update_task_agent_selection(task.id, selection.clone())
+                .context("failed to save task model and agent")?;
+        }
+        if let Some(kind) = backend.kind().task_value() {
+            db.update_task_backend(task.id, Some(kind))
+                .context("failed to save task backend")?;
+        }
+        place_after_source(db, source, task.id).context("failed to position forked task")?;
+
+        let session_id = match source.opencode_session_id.as_deref() {
+            Some(source_session) if backend.supports_sessions() => {
+                match fork_session(
+                    db,
+                    runtime,
+                    task.id,
+                    source,
+                    source_session,
+                    &worktree_path,
+                    &mut created_opencode_session,
+                ) {
+                    Ok(session_id) => Some(session_id),
+                    Err(err) => {
+                        if let Some(session_id) = created_opencode_session.take() {
+                            let _ = runtime.opencode_delete_session(&session_id);
+                        }
+                        warning = Some(format!("session not forked: {err:#}"));
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        let session_name =
+            next_available_session_name_by(None, project_slug, &repo.name, &branch, |name| {
+                runtime.tmux_session_exists(name)
+            });
+        let command = backend.launch_command(&AgentLaunch {
+            session_id: session_id.as_deref(),
+            worktree_dir: Some(worktree_path.to_string_lossy().as_ref()),
+            selection: &selection,
+        });
+        let env = task_session_env(project_slug.unwrap_or(DEFAULT_PROJECT), task.id);
+        runtime
+            .tmux_create_session(&session_name, &worktree_path, Some(&command), &env)
+            .context("tmux session creation failed")?;
+        created_session_name = Some(session_name.clone());
+
+        db.update_task_tmux(
+            task.id,
+            Some(session_name),
+            Some(worktree_path.display().to_string()),
+        )
+        .context("failed to save task runtime metadata")?;
+        db.update_task_status(task.id, Status::Idle.as_str())
+            .context("failed to save task runtime status")?;
+
+        Ok(task.id)
+    };
+
+    match operation() {
+        Ok(task_id) => Ok(ForkTaskOutcome { task_id, warning }),
+        Err(err) => {
+            if let Some(task_id) = created_task_id {
+                let _ = db.delete_task(task_id);
+            }
+            if let Some(session_name) = created_session_name {
+                let _ = runtime.tmux_kill_session(&session_name);
+            }
+            if let Some(session_id) = created_opencode_session {
+                let _ = runtime.opencode_delete_session(&session_id);
+            }
+            let _ = runtime.git_remove_worktree(&repo_path, &worktree_path);
+            let _ = runtime.git_discard_branch(&repo_path, &branch);
+            Err(err)
+        }
+    }
+}
+
+/// Binds the fork to a server-side copy of the source session, or to a fresh
+/// session seeded with a summary when the server cannot fork. The new session
+/// is recorded in `created` as soon as it exists so a failure can delete it.
+fn fork_session(
+    db: &Database,
+    runtime: &impl CreateTaskRuntime,
+    task_id: Uuid,
+    source: &Task,
+    source_session: &str,
+    worktree_path: &Path,
+    created: &mut Option<String>,
+) -> Result<String> {
+    let session_id = match runtime.opencode_fork_session(source_session, worktree_path)? {
+        Some(session_id) => {
+            *created = Some(session_id.clone());
+            session_id
+        }
+        None => {
+            let messages = runtime
+                .opencode_session_messages(source_session)
+                .context("failed to read source session")?;
+            let session_id = runtime
+                .opencode_create_session(worktree_path, &source.title)
+                .context("failed to create OpenCode session")?;
+            *created = Some(session_id.clone());
+            runtime
+                .opencode_seed_session(&session_id, &fork_seed_summary(source, &messages))
+                .context("failed to seed OpenCode session")?;
+            session_id
+        }
+    };
+    db.update_task_session_binding(task_id, Some(session_id.clone()))
+        .context("failed to bind OpenCode session")?;
+    Ok(session_id)
+}
+
+/// Moves `task_id` directly after `source` in the source's column.
+fn place_after_source(db: &Database, source: &Task, task_id: Uuid) -> Result<()> {
+    let mut column: Vec<Task> = db
+        .list_tasks()?
+        .into_iter()
+        .filter(|task| task.category_id == source.category_id && task.id != task_id)
+        .collect();
+    column.sort_by_key(|task| task.position);
+
+    let mut ordered: Vec<Uuid> = column.iter().map(|task| task.id).collect();
+    let insert_at = ordered
+        .iter()
+        .position(|id| *id == source.id)
+        .map_or(ordered.len(), |idx| idx + 1);
+    ordered.insert(insert_at, task_id);
+
+    for (position, id) in ordered.into_iter().enumerate() {
+        db.update_task_position(id, position as i64)?;
+    }
+    Ok(())
+}
+
+/// Context handed to a fresh session standing in for a fork: where the work
+/// came from and the tail of the source conversation.
+fn fork_seed_summary(source: &Task, messages: &[SessionMessageItem]) -> String {
+    let mut summary = format!(
+        "This session continues the task \"{}\" (branch `{}`) from its current state; \
+         the worktree already contains its commits and uncommitted changes.",
+        source.title, source.branch
+    );
+
+    let recent: Vec<&SessionMessageItem> = messages
+        .iter()
+        .filter(|message| !message.content.trim().is_empty())
+        .collect();
+    let recent = &recent[recent.len().saturating_sub(SEED_MESSAGE_LIMIT)..];
+    if !recent.is_empty() {
+        summary.push_str("\n\nMost recent conversation:");
+        for message in recent {
+            let role = message.role.as_deref().unwrap_or("message");
+            let content = message.content.trim();
+            let mut excerpt: String = content.chars().take(SEED_MESSAGE_CHARS).collect();
+            if content.chars().count() > SEED_MESSAGE_CHARS {
+                excerpt.push('…');
+            }
+            summary.push_str(&format!("\n\n[{role}]\n{excerpt}"));
+        }
+    }
+
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::path::{Path, PathBuf};
+
+    use anyhow::Result;
+    use tempfile::TempDir;
+
+    use super::{fork_seed_summary, fork_task_pipeline_with_runtime, place_after_source};
+    use crate::app::runtime::CreateTaskRuntime;
+    use crate::app::state::{ForkTaskDialogState, ForkTaskField};
+    use crate::app::workflows::bind_initial_prompt_session;
+    use crate::db::Database;
+    use crate::types::{AgentSelection, Repo, SessionMessageItem, Task};
+    use uuid::Uuid;
+
+    /// Records what the pipeline created and removed; the server either
+    /// forks sessions or, with `fork_supported` off, only creates them.
+    /// `server_down` makes the readiness wait fail.
+    #[derive(Default)]
+    struct FakeForkRuntime {
+        fork_supported: bool,
+        fail_tmux: bool,
+        server_down: bool,
+        created_sessions: RefCell<Vec<String>>,
+        seeded_sessions: RefCell<Vec<String>>,
+        deleted_sessions: RefCell<Vec<String>>,
+        removed_worktrees: RefCell<Vec<PathBuf>>,
+        discarded_branches: RefCell<Vec<String>>,
+    }
+
+    impl CreateTaskRuntime for FakeForkRuntime {
+        fn git_is_valid_repo(&self, _path: &Path) -> bool {
+            true
+        }
+
+        fn git_resolve_repo_root(&self, path: &Path) -> Result<PathBuf> {
+            Ok(path.to_path_buf())
+        }
+
+        fn git_current_branch(&self, _path: &Path) -> Result<String> {
+            Ok("main".to_string())
+        }
+
+        fn git_detect_default_branch(&self, _repo_path: &Path) -> String {
+            "main".to_string()
+        }
+
+        fn git_fetch(&self, _repo_path: &Path) -> Result<()> {
+            Ok(())
+        }
+
+        fn git_validate_branch(&self, _repo_path: &Path, _branch_name: &str) -> Result<()> {
+            Ok(())
+        }
+
+        fn git_check_branch_up_to_date(&self, _repo_path: &Path, _base_ref: &str) -> Result<()> {
+            Ok(())
+        }
+
+        fn git_create_worktree(
+            &self,
+            _repo_path: &Path,
+            _worktree_path: &Path,
+            _branch_name: &str,
+            _base_ref: &str,
+        ) -> Result<()> {
+            Ok(())
+        }
+
+        fn git_remove_worktree(&self, _repo_path: &Path, worktree_path: &Path) -> Result<()> {
+            self.removed_worktrees
+                .borrow_mut()
+                .push(worktree_path.to_path_buf());
+            Ok(())
+        }
+
+        fn git_discard_branch(&self, _repo_path: &Path, branch_name: &str) -> Result<()> {
+            self.discarded_branches
+                .borrow_mut()
+                .push(branch_name.to_string());
+            Ok(())
+        }
+
+        fn git_head_commit(&self, _worktree_path: &Path) -> Result<String> {
+            Ok("abc123".to_string())
+        }
+
+        fn git_copy_uncommitted_changes(&self, _source: &Path, _target: &Path) -> Result<()> {
+            Ok(())
+        }
+
+        fn tmux_session_exists(&self, _session_name: &str) -> bool {
+            false
+        }
+
+        fn tmux_create_session(
+            &self,
+            _session_name: &str,
+            _working_dir: &Path,
+            _command: Option<&str>,
+            _env: &[(String, String)],
+        ) -> Result<()> {
+            if self.fail_tmux {
+                anyhow::bail!("tmux is gone");
+            }
+            Ok(())
+        }
+
+        fn tmux_kill_session(&self, _session_name: &str) -> Result<()> {
+            Ok(())
+        }
+
+        fn opencode_wait_for_server(&self) -> Result<()> {
+            if self.server_down {
+                anyhow::bail!("OpenCode server is not ready: timed out");
+            }
+            Ok(())
+        }
+
+        fn opencode_create_session(&self, _worktree_path: &Path, _title: &str) -> Result<String> {
+            self.created_sessions
+                .borrow_mut()
+                .push("ses-seeded".to_string());
+            Ok("ses-seeded".to_string())
+        }
+
+        fn opencode_send_prompt(
+            &self,
+            _session_id: &str,
+            _prompt: &str,
+            _selection: &AgentSelection,
+        ) -> Result<()> {
+            Ok(())
+        }
+
+        fn opencode_fork_session(
+            &self,
+            _session_id: &str,
+            _worktree_path: &Path,
+        ) -> Result<Option<String>> {
+            if !self.fork_supported {
+                return Ok(None);
+            }
+            self.created_sessions
+                .borrow_mut()
+                .push("ses-forked".to_string());
+            Ok(Some("ses-forked".to_string()))
+        }
+
+        fn opencode_seed_session(&self, session_id: &str, _text: &str) -> Result<()> {
+            self.seeded_sessions
+                .borrow_mut()
+                .push(session_id.to_string());
+            Ok(())
+        }
+
+        fn opencode_delete_session(&self, session_id: &str) -> Result<()> {
+            self.deleted_sessions
+                .borrow_mut()
+                .push(session_id.to_string());
+            Ok(())
+        }
+
+        fn opencode_session_messages(&self, _session_id: &str) -> Result<Vec<SessionMessageItem>> {
+            Ok(vec![message("user", "keep going")])
+        }
+    }
+
+    struct PipelineFixture {
+        db: Database,
+        repo: Repo,
+        source: Task,
+        _repo_dir: TempDir,
+        _source_worktree: TempDir,
+    }
+
+    fn pipeline_fixture() -> PipelineFixture {
+        let db = Database::open(":memory:").unwrap();
+        let repo_dir = TempDir::new().expect("temp repo dir");
+        let source_worktree = TempDir::new().expect("temp source worktree");
+        let repo = db.add_repo(repo_dir.path()).unwrap();
+        let category = db.list_categories().unwrap()[0].id;
+        let source = db
+            .add_task(repo.id, "feature/source", "Source", category)
+            .unwrap();
+        db.update_task_tmux(
+            source.id,
+            None,
+            Some(source_worktree.path().display().to_string()),
+        )
+        .unwrap();
+        db.update_task_session_binding(source.id, Some("ses-source".to_string()))
+            .unwrap();
+        let source = db.get_task(source.id).unwrap();
+        PipelineFixture {
+            db,
+            repo,
+            source,
+            _repo_dir: repo_dir,
+            _source_worktree: source_worktree,
+        }
+    }
+
+    fn fork_state(source: &Task) -> ForkTaskDialogState {
+        ForkTaskDialogState {
+            task_id: source.id,
+            task_title: source.title.clone(),
+            branch_input: "feature/fork".to_string(),
+            title_input: "Fork".to_string(),
+            focused_field: ForkTaskField::Fork,
+        }
+    }
+
+    fn message(role: &str, content: &str) -> SessionMessageItem {
+        SessionMessageItem {
+            id: None,
+            message_type: Some("text".to_string()),
+            role: Some(role.to_string()),
+            content: content.to_string(),
+            timestamp: None,
+            parts: Vec::new(),
+        }
+    }
+
+    fn source_task() -> Task {
+        Task {
+            id: Uuid::new_v4(),
+            title: "Ship fork".to_string(),
+            repo_id: Uuid::new_v4(),
+            branch: "feature/fork".to_string(),
+            category_id: Uuid::new_v4(),
+            position: 0,
+            tmux_session_name: None,
+            worktree_path: None,
+            tmux_status: "idle".to_string(),
+            status_source: "none".to_string(),
+            status_fetched_at: None,
+            status_error: None,
+            opencode_session_id: Some("ses-1".to_string()),
+            model: None,
+            agent: None,
+            forked_from: None,
+            backend: None,
+            attach_overlay_shown: false,
+            needs_inspection: false,
+            archived: false,
+            archived_at: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn fork_seed_summary_keeps_recent_messages_in_order() {
+        let mut messages: Vec<SessionMessageItem> = (0..8)
+            .map(|idx| message("user", &format!("step {idx}")))
+            .collect();
+        messages.push(message("assistant", ""));
+        messages.push(message("assistant", &"x".repeat(700)));
+
+        let summary = fork_seed_summary(&source_task(), &messages);
+
+        assert!(summary.contains("\"Ship fork\" (branch `feature/fork`)"));
+        assert!(!summary.contains("step 2"));
+        let step3 = summary.find("step 3").expect("oldest kept message");
+        let step7 = summary.find("step 7").expect("newest user message");
+        assert!(step3 < step7);
+        assert!(summary.contains(&format!("[assistant]\n{}…", "x".repeat(600))));
+    }
+
+    #[test]
+    fn fork_seed_summary_without_messages_only_describes_source() {
+        let summary = fork_seed_summary(&source_task(), &[]);
+        assert!(!summary.contains("Most recent conversation"));
+    }
+
+    #[test]
+    fn place_after_source_inserts_fork_next_to_original() {
+        let db = Database::open(":memory:").unwrap();
+        let repo_dir = tempfile::TempDir::new().expect("temp repo dir");
+        let repo = db.add_repo(repo_dir.path()).unwrap();
+        let category = db.list_categories().unwrap()[0].id;
+        let first = db.add_task(repo.id, "a", "A", category).unwrap();
+        let source = db.add_task(repo.id, "b", "B", category).unwrap();
+        let last = db.add_task(repo.id, "c", "C", category).unwrap();
+        let fork = db
+            .add_task(repo.id, "b-fork", "B (fork)", category)
+            .unwrap();
+
+        place_after_source(&db, &source, fork.id).unwrap();
+
+        let mut column = db.list_tasks().unwrap();
+        column.sort_by_key(|task| task.position);
+        let order: Vec<Uuid> = column.iter().map(|task| task.id).collect();
+        assert_eq!(order, vec![first.id, source.id, fork.id, last.id]);
+    }
+
+    #[test]
+    fn fork_pipeline_binds_seeded_session_when_server_cannot_fork() {
+        let fixture = pipeline_fixture();
+        let runtime = FakeForkRuntime::default();
+
+        let outcome = fork_task_pipeline_with_runtime(
+            &fixture.db,
+            &fixture.source,
+            &fixture.repo,
+            &fork_state(&fixture.source),
+            None,
+            &runtime,
+        )
+        .expect("fork should succeed");
+
+        assert_eq!(outcome.warning, None);
+        let fork = fixture.db.get_task(outcome.task_id).unwrap();
+        assert_eq!(fork.opencode_session_id.as_deref(), Some("ses-seeded"));
+        assert_eq!(fork.forked_from, Some(fixture.source.id));
+        assert_eq!(runtime.seeded_sessions.borrow().as_slice(), ["ses-seeded"]);
+        assert!(runtime.deleted_sessions.borrow().is_empty());
+    }
+
+    #[test]
+    fn fork_pipeline_rolls_back_everything_when_tmux_fails() {
+        for fork_supported in [true, false] {
+            let fixture = pipeline_fixture();
+            let runtime = FakeForkRuntime {
+                fork_supported,
+                fail_tmux: true,
+                ..FakeForkRuntime::default()
+            };
+
+            let result = fork_task_pipeline_with_runtime(
+                &fixture.db,
+                &fixture.source,
+                &fixture.repo,
+                &fork_state(&fixture.source),
+                None,
+                &runtime,
+            );
+
+            assert!(result.is_err());
+            let tasks = fixture.db.list_tasks().unwrap();
+            assert_eq!(tasks.len(), 1, "only the source task should remain");
+            assert_eq!(
+                runtime.deleted_sessions.borrow().as_slice(),
+                runtime.created_sessions.borrow().as_slice()
+            );
+            assert_eq!(runtime.deleted_sessions.borrow().len(), 1);
+            assert_eq!(runtime.removed_worktrees.borrow().len(), 1);
+            assert_eq!(
+                runtime.discarded_branches.borrow().as_slice(),
+                ["feature/fork"]
+            );
+        }
+    }
+
+    #[test]
+    fn bind_initial_prompt_session_waits_for_the_server() {
+        let fixture = pipeline_fixture();
+        let worktree = Path::new("/tmp/prompt-worktree");
+
+        let down = FakeForkRuntime {
+            server_down: true,
+            ..FakeForkRuntime::default()
+        };
+        let err =
+            bind_initial_prompt_session(&fixture.db, &down, fixture.source.id, worktree, "Prompt")
+                .expect_err("an unready server should fail the binding");
+        assert!(err.to_string().contains("not ready"));
+        assert!(down.created_sessions.borrow().is_empty());
+        let task = fixture.db.get_task(fixture.source.id).unwrap();
+        assert_eq!(task.opencode_session_id.as_deref(), Some("ses-source"));
+
+        let up = FakeForkRuntime::default();
+        let session_id =
+            bind_initial_prompt_session(&fixture.db, &up, fixture.source.id, worktree, "Prompt")
+                .expect("a ready server should bind the session");
+        assert_eq!(session_id, "ses-seeded");
+        let task = fixture.db.get_task(fixture.source.id).unwrap();
+        assert_eq!(task.opencode_session_id.as_deref(), Some("ses-seeded"));
+    }
+}
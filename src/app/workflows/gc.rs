@@ -0,0 +1,692 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::app::runtime::{GcRuntime, worktrees_root_for_repo};
+use crate::db::Database;
+use crate::projects::{self, DEFAULT_PROJECT};
+use crate::tmux::{project_session_prefix, repo_session_prefix};
+
+const KANBAN_SESSION_NAME: &str = "opencode-kanban";
+
+/// Worktrees touched more recently than this are left alone: task creation
+/// makes the worktree before the task row records its path.
+const ORPHAN_WORKTREE_MIN_AGE: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct OrphanWorktree {
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    pub repo_path: PathBuf,
+    pub path: PathBuf,
+    pub branch: Option<String>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MissingWorktreeTask {
+    pub task_id: Uuid,
+    pub title: String,
+    pub worktree_path: String,
+}
+
+/// Everything `gc` would touch, in both directions: resources no task
+/// references, and tasks whose worktree no longer exists.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct GcReport {
+    pub orphan_worktrees: Vec<OrphanWorktree>,
+    pub orphan_sessions: Vec<String>,
+    pub missing_worktree_tasks: Vec<MissingWorktreeTask>,
+}
+
+impl GcReport {
+    pub fn is_empty(&self) -> bool {
+        self.orphan_worktrees.is_empty()
+            && self.orphan_sessions.is_empty()
+            && self.missing_worktree_tasks.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum GcItemKind {
+    Worktree,
+    Branch,
+    Session,
+    Task,
+}
+
+impl GcItemKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Worktree => "worktree",
+            Self::Branch => "branch",
+            Self::Session => "session",
+            Self::Task => "task",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum GcAction {
+    Removed,
+    Adopted,
+    Deleted,
+    Killed,
+    Archived,
+}
+
+impl GcAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Removed => "removed",
+            Self::Adopted => "adopted",
+            Self::Deleted => "deleted",
+            Self::Killed => "killed",
+            Self::Archived => "archived",
+        }
+    }
+    pub fn verb(self) -> &'static str {
+        match self {
+            Self::Removed => "remove",
+            Self::Adopted => "adopt",
+            Self::Deleted => "delete",
+            Self::Killed => "kill",
+            Self::Archived => "archive",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GcOutcome {
+    pub kind: GcItemKind,
+    pub target: String,
+    pub action: GcAction,
+    pub error: Option<String>,
+}
+
+/// Opens every other project database so references from sibling boards
+/// keep their worktrees and sessions out of the orphan lists.
+pub(crate) fn open_other_project_dbs(current_path: &Path) -> Vec<(String, Database)> {
+    let current = normalize_path(current_path);
+    let projects = match projects::list_projects() {
+        Ok(projects) => projects,
+        Err(err) => {
+            warn!(error = %err, "failed to list projects for gc");
+            return Vec::new();
+        }
+    };
+
+    projects
+        .into_iter()
+        .filter(|project| normalize_path(&project.path) != current)
+        .filter_map(|project| match Database::open(&project.path) {
+            Ok(db) => Some((project.name, db)),
+            Err(err) => {
+                warn!(project = %project.name, error = %err, "failed to open project for gc");
+                None
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn scan_orphans(
+    db: &Database,
+    project_slug: Option<&str>,
+    other_projects: &[(String, Database)],
+    runtime: &impl GcRuntime,
+) -> Result<GcReport> {
+    let mut referenced_worktrees = HashSet::new();
+    let mut referenced_sessions = HashSet::new();
+    for project_db in std::iter::once(db).chain(other_projects.iter().map(|(_, db)| db)) {
+        let tasks = project_db
+            .list_tasks()?
+            .into_iter()
+            .chain(project_db.list_archived_tasks()?);
+        for task in tasks {
+            if let Some(path) = task.worktree_path.as_deref() {
+                referenced_worktrees.insert(normalize_path(Path::new(path)));
+            }
+            if let Some(session) = task.tmux_session_name {
+                referenced_sessions.insert(session);
+            }
+        }
+    }
+
+    let mut report = GcReport::default();
+
+    let repos = db.list_repos().context("failed to load repos")?;
+    for repo in &repos {
+        let repo_path = PathBuf::from(&repo.path);
+        if !runtime.path_exists(&repo_path) {
+            continue;
+        }
+        let root = normalize_path(&worktrees_root_for_repo(&repo_path));
+        let worktrees = match runtime.list_worktrees(&repo_path) {
+            Ok(worktrees) => worktrees,
+            Err(err) => {
+                warn!(repo = %repo.name, error = %err, "failed to list worktrees for gc");
+                continue;
+            }
+        };
+
+        for worktree in worktrees {
+            let path = normalize_path(&worktree.path);
+            if worktree.is_main || !path.starts_with(&root) || referenced_worktrees.contains(&path)
+            {
+                continue;
+            }
+            if runtime
+                .path_age(&worktree.path)
+                .is_some_and(|age| age < ORPHAN_WORKTREE_MIN_AGE)
+            {
+                continue;
+            }
+            report.orphan_worktrees.push(OrphanWorktree {
+                repo_id: repo.id,
+                repo_name: repo.name.clone(),
+                repo_path: repo_path.clone(),
+                path: worktree.path,
+                branch: worktree.branch,
+            });
+        }
+    }
+
+    // The default project's sessions carry no project prefix, so only
+    // sessions named after one of its repos are its own; anything else may
+    // belong to a project whose database could not be opened. Sessions
+    // prefixed with another project's slug are that project's.
+    let own_prefixes = repos
+        .iter()
+        .map(|repo| repo_session_prefix(project_slug, &repo.name))
+        .collect::<Vec<_>>();
+    let foreign_prefixes = if project_slug.is_none() {
+        other_projects
+            .iter()
+            .filter(|(name, _)| name != DEFAULT_PROJECT)
+            .map(|(name, _)| project_session_prefix(Some(name)))
+            .collect::<Vec<_>>()
+    } else {
+        Vec::new()
+    };
+    report.orphan_sessions = runtime
+        .list_project_sessions(project_slug)
+        .into_iter()
+        .filter(|name| name != KANBAN_SESSION_NAME)
+        .filter(|name| !referenced_sessions.contains(name))
+        .filter(|name| own_prefixes.iter().any(|prefix| name.starts_with(prefix)))
+        .filter(|name| {
+            !foreign_prefixes
+                .iter()
+                .any(|prefix| name.starts_with(prefix))
+        })
+        .collect();
+    report.orphan_sessions.sort();
+
+    for task in db.list_tasks().context("failed to load tasks")? {
+        let Some(worktree_path) = task.worktree_path else {
+            continue;
+        };
+        if !runtime.path_exists(Path::new(&worktree_path)) {
+            report.missing_worktree_tasks.push(MissingWorktreeTask {
+                task_id: task.id,
+                title: task.title,
+                worktree_path,
+            });
+        }
+    }
+
+    Ok(report)
+}
+
+/// What `apply_gc` may do beyond removing clean orphans and killing sessions.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct GcOptions {
+    /// Turn orphan worktrees into tasks instead of removing them.
+    pub adopt_worktrees: bool,
+    /// Remove orphan worktrees even when that discards uncommitted changes.
+    pub force: bool,
+    /// Archive tasks whose worktree no longer exists.
+    pub archive_missing: bool,
+}
+
+/// Applies a scan: orphan worktrees are removed together with their branch
+/// (or adopted as new tasks) and orphan sessions are killed. A worktree with
+/// uncommitted changes fails unless `force` is set, and tasks whose worktree
+/// vanished are only archived with `archive_missing`. Failures are recorded
+/// per item.
+pub(crate) fn apply_gc(
+    db: &Database,
+    report: &GcReport,
+    options: GcOptions,
+    runtime: &impl GcRuntime,
+) -> Vec<GcOutcome> {
+    let mut outcomes = Vec::new();
+
+    for orphan in &report.orphan_worktrees {
+        let target = orphan.path.display().to_string();
+        if options.adopt_worktrees {
+            outcomes.push(GcOutcome {
+                kind: GcItemKind::Worktree,
+                target,
+                action: GcAction::Adopted,
+                error: adopt_worktree(db, orphan)
+                    .err()
+                    .map(|err| format!("{err:#}")),
+            });
+            continue;
+        }
+
+        let removed = runtime.remove_worktree(&orphan.repo_path, &orphan.path, options.force);
+        let removed_ok = removed.is_ok();
+        outcomes.push(GcOutcome {
+            kind: GcItemKind::Worktree,
+            target,
+            action: GcAction::Removed,
+            error: removed.err().map(|err| format!("{err:#}")),
+        });
+
+        if let (true, Some(branch)) = (removed_ok, orphan.branch.as_deref()) {
+            outcomes.push(GcOutcome {
+                kind: GcItemKind::Branch,
+                target: format!("{}:{branch}", orphan.repo_name),
+                action: GcAction::Deleted,
+                error: runtime
+                    .delete_branch(&orphan.repo_path, branch)
+                    .err()
+                    .map(|err| format!("{err:#}")),
+            });
+        }
+    }
+
+    for session in &report.orphan_sessions {
+        outcomes.push(GcOutcome {
+            kind: GcItemKind::Session,
+            target: session.clone(),
+            action: GcAction::Killed,
+            error: runtime
+                .kill_session(session)
+                .err()
+                .map(|err| format!("{err:#}")),
+        });
+    }
+
+    if options.archive_missing {
+        for task in &report.missing_worktree_tasks {
+            outcomes.push(GcOutcome {
+                kind: GcItemKind::Task,
+                target: task.title.clone(),
+                action: GcAction::Archived,
+                error: db
+                    .archive_task(task.task_id)
+                    .err()
+                    .map(|err| format!("{err:#}")),
+            });
+        }
+    }
+
+    outcomes
+}
+
+fn adopt_worktree(db: &Database, orphan: &OrphanWorktree) -> Result<()> {
+    let Some(branch) = orphan.branch.as_deref() else {
+        anyhow::bail!("worktree has a detached HEAD; check out a branch before adopting it");
+    };
+    let category = db
+        .list_categories()?
+        .into_iter()
+        .min_by_key(|category| category.position)
+        .context("project has no categories to adopt into")?;
+
+    let task = db.add_task(orphan.repo_id, branch, branch, category.id)?;
+    db.update_task_tmux(task.id, None, Some(orphan.path.display().to_string()))?;
+    Ok(())
+}
+
+fn normalize_path(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::git::GitWorktree;
+    use crate::tmux::sanitize_session_name;
+
+    #[derive(Default)]
+    struct FakeGcRuntime {
+        worktrees: Vec<GitWorktree>,
+        sessions: Vec<String>,
+        path_age: Option<Duration>,
+        dirty: Vec<PathBuf>,
+        removed: RefCell<Vec<PathBuf>>,
+        deleted_branches: RefCell<Vec<String>>,
+        killed: RefCell<Vec<String>>,
+    }
+
+    impl GcRuntime for FakeGcRuntime {
+        fn list_worktrees(&self, _repo_path: &Path) -> Result<Vec<GitWorktree>> {
+            Ok(self.worktrees.clone())
+        }
+
+        fn list_project_sessions(&self, _project_slug: Option<&str>) -> Vec<String> {
+            self.sessions.clone()
+        }
+
+        fn path_exists(&self, path: &Path) -> bool {
+            path.exists()
+        }
+
+        fn path_age(&self, _path: &Path) -> Option<Duration> {
+            self.path_age
+        }
+
+        fn remove_worktree(
+            &self,
+            _repo_path: &Path,
+            worktree_path: &Path,
+            force: bool,
+        ) -> Result<()> {
+            if !force && self.dirty.iter().any(|path| path == worktree_path) {
+                anyhow::bail!("worktree has uncommitted changes");
+            }
+            self.removed.borrow_mut().push(worktree_path.to_path_buf());
+            Ok(())
+        }
+
+        fn delete_branch(&self, _repo_path: &Path, branch_name: &str) -> Result<()> {
+            self.deleted_branches
+                .borrow_mut()
+                .push(branch_name.to_string());
+            Ok(())
+        }
+
+        fn kill_session(&self, session_name: &str) -> Result<()> {
+            self.killed.borrow_mut().push(session_name.to_string());
+            Ok(())
+        }
+    }
+
+    struct Fixture {
+        db: Database,
+        repo_dir: TempDir,
+        repo_id: Uuid,
+        repo_name: String,
+        worktree_root: PathBuf,
+    }
+
+    fn fixture() -> Fixture {
+        let repo_dir = TempDir::new().expect("temp repo dir");
+        let db = Database::open(":memory:").expect("db should open");
+        let repo = db.add_repo(repo_dir.path()).expect("repo should save");
+        let worktree_root = worktrees_root_for_repo(repo_dir.path());
+        Fixture {
+            db,
+            repo_dir,
+            repo_id: repo.id,
+            repo_name: repo.name,
+            worktree_root,
+        }
+    }
+
+    fn worktree(path: PathBuf, branch: &str, is_main: bool) -> GitWorktree {
+        GitWorktree {
+            path,
+            branch: Some(branch.to_string()),
+            is_main,
+        }
+    }
+
+    #[test]
+    fn scan_orphans_reports_each_direction() {
+        let fixture = fixture();
+        let category = fixture.db.list_categories().expect("categories")[0].id;
+        let kept_path = fixture.worktree_root.join("gc-test-repo").join("kept");
+        let kept_session = sanitize_session_name(&fixture.repo_name, "kept");
+        let stray_session = sanitize_session_name(&fixture.repo_name, "stray");
+        let task = fixture
+            .db
+            .add_task(fixture.repo_id, "kept", "Kept task", category)
+            .expect("task should save");
+        fixture
+            .db
+            .update_task_tmux(
+                task.id,
+                Some(kept_session.clone()),
+                Some(kept_path.display().to_string()),
+            )
+            .expect("task tmux should save");
+
+        let runtime = FakeGcRuntime {
+            worktrees: vec![
+                worktree(fixture.repo_dir.path().to_path_buf(), "main", true),
+                worktree(kept_path.clone(), "kept", false),
+                worktree(
+                    fixture.worktree_root.join("gc-test-repo").join("stray"),
+                    "stray",
+                    false,
+                ),
+                worktree(PathBuf::from("/elsewhere/manual"), "manual", false),
+            ],
+            sessions: vec![
+                "opencode-kanban".to_string(),
+                kept_session,
+                stray_session.clone(),
+            ],
+            ..FakeGcRuntime::default()
+        };
+
+        let report = scan_orphans(&fixture.db, None, &[], &runtime).expect("scan should work");
+
+        assert_eq!(report.orphan_worktrees.len(), 1);
+        assert_eq!(report.orphan_worktrees[0].branch.as_deref(), Some("stray"));
+        assert_eq!(report.orphan_sessions, vec![stray_session]);
+        assert_eq!(report.missing_worktree_tasks.len(), 1);
+        assert_eq!(report.missing_worktree_tasks[0].task_id, task.id);
+    }
+
+    #[test]
+    fn scan_orphans_keeps_resources_referenced_by_other_projects() {
+        let fixture = fixture();
+        let other = Database::open(":memory:").expect("other db should open");
+        let other_repo = other
+            .add_repo(fixture.repo_dir.path())
+            .expect("repo should save");
+        let category = other.list_categories().expect("categories")[0].id;
+        let shared_path = fixture.worktree_root.join("gc-test-repo").join("shared");
+        let task = other
+            .add_task(other_repo.id, "shared", "Shared", category)
+            .expect("task should save");
+        other
+            .update_task_tmux(
+                task.id,
+                Some("ok-beta-repo-shared".to_string()),
+                Some(shared_path.display().to_string()),
+            )
+            .expect("task tmux should save");
+
+        let runtime = FakeGcRuntime {
+            worktrees: vec![worktree(shared_path, "shared", false)],
+            sessions: vec!["ok-beta-repo-other".to_string()],
+            ..FakeGcRuntime::default()
+        };
+
+        let report = scan_orphans(&fixture.db, None, &[("beta".to_string(), other)], &runtime)
+            .expect("scan should work");
+
+        assert!(report.is_empty());
+    }
+
+    #[test]
+    fn scan_orphans_ignores_sessions_of_projects_without_a_readable_database() {
+        let fixture = fixture();
+        // "ghost" has no database to open, and "my.proj" is sanitized in its
+        // session names.
+        let other = Database::open(":memory:").expect("other db should open");
+        let runtime = FakeGcRuntime {
+            sessions: vec![
+                "ok-ghost-repo-main".to_string(),
+                "ok-my-proj-repo-main".to_string(),
+                sanitize_session_name(&fixture.repo_name, "stray"),
+            ],
+            ..FakeGcRuntime::default()
+        };
+
+        let report = scan_orphans(
+            &fixture.db,
+            None,
+            &[("my.proj".to_string(), other)],
+            &runtime,
+        )
+        .expect("scan should work");
+
+        assert_eq!(
+            report.orphan_sessions,
+            vec![sanitize_session_name(&fixture.repo_name, "stray")]
+        );
+    }
+
+    #[test]
+    fn apply_gc_removes_or_adopts_worktrees() {
+        let fixture = fixture();
+        let orphan = OrphanWorktree {
+            repo_id: fixture.repo_id,
+            repo_name: "repo".to_string(),
+            repo_path: fixture.repo_dir.path().to_path_buf(),
+            path: fixture.worktree_root.join("repo").join("stray"),
+            branch: Some("stray".to_string()),
+        };
+        let report = GcReport {
+            orphan_worktrees: vec![orphan],
+            orphan_sessions: vec!["ok-repo-stray".to_string()],
+            missing_worktree_tasks: Vec::new(),
+        };
+
+        let runtime = FakeGcRuntime::default();
+        let outcomes = apply_gc(&fixture.db, &report, GcOptions::default(), &runtime);
+        assert_eq!(outcomes.len(), 3);
+        assert!(outcomes.iter().all(|outcome| outcome.error.is_none()));
+        assert_eq!(runtime.deleted_branches.borrow().as_slice(), ["stray"]);
+        assert_eq!(runtime.killed.borrow().as_slice(), ["ok-repo-stray"]);
+
+        let adopt_runtime = FakeGcRuntime::default();
+        let adopt = GcOptions {
+            adopt_worktrees: true,
+            ..GcOptions::default()
+        };
+        let outcomes = apply_gc(&fixture.db, &report, adopt, &adopt_runtime);
+        assert_eq!(outcomes[0].action, GcAction::Adopted);
+        assert!(adopt_runtime.removed.borrow().is_empty());
+        let adopted = fixture
+            .db
+            .list_tasks()
+            .expect("tasks")
+            .into_iter()
+            .find(|task| task.branch == "stray")
+            .expect("adopted task should exist");
+        assert!(
+            adopted
+                .worktree_path
+                .expect("worktree path")
+                .ends_with("stray")
+        );
+    }
+
+    #[test]
+    fn apply_gc_keeps_dirty_worktrees_unless_forced() {
+        let fixture = fixture();
+        let path = fixture.worktree_root.join("repo").join("dirty");
+        let report = GcReport {
+            orphan_worktrees: vec![OrphanWorktree {
+                repo_id: fixture.repo_id,
+                repo_name: "repo".to_string(),
+                repo_path: fixture.repo_dir.path().to_path_buf(),
+                path: path.clone(),
+                branch: Some("dirty".to_string()),
+            }],
+            ..GcReport::default()
+        };
+        let runtime = FakeGcRuntime {
+            dirty: vec![path.clone()],
+            ..FakeGcRuntime::default()
+        };
+
+        let outcomes = apply_gc(&fixture.db, &report, GcOptions::default(), &runtime);
+        assert_eq!(outcomes.len(), 1);
+        assert!(outcomes[0].error.is_some());
+        assert!(runtime.removed.borrow().is_empty());
+        assert!(runtime.deleted_branches.borrow().is_empty());
+
+        let force = GcOptions {
+            force: true,
+            ..GcOptions::default()
+        };
+        let outcomes = apply_gc(&fixture.db, &report, force, &runtime);
+        assert!(outcomes.iter().all(|outcome| outcome.error.is_none()));
+        assert_eq!(runtime.removed.borrow().as_slice(), [path]);
+    }
+
+    #[test]
+    fn apply_gc_archives_missing_worktree_tasks_only_when_asked() {
+        let fixture = fixture();
+        let category = fixture.db.list_categories().expect("categories")[0].id;
+        let task = fixture
+            .db
+            .add_task(fixture.repo_id, "gone", "Gone", category)
+            .expect("task should save");
+        let report = GcReport {
+            missing_worktree_tasks: vec![MissingWorktreeTask {
+                task_id: task.id,
+                title: task.title.clone(),
+                worktree_path: "/missing/gone".to_string(),
+            }],
+            ..GcReport::default()
+        };
+        let runtime = FakeGcRuntime::default();
+
+        assert!(apply_gc(&fixture.db, &report, GcOptions::default(), &runtime).is_empty());
+        assert!(
+            fixture
+                .db
+                .list_archived_tasks()
+                .expect("archived")
+                .is_empty()
+        );
+
+        let archive = GcOptions {
+            archive_missing: true,
+            ..GcOptions::default()
+        };
+        let outcomes = apply_gc(&fixture.db, &report, archive, &runtime);
+        assert_eq!(outcomes[0].action, GcAction::Archived);
+        assert_eq!(
+            fixture.db.list_archived_tasks().expect("archived")[0].id,
+            task.id
+        );
+    }
+
+    #[test]
+    fn scan_orphans_skips_recently_created_worktrees() {
+        let fixture = fixture();
+        let runtime = FakeGcRuntime {
+            worktrees: vec![worktree(
+                fixture.worktree_root.join("gc-test-repo").join("fresh"),
+                "fresh",
+                false,
+            )],
+            path_age: Some(Duration::from_secs(5)),
+            ..FakeGcRuntime::default()
+        };
+
+        let report = scan_orphans(&fixture.db, None, &[], &runtime).expect("scan should work");
+
+        assert!(report.orphan_worktrees.is_empty());
+    }
+}
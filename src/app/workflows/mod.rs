@@ -1,13 +1,16 @@
 mod attach;
 mod create_task;
 mod errors;
+mod fork_task;
+mod gc;
 mod recovery;
 
 pub(crate) use attach::{attach_task_with_runtime, open_task_in_new_terminal_with_runtime};
 #[cfg(test)]
 pub(crate) use attach::{build_attach_popup_lines, popup_style_from_theme, tmux_hex_color};
 pub(crate) use create_task::{
-    create_task_pipeline_with_runtime, rank_repos_for_query, repo_selection_usage_map,
+    bind_initial_prompt_session, create_task_pipeline_with_runtime, rank_repos_for_query,
+    repo_selection_usage_map,
 };
 #[cfg(test)]
 pub(crate) use create_task::{
@@ -16,4 +19,9 @@ pub(crate) use create_task::{
 pub(crate) use errors::create_task_error_dialog_state;
 #[cfg(test)]
 pub(crate) use errors::parse_existing_branch_name;
+pub(crate) use fork_task::fork_task_pipeline_with_runtime;
+pub use gc::{
+    GcAction, GcItemKind, GcOptions, GcOutcome, GcReport, MissingWorktreeTask, OrphanWorktree,
+};
+pub(crate) use gc::{apply_gc, open_other_project_dbs, scan_orphans};
 pub(crate) use recovery::reconcile_startup_tasks;
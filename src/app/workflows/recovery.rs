@@ -65,17 +65,15 @@ fn reconcile_desired_vs_observed(
         return Status::Idle.as_str().to_string();
     }
 
-    observed
-        .session_status
-        .as_ref()
-        .map(|status| status.state.as_str().to_string())
-        .unwrap_or_else(|| {
-            if SessionState::from_raw_status(current_status) == SessionState::Running {
-                Status::Running.as_str().to_string()
-            } else {
-                Status::Idle.as_str().to_string()
-            }
-        })
+    let current = SessionState::from_raw_status(current_status);
+    let state = match observed.session_status.as_ref().map(|status| status.state) {
+        // A failed session reports idle; the failure stays until the next run.
+        Some(SessionState::Idle) if current == SessionState::Error => SessionState::Error,
+        Some(state) => state,
+        None if matches!(current, SessionState::Running | SessionState::Error) => current,
+        None => SessionState::Idle,
+    };
+    state.as_str().to_string()
 }
 
 pub(crate) fn reconcile_startup_tasks(
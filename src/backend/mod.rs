@@ -0,0 +1,165 @@
+//! Agents that can run inside a task's tmux session.
+//!
+//! OpenCode is the default backend. The `[backend]` table in `settings.toml`
+//! is registered once at startup with [`configure`] and picks the backend for
+//! new tasks; a task records its backend so later restarts and status polls
+//! keep using the agent it was created with.
+
+use std::sync::RwLock;
+
+use anyhow::Result;
+
+use crate::opencode::StatusProvider;
+use crate::opencode::status_server::SessionMessageLog;
+use crate::settings::BackendSettings;
+use crate::types::{AgentSelection, SessionTodoItem, Task};
+
+pub mod opencode;
+pub mod shell;
+
+pub use opencode::OpenCodeBackend;
+pub use shell::{ProcessStatusProvider, ShellBackend};
+
+static CONFIGURED: RwLock<Option<BackendSettings>> = RwLock::new(None);
+
+/// Registers the `[backend]` settings used for every later task.
+pub fn configure(settings: BackendSettings) {
+    match CONFIGURED.write() {
+        Ok(mut configured) => *configured = Some(settings),
+        Err(poisoned) => *poisoned.into_inner() = Some(settings),
+    }
+}
+
+fn configured() -> BackendSettings {
+    match CONFIGURED.read() {
+        Ok(configured) => configured.clone(),
+        Err(poisoned) => poisoned.into_inner().clone(),
+    }
+    .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgentBackendKind {
+    OpenCode,
+    Shell,
+}
+
+impl AgentBackendKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AgentBackendKind::OpenCode => "opencode",
+            AgentBackendKind::Shell => "shell",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "opencode" => Some(AgentBackendKind::OpenCode),
+            "shell" => Some(AgentBackendKind::Shell),
+            _ => None,
+        }
+    }
+
+    /// The value stored in `tasks.backend`; OpenCode tasks leave it empty.
+    pub fn task_value(self) -> Option<String> {
+        match self {
+            AgentBackendKind::OpenCode => None,
+            kind => Some(kind.as_str().to_string()),
+        }
+    }
+}
+
+/// Where and how an agent is started for a task.
+#[derive(Debug, Clone, Copy)]
+pub struct AgentLaunch<'a> {
+    pub session_id: Option<&'a str>,
+    pub worktree_dir: Option<&'a str>,
+    pub selection: &'a AgentSelection,
+}
+
+pub trait AgentBackend: Send + Sync {
+    fn kind(&self) -> AgentBackendKind;
+
+    /// Command a new task's tmux session starts with.
+    fn launch_command(&self, launch: &AgentLaunch<'_>) -> String;
+
+    /// Command that brings the agent back when a task's tmux session is
+    /// recreated.
+    fn attach_command(&self, launch: &AgentLaunch<'_>) -> String;
+
+    fn status_provider(&self) -> &dyn StatusProvider;
+
+    /// Identifier [`Self::status_provider`] knows the task by.
+    fn status_key(&self, task: &Task) -> Option<String>;
+
+    /// Todo list of `session_id`; empty for agents without sessions.
+    fn fetch_todos(&self, session_id: &str) -> Result<Vec<SessionTodoItem>>;
+
+    /// Messages of `session_id` with the token usage they reported; empty
+    /// for agents without sessions.
+    fn fetch_messages(&self, session_id: &str) -> Result<SessionMessageLog>;
+
+    /// Whether the agent has server-side sessions: prompts, todos, message
+    /// logs, forks and reverts.
+    fn supports_sessions(&self) -> bool {
+        true
+    }
+}
+
+pub fn backend_for_kind(kind: AgentBackendKind) -> Box<dyn AgentBackend> {
+    match kind {
+        AgentBackendKind::OpenCode => Box::new(OpenCodeBackend::default()),
+        AgentBackendKind::Shell => Box::new(ShellBackend::new(
+            configured()
+                .shell_command
+                .unwrap_or_else(shell::default_shell_command),
+        )),
+    }
+}
+
+/// The backend new tasks are created with.
+pub fn default_backend() -> Box<dyn AgentBackend> {
+    backend_for_kind(default_backend_kind())
+}
+
+pub fn default_backend_kind() -> AgentBackendKind {
+    configured()
+        .kind
+        .as_deref()
+        .and_then(AgentBackendKind::parse)
+        .unwrap_or(AgentBackendKind::OpenCode)
+}
+
+pub fn task_backend_kind(task: &Task) -> AgentBackendKind {
+    task.backend
+        .as_deref()
+        .and_then(AgentBackendKind::parse)
+        .unwrap_or(AgentBackendKind::OpenCode)
+}
+
+/// The backend a task was created with.
+pub fn backend_for_task(task: &Task) -> Box<dyn AgentBackend> {
+    backend_for_kind(task_backend_kind(task))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AgentBackendKind;
+
+    #[test]
+    fn backend_kind_round_trips_and_opencode_is_stored_as_empty() {
+        for kind in [AgentBackendKind::OpenCode, AgentBackendKind::Shell] {
+            assert_eq!(AgentBackendKind::parse(kind.as_str()), Some(kind));
+        }
+        assert_eq!(
+            AgentBackendKind::parse(" Shell "),
+            Some(AgentBackendKind::Shell)
+        );
+        assert_eq!(AgentBackendKind::parse("claude"), None);
+        assert_eq!(AgentBackendKind::OpenCode.task_value(), None);
+        assert_eq!(
+            AgentBackendKind::Shell.task_value().as_deref(),
+            Some("shell")
+        );
+    }
+}
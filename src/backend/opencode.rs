@@ -0,0 +1,48 @@
+//! The OpenCode backend: tmux sessions attach to the shared OpenCode server,
+//! which reports status, todos and messages per session.
+
+use anyhow::Result;
+
+use super::{AgentBackend, AgentBackendKind, AgentLaunch};
+use crate::opencode::status_server::SessionMessageLog;
+use crate::opencode::{
+    ServerStatusProvider, StatusProvider, block_on_opencode, opencode_attach_command,
+};
+use crate::types::{SessionTodoItem, Task};
+
+#[derive(Debug, Clone, Default)]
+pub struct OpenCodeBackend {
+    server: ServerStatusProvider,
+}
+
+impl AgentBackend for OpenCodeBackend {
+    fn kind(&self) -> AgentBackendKind {
+        AgentBackendKind::OpenCode
+    }
+
+    /// New sessions attach to the server too, so launching and attaching
+    /// run the same command.
+    fn launch_command(&self, launch: &AgentLaunch<'_>) -> String {
+        self.attach_command(launch)
+    }
+
+    fn attach_command(&self, launch: &AgentLaunch<'_>) -> String {
+        opencode_attach_command(launch.session_id, launch.worktree_dir, launch.selection)
+    }
+
+    fn status_provider(&self) -> &dyn StatusProvider {
+        &self.server
+    }
+
+    fn status_key(&self, task: &Task) -> Option<String> {
+        task.opencode_session_id.clone()
+    }
+
+    fn fetch_todos(&self, session_id: &str) -> Result<Vec<SessionTodoItem>> {
+        block_on_opencode(self.server.fetch_session_todo(session_id))
+    }
+
+    fn fetch_messages(&self, session_id: &str) -> Result<SessionMessageLog> {
+        block_on_opencode(self.server.fetch_session_message_log(session_id))
+    }
+}
@@ -0,0 +1,174 @@
+//! A backend that runs an arbitrary shell command in the task's tmux session.
+//! It has no sessions to query, so a task is running for as long as the
+//! command's process is alive.
+
+use std::env;
+use std::process::Command;
+use std::time::SystemTime;
+
+use anyhow::Result;
+
+use super::{AgentBackend, AgentBackendKind, AgentLaunch};
+use crate::opencode::StatusProvider;
+use crate::opencode::status_server::SessionMessageLog;
+use crate::tmux::tmux_get_pane_pid;
+use crate::types::{SessionState, SessionStatus, SessionStatusSource, SessionTodoItem, Task};
+
+/// Used when `backend.shell_command` is unset, e.g. for a shell task whose
+/// settings have since changed.
+pub fn default_shell_command() -> String {
+    env::var("SHELL")
+        .ok()
+        .filter(|shell| !shell.trim().is_empty())
+        .unwrap_or_else(|| "sh".to_string())
+}
+
+#[derive(Debug, Clone)]
+pub struct ShellBackend {
+    command: String,
+    status: ProcessStatusProvider,
+}
+
+impl ShellBackend {
+    pub fn new(command: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+            status: ProcessStatusProvider::default(),
+        }
+    }
+}
+
+impl AgentBackend for ShellBackend {
+    fn kind(&self) -> AgentBackendKind {
+        AgentBackendKind::Shell
+    }
+
+    fn launch_command(&self, _launch: &AgentLaunch<'_>) -> String {
+        self.command.clone()
+    }
+
+    /// The command keeps no state between runs, so it is simply started again.
+    fn attach_command(&self, launch: &AgentLaunch<'_>) -> String {
+        self.launch_command(launch)
+    }
+
+    fn status_provider(&self) -> &dyn StatusProvider {
+        &self.status
+    }
+
+    fn status_key(&self, task: &Task) -> Option<String> {
+        task.tmux_session_name.clone()
+    }
+
+    fn fetch_todos(&self, _session_id: &str) -> Result<Vec<SessionTodoItem>> {
+        Ok(Vec::new())
+    }
+
+    fn fetch_messages(&self, _session_id: &str) -> Result<SessionMessageLog> {
+        Ok(SessionMessageLog::default())
+    }
+
+    fn supports_sessions(&self) -> bool {
+        false
+    }
+}
+
+/// Reports a tmux session as running while its pane process is alive and
+/// idle once it has exited.
+#[derive(Debug, Clone, Copy)]
+pub struct ProcessStatusProvider {
+    pane_pid: fn(&str) -> Option<u32>,
+    process_alive: fn(u32) -> bool,
+}
+
+impl Default for ProcessStatusProvider {
+    fn default() -> Self {
+        Self::new(tmux_get_pane_pid, process_alive)
+    }
+}
+
+impl ProcessStatusProvider {
+    pub fn new(pane_pid: fn(&str) -> Option<u32>, process_alive: fn(u32) -> bool) -> Self {
+        Self {
+            pane_pid,
+            process_alive,
+        }
+    }
+}
+
+impl StatusProvider for ProcessStatusProvider {
+    fn get_status(&self, tmux_session_name: &str) -> SessionStatus {
+        let alive = (self.pane_pid)(tmux_session_name).is_some_and(self.process_alive);
+        SessionStatus {
+            state: if alive {
+                SessionState::Running
+            } else {
+                SessionState::Idle
+            },
+            source: SessionStatusSource::Process,
+            fetched_at: SystemTime::now(),
+            error: None,
+        }
+    }
+}
+
+/// Whether `pid` exists and is not a zombie.
+fn process_alive(pid: u32) -> bool {
+    Command::new("ps")
+        .args(["-p", &pid.to_string(), "-o", "stat="])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .is_some_and(|stat| !stat.is_empty() && !stat.starts_with('Z'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AgentBackend, ProcessStatusProvider, ShellBackend};
+    use crate::backend::AgentLaunch;
+    use crate::opencode::StatusProvider;
+    use crate::types::{AgentSelection, SessionState, SessionStatusSource};
+
+    fn pane_pid(session_name: &str) -> Option<u32> {
+        match session_name {
+            "ok-live" => Some(10),
+            "ok-exited" => Some(20),
+            _ => None,
+        }
+    }
+
+    fn process_alive(pid: u32) -> bool {
+        pid == 10
+    }
+
+    #[test]
+    fn process_status_follows_pane_process_liveness() {
+        let provider = ProcessStatusProvider::new(pane_pid, process_alive);
+
+        let live = provider.get_status("ok-live");
+        assert_eq!(live.state, SessionState::Running);
+        assert_eq!(live.source, SessionStatusSource::Process);
+        assert_eq!(live.error, None);
+
+        assert_eq!(provider.get_status("ok-exited").state, SessionState::Idle);
+        assert_eq!(provider.get_status("ok-missing").state, SessionState::Idle);
+    }
+
+    #[test]
+    fn shell_backend_runs_configured_command_without_sessions() {
+        let backend = ShellBackend::new("aider --yes");
+        let selection = AgentSelection::default();
+        let launch = AgentLaunch {
+            session_id: Some("ses-1"),
+            worktree_dir: Some("/tmp/wt"),
+            selection: &selection,
+        };
+
+        assert_eq!(backend.launch_command(&launch), "aider --yes");
+        assert_eq!(backend.attach_command(&launch), "aider --yes");
+        assert!(!backend.supports_sessions());
+        assert!(backend.fetch_todos("ses-1").unwrap().is_empty());
+        assert!(backend.fetch_messages("ses-1").unwrap().messages.is_empty());
+    }
+}
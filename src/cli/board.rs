@@ -0,0 +1,160 @@
+use std::fmt::Write as _;
+
+use anyhow::{Context, Result};
+use tuirealm::ratatui::{
+    Terminal,
+    backend::TestBackend,
+    buffer::{Buffer, Cell},
+    style::{Color, Modifier},
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::app::App;
+use crate::ui;
+
+pub(super) fn render_app_buffer(app: &mut App, width: u16, height: u16) -> Result<Buffer> {
+    let mut terminal =
+        Terminal::new(TestBackend::new(width, height)).context("failed to create test backend")?;
+    terminal
+        .draw(|frame| ui::render(frame, app))
+        .context("failed to render board")?;
+    Ok(terminal.backend().buffer().clone())
+}
+
+pub(super) fn buffer_to_plain_lines(buffer: &Buffer) -> Vec<String> {
+    buffer_rows(buffer)
+        .map(|row| {
+            let mut line = String::new();
+            for cell in row {
+                line.push_str(cell.symbol());
+            }
+            line.trim_end().to_string()
+        })
+        .collect()
+}
+
+pub(super) fn buffer_to_ansi_lines(buffer: &Buffer) -> Vec<String> {
+    buffer_rows(buffer)
+        .map(|row| {
+            let mut line = String::new();
+            let mut current: Option<(Color, Color, Modifier)> = None;
+            for cell in row {
+                let style = (cell.fg, cell.bg, cell.modifier);
+                if current != Some(style) {
+                    line.push_str(&sgr_sequence(cell));
+                    current = Some(style);
+                }
+                line.push_str(cell.symbol());
+            }
+            line.push_str("\x1b[0m");
+            line
+        })
+        .collect()
+}
+
+// Wide graphemes occupy several cells; the trailing cells only hold padding
+// and are skipped so each row prints at its on-screen width.
+fn buffer_rows(buffer: &Buffer) -> impl Iterator<Item = Vec<&Cell>> {
+    let width = usize::from(buffer.area.width);
+    buffer.content.chunks(width.max(1)).map(|row| {
+        let mut cells = Vec::with_capacity(row.len());
+        let mut skip = 0usize;
+        for cell in row {
+            if skip > 0 {
+                skip -= 1;
+                continue;
+            }
+            skip = cell.symbol().width().saturating_sub(1);
+            cells.push(cell);
+        }
+        cells
+    })
+}
+
+fn sgr_sequence(cell: &Cell) -> String {
+    let mut codes = vec!["0".to_string()];
+    for (modifier, code) in [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::SLOW_BLINK, "5"),
+        (Modifier::RAPID_BLINK, "6"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::HIDDEN, "8"),
+        (Modifier::CROSSED_OUT, "9"),
+    ] {
+        if cell.modifier.contains(modifier) {
+            codes.push(code.to_string());
+        }
+    }
+    if let Some(code) = color_code(cell.fg, false) {
+        codes.push(code);
+    }
+    if let Some(code) = color_code(cell.bg, true) {
+        codes.push(code);
+    }
+
+    let mut sequence = String::from("\x1b[");
+    let _ = write!(sequence, "{}m", codes.join(";"));
+    sequence
+}
+
+fn color_code(color: Color, background: bool) -> Option<String> {
+    let base = if background { 40 } else { 30 };
+    let code = match color {
+        Color::Reset => return None,
+        Color::Black => base,
+        Color::Red => base + 1,
+        Color::Green => base + 2,
+        Color::Yellow => base + 3,
+        Color::Blue => base + 4,
+        Color::Magenta => base + 5,
+        Color::Cyan => base + 6,
+        Color::Gray => base + 7,
+        Color::DarkGray => base + 60,
+        Color::LightRed => base + 61,
+        Color::LightGreen => base + 62,
+        Color::LightYellow => base + 63,
+        Color::LightBlue => base + 64,
+        Color::LightMagenta => base + 65,
+        Color::LightCyan => base + 66,
+        Color::White => base + 67,
+        Color::Indexed(index) => return Some(format!("{};5;{index}", base + 8)),
+        Color::Rgb(r, g, b) => return Some(format!("{};2;{r};{g};{b}", base + 8)),
+    };
+    Some(code.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use tuirealm::ratatui::layout::Rect;
+    use tuirealm::ratatui::style::Style;
+
+    use super::*;
+
+    #[test]
+    fn plain_lines_trim_padding_and_skip_wide_cells() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 6, 2));
+        buffer.set_string(0, 0, "界a", Style::default());
+        buffer.set_string(0, 1, "ok", Style::default());
+
+        assert_eq!(buffer_to_plain_lines(&buffer), vec!["界a", "ok"]);
+    }
+
+    #[test]
+    fn ansi_lines_emit_sgr_on_style_changes() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 3, 1));
+        buffer.set_string(
+            0,
+            0,
+            "ab",
+            Style::default()
+                .fg(Color::Rgb(1, 2, 3))
+                .add_modifier(Modifier::BOLD),
+        );
+
+        let lines = buffer_to_ansi_lines(&buffer);
+        assert_eq!(lines, vec!["\x1b[0;1;38;2;1;2;3mab\x1b[0m \x1b[0m"]);
+    }
+}
@@ -0,0 +1,154 @@
+use std::path::{Path, PathBuf};
+
+use serde_json::json;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::projects::ProjectInfo;
+use crate::types::Task;
+
+use super::{CliResult, conflict_error, runtime_error, usage_error};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(super) struct WorktreeTaskMatch {
+    pub project: String,
+    pub task_id: Uuid,
+    pub title: String,
+}
+
+/// Tasks whose worktree is `dir` or one of its ancestors.
+pub(super) fn tasks_containing_dir<'a>(dir: &Path, tasks: &'a [Task]) -> Vec<&'a Task> {
+    let dir = canonical(dir);
+    tasks
+        .iter()
+        .filter(|task| {
+            task.worktree_path
+                .as_deref()
+                .filter(|path| !path.trim().is_empty())
+                .is_some_and(|path| dir.starts_with(canonical(Path::new(path))))
+        })
+        .collect()
+}
+
+pub(super) fn find_worktree_tasks(dir: &Path, projects: &[ProjectInfo]) -> Vec<WorktreeTaskMatch> {
+    let mut matches = Vec::new();
+    for project in projects {
+        if !project.path.exists() {
+            continue;
+        }
+        let tasks = Database::open(&project.path).and_then(|db| {
+            let mut tasks = db.list_tasks()?;
+            tasks.extend(db.list_archived_tasks()?);
+            Ok(tasks)
+        });
+        let tasks = match tasks {
+            Ok(tasks) => tasks,
+            Err(err) => {
+                warn!(project = %project.name, error = %err, "skipping project during task inference");
+                continue;
+            }
+        };
+        matches.extend(tasks_containing_dir(dir, &tasks).into_iter().map(|task| {
+            WorktreeTaskMatch {
+                project: project.name.clone(),
+                task_id: task.id,
+                title: task.title.clone(),
+            }
+        }));
+    }
+    matches
+}
+
+pub(super) fn single_worktree_task(
+    dir: &Path,
+    mut matches: Vec<WorktreeTaskMatch>,
+) -> CliResult<WorktreeTaskMatch> {
+    match matches.len() {
+        0 => Err(usage_error(
+            "TASK_ID_REQUIRED",
+            format!(
+                "--id is required: {} is not inside a task worktree",
+                dir.display()
+            ),
+        )),
+        1 => Ok(matches.remove(0)),
+        count => Err(conflict_error(
+            "TASK_AMBIGUOUS",
+            format!(
+                "{} is inside {count} task worktrees; pass --project and --id",
+                dir.display()
+            ),
+            Some(json!({
+                "matches": matches
+                    .iter()
+                    .map(|found| json!({
+                        "project": found.project,
+                        "id": found.task_id.to_string(),
+                        "title": found.title,
+                    }))
+                    .collect::<Vec<_>>()
+            })),
+        )),
+    }
+}
+
+pub(super) fn current_dir() -> CliResult<PathBuf> {
+    std::env::current_dir().map_err(|err| runtime_error(anyhow::Error::from(err)))
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn tasks_containing_dir_matches_worktree_and_subdirectories() {
+        let worktree = TempDir::new().expect("temp worktree");
+        let nested = worktree.path().join("src");
+        std::fs::create_dir_all(&nested).expect("nested dir");
+        let db = Database::open(":memory:").expect("db should open");
+        let repo = db.add_repo(worktree.path()).expect("repo should save");
+        let category = db.list_categories().expect("categories")[0].id;
+        let inside = db
+            .add_task(repo.id, "feature/inside", "Inside", category)
+            .expect("task should save");
+        db.update_task_tmux(inside.id, None, Some(worktree.path().display().to_string()))
+            .expect("worktree should save");
+        db.add_task(repo.id, "feature/other", "Other", category)
+            .expect("task should save");
+        let tasks = db.list_tasks().expect("tasks");
+
+        let found = tasks_containing_dir(&nested, &tasks);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, inside.id);
+
+        let outside = TempDir::new().expect("temp dir");
+        assert!(tasks_containing_dir(outside.path(), &tasks).is_empty());
+    }
+
+    #[test]
+    fn single_worktree_task_reports_missing_and_ambiguous_matches() {
+        let dir = Path::new("/tmp/worktree");
+        let err = single_worktree_task(dir, Vec::new()).expect_err("no match");
+        assert_eq!(err.code, "TASK_ID_REQUIRED");
+
+        let found = |project: &str| WorktreeTaskMatch {
+            project: project.to_string(),
+            task_id: Uuid::new_v4(),
+            title: "task".to_string(),
+        };
+        let err = single_worktree_task(dir, vec![found("a"), found("b")]).expect_err("ambiguous");
+        assert_eq!(err.code, "TASK_AMBIGUOUS");
+        assert_eq!(err.exit_code, 4);
+        assert_eq!(err.details.expect("details")["matches"][1]["project"], "b");
+
+        let single = single_worktree_task(dir, vec![found("a")]).expect("single match");
+        assert_eq!(single.project, "a");
+    }
+}
@@ -0,0 +1,355 @@
+use std::path::Path;
+
+use serde_json::{Value, json};
+
+use crate::db::Database;
+use crate::git::git_is_valid_repo;
+use crate::opencode::{
+    ServerEndpoint, ensure_opencode_available, opencode_binary, server_is_healthy,
+};
+use crate::projects;
+use crate::tmux::ensure_tmux_installed;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(super) enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl CheckStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Pass => "pass",
+            Self::Warn => "warn",
+            Self::Fail => "fail",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct DoctorCheck {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub message: String,
+    pub hint: Option<String>,
+}
+
+impl DoctorCheck {
+    fn pass(name: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Pass,
+            message: message.into(),
+            hint: None,
+        }
+    }
+
+    fn warn(name: &'static str, message: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Warn,
+            message: message.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn fail(name: &'static str, message: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Fail,
+            message: message.into(),
+            hint: Some(hint.into()),
+        }
+    }
+}
+
+pub(super) fn run_checks(project: &str) -> Vec<DoctorCheck> {
+    let mut checks = vec![check_tmux(), check_opencode_binary(), check_server()];
+    checks.push(check_projects());
+
+    let (db_check, db) = check_project_db(project, &projects::get_project_path(project));
+    checks.push(db_check);
+    if let Some(db) = db {
+        checks.push(check_repos(&db));
+        checks.push(check_worktrees(&db));
+    }
+    checks
+}
+
+pub(super) fn has_failures(checks: &[DoctorCheck]) -> bool {
+    checks.iter().any(|check| check.status == CheckStatus::Fail)
+}
+
+fn check_tmux() -> DoctorCheck {
+    match ensure_tmux_installed() {
+        Ok(()) => DoctorCheck::pass("tmux", "tmux is installed and on PATH"),
+        Err(err) => DoctorCheck::fail(
+            "tmux",
+            err.to_string(),
+            "Install tmux and confirm `tmux -V` works in the same shell.",
+        ),
+    }
+}
+
+fn check_opencode_binary() -> DoctorCheck {
+    let binary = opencode_binary();
+    match ensure_opencode_available(&binary) {
+        Ok(()) => DoctorCheck::pass("opencode", format!("`{binary}` is runnable")),
+        Err(err) => DoctorCheck::fail(
+            "opencode",
+            err.to_string(),
+            "Install OpenCode and confirm `opencode --version` works, or point OPENCODE_BIN at the binary.",
+        ),
+    }
+}
+
+// Only probes the configured server: doctor must not start one as a side
+// effect of diagnosing it.
+fn check_server() -> DoctorCheck {
+    let endpoint = ServerEndpoint::current();
+    let server_url = endpoint.base_url();
+    if server_is_healthy() {
+        DoctorCheck::pass(
+            "server",
+            format!("OpenCode server is healthy at {server_url}"),
+        )
+    } else {
+        DoctorCheck::warn(
+            "server",
+            format!("no healthy OpenCode server at {server_url}"),
+            format!(
+                "The board starts the server on demand; run `{}` to start it yourself and check its output.",
+                endpoint.serve_command()
+            ),
+        )
+    }
+}
+
+fn check_projects() -> DoctorCheck {
+    let data_dir = projects::get_data_dir();
+    match projects::list_projects() {
+        Ok(list) => DoctorCheck::pass(
+            "projects",
+            format!("{} project(s) in {}", list.len(), data_dir.display()),
+        ),
+        Err(err) => DoctorCheck::fail(
+            "projects",
+            format!("{err:#}"),
+            format!(
+                "Check that {} exists and is readable by the current user.",
+                data_dir.display()
+            ),
+        ),
+    }
+}
+
+fn check_project_db(project: &str, path: &Path) -> (DoctorCheck, Option<Database>) {
+    if !path.exists() {
+        return (
+            DoctorCheck::warn(
+                "database",
+                format!("project '{project}' has no database at {}", path.display()),
+                format!("Run `opencode-kanban --project {project}` once to create it."),
+            ),
+            None,
+        );
+    }
+
+    let opened = Database::open(path).and_then(|db| {
+        db.list_tasks()?;
+        db.list_categories()?;
+        db.list_repos()?;
+        Ok(db)
+    });
+    match opened {
+        Ok(db) => (
+            DoctorCheck::pass("database", format!("{} is readable", path.display())),
+            Some(db),
+        ),
+        Err(err) => (
+            DoctorCheck::fail(
+                "database",
+                format!("failed to read {}: {err:#}", path.display()),
+                "Check file permissions, or move the file aside and let the app recreate it.",
+            ),
+            None,
+        ),
+    }
+}
+
+fn check_repos(db: &Database) -> DoctorCheck {
+    let repos = match db.list_repos() {
+        Ok(repos) => repos,
+        Err(err) => {
+            return DoctorCheck::fail(
+                "repos",
+                format!("failed to list repos: {err:#}"),
+                "Check that the project database is readable.",
+            );
+        }
+    };
+
+    let broken = repos
+        .iter()
+        .filter(|repo| {
+            let path = Path::new(&repo.path);
+            !path.is_dir() || !git_is_valid_repo(path)
+        })
+        .map(|repo| format!("{} ({})", repo.name, repo.path))
+        .collect::<Vec<_>>();
+
+    if broken.is_empty() {
+        DoctorCheck::pass("repos", format!("{} repo(s) reachable", repos.len()))
+    } else {
+        DoctorCheck::warn(
+            "repos",
+            format!("missing or invalid repo paths: {}", broken.join(", ")),
+            "Restore the repositories, or remove them from the board's repo list.",
+        )
+    }
+}
+
+fn check_worktrees(db: &Database) -> DoctorCheck {
+    let tasks = match db.list_tasks() {
+        Ok(tasks) => tasks,
+        Err(err) => {
+            return DoctorCheck::fail(
+                "worktrees",
+                format!("failed to list tasks: {err:#}"),
+                "Check that the project database is readable.",
+            );
+        }
+    };
+
+    let stale = tasks
+        .iter()
+        .filter_map(|task| {
+            let worktree = task.worktree_path.as_deref()?;
+            (!Path::new(worktree).is_dir()).then(|| format!("{} ({worktree})", task.title))
+        })
+        .collect::<Vec<_>>();
+
+    if stale.is_empty() {
+        DoctorCheck::pass("worktrees", "all task worktrees exist")
+    } else {
+        DoctorCheck::warn(
+            "worktrees",
+            format!("tasks point at missing worktrees: {}", stale.join(", ")),
+            "Run `git worktree prune` in the repo and archive or delete the affected tasks.",
+        )
+    }
+}
+
+pub(super) fn checks_json(checks: &[DoctorCheck]) -> Value {
+    let count = |status| checks.iter().filter(|check| check.status == status).count();
+    json!({
+        "version": env!("OPENCODE_KANBAN_BUILD_VERSION"),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "checks": checks
+            .iter()
+            .map(|check| json!({
+                "name": check.name,
+                "status": check.status.as_str(),
+                "message": check.message,
+                "hint": check.hint,
+            }))
+            .collect::<Vec<_>>(),
+        "summary": {
+            "pass": count(CheckStatus::Pass),
+            "warn": count(CheckStatus::Warn),
+            "fail": count(CheckStatus::Fail),
+        },
+    })
+}
+
+pub(super) fn render_checks_text(checks: &[DoctorCheck]) -> String {
+    let name_width = checks
+        .iter()
+        .map(|check| check.name.len())
+        .max()
+        .unwrap_or(0);
+    let count = |status| checks.iter().filter(|check| check.status == status).count();
+
+    let mut lines = Vec::new();
+    for check in checks {
+        lines.push(format!(
+            "[{}] {:<name_width$}  {}",
+            check.status.as_str(),
+            check.name,
+            check.message
+        ));
+        if let Some(hint) = &check.hint {
+            lines.push(format!("       {:<name_width$}  hint: {hint}", ""));
+        }
+    }
+    lines.push(String::new());
+    lines.push(format!(
+        "{} passed, {} warning(s), {} failed",
+        count(CheckStatus::Pass),
+        count(CheckStatus::Warn),
+        count(CheckStatus::Fail)
+    ));
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn project_db_check_warns_when_database_is_missing() {
+        let dir = TempDir::new().expect("temp dir");
+        let (check, db) = check_project_db("ghost", &dir.path().join("ghost.sqlite"));
+        assert_eq!(check.status, CheckStatus::Warn);
+        assert!(db.is_none());
+        assert!(check.hint.expect("hint").contains("--project ghost"));
+    }
+
+    #[test]
+    fn repo_and_worktree_checks_flag_missing_paths() {
+        let repo_dir = TempDir::new().expect("temp repo dir");
+        let db = Database::open(":memory:").expect("db should open");
+        let repo = db.add_repo(repo_dir.path()).expect("repo should save");
+        let category = db.list_categories().expect("categories")[0].id;
+        let task = db
+            .add_task(repo.id, "feature/gone", "Gone task", category)
+            .expect("task should save");
+        db.update_task_tmux(
+            task.id,
+            None,
+            Some("/nonexistent/worktree/path".to_string()),
+        )
+        .expect("worktree should save");
+        drop(repo_dir);
+
+        let repos = check_repos(&db);
+        assert_eq!(repos.status, CheckStatus::Warn);
+        assert!(repos.message.contains(&repo.name));
+
+        let worktrees = check_worktrees(&db);
+        assert_eq!(worktrees.status, CheckStatus::Warn);
+        assert!(worktrees.message.contains("Gone task"));
+    }
+
+    #[test]
+    fn render_checks_text_lists_hints_and_summary() {
+        let checks = vec![
+            DoctorCheck::pass("tmux", "ok"),
+            DoctorCheck::fail("server", "down", "start it"),
+        ];
+        let text = render_checks_text(&checks);
+        assert!(text.contains("[pass] tmux"));
+        assert!(text.contains("[fail] server  down"));
+        assert!(text.contains("hint: start it"));
+        assert!(text.ends_with("1 passed, 0 warning(s), 1 failed"));
+        assert!(has_failures(&checks));
+
+        let json = checks_json(&checks);
+        assert_eq!(json["summary"]["fail"], 1);
+        assert_eq!(json["checks"][1]["hint"], "start it");
+    }
+}
@@ -0,0 +1,420 @@
+//! `opencode-kanban mcp`: a Model Context Protocol server over stdio.
+//!
+//! Agents running inside a task's tmux session use it to read and update
+//! their own card. Every tool runs the matching CLI command and returns its
+//! JSON envelope; the current task comes from the session environment.
+
+use std::io::{self, BufRead, Write};
+
+use serde_json::{Map, Value, json};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::db::Database;
+
+use super::{
+    BatchLine, CliResult, batch_command, error_envelope, execute_with_db, success_envelope,
+};
+
+const SERVER_NAME: &str = "opencode-kanban";
+const PROTOCOL_VERSIONS: [&str; 3] = ["2025-06-18", "2025-03-26", "2024-11-05"];
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+pub(super) struct McpServer {
+    db: Database,
+    project: String,
+    current_task: Option<String>,
+}
+
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl McpServer {
+    pub(super) fn new(db: Database, project: String, current_task: Option<String>) -> Self {
+        Self {
+            db,
+            project,
+            current_task,
+        }
+    }
+
+    /// Serves newline-delimited JSON-RPC messages until `input` closes.
+    pub(super) fn run(&self, input: impl BufRead, output: &mut impl Write) -> io::Result<()> {
+        for line in input.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(response) = self.handle_line(&line) {
+                writeln!(output, "{response}")?;
+                output.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_line(&self, line: &str) -> Option<Value> {
+        let message = match serde_json::from_str::<Value>(line) {
+            Ok(Value::Object(message)) => message,
+            Ok(_) => {
+                return Some(error_response(
+                    Value::Null,
+                    RpcError::new(INVALID_REQUEST, "expected a JSON-RPC object"),
+                ));
+            }
+            Err(err) => {
+                return Some(error_response(
+                    Value::Null,
+                    RpcError::new(PARSE_ERROR, format!("invalid JSON: {err}")),
+                ));
+            }
+        };
+
+        // Notifications and responses to our (nonexistent) requests carry no
+        // id and get no reply.
+        let id = message.get("id").cloned()?;
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            return Some(error_response(
+                id,
+                RpcError::new(INVALID_REQUEST, "missing method"),
+            ));
+        };
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+        Some(match self.dispatch(method, params) {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(err) => error_response(id, err),
+        })
+    }
+
+    fn dispatch(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        match method {
+            "initialize" => Ok(initialize_result(&params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+            "tools/call" => self.call_tool(params),
+            other => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("method '{other}' is not supported"),
+            )),
+        }
+    }
+
+    fn call_tool(&self, params: Value) -> Result<Value, RpcError> {
+        let Some(name) = params.get("name").and_then(Value::as_str) else {
+            return Err(RpcError::new(INVALID_PARAMS, "tools/call requires a name"));
+        };
+        let arguments = match params.get("arguments") {
+            None | Some(Value::Null) => Map::new(),
+            Some(Value::Object(arguments)) => arguments.clone(),
+            Some(_) => {
+                return Err(RpcError::new(
+                    INVALID_PARAMS,
+                    "tool arguments must be an object",
+                ));
+            }
+        };
+
+        let line = self.tool_batch_line(name, arguments)?;
+        Ok(tool_result(self.run_command(line)))
+    }
+
+    fn tool_batch_line(
+        &self,
+        name: &str,
+        mut arguments: Map<String, Value>,
+    ) -> Result<BatchLine, RpcError> {
+        let command = match name {
+            "get_current_task" => "task show",
+            "move_task" => "task move",
+            "add_note" => "task note",
+            "create_task" => "task create",
+            "list_tasks" => "task list",
+            "list_categories" => "category list",
+            other => {
+                return Err(RpcError::new(
+                    INVALID_PARAMS,
+                    format!("unknown tool '{other}'"),
+                ));
+            }
+        };
+
+        match name {
+            "get_current_task" | "move_task" | "add_note" => {
+                if let Some(task_id) = &self.current_task {
+                    arguments
+                        .entry("id")
+                        .or_insert_with(|| Value::String(task_id.clone()));
+                }
+            }
+            "create_task" if !arguments.contains_key("repo") => {
+                if let Some(repo_path) = self.current_repo_path() {
+                    arguments.insert("repo".to_string(), Value::String(repo_path));
+                }
+            }
+            _ => {}
+        }
+
+        Ok(BatchLine {
+            command: command.to_string(),
+            args: Value::Object(arguments),
+        })
+    }
+
+    // Follow-up tasks default to the repository of the task that creates them.
+    fn current_repo_path(&self) -> Option<String> {
+        let task_id = Uuid::parse_str(self.current_task.as_deref()?).ok()?;
+        let task = self.db.get_task(task_id).ok()?;
+        self.db
+            .list_repos()
+            .ok()?
+            .into_iter()
+            .find(|repo| repo.id == task.repo_id)
+            .map(|repo| repo.path)
+    }
+
+    fn run_command(&self, line: BatchLine) -> CliResult<Value> {
+        let command = batch_command(line)?;
+        execute_with_db(&self.db, &self.project, command).map(|output| success_envelope(&output))
+    }
+}
+
+fn initialize_result(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let version = requested
+        .filter(|version| PROTOCOL_VERSIONS.contains(version))
+        .unwrap_or(PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": {} },
+        "serverInfo": {
+            "name": SERVER_NAME,
+            "version": env!("OPENCODE_KANBAN_BUILD_VERSION"),
+        },
+        "instructions": "Tools act on the kanban task this session belongs to unless an id is given.",
+    })
+}
+
+fn tool_result(result: CliResult<Value>) -> Value {
+    let (envelope, is_error) = match result {
+        Ok(envelope) => (envelope, false),
+        Err(err) => {
+            warn!(code = err.code, message = %err.message, "mcp tool call failed");
+            (error_envelope(&err), true)
+        }
+    };
+    json!({
+        "content": [{ "type": "text", "text": envelope.to_string() }],
+        "structuredContent": envelope,
+        "isError": is_error,
+    })
+}
+
+fn error_response(id: Value, err: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": err.code, "message": err.message },
+    })
+}
+
+fn tool_definitions() -> Vec<Value> {
+    let task_id = json!({
+        "type": "string",
+        "description": "Task id or unique id prefix. Defaults to the current task.",
+    });
+    let category_slug = json!({
+        "type": "string",
+        "description": "Column slug, as returned by list_categories.",
+    });
+
+    vec![
+        json!({
+            "name": "get_current_task",
+            "description": "Show the kanban task this session works on, including its column and notes.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "id": task_id },
+            },
+        }),
+        json!({
+            "name": "move_task",
+            "description": "Move a task to another column, for example to mark it ready for review.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "id": task_id,
+                    "category_slug": category_slug,
+                    "category_id": { "type": "string", "description": "Column id, instead of a slug." },
+                },
+            },
+        }),
+        json!({
+            "name": "add_note",
+            "description": "Append a note to a task, such as a progress summary or a question for the reviewer.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "id": task_id,
+                    "text": { "type": "string", "description": "Note text." },
+                },
+                "required": ["text"],
+            },
+        }),
+        json!({
+            "name": "create_task",
+            "description": "Create a follow-up task with its own branch, worktree and session.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "title": { "type": "string" },
+                    "branch": { "type": "string", "description": "New branch name." },
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository name or path. Defaults to the current task's repository.",
+                    },
+                    "category_slug": category_slug,
+                    "prompt": {
+                        "type": "string",
+                        "description": "Initial prompt that starts the new task's agent.",
+                    },
+                },
+                "required": ["title", "branch"],
+            },
+        }),
+        json!({
+            "name": "list_tasks",
+            "description": "List tasks on the board, optionally filtered by column or repository.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "category_slug": category_slug,
+                    "repo": { "type": "string", "description": "Repository name or path." },
+                    "archived": { "type": "boolean", "description": "List archived tasks instead." },
+                },
+            },
+        }),
+        json!({
+            "name": "list_categories",
+            "description": "List the board's columns and their slugs.",
+            "inputSchema": { "type": "object", "properties": {} },
+        }),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exchange(server: &McpServer, requests: &[Value]) -> Vec<Value> {
+        let input = requests
+            .iter()
+            .map(Value::to_string)
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut output = Vec::new();
+        server
+            .run(input.as_bytes(), &mut output)
+            .expect("server should run");
+        String::from_utf8(output)
+            .expect("utf8 output")
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("json response"))
+            .collect()
+    }
+
+    fn call(id: i64, name: &str, arguments: Value) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": "tools/call",
+            "params": { "name": name, "arguments": arguments },
+        })
+    }
+
+    #[test]
+    fn mcp_server_handshakes_and_lists_tools() {
+        let db = Database::open(":memory:").expect("db should open");
+        let server = McpServer::new(db, "demo".to_string(), None);
+
+        let responses = exchange(
+            &server,
+            &[
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "method": "initialize",
+                    "params": { "protocolVersion": "2025-03-26", "capabilities": {} },
+                }),
+                json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+                json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" }),
+                json!({ "jsonrpc": "2.0", "id": 3, "method": "resources/list" }),
+            ],
+        );
+
+        assert_eq!(responses.len(), 3);
+        assert_eq!(responses[0]["result"]["protocolVersion"], "2025-03-26");
+        let names = responses[1]["result"]["tools"]
+            .as_array()
+            .expect("tools")
+            .iter()
+            .map(|tool| tool["name"].as_str().expect("name"))
+            .collect::<Vec<_>>();
+        assert!(names.contains(&"get_current_task"));
+        assert!(names.contains(&"add_note"));
+        assert_eq!(responses[2]["error"]["code"], METHOD_NOT_FOUND);
+    }
+
+    #[test]
+    fn mcp_tools_act_on_the_session_task() {
+        let db = Database::open(":memory:").expect("db should open");
+        let repo_dir = tempfile::TempDir::new().expect("temp repo");
+        let repo = db.add_repo(repo_dir.path()).expect("repo should save");
+        let categories = db.list_categories().expect("categories");
+        let task = db
+            .add_task(repo.id, "feature/mcp", "MCP", categories[0].id)
+            .expect("task should save");
+        let server = McpServer::new(db.clone(), "demo".to_string(), Some(task.id.to_string()));
+
+        let responses = exchange(
+            &server,
+            &[
+                call(1, "add_note", json!({ "text": "tests pass" })),
+                call(
+                    2,
+                    "move_task",
+                    json!({ "category_slug": categories[1].slug }),
+                ),
+                call(3, "get_current_task", json!({})),
+                call(4, "move_task", json!({ "category_slug": "missing" })),
+            ],
+        );
+
+        assert_eq!(responses[0]["result"]["isError"], false);
+        assert_eq!(responses[1]["result"]["isError"], false);
+        let shown = &responses[2]["result"]["structuredContent"]["data"];
+        assert_eq!(shown["task"]["id"], task.id.to_string());
+        assert_eq!(shown["task"]["category"]["slug"], categories[1].slug);
+        assert_eq!(shown["notes"][0]["body"], "tests pass");
+        assert_eq!(responses[3]["result"]["isError"], true);
+        assert_eq!(
+            db.get_task(task.id).expect("task").category_id,
+            categories[1].id
+        );
+    }
+}
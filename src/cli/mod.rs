@@ -1,28 +1,64 @@
 use std::{
     collections::{HashMap, HashSet},
+    ffi::OsStr,
     fs,
+    io::{self, BufRead, IsTerminal, Read, Write},
+    net::SocketAddr,
     path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant, SystemTime},
 };
 
 use anyhow::Context;
-use clap::{Args, Subcommand};
+use chrono::Utc;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use clap_complete::{
+    ArgValueCompleter, CompletionCandidate,
+    env::{Bash, EnvCompleter, Fish, Zsh},
+};
 use serde_json::{Value, json};
+use tokio::runtime::{Builder as RuntimeBuilder, Handle, RuntimeFlavor};
 use tracing::{error, warn};
 use uuid::Uuid;
 
 use crate::{
-    app::runtime::{
-        CreateTaskRuntime, RealCreateTaskRuntime, next_available_session_name_by,
-        worktrees_root_for_repo,
+    app::{
+        App, ViewMode,
+        runtime::{
+            CreateTaskRuntime, PROJECT_ENV, RealCreateTaskRuntime, RealGcRuntime, TASK_ID_ENV,
+            next_available_session_name_by, task_session_env, worktrees_root_for_repo,
+        },
+        workflows::{
+            GcOptions, GcOutcome, GcReport, apply_gc, bind_initial_prompt_session,
+            open_other_project_dbs, scan_orphans,
+        },
     },
+    backend::{AgentLaunch, default_backend},
     db::Database,
     git::derive_worktree_path,
-    opencode::{Status, opencode_attach_command},
+    opencode::{ServerStatusProvider, Status, status_server::ServerStatusConfig},
     projects,
-    types::{Category, Repo, Task},
+    types::{
+        AgentSelection, Category, Repo, SessionMessageItem, SessionStatusError, Task, TaskNote,
+    },
 };
 
+mod board;
+mod cwd;
+mod doctor;
+mod mcp;
+mod output;
+mod serve;
+mod stats;
+
+pub use output::{OutputFormat, OutputOptions};
+
+use output::{ListColumn, Listing, column, render_csv, render_markdown_table, render_yaml};
+
 const SCHEMA_VERSION: &str = "cli.v1";
+const PROMPT_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+const PROMPT_WAIT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+const API_TOKEN_ENV: &str = "OPENCODE_KANBAN_API_TOKEN";
 
 #[derive(Debug, Clone, Subcommand)]
 pub enum RootCommand {
@@ -34,6 +70,26 @@ pub enum RootCommand {
         #[command(subcommand)]
         command: CategoryCommand,
     },
+    Board {
+        #[command(subcommand)]
+        command: BoardCommand,
+    },
+    Batch(BatchArgs),
+    Doctor,
+    Gc(GcArgs),
+    Stats(StatsArgs),
+    Serve(ServeArgs),
+    /// Serve the Model Context Protocol over stdio for agents inside a task session.
+    Mcp,
+    Completions(CompletionsArgs),
+    Man,
+}
+
+#[derive(Debug, Parser)]
+#[command(no_binary_name = true)]
+struct BatchCommandLine {
+    #[command(subcommand)]
+    command: RootCommand,
 }
 
 #[derive(Debug, Clone, Subcommand)]
@@ -43,6 +99,9 @@ pub enum TaskCommand {
     Move(TaskMoveArgs),
     Archive(TaskArchiveArgs),
     Show(TaskShowArgs),
+    Prompt(TaskPromptArgs),
+    Abort(TaskAbortArgs),
+    Note(TaskNoteArgs),
 }
 
 #[derive(Debug, Clone, Subcommand)]
@@ -59,7 +118,12 @@ pub struct OptionalCategorySelectorArgs {
     #[arg(long, value_name = "UUID", group = "category_selector")]
     pub category_id: Option<Uuid>,
 
-    #[arg(long, value_name = "SLUG", group = "category_selector")]
+    #[arg(
+        long,
+        value_name = "SLUG",
+        group = "category_selector",
+        add = ArgValueCompleter::new(complete_category_slugs)
+    )]
     pub category_slug: Option<String>,
 }
 
@@ -69,7 +133,12 @@ pub struct RequiredCategorySelectorArgs {
     #[arg(long, value_name = "UUID", group = "category_selector")]
     pub category_id: Option<Uuid>,
 
-    #[arg(long, value_name = "SLUG", group = "category_selector")]
+    #[arg(
+        long,
+        value_name = "SLUG",
+        group = "category_selector",
+        add = ArgValueCompleter::new(complete_category_slugs)
+    )]
     pub category_slug: Option<String>,
 }
 
@@ -81,7 +150,7 @@ pub struct TaskListArgs {
     #[arg(long)]
     pub archived: bool,
 
-    #[arg(long, value_name = "REPO")]
+    #[arg(long, value_name = "REPO", add = ArgValueCompleter::new(complete_repo_names))]
     pub repo: Option<String>,
 }
 
@@ -96,17 +165,35 @@ pub struct TaskCreateArgs {
     #[arg(long = "existing-dir", value_name = "PATH")]
     pub existing_dir: Option<String>,
 
-    #[arg(long, value_name = "REPO")]
+    #[arg(long, value_name = "REPO", add = ArgValueCompleter::new(complete_repo_names))]
     pub repo: Option<String>,
 
     #[command(flatten)]
     pub selector: OptionalCategorySelectorArgs,
+
+    /// Sent to the new session as soon as it starts. If it cannot be sent the
+    /// task is still created and the output carries a `warning`.
+    #[arg(long, value_name = "TEXT", conflicts_with = "prompt_file")]
+    pub prompt: Option<String>,
+
+    /// Read the initial prompt from a file, or from stdin when PATH is `-`.
+    #[arg(long = "prompt-file", value_name = "PATH")]
+    pub prompt_file: Option<PathBuf>,
+
+    /// Model the task's agent runs with, as provider/model.
+    #[arg(long, value_name = "PROVIDER/MODEL")]
+    pub model: Option<String>,
+
+    /// OpenCode agent to use, e.g. build or plan.
+    #[arg(long, value_name = "AGENT")]
+    pub agent: Option<String>,
 }
 
 #[derive(Debug, Clone, Args)]
 pub struct TaskMoveArgs {
-    #[arg(long, value_name = "TASK_ID")]
-    pub id: String,
+    /// Defaults to the task whose worktree contains the current directory.
+    #[arg(long, value_name = "TASK_ID", add = ArgValueCompleter::new(complete_task_ids))]
+    pub id: Option<String>,
 
     #[command(flatten)]
     pub selector: RequiredCategorySelectorArgs,
@@ -114,14 +201,146 @@ pub struct TaskMoveArgs {
 
 #[derive(Debug, Clone, Args)]
 pub struct TaskArchiveArgs {
-    #[arg(long, value_name = "TASK_ID")]
-    pub id: String,
+    /// Defaults to the task whose worktree contains the current directory.
+    #[arg(long, value_name = "TASK_ID", add = ArgValueCompleter::new(complete_task_ids))]
+    pub id: Option<String>,
 }
 
 #[derive(Debug, Clone, Args)]
 pub struct TaskShowArgs {
-    #[arg(long, value_name = "TASK_ID")]
-    pub id: String,
+    /// Defaults to the task whose worktree contains the current directory.
+    #[arg(long, value_name = "TASK_ID", add = ArgValueCompleter::new(complete_task_ids))]
+    pub id: Option<String>,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct TaskPromptArgs {
+    /// Defaults to the task whose worktree contains the current directory.
+    #[arg(long, value_name = "TASK_ID", add = ArgValueCompleter::new(complete_task_ids))]
+    pub id: Option<String>,
+
+    #[command(flatten)]
+    pub source: PromptSourceArgs,
+
+    #[arg(long)]
+    pub wait: bool,
+
+    #[arg(long, value_name = "SECONDS", default_value_t = 600, requires = "wait")]
+    pub wait_timeout: u64,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct TaskAbortArgs {
+    /// Defaults to the task whose worktree contains the current directory.
+    #[arg(long, value_name = "TASK_ID", add = ArgValueCompleter::new(complete_task_ids))]
+    pub id: Option<String>,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct TaskNoteArgs {
+    /// Defaults to the task whose worktree contains the current directory.
+    #[arg(long, value_name = "TASK_ID", add = ArgValueCompleter::new(complete_task_ids))]
+    pub id: Option<String>,
+
+    #[arg(long, value_name = "TEXT")]
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Args)]
+#[group(id = "prompt_source", required = true, multiple = false)]
+pub struct PromptSourceArgs {
+    #[arg(long, value_name = "TEXT", group = "prompt_source")]
+    pub message: Option<String>,
+
+    /// Read the prompt from a file, or from stdin when PATH is `-`.
+    #[arg(long, value_name = "PATH", group = "prompt_source")]
+    pub file: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct BatchArgs {
+    /// Roll back every change if any command fails. Commands with effects
+    /// outside the database (task create, prompt, abort, gc) are rejected.
+    #[arg(long)]
+    pub atomic: bool,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum BoardCommand {
+    Render(BoardRenderArgs),
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct BoardRenderArgs {
+    #[arg(long, default_value_t = 120, value_parser = clap::value_parser!(u16).range(20..=1000))]
+    pub width: u16,
+    #[arg(long, default_value_t = 40, value_parser = clap::value_parser!(u16).range(5..=500))]
+    pub height: u16,
+    #[arg(long, value_enum, default_value_t = BoardView::Kanban)]
+    pub view: BoardView,
+    #[arg(long, conflicts_with = "plain")]
+    pub ansi: bool,
+    #[arg(long)]
+    pub plain: bool,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, ValueEnum)]
+pub enum BoardView {
+    Kanban,
+    Detail,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct GcArgs {
+    #[arg(long)]
+    pub apply: bool,
+    #[arg(long, requires = "apply")]
+    pub adopt: bool,
+    /// Remove orphan worktrees even if that discards uncommitted changes.
+    #[arg(long, requires = "apply", conflicts_with = "adopt")]
+    pub force: bool,
+    /// Archive tasks whose worktree no longer exists.
+    #[arg(long, requires = "apply")]
+    pub archive_missing: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct StatsArgs {
+    #[command(subcommand)]
+    pub command: Option<StatsCommand>,
+    /// Report window: 7d, 12h, 2w, or a date/RFC 3339 timestamp. Defaults to all time.
+    #[arg(long, value_name = "WINDOW")]
+    pub since: Option<String>,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum StatsCommand {
+    /// Token usage and cost of agent sessions, grouped by repo and model.
+    Cost,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct ServeArgs {
+    #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:4097")]
+    pub listen: SocketAddr,
+    /// Require `Authorization: Bearer <TOKEN>` on every request (defaults to
+    /// $OPENCODE_KANBAN_API_TOKEN); needed to listen on a non-loopback address.
+    #[arg(long, value_name = "TOKEN")]
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct CompletionsArgs {
+    #[arg(value_enum)]
+    pub shell: CompletionShell,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
 }
 
 #[derive(Debug, Clone, Args)]
@@ -154,24 +373,99 @@ pub struct CategoryDeleteArgs {
     pub id: Uuid,
 }
 
-pub fn run(project_name: &str, command: RootCommand, json_output: bool, quiet: bool) -> i32 {
-    match execute(project_name, command) {
-        Ok(output) => {
-            print_success(output, json_output, quiet);
-            0
-        }
+pub fn run(
+    project_name: Option<&str>,
+    mut command: RootCommand,
+    options: &OutputOptions,
+    quiet: bool,
+) -> i32 {
+    let project_name = match project_name {
+        Some(project_name) => project_name.to_string(),
+        None => match infer_project(&mut command) {
+            Ok(project_name) => project_name,
+            Err(err) => {
+                print_error(&err, options.format);
+                return err.exit_code;
+            }
+        },
+    };
+    let project_name = project_name.as_str();
+
+    match command {
+        RootCommand::Batch(args) => return run_batch(project_name, &args),
+        RootCommand::Doctor => return run_doctor(project_name, options, quiet),
+        RootCommand::Serve(args) => return run_serve(project_name, &args),
+        RootCommand::Mcp => return run_mcp(project_name),
+        _ => {}
+    }
+
+    match execute(project_name, command).and_then(|output| print_success(&output, options, quiet)) {
+        Ok(()) => 0,
         Err(err) => {
-            print_error(&err, json_output);
+            print_error(&err, options.format);
             err.exit_code
         }
     }
 }
 
+fn infer_project(command: &mut RootCommand) -> CliResult<String> {
+    if !matches!(command, RootCommand::Mcp) {
+        return infer_project_from_cwd(command);
+    }
+    if let Some(project) = session_env(PROJECT_ENV) {
+        return Ok(project);
+    }
+
+    let dir = cwd::current_dir()?;
+    let projects = projects::list_projects().map_err(runtime_error)?;
+    cwd::single_worktree_task(&dir, cwd::find_worktree_tasks(&dir, &projects))
+        .map(|found| found.project)
+        .map_err(|_| {
+            usage_error(
+                "PROJECT_REQUIRED",
+                format!("--project is required outside a task session ({PROJECT_ENV} is not set)"),
+            )
+        })
+}
+
+// Without --project, task commands that would otherwise need --id can find
+// both the project and the task from the worktree they are run in.
+fn infer_project_from_cwd(command: &mut RootCommand) -> CliResult<String> {
+    let Some(selector) = task_id_selector_mut(command).filter(|selector| selector.is_none()) else {
+        return Err(usage_error(
+            "PROJECT_REQUIRED",
+            "--project is required for CLI commands",
+        ));
+    };
+
+    let dir = cwd::current_dir()?;
+    let projects = projects::list_projects().map_err(runtime_error)?;
+    let found = cwd::single_worktree_task(&dir, cwd::find_worktree_tasks(&dir, &projects))?;
+    *selector = Some(found.task_id.to_string());
+    Ok(found.project)
+}
+
+fn task_id_selector_mut(command: &mut RootCommand) -> Option<&mut Option<String>> {
+    match command {
+        RootCommand::Task { command } => match command {
+            TaskCommand::Move(args) => Some(&mut args.id),
+            TaskCommand::Archive(args) => Some(&mut args.id),
+            TaskCommand::Show(args) => Some(&mut args.id),
+            TaskCommand::Prompt(args) => Some(&mut args.id),
+            TaskCommand::Abort(args) => Some(&mut args.id),
+            TaskCommand::Note(args) => Some(&mut args.id),
+            TaskCommand::List(_) | TaskCommand::Create(_) => None,
+        },
+        _ => None,
+    }
+}
+
 struct CommandOutput {
     command: &'static str,
     project: String,
     data: Value,
     text: String,
+    listing: Option<Listing>,
 }
 
 #[derive(Debug)]
@@ -189,9 +483,653 @@ fn execute(project_name: &str, command: RootCommand) -> CliResult<CommandOutput>
     let db_path = resolve_existing_project_db_path(&project)?;
     let db = Database::open(&db_path).map_err(runtime_error)?;
 
+    execute_with_db(&db, &project, command)
+}
+
+fn execute_with_db(db: &Database, project: &str, command: RootCommand) -> CliResult<CommandOutput> {
     match command {
-        RootCommand::Task { command } => execute_task_command(&db, &project, command),
-        RootCommand::Category { command } => execute_category_command(&db, &project, command),
+        RootCommand::Task { command } => execute_task_command(db, project, command),
+        RootCommand::Category { command } => execute_category_command(db, project, command),
+        RootCommand::Board { command } => execute_board_command(db, project, command),
+        RootCommand::Doctor => Ok(doctor_output(project, &doctor::run_checks(project))),
+        RootCommand::Gc(args) => gc(db, project, args),
+        RootCommand::Stats(StatsArgs {
+            command: Some(StatsCommand::Cost),
+            ..
+        }) => stats_cost(db, project),
+        RootCommand::Stats(args) => board_stats(db, project, args),
+        RootCommand::Batch(_) => Err(usage_error(
+            "BATCH_NESTED",
+            "batch commands cannot be nested",
+        )),
+        RootCommand::Serve(_) | RootCommand::Mcp => Err(usage_error(
+            "COMMAND_UNSUPPORTED",
+            "serve and mcp cannot run inside batch or the API",
+        )),
+        RootCommand::Completions(_) | RootCommand::Man => Err(usage_error(
+            "COMMAND_UNSUPPORTED",
+            "completions and man do not operate on a project",
+        )),
+    }
+}
+
+fn gc(db: &Database, project: &str, args: GcArgs) -> CliResult<CommandOutput> {
+    let project_slug = (project != projects::DEFAULT_PROJECT).then_some(project);
+    let other_projects = open_other_project_dbs(&projects::get_project_path(project));
+    let report =
+        scan_orphans(db, project_slug, &other_projects, &RealGcRuntime).map_err(runtime_error)?;
+    let outcomes = if args.apply {
+        let options = GcOptions {
+            adopt_worktrees: args.adopt,
+            force: args.force,
+            archive_missing: args.archive_missing,
+        };
+        apply_gc(db, &report, options, &RealGcRuntime)
+    } else {
+        Vec::new()
+    };
+
+    let data = json!({
+        "applied": args.apply,
+        "orphan_worktrees": report.orphan_worktrees.iter().map(|orphan| json!({
+            "repo_id": orphan.repo_id.to_string(),
+            "repo": orphan.repo_name,
+            "path": orphan.path.display().to_string(),
+            "branch": orphan.branch,
+        })).collect::<Vec<_>>(),
+        "orphan_sessions": report.orphan_sessions,
+        "missing_worktree_tasks": report.missing_worktree_tasks.iter().map(|task| json!({
+            "id": task.task_id.to_string(),
+            "title": task.title,
+            "worktree_path": task.worktree_path,
+        })).collect::<Vec<_>>(),
+        "outcomes": outcomes.iter().map(|outcome| json!({
+            "kind": outcome.kind.as_str(),
+            "target": outcome.target,
+            "action": outcome.action.as_str(),
+            "ok": outcome.error.is_none(),
+            "error": outcome.error,
+        })).collect::<Vec<_>>(),
+    });
+
+    let failed = outcomes
+        .iter()
+        .filter(|outcome| outcome.error.is_some())
+        .count();
+    if failed > 0 {
+        return Err(CliError {
+            exit_code: 5,
+            code: "GC_INCOMPLETE",
+            message: format!("{failed} of {} cleanup actions failed", outcomes.len()),
+            details: Some(data),
+        });
+    }
+
+    Ok(CommandOutput {
+        command: "gc",
+        project: project.to_string(),
+        data,
+        text: render_gc_text(&report, &outcomes, args.apply),
+        listing: None,
+    })
+}
+
+fn render_gc_text(report: &GcReport, outcomes: &[GcOutcome], applied: bool) -> String {
+    if report.is_empty() {
+        return "Nothing to clean up.".to_string();
+    }
+
+    let mut lines = Vec::new();
+    if !report.orphan_worktrees.is_empty() {
+        lines.push("Worktrees no task references:".to_string());
+        for orphan in &report.orphan_worktrees {
+            lines.push(format!(
+                "  {}:{}  {}",
+                orphan.repo_name,
+                orphan.branch.as_deref().unwrap_or("(detached)"),
+                orphan.path.display()
+            ));
+        }
+    }
+    if !report.orphan_sessions.is_empty() {
+        lines.push("tmux sessions no task references:".to_string());
+        for session in &report.orphan_sessions {
+            lines.push(format!("  {session}"));
+        }
+    }
+    if !report.missing_worktree_tasks.is_empty() {
+        lines.push("Tasks whose worktree is gone:".to_string());
+        for task in &report.missing_worktree_tasks {
+            let short_id = task.task_id.to_string().chars().take(8).collect::<String>();
+            lines.push(format!(
+                "  {short_id}  {}  {}",
+                task.title, task.worktree_path
+            ));
+        }
+    }
+
+    lines.push(String::new());
+    if !applied {
+        lines.push(
+            "Run with --apply to remove clean orphan worktrees and sessions (--force also discards uncommitted changes), --apply --adopt to turn orphan worktrees into tasks, or --apply --archive-missing to archive tasks whose worktree is gone."
+                .to_string(),
+        );
+        return lines.join("\n");
+    }
+
+    for outcome in outcomes {
+        match &outcome.error {
+            None => lines.push(format!(
+                "{} {} {}",
+                outcome.action.as_str(),
+                outcome.kind.as_str(),
+                outcome.target
+            )),
+            Some(error) => lines.push(format!(
+                "failed to {} {} {}: {error}",
+                outcome.action.verb(),
+                outcome.kind.as_str(),
+                outcome.target
+            )),
+        }
+    }
+    lines.join("\n")
+}
+
+// Doctor diagnoses setups where the project database may be missing or
+// unreadable, so it runs before any database is opened. Failed checks still
+// print the full checklist and then exit with the runtime error code.
+fn run_doctor(project_name: &str, options: &OutputOptions, quiet: bool) -> i32 {
+    let checks = doctor::run_checks(project_name);
+    let output = doctor_output(project_name, &checks);
+
+    if let Err(err) = print_success(&output, options, quiet) {
+        print_error(&err, options.format);
+        return err.exit_code;
+    }
+    if doctor::has_failures(&checks) { 5 } else { 0 }
+}
+
+fn board_stats(db: &Database, project: &str, args: StatsArgs) -> CliResult<CommandOutput> {
+    let now = Utc::now();
+    let since = args
+        .since
+        .as_deref()
+        .map(|value| stats::parse_since(value, now))
+        .transpose()
+        .map_err(|message| usage_error("INVALID_SINCE", message))?;
+
+    let mut tasks = db.list_tasks().map_err(runtime_error)?;
+    tasks.extend(db.list_archived_tasks().map_err(runtime_error)?);
+    let categories = db.list_categories().map_err(runtime_error)?;
+    let repos = db.list_repos().map_err(runtime_error)?;
+    let events = db.list_task_events().map_err(runtime_error)?;
+
+    let report = stats::compute_stats(&tasks, &categories, &repos, &events, since, now);
+    Ok(CommandOutput {
+        command: "stats",
+        project: project.to_string(),
+        data: stats::stats_json(&report),
+        text: stats::render_stats_markdown(project, &report),
+        listing: None,
+    })
+}
+
+fn stats_cost(db: &Database, project: &str) -> CliResult<CommandOutput> {
+    let mut tasks = db.list_tasks().map_err(runtime_error)?;
+    tasks.extend(db.list_archived_tasks().map_err(runtime_error)?);
+    let repos = db.list_repos().map_err(runtime_error)?;
+    let records = db.list_session_usage().map_err(runtime_error)?;
+
+    let report = stats::compute_cost(&tasks, &repos, &records);
+    Ok(CommandOutput {
+        command: "stats cost",
+        project: project.to_string(),
+        data: stats::cost_json(&report),
+        text: stats::render_cost_markdown(project, &report),
+        listing: None,
+    })
+}
+
+fn doctor_output(project: &str, checks: &[doctor::DoctorCheck]) -> CommandOutput {
+    CommandOutput {
+        command: "doctor",
+        project: project.to_string(),
+        data: doctor::checks_json(checks),
+        text: doctor::render_checks_text(checks),
+        listing: None,
+    }
+}
+
+const COMPLETION_ENV_VAR: &str = "COMPLETE";
+const COMPLETION_BIN_NAME: &str = "opencode-kanban";
+
+// The generated scripts call back into the binary with COMPLETE set, which
+// `main` hands to clap_complete before any other work happens.
+pub fn write_completions(shell: CompletionShell, output: &mut impl Write) -> io::Result<()> {
+    let completer: &dyn EnvCompleter = match shell {
+        CompletionShell::Bash => &Bash,
+        CompletionShell::Zsh => &Zsh,
+        CompletionShell::Fish => &Fish,
+    };
+    completer.write_registration(
+        COMPLETION_ENV_VAR,
+        COMPLETION_BIN_NAME,
+        COMPLETION_BIN_NAME,
+        COMPLETION_BIN_NAME,
+        output,
+    )
+}
+
+pub fn complete_project_names(current: &OsStr) -> Vec<CompletionCandidate> {
+    let prefix = current.to_string_lossy();
+    projects::list_projects()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|project| project.name.starts_with(prefix.as_ref()))
+        .map(|project| CompletionCandidate::new(project.name))
+        .collect()
+}
+
+fn complete_category_slugs(current: &OsStr) -> Vec<CompletionCandidate> {
+    let Some(db) = completion_db() else {
+        return Vec::new();
+    };
+    let prefix = current.to_string_lossy();
+    db.list_categories()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|category| category.slug.starts_with(prefix.as_ref()))
+        .map(|category| CompletionCandidate::new(category.slug).help(Some(category.name.into())))
+        .collect()
+}
+
+fn complete_repo_names(current: &OsStr) -> Vec<CompletionCandidate> {
+    let Some(db) = completion_db() else {
+        return Vec::new();
+    };
+    let prefix = current.to_string_lossy();
+    db.list_repos()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|repo| repo.name.starts_with(prefix.as_ref()))
+        .map(|repo| CompletionCandidate::new(repo.name).help(Some(repo.path.into())))
+        .collect()
+}
+
+fn complete_task_ids(current: &OsStr) -> Vec<CompletionCandidate> {
+    completion_db()
+        .map(|db| task_id_candidates(&db, &current.to_string_lossy()))
+        .unwrap_or_default()
+}
+
+fn task_id_candidates(db: &Database, prefix: &str) -> Vec<CompletionCandidate> {
+    let needle = prefix.to_ascii_lowercase();
+    db.list_tasks()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|task| {
+            let short_id = task.id.to_string().chars().take(8).collect::<String>();
+            (short_id, task.title.replace('\n', " "))
+        })
+        .filter(|(short_id, _)| short_id.starts_with(&needle))
+        .map(|(short_id, title)| CompletionCandidate::new(short_id).help(Some(title.into())))
+        .collect()
+}
+
+fn completion_db() -> Option<Database> {
+    let project = completion_project_name(std::env::args());
+    let db_path = projects::get_project_path(&project);
+    if !db_path.exists() {
+        return None;
+    }
+    Database::open(&db_path).ok()
+}
+
+fn completion_project_name(args: impl IntoIterator<Item = String>) -> String {
+    let mut args = args.into_iter();
+    let mut project = None;
+    while let Some(arg) = args.next() {
+        if arg == "-p" || arg == "--project" {
+            project = args.next();
+        } else if let Some(value) = arg.strip_prefix("--project=") {
+            project = Some(value.to_string());
+        } else if let Some(value) = arg.strip_prefix("-p")
+            && !value.is_empty()
+            && !arg.starts_with("--")
+        {
+            project = Some(value.to_string());
+        }
+    }
+
+    project
+        .filter(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| projects::DEFAULT_PROJECT.to_string())
+}
+
+fn run_batch(project_name: &str, args: &BatchArgs) -> i32 {
+    let db = match resolve_existing_project_db_path(project_name).and_then(|db_path| {
+        // Atomic batches pin every command to one connection so the
+        // surrounding BEGIN/COMMIT covers all of them.
+        let opened = if args.atomic {
+            Database::open_single_connection(&db_path)
+        } else {
+            Database::open(&db_path)
+        };
+        opened.map_err(runtime_error)
+    }) {
+        Ok(db) => db,
+        Err(err) => {
+            print_error(&err, OutputFormat::Json);
+            return err.exit_code;
+        }
+    };
+
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    execute_batch(
+        &db,
+        project_name,
+        args.atomic,
+        stdin.lock(),
+        &mut stdout.lock(),
+    )
+}
+
+fn run_serve(project_name: &str, args: &ServeArgs) -> i32 {
+    let token = args
+        .token
+        .clone()
+        .or_else(|| std::env::var(API_TOKEN_ENV).ok())
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty());
+    let served = if !args.listen.ip().is_loopback() && token.is_none() {
+        Err(usage_error(
+            "API_TOKEN_REQUIRED",
+            format!(
+                "refusing to serve on non-loopback address {} without --token or ${API_TOKEN_ENV}",
+                args.listen
+            ),
+        ))
+    } else {
+        Ok(())
+    };
+    let served = served
+        .and_then(|()| resolve_existing_project_db_path(project_name))
+        .and_then(|db_path| Database::open(&db_path).map_err(runtime_error))
+        .and_then(|db| {
+            block_on_server(serve::serve(
+                args.listen,
+                db,
+                project_name.to_string(),
+                token,
+            ))?
+            .map_err(|err| runtime_error(format!("failed to serve on {}: {err}", args.listen)))
+        });
+    match served {
+        Ok(()) => 0,
+        Err(err) => {
+            print_error(&err, OutputFormat::Table);
+            err.exit_code
+        }
+    }
+}
+
+// Sessions created for a task carry its id; it only applies to the board the
+// session belongs to, so an explicit --project for another board ignores it.
+fn run_mcp(project_name: &str) -> i32 {
+    let db = match resolve_existing_project_db_path(project_name)
+        .and_then(|db_path| Database::open(&db_path).map_err(runtime_error))
+    {
+        Ok(db) => db,
+        Err(err) => {
+            print_error(&err, OutputFormat::Json);
+            return err.exit_code;
+        }
+    };
+    let current_task = session_env(TASK_ID_ENV).filter(|_| {
+        session_env(PROJECT_ENV).is_none_or(|session_project| session_project == project_name)
+    });
+
+    let server = mcp::McpServer::new(db, project_name.to_string(), current_task);
+    let stdin = io::stdin();
+    match server.run(stdin.lock(), &mut io::stdout().lock()) {
+        Ok(()) => 0,
+        Err(err) => {
+            let err = runtime_error(format!("mcp transport failed: {err}"));
+            print_error(&err, OutputFormat::Json);
+            err.exit_code
+        }
+    }
+}
+
+fn session_env(name: &str) -> Option<String> {
+    std::env::var(name)
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+}
+
+fn execute_batch(
+    db: &Database,
+    project: &str,
+    atomic: bool,
+    input: impl BufRead,
+    output: &mut impl Write,
+) -> i32 {
+    if atomic && let Err(err) = db.begin_transaction() {
+        let err = runtime_error(format_anyhow_error_chain(&err));
+        print_error(&err, OutputFormat::Json);
+        return err.exit_code;
+    }
+
+    let mut exit_code = 0;
+    let mut results: Vec<(usize, CliResult<CommandOutput>)> = Vec::new();
+    let mut lines = input.lines().enumerate();
+
+    for (index, line) in lines.by_ref() {
+        let result = match line {
+            Ok(line) if line.trim().is_empty() => continue,
+            Ok(line) => parse_batch_line(&line)
+                .and_then(|command| check_batch_command(&command, atomic).map(|()| command))
+                .and_then(|command| execute_with_db(db, project, command)),
+            Err(err) => Err(runtime_error(format!("failed to read batch input: {err}"))),
+        };
+
+        let failed = result.is_err();
+        if let Err(err) = &result
+            && exit_code == 0
+        {
+            exit_code = err.exit_code;
+        }
+
+        if atomic {
+            results.push((index + 1, result));
+            if failed {
+                break;
+            }
+        } else {
+            write_batch_envelope(output, index + 1, &result);
+        }
+    }
+
+    if !atomic {
+        return exit_code;
+    }
+
+    if exit_code == 0 {
+        if let Err(err) = db.commit_transaction() {
+            let message = format_anyhow_error_chain(&err);
+            for (_, result) in &mut results {
+                *result = Err(runtime_error(&message));
+            }
+            exit_code = 5;
+        }
+    } else {
+        if let Err(err) = db.rollback_transaction() {
+            warn!(error = %err, "failed to roll back batch transaction");
+        }
+        for (_, result) in &mut results {
+            if result.is_ok() {
+                *result = Err(conflict_error(
+                    "BATCH_ROLLED_BACK",
+                    "rolled back because a later command in the atomic batch failed",
+                    None,
+                ));
+            }
+        }
+        for (index, line) in lines {
+            if line.as_deref().is_ok_and(|line| line.trim().is_empty()) {
+                continue;
+            }
+            results.push((
+                index + 1,
+                Err(conflict_error(
+                    "BATCH_ABORTED",
+                    "skipped because an earlier command in the atomic batch failed",
+                    None,
+                )),
+            ));
+        }
+    }
+
+    for (line, result) in &results {
+        write_batch_envelope(output, *line, result);
+    }
+    exit_code
+}
+
+/// Batch input is the command stream itself, so no command may read stdin,
+/// and an atomic batch can only roll back what lives in the database.
+fn check_batch_command(command: &RootCommand, atomic: bool) -> CliResult<()> {
+    let reads_stdin = match command {
+        RootCommand::Task {
+            command: TaskCommand::Create(args),
+        } => args.prompt_file.as_deref() == Some(Path::new("-")),
+        RootCommand::Task {
+            command: TaskCommand::Prompt(args),
+        } => args.source.file.as_deref() == Some(Path::new("-")),
+        _ => false,
+    };
+    if reads_stdin {
+        return Err(usage_error(
+            "BATCH_STDIN_UNAVAILABLE",
+            "commands inside a batch cannot read from stdin; pass the text or a file path",
+        ));
+    }
+
+    let external_effects = matches!(
+        command,
+        RootCommand::Task {
+            command: TaskCommand::Create(_) | TaskCommand::Prompt(_) | TaskCommand::Abort(_),
+        } | RootCommand::Gc(GcArgs { apply: true, .. })
+    );
+    if atomic && external_effects {
+        return Err(usage_error(
+            "BATCH_NOT_ATOMIC",
+            "this command changes worktrees, tmux or OpenCode sessions, which an atomic batch cannot roll back",
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct BatchLine {
+    command: String,
+    #[serde(default)]
+    args: Value,
+}
+
+fn parse_batch_line(line: &str) -> CliResult<RootCommand> {
+    let parsed: BatchLine = serde_json::from_str(line).map_err(|err| {
+        usage_error(
+            "BATCH_INVALID_LINE",
+            format!("expected {{\"command\": ..., \"args\": ...}} JSON: {err}"),
+        )
+    })?;
+    batch_command(parsed)
+}
+
+fn batch_command(parsed: BatchLine) -> CliResult<RootCommand> {
+    let mut argv = parsed
+        .command
+        .split_whitespace()
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    match parsed.args {
+        Value::Null => {}
+        Value::Array(items) => {
+            for item in items {
+                let Value::String(arg) = item else {
+                    return Err(usage_error(
+                        "BATCH_INVALID_ARGS",
+                        "`args` arrays must contain only strings",
+                    ));
+                };
+                argv.push(arg);
+            }
+        }
+        Value::Object(map) => {
+            for (key, value) in map {
+                push_batch_flag(&mut argv, &format!("--{}", key.replace('_', "-")), value)?;
+            }
+        }
+        _ => {
+            return Err(usage_error(
+                "BATCH_INVALID_ARGS",
+                "`args` must be an object or an array of strings",
+            ));
+        }
+    }
+
+    BatchCommandLine::try_parse_from(argv)
+        .map(|parsed| parsed.command)
+        .map_err(|err| {
+            let rendered = err.to_string();
+            let message = rendered
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .trim_start_matches("error: ")
+                .to_string();
+            usage_error("BATCH_INVALID_COMMAND", message)
+        })
+}
+
+fn push_batch_flag(argv: &mut Vec<String>, flag: &str, value: Value) -> CliResult<()> {
+    match value {
+        Value::Null | Value::Bool(false) => {}
+        Value::Bool(true) => argv.push(flag.to_string()),
+        Value::String(text) => argv.push(format!("{flag}={text}")),
+        Value::Number(number) => argv.push(format!("{flag}={number}")),
+        Value::Array(items) => {
+            for item in items {
+                push_batch_flag(argv, flag, item)?;
+            }
+        }
+        Value::Object(_) => {
+            return Err(usage_error(
+                "BATCH_INVALID_ARGS",
+                format!("argument {flag} cannot be an object"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn write_batch_envelope(output: &mut impl Write, line: usize, result: &CliResult<CommandOutput>) {
+    let mut payload = match result {
+        Ok(command_output) => success_envelope(command_output),
+        Err(err) => error_envelope(err),
+    };
+    if let Value::Object(map) = &mut payload {
+        map.insert("line".to_string(), json!(line));
+    }
+
+    if writeln!(output, "{payload}")
+        .and_then(|_| output.flush())
+        .is_err()
+    {
+        warn!(line, "failed to write batch result");
     }
 }
 
@@ -217,9 +1155,69 @@ fn execute_task_command(
         TaskCommand::Move(args) => task_move(db, project, args),
         TaskCommand::Archive(args) => task_archive(db, project, args),
         TaskCommand::Show(args) => task_show(db, project, args),
+        TaskCommand::Prompt(args) => task_prompt(db, project, args),
+        TaskCommand::Abort(args) => task_abort(db, project, args),
+        TaskCommand::Note(args) => task_note(db, project, args),
     }
 }
 
+fn execute_board_command(
+    db: &Database,
+    project: &str,
+    command: BoardCommand,
+) -> CliResult<CommandOutput> {
+    match command {
+        BoardCommand::Render(args) => board_render(db, project, args),
+    }
+}
+
+fn board_render(db: &Database, project: &str, args: BoardRenderArgs) -> CliResult<CommandOutput> {
+    let view_mode = match args.view {
+        BoardView::Kanban => ViewMode::Kanban,
+        BoardView::Detail => ViewMode::SidePanel,
+    };
+    let ansi = if args.ansi || args.plain {
+        args.ansi
+    } else {
+        io::stdout().is_terminal()
+    };
+
+    let mut app = App::snapshot(
+        db.clone(),
+        projects::get_project_path(project),
+        None,
+        view_mode,
+        (args.width, args.height),
+    )
+    .map_err(runtime_error)?;
+    let buffer =
+        board::render_app_buffer(&mut app, args.width, args.height).map_err(runtime_error)?;
+    let lines = if ansi {
+        board::buffer_to_ansi_lines(&buffer)
+    } else {
+        board::buffer_to_plain_lines(&buffer)
+    };
+
+    let data = json!({
+        "width": args.width,
+        "height": args.height,
+        "view": match args.view {
+            BoardView::Kanban => "kanban",
+            BoardView::Detail => "detail",
+        },
+        "ansi": ansi,
+        "lines": lines,
+    });
+
+    Ok(CommandOutput {
+        command: "board render",
+        project: project.to_string(),
+        data,
+        text: lines.join("\n"),
+        listing: None,
+    })
+}
+
 fn execute_category_command(
     db: &Database,
     project: &str,
@@ -238,22 +1236,29 @@ fn category_list(db: &Database, project: &str) -> CliResult<CommandOutput> {
     let data = json!({
         "categories": categories.iter().map(category_json).collect::<Vec<_>>()
     });
-    let text = render_category_list_text(&categories);
+    let listing = category_listing(&categories);
+    let text = render_listing_text(&listing);
 
     Ok(CommandOutput {
         command: "category list",
         project: project.to_string(),
         data,
         text,
+        listing: Some(listing),
     })
 }
 
-fn render_category_list_text(categories: &[Category]) -> String {
-    if categories.is_empty() {
-        return "No categories found.".to_string();
-    }
-
-    let headers = ["ID", "Slug", "Name", "Pos", "Color"];
+const CATEGORY_LIST_COLUMNS: &[ListColumn] = &[
+    column("id", "ID"),
+    column("slug", "Slug"),
+    column("name", "Name"),
+    column("position", "Pos"),
+    column("color", "Color"),
+    column("full_id", "Full ID"),
+    column("created_at", "Created"),
+];
+
+fn category_listing(categories: &[Category]) -> Listing {
     let rows = categories
         .iter()
         .map(|category| {
@@ -268,11 +1273,19 @@ fn render_category_list_text(categories: &[Category]) -> String {
                 name,
                 category.position.to_string(),
                 color,
+                id,
+                category.created_at.clone(),
             ]
         })
         .collect::<Vec<_>>();
 
-    render_text_table(&headers, &rows)
+    Listing {
+        columns: CATEGORY_LIST_COLUMNS,
+        default_columns: &["id", "slug", "name", "position", "color"],
+        rows,
+        records: categories.iter().map(category_json).collect(),
+        empty_text: "No categories found.",
+    }
 }
 
 fn category_create(
@@ -299,6 +1312,7 @@ fn category_create(
         project: project.to_string(),
         data,
         text: format!("created category {} ({})", created.slug, created.id),
+        listing: None,
     })
 }
 
@@ -353,6 +1367,7 @@ fn category_update(
         project: project.to_string(),
         data,
         text: format!("updated category {} ({})", updated.slug, updated.id),
+        listing: None,
     })
 }
 
@@ -369,6 +1384,7 @@ fn category_delete(
         project: project.to_string(),
         data,
         text: format!("deleted category {}", args.id),
+        listing: None,
     })
 }
 
@@ -409,26 +1425,39 @@ fn task_list(db: &Database, project: &str, args: TaskListArgs) -> CliResult<Comm
             .collect::<Vec<_>>()
     });
 
-    let text = render_task_list_text(&filtered, &category_by_id, &repo_by_id);
+    let listing = task_listing(&filtered, &category_by_id, &repo_by_id);
+    let text = render_listing_text(&listing);
 
     Ok(CommandOutput {
         command: "task list",
         project: project.to_string(),
         data,
         text,
+        listing: Some(listing),
     })
 }
 
-fn render_task_list_text(
+const TASK_LIST_COLUMNS: &[ListColumn] = &[
+    column("id", "ID"),
+    column("category", "Category"),
+    column("repo_branch", "Repo:Branch"),
+    column("title", "Title"),
+    column("full_id", "Full ID"),
+    column("repo", "Repo"),
+    column("branch", "Branch"),
+    column("status", "Status"),
+    column("session", "Session"),
+    column("worktree", "Worktree"),
+    column("archived", "Archived"),
+    column("created_at", "Created"),
+    column("updated_at", "Updated"),
+];
+
+fn task_listing(
     tasks: &[Task],
     category_by_id: &HashMap<Uuid, Category>,
     repo_by_id: &HashMap<Uuid, Repo>,
-) -> String {
-    if tasks.is_empty() {
-        return "No tasks found.".to_string();
-    }
-
-    let headers = ["ID", "Category", "Repo:Branch", "Title"];
+) -> Listing {
     let rows = tasks
         .iter()
         .map(|task| {
@@ -445,17 +1474,72 @@ fn render_task_list_text(
             let repo_branch = format!("{}:{}", repo_name, task.branch);
             let title = task.title.replace('\n', " ");
 
-            vec![short_id, category_label, repo_branch, title]
+            vec![
+                short_id,
+                category_label,
+                repo_branch,
+                title,
+                id,
+                repo_name,
+                task.branch.clone(),
+                task.tmux_status.clone(),
+                task.opencode_session_id
+                    .clone()
+                    .unwrap_or_else(|| "-".to_string()),
+                task.worktree_path
+                    .clone()
+                    .unwrap_or_else(|| "-".to_string()),
+                task.archived.to_string(),
+                task.created_at.clone(),
+                task.updated_at.clone(),
+            ]
         })
         .collect::<Vec<_>>();
 
-    render_text_table(&headers, &rows)
+    Listing {
+        columns: TASK_LIST_COLUMNS,
+        default_columns: &["id", "category", "repo_branch", "title"],
+        rows,
+        records: tasks
+            .iter()
+            .map(|task| task_json(task, category_by_id, repo_by_id))
+            .collect(),
+        empty_text: "No tasks found.",
+    }
+}
+
+fn render_listing_text(listing: &Listing) -> String {
+    if listing.rows.is_empty() {
+        return listing.empty_text.to_string();
+    }
+
+    let selected = listing
+        .select_columns(None)
+        .expect("default columns should exist");
+    render_text_table(
+        &listing.headers(&selected),
+        &listing.selected_rows(&selected),
+    )
 }
 
 fn render_text_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    render_text_table_with_header(headers, rows, true)
+}
+
+fn render_text_table_with_header(
+    headers: &[&str],
+    rows: &[Vec<String>],
+    include_header: bool,
+) -> String {
     let mut widths = headers
         .iter()
-        .map(|header| header.chars().count())
+        .map(|header| {
+            if include_header {
+                header.chars().count()
+            } else {
+                0
+            }
+        })
         .collect::<Vec<_>>();
 
     for row in rows {
@@ -478,16 +1562,18 @@ fn render_text_table(headers: &[&str], rows: &[Vec<String>]) -> String {
 
     let mut lines = Vec::new();
     lines.push(border.clone());
-    lines.push(format!(
-        "| {} |",
-        headers
-            .iter()
-            .enumerate()
-            .map(|(index, header)| format!("{header:<width$}", width = widths[index]))
-            .collect::<Vec<_>>()
-            .join(" | ")
-    ));
-    lines.push(border.clone());
+    if include_header {
+        lines.push(format!(
+            "| {} |",
+            headers
+                .iter()
+                .enumerate()
+                .map(|(index, header)| format!("{header:<width$}", width = widths[index]))
+                .collect::<Vec<_>>()
+                .join(" | ")
+        ));
+        lines.push(border.clone());
+    }
 
     for row in rows {
         lines.push(format!(
@@ -522,6 +1608,21 @@ fn task_create(db: &Database, project: &str, args: TaskCreateArgs) -> CliResult<
         return Err(usage_error("BRANCH_REQUIRED", "branch cannot be empty"));
     }
 
+    let selection = AgentSelection::parse(
+        args.model.as_deref().unwrap_or_default(),
+        args.agent.as_deref().unwrap_or_default(),
+    )
+    .map_err(|message| usage_error("INVALID_MODEL", message))?;
+
+    let initial_prompt = if args.prompt.is_some() || args.prompt_file.is_some() {
+        Some(read_prompt_text(&PromptSourceArgs {
+            message: args.prompt.clone(),
+            file: args.prompt_file.clone(),
+        })?)
+    } else {
+        None
+    };
+
     let runtime = RealCreateTaskRuntime;
     let repo_path = PathBuf::from(&repo.path);
 
@@ -618,25 +1719,54 @@ fn task_create(db: &Database, project: &str, args: TaskCreateArgs) -> CliResult<
         });
 
     let worktree_path_string = worktree_path.display().to_string();
-    let command = opencode_attach_command(None, Some(&worktree_path_string));
 
+    let backend = default_backend();
     let mut created_task_id: Option<Uuid> = None;
     let mut tmux_created = false;
+    let mut prompt_session_id: Option<String> = None;
+    let mut prompt_error: Option<String> = None;
     let create_result = (|| -> anyhow::Result<Task> {
+        let task = db
+            .add_task(repo.id, branch, &args.title, category_id)
+            .context("failed to save task")?;
+        created_task_id = Some(task.id);
+        if !selection.is_empty() {
+            db.update_task_agent_selection(task.id, selection.clone())
+                .context("failed to save task model and agent")?;
+        }
+
+        if let Some(kind) = backend.kind().task_value() {
+            db.update_task_backend(task.id, Some(kind))
+                .context("failed to save task backend")?;
+        }
+
+        if initial_prompt.is_some() && !backend.supports_sessions() {
+            prompt_error = Some(format!(
+                "the {} backend has no sessions",
+                backend.kind().as_str()
+            ));
+        } else if initial_prompt.is_some() {
+            match bind_initial_prompt_session(db, &runtime, task.id, &worktree_path, &args.title) {
+                Ok(session_id) => prompt_session_id = Some(session_id),
+                Err(err) => prompt_error = Some(format!("{err:#}")),
+            }
+        }
+        let command = backend.launch_command(&AgentLaunch {
+            session_id: prompt_session_id.as_deref(),
+            worktree_dir: Some(&worktree_path_string),
+            selection: &selection,
+        });
+
         CreateTaskRuntime::tmux_create_session(
             &runtime,
             &session_name,
             &worktree_path,
             Some(&command),
+            &task_session_env(project, task.id),
         )
         .context("tmux session creation failed")?;
         tmux_created = true;
 
-        let task = db
-            .add_task(repo.id, branch, &args.title, category_id)
-            .context("failed to save task")?;
-        created_task_id = Some(task.id);
-
         db.update_task_tmux(
             task.id,
             Some(session_name.clone()),
@@ -646,6 +1776,13 @@ fn task_create(db: &Database, project: &str, args: TaskCreateArgs) -> CliResult<
         db.update_task_status(task.id, Status::Idle.as_str())
             .context("failed to save task runtime status")?;
 
+        if let (Some(prompt), Some(session_id)) = (&initial_prompt, &prompt_session_id)
+            && let Err(err) =
+                CreateTaskRuntime::opencode_send_prompt(&runtime, session_id, prompt, &selection)
+        {
+            prompt_error = Some(format!("{err:#}"));
+        }
+
         Ok(task)
     })();
 
@@ -677,17 +1814,61 @@ fn task_create(db: &Database, project: &str, args: TaskCreateArgs) -> CliResult<
         .collect();
     let repo_by_id = HashMap::from([(repo.id, repo.clone())]);
 
-    let data = json!({ "task": task_json(&created, &category_by_id, &repo_by_id) });
-    Ok(CommandOutput {
+    let prompt_result = initial_prompt.map(|_| InitialPromptResult {
+        session_id: prompt_session_id,
+        error: prompt_error,
+    });
+    Ok(task_create_output(
+        project,
+        &created,
+        task_json(&created, &category_by_id, &repo_by_id),
+        prompt_result,
+    ))
+}
+
+struct InitialPromptResult {
+    session_id: Option<String>,
+    error: Option<String>,
+}
+
+/// A prompt that could not be sent does not fail the command: the task
+/// already exists, so the output reports it with a `warning` instead.
+fn task_create_output(
+    project: &str,
+    created: &Task,
+    task: Value,
+    initial_prompt: Option<InitialPromptResult>,
+) -> CommandOutput {
+    let warning = initial_prompt
+        .as_ref()
+        .and_then(|result| result.error.as_ref())
+        .map(|err| format!("initial prompt not sent: {err}"));
+    let mut text = format!("created task {} ({})", created.title, created.id);
+    if let Some(warning) = &warning {
+        text.push_str(&format!("\nwarning: {warning}"));
+    }
+    let initial_prompt_json = initial_prompt.map(|result| {
+        json!({
+            "sent": result.error.is_none(),
+            "session_id": result.session_id,
+            "error": result.error,
+        })
+    });
+    CommandOutput {
         command: "task create",
         project: project.to_string(),
-        data,
-        text: format!("created task {} ({})", created.title, created.id),
-    })
+        data: json!({
+            "task": task,
+            "initial_prompt": initial_prompt_json,
+            "warning": warning,
+        }),
+        text,
+        listing: None,
+    }
 }
 
 fn task_move(db: &Database, project: &str, args: TaskMoveArgs) -> CliResult<CommandOutput> {
-    let task_id = resolve_task_id_selector(db, &args.id)?;
+    let task_id = resolve_task_selector(db, project, args.id.as_deref())?;
     let target_category_id = resolve_required_category_selector(
         db,
         args.selector.category_id,
@@ -717,11 +1898,12 @@ fn task_move(db: &Database, project: &str, args: TaskMoveArgs) -> CliResult<Comm
         project: project.to_string(),
         data,
         text: format!("moved task {} to {}", updated.id, updated.category_id),
+        listing: None,
     })
 }
 
 fn task_archive(db: &Database, project: &str, args: TaskArchiveArgs) -> CliResult<CommandOutput> {
-    let task_id = resolve_task_id_selector(db, &args.id)?;
+    let task_id = resolve_task_selector(db, project, args.id.as_deref())?;
     let existing = db
         .get_task(task_id)
         .map_err(|err| task_lookup_error(task_id, err.to_string()))?;
@@ -741,35 +1923,253 @@ fn task_archive(db: &Database, project: &str, args: TaskArchiveArgs) -> CliResul
     let repos = db.list_repos().map_err(runtime_error)?;
     let repo_by_id: HashMap<Uuid, Repo> = repos.into_iter().map(|repo| (repo.id, repo)).collect();
 
-    let data = json!({ "task": task_json(&archived, &category_by_id, &repo_by_id) });
-    Ok(CommandOutput {
-        command: "task archive",
-        project: project.to_string(),
-        data,
-        text: format!("archived task {}", archived.id),
+    let data = json!({ "task": task_json(&archived, &category_by_id, &repo_by_id) });
+    Ok(CommandOutput {
+        command: "task archive",
+        project: project.to_string(),
+        data,
+        text: format!("archived task {}", archived.id),
+        listing: None,
+    })
+}
+
+fn task_show(db: &Database, project: &str, args: TaskShowArgs) -> CliResult<CommandOutput> {
+    let task_id = resolve_task_selector(db, project, args.id.as_deref())?;
+    let task = db
+        .get_task(task_id)
+        .map_err(|err| task_lookup_error(task_id, err.to_string()))?;
+    let categories = db.list_categories().map_err(runtime_error)?;
+    let category_by_id: HashMap<Uuid, Category> = categories
+        .into_iter()
+        .map(|category| (category.id, category))
+        .collect();
+    let repos = db.list_repos().map_err(runtime_error)?;
+    let repo_by_id: HashMap<Uuid, Repo> = repos.into_iter().map(|repo| (repo.id, repo)).collect();
+
+    let notes = db.list_task_notes(task.id).map_err(runtime_error)?;
+
+    let data = json!({
+        "task": task_json(&task, &category_by_id, &repo_by_id),
+        "notes": notes.iter().map(note_json).collect::<Vec<_>>(),
+    });
+    Ok(CommandOutput {
+        command: "task show",
+        project: project.to_string(),
+        data,
+        text: format!("{} {}", task.id, task.title),
+        listing: None,
+    })
+}
+
+fn task_note(db: &Database, project: &str, args: TaskNoteArgs) -> CliResult<CommandOutput> {
+    let task_id = resolve_task_selector(db, project, args.id.as_deref())?;
+    let text = args.text.trim();
+    if text.is_empty() {
+        return Err(usage_error("NOTE_EMPTY", "note text cannot be empty"));
+    }
+    let task = db
+        .get_task(task_id)
+        .map_err(|err| task_lookup_error(task_id, err.to_string()))?;
+    let note = db.add_task_note(task.id, text).map_err(classify_db_error)?;
+
+    Ok(CommandOutput {
+        command: "task note",
+        project: project.to_string(),
+        data: json!({ "task_id": task.id, "note": note_json(&note) }),
+        text: format!("added note to task {}", task.id),
+        listing: None,
+    })
+}
+
+fn task_prompt(db: &Database, project: &str, args: TaskPromptArgs) -> CliResult<CommandOutput> {
+    let task_id = resolve_task_selector(db, project, args.id.as_deref())?;
+    let task = db
+        .get_task(task_id)
+        .map_err(|err| task_lookup_error(task_id, err.to_string()))?;
+    let session_id = bound_session_id(&task)?;
+
+    let prompt = read_prompt_text(&args.source)?;
+    let provider = ServerStatusProvider::new(ServerStatusConfig {
+        request_timeout: PROMPT_REQUEST_TIMEOUT,
+        ..ServerStatusConfig::default()
+    });
+
+    let baseline = if args.wait {
+        block_on_server(provider.fetch_session_messages(&session_id))?
+            .map_err(server_error)?
+            .len()
+    } else {
+        0
+    };
+
+    block_on_server(provider.send_session_prompt(&session_id, &prompt, &task.agent_selection()))?
+        .map_err(server_error)?;
+
+    let reply = if args.wait {
+        Some(wait_for_session_reply(
+            &provider,
+            &session_id,
+            task.worktree_path.as_deref(),
+            baseline,
+            Duration::from_secs(args.wait_timeout),
+        )?)
+    } else {
+        None
+    };
+
+    let data = json!({
+        "task_id": task.id,
+        "session_id": session_id,
+        "waited": args.wait,
+        "reply": reply.as_ref().map(session_message_json),
+    });
+    let text = match reply {
+        Some(message) => message.content,
+        None => format!("sent prompt to task {} (session {})", task.id, session_id),
+    };
+
+    Ok(CommandOutput {
+        command: "task prompt",
+        project: project.to_string(),
+        data,
+        text,
+        listing: None,
+    })
+}
+
+fn task_abort(db: &Database, project: &str, args: TaskAbortArgs) -> CliResult<CommandOutput> {
+    let task_id = resolve_task_selector(db, project, args.id.as_deref())?;
+    let task = db
+        .get_task(task_id)
+        .map_err(|err| task_lookup_error(task_id, err.to_string()))?;
+    let session_id = bound_session_id(&task)?;
+
+    let provider = ServerStatusProvider::new(ServerStatusConfig {
+        request_timeout: PROMPT_REQUEST_TIMEOUT,
+        ..ServerStatusConfig::default()
+    });
+    block_on_server(provider.abort_session(&session_id))?.map_err(server_error)?;
+    db.update_task_status(task.id, Status::Idle.as_str())
+        .map_err(runtime_error)?;
+
+    Ok(CommandOutput {
+        command: "task abort",
+        project: project.to_string(),
+        data: json!({
+            "task_id": task.id,
+            "session_id": session_id,
+            "status": Status::Idle.as_str(),
+        }),
+        text: format!("interrupted task {} (session {})", task.id, session_id),
+        listing: None,
+    })
+}
+
+fn bound_session_id(task: &Task) -> CliResult<String> {
+    task.opencode_session_id
+        .clone()
+        .filter(|value| !value.trim().is_empty())
+        .ok_or_else(|| {
+            conflict_error(
+                "TASK_SESSION_UNBOUND",
+                format!(
+                    "task {} is not bound to an OpenCode session yet; attach to it first",
+                    task.id
+                ),
+                None,
+            )
+        })
+}
+
+fn read_prompt_text(source: &PromptSourceArgs) -> CliResult<String> {
+    let raw = match (source.message.as_deref(), source.file.as_deref()) {
+        (Some(message), _) => message.to_string(),
+        (None, Some(path)) if path == Path::new("-") => {
+            let mut buffer = String::new();
+            io::stdin()
+                .read_to_string(&mut buffer)
+                .context("failed to read prompt from stdin")
+                .map_err(runtime_error)?;
+            buffer
+        }
+        (None, Some(path)) => fs::read_to_string(path).map_err(|err| {
+            not_found_error(
+                "PROMPT_FILE_UNREADABLE",
+                format!("failed to read prompt file '{}': {err}", path.display()),
+            )
+        })?,
+        (None, None) => {
+            return Err(usage_error(
+                "PROMPT_REQUIRED",
+                "provide one of --message or --file",
+            ));
+        }
+    };
+
+    let prompt = raw.trim();
+    if prompt.is_empty() {
+        return Err(usage_error("PROMPT_EMPTY", "prompt cannot be empty"));
+    }
+    Ok(prompt.to_string())
+}
+
+fn wait_for_session_reply(
+    provider: &ServerStatusProvider,
+    session_id: &str,
+    directory: Option<&str>,
+    baseline: usize,
+    timeout: Duration,
+) -> CliResult<SessionMessageItem> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let statuses = block_on_server(provider.fetch_all_statuses(SystemTime::now(), directory))?
+            .map_err(server_error)?;
+        let running = statuses
+            .get(session_id)
+            .is_some_and(|status| status.state == Status::Running);
+
+        if !running {
+            let messages = block_on_server(provider.fetch_session_messages(session_id))?
+                .map_err(server_error)?;
+            if let Some(reply) = last_assistant_message(messages.get(baseline..).unwrap_or(&[])) {
+                return Ok(reply.clone());
+            }
+        }
+
+        if Instant::now() >= deadline {
+            return Err(CliError {
+                exit_code: 5,
+                code: "PROMPT_WAIT_TIMEOUT",
+                message: format!(
+                    "session {} did not finish within {}s",
+                    session_id,
+                    timeout.as_secs()
+                ),
+                details: None,
+            });
+        }
+        thread::sleep(PROMPT_WAIT_POLL_INTERVAL);
+    }
+}
+
+fn last_assistant_message(messages: &[SessionMessageItem]) -> Option<&SessionMessageItem> {
+    messages.iter().rev().find(|message| {
+        message.role.as_deref() == Some("assistant") && !message.content.trim().is_empty()
     })
 }
 
-fn task_show(db: &Database, project: &str, args: TaskShowArgs) -> CliResult<CommandOutput> {
-    let task_id = resolve_task_id_selector(db, &args.id)?;
-    let task = db
-        .get_task(task_id)
-        .map_err(|err| task_lookup_error(task_id, err.to_string()))?;
-    let categories = db.list_categories().map_err(runtime_error)?;
-    let category_by_id: HashMap<Uuid, Category> = categories
-        .into_iter()
-        .map(|category| (category.id, category))
-        .collect();
-    let repos = db.list_repos().map_err(runtime_error)?;
-    let repo_by_id: HashMap<Uuid, Repo> = repos.into_iter().map(|repo| (repo.id, repo)).collect();
+fn block_on_server<F: Future>(future: F) -> CliResult<F::Output> {
+    if let Ok(handle) = Handle::try_current()
+        && handle.runtime_flavor() == RuntimeFlavor::MultiThread
+    {
+        return Ok(tokio::task::block_in_place(|| handle.block_on(future)));
+    }
 
-    let data = json!({ "task": task_json(&task, &category_by_id, &repo_by_id) });
-    Ok(CommandOutput {
-        command: "task show",
-        project: project.to_string(),
-        data,
-        text: format!("{} {}", task.id, task.title),
-    })
+    let runtime = RuntimeBuilder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(runtime_error)?;
+    Ok(runtime.block_on(future))
 }
 
 fn resolve_repo_filter_id(
@@ -888,6 +2288,25 @@ fn resolve_required_category_selector(
     })
 }
 
+fn resolve_task_selector(db: &Database, project: &str, selector: Option<&str>) -> CliResult<Uuid> {
+    if let Some(selector) = selector {
+        return resolve_task_id_selector(db, selector);
+    }
+
+    let dir = cwd::current_dir()?;
+    let mut tasks = db.list_tasks().map_err(runtime_error)?;
+    tasks.extend(db.list_archived_tasks().map_err(runtime_error)?);
+    let matches = cwd::tasks_containing_dir(&dir, &tasks)
+        .into_iter()
+        .map(|task| cwd::WorktreeTaskMatch {
+            project: project.to_string(),
+            task_id: task.id,
+            title: task.title.clone(),
+        })
+        .collect();
+    cwd::single_worktree_task(&dir, matches).map(|found| found.task_id)
+}
+
 fn resolve_task_id_selector(db: &Database, selector: &str) -> CliResult<Uuid> {
     let trimmed = selector.trim();
     if trimmed.is_empty() {
@@ -969,6 +2388,10 @@ fn task_json(
         "status_fetched_at": task.status_fetched_at,
         "status_error": task.status_error,
         "opencode_session_id": task.opencode_session_id,
+        "model": task.model,
+        "agent": task.agent,
+        "forked_from": task.forked_from,
+        "backend": task.backend.as_deref().unwrap_or("opencode"),
         "attach_overlay_shown": task.attach_overlay_shown,
         "needs_inspection": task.needs_inspection,
         "created_at": task.created_at,
@@ -976,6 +2399,25 @@ fn task_json(
     })
 }
 
+fn note_json(note: &TaskNote) -> Value {
+    json!({
+        "id": note.id,
+        "body": note.body,
+        "created_at": note.created_at
+    })
+}
+
+fn session_message_json(message: &SessionMessageItem) -> Value {
+    json!({
+        "id": message.id,
+        "type": message.message_type,
+        "role": message.role,
+        "content": message.content,
+        "timestamp": message.timestamp,
+        "parts": message.parts
+    })
+}
+
 fn category_json(category: &Category) -> Value {
     json!({
         "id": category.id,
@@ -1027,6 +2469,15 @@ fn runtime_error(err: impl std::fmt::Display) -> CliError {
     }
 }
 
+fn server_error(err: SessionStatusError) -> CliError {
+    CliError {
+        exit_code: 5,
+        code: "SERVER_REQUEST_FAILED",
+        message: err.message,
+        details: Some(json!({ "server_code": err.code })),
+    }
+}
+
 fn task_lookup_error(task_id: Uuid, message: String) -> CliError {
     if message.contains("not found") {
         return not_found_error("TASK_NOT_FOUND", format!("task {} not found", task_id));
@@ -1059,33 +2510,78 @@ fn classify_db_error(err: anyhow::Error) -> CliError {
     runtime_error(message)
 }
 
-fn print_success(output: CommandOutput, json_output: bool, quiet: bool) {
-    if json_output {
-        let payload = json!({
-            "schema_version": SCHEMA_VERSION,
-            "command": output.command,
-            "project": output.project,
-            "data": output.data
-        });
-        match serde_json::to_string_pretty(&payload) {
-            Ok(value) => println!("{value}"),
-            Err(_) => println!("{}", payload),
+fn success_envelope(output: &CommandOutput) -> Value {
+    json!({
+        "schema_version": SCHEMA_VERSION,
+        "command": output.command,
+        "project": output.project,
+        "data": output.data
+    })
+}
+
+fn error_envelope(err: &CliError) -> Value {
+    json!({
+        "schema_version": SCHEMA_VERSION,
+        "error": {
+            "code": err.code,
+            "message": err.message,
+            "details": err.details
         }
-        return;
+    })
+}
+
+fn print_success(output: &CommandOutput, options: &OutputOptions, quiet: bool) -> CliResult<()> {
+    if quiet && options.format == OutputFormat::Table {
+        return Ok(());
     }
 
-    if quiet {
-        return;
+    let rendered = render_success(output, options)?;
+    if !rendered.is_empty() {
+        println!("{rendered}");
     }
+    Ok(())
+}
 
-    if output.text.is_empty() {
-        println!("ok");
-    } else {
-        println!("{}", output.text);
+fn render_success(output: &CommandOutput, options: &OutputOptions) -> CliResult<String> {
+    let columns = options.columns.as_deref();
+    let column_error = |message: String| usage_error("UNKNOWN_COLUMN", message);
+
+    match (options.format, output.listing.as_ref()) {
+        (OutputFormat::Json, _) => {
+            let payload = success_envelope(output);
+            Ok(serde_json::to_string_pretty(&payload).unwrap_or_else(|_| payload.to_string()))
+        }
+        (OutputFormat::Ndjson, Some(listing)) => Ok(listing
+            .structured_records(columns)
+            .map_err(column_error)?
+            .iter()
+            .map(Value::to_string)
+            .collect::<Vec<_>>()
+            .join("\n")),
+        (OutputFormat::Ndjson, None) => Ok(success_envelope(output).to_string()),
+        (OutputFormat::Yaml, Some(listing)) => Ok(render_yaml(&Value::Array(
+            listing.structured_records(columns).map_err(column_error)?,
+        ))),
+        (OutputFormat::Yaml, None) => Ok(render_yaml(&output.data)),
+        (format, Some(listing)) => {
+            let selected = listing.select_columns(columns).map_err(column_error)?;
+            let headers = listing.headers(&selected);
+            let rows = listing.selected_rows(&selected);
+            let include_header = !options.no_header;
+
+            Ok(match format {
+                OutputFormat::Csv => render_csv(&headers, &rows, include_header),
+                OutputFormat::Markdown => render_markdown_table(&headers, &rows, include_header),
+                _ if rows.is_empty() => listing.empty_text.to_string(),
+                _ => render_text_table_with_header(&headers, &rows, include_header),
+            })
+        }
+        (_, None) if output.text.is_empty() => Ok("ok".to_string()),
+        (_, None) => Ok(output.text.clone()),
     }
 }
 
-fn print_error(err: &CliError, json_output: bool) {
+fn print_error(err: &CliError, format: OutputFormat) {
     error!(
         code = err.code,
         message = %err.message,
@@ -1093,23 +2589,18 @@ fn print_error(err: &CliError, json_output: bool) {
         "cli command failed"
     );
 
-    if json_output {
-        let payload = json!({
-            "schema_version": SCHEMA_VERSION,
-            "error": {
-                "code": err.code,
-                "message": err.message,
-                "details": err.details
-            }
-        });
-        match serde_json::to_string_pretty(&payload) {
+    let payload = error_envelope(err);
+    match format {
+        OutputFormat::Json => match serde_json::to_string_pretty(&payload) {
             Ok(value) => eprintln!("{value}"),
             Err(_) => eprintln!("{}", payload),
+        },
+        OutputFormat::Ndjson => eprintln!("{payload}"),
+        OutputFormat::Yaml => eprintln!("{}", render_yaml(&payload)),
+        OutputFormat::Table | OutputFormat::Csv | OutputFormat::Markdown => {
+            eprintln!("error[{}]: {}", err.code, err.message);
         }
-        return;
     }
-
-    eprintln!("error[{}]: {}", err.code, err.message);
 }
 
 fn format_anyhow_error_chain(err: &anyhow::Error) -> String {
@@ -1147,6 +2638,7 @@ fn find_constraint_detail(err: &anyhow::Error, needle: &str) -> Option<String> {
 mod tests {
     use super::*;
     use crate::db::Database;
+    use crate::types::{ModelUsage, TokenUsage};
     use tempfile::TempDir;
 
     fn fake_repo(name: &str, path: &str) -> Repo {
@@ -1267,6 +2759,10 @@ mod tests {
             status_fetched_at: None,
             status_error: None,
             opencode_session_id: None,
+            model: None,
+            agent: None,
+            forked_from: None,
+            backend: None,
             attach_overlay_shown: false,
             needs_inspection: false,
             archived: false,
@@ -1277,13 +2773,223 @@ mod tests {
 
         let category_by_id = HashMap::from([(category_id, category)]);
         let repo_by_id = HashMap::from([(repo_id, repo)]);
-        let output = render_task_list_text(&[task], &category_by_id, &repo_by_id);
+        let output = render_listing_text(&task_listing(&[task], &category_by_id, &repo_by_id));
 
         assert!(output.contains("Repo:Branch"));
         assert!(output.contains("test-repo:feature/table-output"));
         assert!(output.contains("|"));
     }
 
+    #[test]
+    fn board_render_prints_plain_snapshot_of_the_board() {
+        let repo_dir = TempDir::new().expect("temp repo dir");
+        let db = Database::open(":memory:").expect("db should open");
+        let repo = db.add_repo(repo_dir.path()).expect("repo should save");
+        let category = db
+            .get_category_by_slug("todo")
+            .expect("lookup should succeed")
+            .expect("todo category should exist");
+        db.add_task(repo.id, "feature/snapshot", "Snapshot task", category.id)
+            .expect("task should save");
+
+        let output = board_render(
+            &db,
+            "demo",
+            BoardRenderArgs {
+                width: 100,
+                height: 20,
+                view: BoardView::Kanban,
+                ansi: false,
+                plain: true,
+            },
+        )
+        .expect("board should render");
+
+        let lines = output.data["lines"].as_array().expect("lines array");
+        assert_eq!(lines.len(), 20);
+        assert!(output.text.contains("TODO (1)"));
+        assert!(output.text.contains("Snapshot task"));
+        assert!(!output.text.contains('\x1b'));
+    }
+
+    #[test]
+    fn board_stats_reports_recent_activity_and_rejects_bad_windows() {
+        let repo_dir = TempDir::new().expect("temp repo dir");
+        let db = Database::open(":memory:").expect("db should open");
+        let repo = db.add_repo(repo_dir.path()).expect("repo should save");
+        let categories = db.list_categories().expect("categories");
+        let task = db
+            .add_task(repo.id, "feature/stats", "Stats task", categories[0].id)
+            .expect("task should save");
+        db.update_task_category(task.id, categories[1].id, 0)
+            .expect("move should save");
+
+        let output = board_stats(
+            &db,
+            "demo",
+            StatsArgs {
+                command: None,
+                since: Some("7d".to_string()),
+            },
+        )
+        .expect("stats should compute");
+        assert_eq!(output.data["summary"]["created"], 1);
+        assert_eq!(output.data["summary"]["moved"], 1);
+        assert_eq!(output.data["categories"][1]["wip"], 1);
+        assert_eq!(output.data["busiest_repos"][0]["name"], repo.name);
+        assert!(output.text.starts_with("# Board stats: demo"));
+
+        let Err(err) = board_stats(
+            &db,
+            "demo",
+            StatsArgs {
+                command: None,
+                since: Some("last week".to_string()),
+            },
+        ) else {
+            panic!("invalid window should fail");
+        };
+        assert_eq!(err.exit_code, 2);
+        assert_eq!(err.code, "INVALID_SINCE");
+    }
+
+    #[test]
+    fn task_create_rejects_model_without_provider() {
+        let repo_dir = TempDir::new().expect("temp repo dir");
+        let db = Database::open(":memory:").expect("db should open");
+        db.add_repo(repo_dir.path()).expect("repo should save");
+
+        let parsed = BatchCommandLine::try_parse_from([
+            "task", "create", "--title", "Docs", "--branch", "docs/fix", "--model", "sonnet",
+            "--agent", "plan",
+        ])
+        .expect("task create should parse");
+        let RootCommand::Task {
+            command: TaskCommand::Create(args),
+        } = parsed.command
+        else {
+            panic!("expected task create");
+        };
+        assert_eq!(args.agent.as_deref(), Some("plan"));
+
+        let Err(err) = task_create(&db, "demo", args) else {
+            panic!("model without provider should be rejected");
+        };
+        assert_eq!(err.exit_code, 2);
+        assert_eq!(err.code, "INVALID_MODEL");
+        assert!(db.list_tasks().expect("tasks").is_empty());
+    }
+
+    #[test]
+    fn task_create_output_reports_unsent_prompt_as_warning() {
+        let repo_dir = TempDir::new().expect("temp repo dir");
+        let db = Database::open(":memory:").expect("db should open");
+        let repo = db.add_repo(repo_dir.path()).expect("repo should save");
+        let category_id = db.list_categories().expect("categories")[0].id;
+        let task = db
+            .add_task(repo.id, "feature/prompt", "Prompt", category_id)
+            .expect("task should save");
+
+        let output = task_create_output(
+            "demo",
+            &task,
+            json!({ "id": task.id }),
+            Some(InitialPromptResult {
+                session_id: None,
+                error: Some("OpenCode server is not ready: timed out".to_string()),
+            }),
+        );
+        assert_eq!(output.data["initial_prompt"]["sent"], false);
+        assert_eq!(
+            output.data["warning"],
+            "initial prompt not sent: OpenCode server is not ready: timed out"
+        );
+        assert!(output.text.starts_with("created task Prompt"));
+        assert!(output.text.contains("\nwarning: initial prompt not sent"));
+
+        let sent = task_create_output(
+            "demo",
+            &task,
+            json!({ "id": task.id }),
+            Some(InitialPromptResult {
+                session_id: Some("ses-1".to_string()),
+                error: None,
+            }),
+        );
+        assert_eq!(sent.data["initial_prompt"]["sent"], true);
+        assert_eq!(sent.data["initial_prompt"]["session_id"], "ses-1");
+        assert!(sent.data["warning"].is_null());
+        assert!(!sent.text.contains("warning"));
+    }
+
+    #[test]
+    fn stats_cost_groups_usage_by_repo_and_model() {
+        let repo_dir = TempDir::new().expect("temp repo dir");
+        let db = Database::open(":memory:").expect("db should open");
+        let repo = db.add_repo(repo_dir.path()).expect("repo should save");
+        let category_id = db.list_categories().expect("categories")[0].id;
+        let first = db
+            .add_task(repo.id, "feature/first", "First", category_id)
+            .expect("task should save");
+        let second = db
+            .add_task(repo.id, "feature/second", "Second", category_id)
+            .expect("task should save");
+        let usage = |model: &str, input_tokens: u64, cost: f64| ModelUsage {
+            model: model.to_string(),
+            usage: TokenUsage {
+                input_tokens,
+                output_tokens: 10,
+                cost,
+                ..TokenUsage::default()
+            },
+        };
+
+        let empty = stats_cost(&db, "demo").expect("empty report should render");
+        assert!(empty.text.contains("No token usage recorded yet"));
+
+        db.replace_session_usage(first.id, "ses-root", vec![usage("anthropic/a", 100, 1.0)])
+            .expect("usage should save");
+        db.replace_session_usage(first.id, "ses-sub", vec![usage("openai/b", 50, 0.25)])
+            .expect("usage should save");
+        db.replace_session_usage(second.id, "ses-other", vec![usage("anthropic/a", 20, 0.5)])
+            .expect("usage should save");
+        db.archive_task(second.id).expect("archive should save");
+
+        let output = stats_cost(&db, "demo").expect("cost report should compute");
+        assert_eq!(output.command, "stats cost");
+        assert_eq!(output.data["tasks"], 2);
+        assert_eq!(output.data["total"]["cost"], 1.75);
+        assert_eq!(output.data["total"]["total_tokens"], 200);
+        let rows = output.data["by_repo_model"]
+            .as_array()
+            .expect("rows should be an array");
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["repo"], repo.name);
+        assert_eq!(rows[0]["model"], "anthropic/a");
+        assert_eq!(rows[0]["tasks"], 2);
+        assert_eq!(rows[0]["usage"]["input_tokens"], 120);
+        assert_eq!(rows[1]["model"], "openai/b");
+        assert!(output.text.contains("- Total cost: $1.75"));
+        assert!(output.text.contains(&format!(
+            "| {} | anthropic/a | 2 | 120 | 20 | 0 | $1.50 |",
+            repo.name
+        )));
+    }
+
+    #[test]
+    fn stats_cost_subcommand_parses_and_rejects_since() {
+        let parsed =
+            BatchCommandLine::try_parse_from(["stats", "cost"]).expect("stats cost should parse");
+        assert!(matches!(
+            parsed.command,
+            RootCommand::Stats(StatsArgs {
+                command: Some(StatsCommand::Cost),
+                ..
+            })
+        ));
+        assert!(BatchCommandLine::try_parse_from(["stats", "--since", "7d", "cost"]).is_err());
+    }
+
     #[test]
     fn category_list_text_renders_table() {
         let categories = vec![
@@ -1305,13 +3011,78 @@ mod tests {
             },
         ];
 
-        let output = render_category_list_text(&categories);
+        let output = render_listing_text(&category_listing(&categories));
         assert!(output.contains("| ID"));
         assert!(output.contains("Slug"));
         assert!(output.contains("review"));
         assert!(output.contains("blue"));
     }
 
+    #[test]
+    fn render_success_applies_format_and_columns_to_listings() {
+        let categories = vec![Category {
+            id: Uuid::new_v4(),
+            slug: "todo".to_string(),
+            name: "TODO, later".to_string(),
+            position: 0,
+            color: None,
+            created_at: "now".to_string(),
+        }];
+        let output = CommandOutput {
+            command: "category list",
+            project: "demo".to_string(),
+            data: json!({ "categories": [] }),
+            text: String::new(),
+            listing: Some(category_listing(&categories)),
+        };
+
+        let csv = render_success(
+            &output,
+            &OutputOptions {
+                format: OutputFormat::Csv,
+                columns: Some(vec!["slug".to_string(), "name".to_string()]),
+                no_header: false,
+            },
+        )
+        .expect("csv should render");
+        assert_eq!(csv, "Slug,Name\ntodo,\"TODO, later\"");
+
+        let ndjson = render_success(
+            &output,
+            &OutputOptions {
+                format: OutputFormat::Ndjson,
+                columns: Some(vec!["slug".to_string()]),
+                no_header: false,
+            },
+        )
+        .expect("ndjson should render");
+        assert_eq!(ndjson, r#"{"slug":"todo"}"#);
+
+        let table = render_success(
+            &output,
+            &OutputOptions {
+                format: OutputFormat::Table,
+                columns: Some(vec!["slug".to_string()]),
+                no_header: true,
+            },
+        )
+        .expect("table should render");
+        assert!(!table.contains("Slug"));
+        assert!(table.contains("| todo |"));
+
+        let err = render_success(
+            &output,
+            &OutputOptions {
+                format: OutputFormat::Csv,
+                columns: Some(vec!["missing".to_string()]),
+                no_header: false,
+            },
+        )
+        .expect_err("unknown column should fail");
+        assert_eq!(err.code, "UNKNOWN_COLUMN");
+        assert_eq!(err.exit_code, 2);
+    }
+
     #[test]
     fn resolve_task_id_selector_accepts_short_prefix() {
         let repo_dir = TempDir::new().expect("temp repo dir");
@@ -1331,6 +3102,288 @@ mod tests {
         assert_eq!(resolved, task.id);
     }
 
+    #[test]
+    fn task_prompt_requires_bound_session() {
+        let repo_dir = TempDir::new().expect("temp repo dir");
+        let db = Database::open(":memory:").expect("db should open");
+        let repo = db.add_repo(repo_dir.path()).expect("repo should save");
+        let category = db
+            .get_category_by_slug("todo")
+            .expect("lookup should succeed")
+            .expect("todo category should exist");
+        let task = db
+            .add_task(repo.id, "feature/prompt", "prompt task", category.id)
+            .expect("task should save");
+
+        let err = task_prompt(
+            &db,
+            "test",
+            TaskPromptArgs {
+                id: Some(task.id.to_string()),
+                source: PromptSourceArgs {
+                    message: Some("hello".to_string()),
+                    file: None,
+                },
+                wait: false,
+                wait_timeout: 600,
+            },
+        )
+        .err()
+        .expect("unbound task should fail");
+
+        assert_eq!(err.exit_code, 4);
+        assert_eq!(err.code, "TASK_SESSION_UNBOUND");
+    }
+
+    #[test]
+    fn task_abort_requires_bound_session() {
+        let repo_dir = TempDir::new().expect("temp repo dir");
+        let db = Database::open(":memory:").expect("db should open");
+        let repo = db.add_repo(repo_dir.path()).expect("repo should save");
+        let category = db
+            .get_category_by_slug("todo")
+            .expect("lookup should succeed")
+            .expect("todo category should exist");
+        let task = db
+            .add_task(repo.id, "feature/abort", "abort task", category.id)
+            .expect("task should save");
+
+        let err = execute_with_db(
+            &db,
+            "test",
+            RootCommand::Task {
+                command: TaskCommand::Abort(TaskAbortArgs {
+                    id: Some(task.id.to_string()),
+                }),
+            },
+        )
+        .err()
+        .expect("unbound task should fail");
+
+        assert_eq!(err.exit_code, 4);
+        assert_eq!(err.code, "TASK_SESSION_UNBOUND");
+    }
+
+    #[test]
+    fn read_prompt_text_trims_file_contents_and_rejects_empty() {
+        let dir = TempDir::new().expect("temp dir");
+        let prompt_path = dir.path().join("prompt.md");
+        fs::write(&prompt_path, "\n  fix the flaky test  \n").expect("prompt file should write");
+
+        let prompt = read_prompt_text(&PromptSourceArgs {
+            message: None,
+            file: Some(prompt_path),
+        })
+        .expect("prompt file should read");
+        assert_eq!(prompt, "fix the flaky test");
+
+        let err = read_prompt_text(&PromptSourceArgs {
+            message: Some("   ".to_string()),
+            file: None,
+        })
+        .expect_err("blank prompt should fail");
+        assert_eq!(err.code, "PROMPT_EMPTY");
+    }
+
+    #[test]
+    fn last_assistant_message_skips_trailing_user_messages() {
+        let message = |role: &str, content: &str| SessionMessageItem {
+            id: None,
+            message_type: Some("text".to_string()),
+            role: Some(role.to_string()),
+            content: content.to_string(),
+            timestamp: None,
+            parts: Vec::new(),
+        };
+        let messages = vec![
+            message("user", "do it"),
+            message("assistant", "done"),
+            message("assistant", ""),
+            message("user", "thanks"),
+        ];
+
+        let reply = last_assistant_message(&messages).expect("assistant reply should exist");
+        assert_eq!(reply.content, "done");
+        assert!(last_assistant_message(&messages[..1]).is_none());
+    }
+
+    #[test]
+    fn parse_batch_line_maps_object_args_to_flags() {
+        let command = parse_batch_line(
+            r#"{"command":"task move","args":{"id":"abc123","category_slug":"done"}}"#,
+        )
+        .expect("batch line should parse");
+
+        let RootCommand::Task {
+            command: TaskCommand::Move(args),
+        } = command
+        else {
+            panic!("expected task move, got {command:?}");
+        };
+        assert_eq!(args.id.as_deref(), Some("abc123"));
+        assert_eq!(args.selector.category_slug.as_deref(), Some("done"));
+    }
+
+    #[test]
+    fn execute_batch_streams_one_envelope_per_line() {
+        let db = Database::open(":memory:").expect("db should open");
+        let input = [
+            r#"{"command":"category create","args":{"name":"Review"}}"#,
+            "",
+            "not json",
+            r#"{"command":"category list"}"#,
+        ]
+        .join("\n");
+        let mut output = Vec::new();
+
+        let exit_code = execute_batch(&db, "test", false, input.as_bytes(), &mut output);
+
+        let envelopes = String::from_utf8(output)
+            .expect("output should be utf-8")
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).expect("envelope should be json"))
+            .collect::<Vec<_>>();
+        assert_eq!(exit_code, 2);
+        assert_eq!(envelopes.len(), 3);
+        assert_eq!(envelopes[0]["command"], "category create");
+        assert_eq!(envelopes[0]["line"], 1);
+        assert_eq!(envelopes[1]["error"]["code"], "BATCH_INVALID_LINE");
+        assert_eq!(envelopes[1]["line"], 3);
+        assert_eq!(
+            envelopes[2]["data"]["categories"]
+                .as_array()
+                .expect("categories array")
+                .len(),
+            4
+        );
+    }
+
+    #[test]
+    fn execute_batch_atomic_rolls_back_on_failure() {
+        let db = Database::open(":memory:").expect("db should open");
+        let input = [
+            r#"{"command":"category create","args":{"name":"Review"}}"#,
+            r#"{"command":"task show","args":{"id":"ffffffff"}}"#,
+            r#"{"command":"category list"}"#,
+        ]
+        .join("\n");
+        let mut output = Vec::new();
+
+        let exit_code = execute_batch(&db, "test", true, input.as_bytes(), &mut output);
+
+        let envelopes = String::from_utf8(output)
+            .expect("output should be utf-8")
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).expect("envelope should be json"))
+            .collect::<Vec<_>>();
+        assert_eq!(exit_code, 3);
+        assert_eq!(envelopes.len(), 3);
+        assert_eq!(envelopes[0]["error"]["code"], "BATCH_ROLLED_BACK");
+        assert_eq!(envelopes[1]["error"]["code"], "TASK_NOT_FOUND");
+        assert_eq!(envelopes[2]["error"]["code"], "BATCH_ABORTED");
+        assert_eq!(
+            db.list_categories().expect("categories should load").len(),
+            3
+        );
+    }
+
+    #[test]
+    fn execute_batch_rejects_stdin_and_side_effects_in_atomic_mode() {
+        let db = Database::open(":memory:").expect("db should open");
+        let input = [
+            r#"{"command":"category create","args":{"name":"Review"}}"#,
+            r#"{"command":"task create","args":{"title":"T","repo":"r","branch":"b"}}"#,
+        ]
+        .join("\n");
+        let mut output = Vec::new();
+
+        let exit_code = execute_batch(&db, "test", true, input.as_bytes(), &mut output);
+
+        let envelopes = String::from_utf8(output)
+            .expect("output should be utf-8")
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).expect("envelope should be json"))
+            .collect::<Vec<_>>();
+        assert_eq!(exit_code, 2);
+        assert_eq!(envelopes[0]["error"]["code"], "BATCH_ROLLED_BACK");
+        assert_eq!(envelopes[1]["error"]["code"], "BATCH_NOT_ATOMIC");
+
+        let input = r#"{"command":"task prompt","args":{"id":"ffffffff","file":"-"}}"#;
+        let mut output = Vec::new();
+        let exit_code = execute_batch(&db, "test", false, input.as_bytes(), &mut output);
+        let envelope = serde_json::from_slice::<Value>(&output).expect("envelope should be json");
+        assert_eq!(exit_code, 2);
+        assert_eq!(envelope["error"]["code"], "BATCH_STDIN_UNAVAILABLE");
+    }
+
+    #[test]
+    fn completion_project_name_reads_project_flag_forms() {
+        let args = |values: &[&str]| {
+            values
+                .iter()
+                .map(|value| value.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            completion_project_name(args(&[
+                "opencode-kanban",
+                "--",
+                "opencode-kanban",
+                "-p",
+                "demo",
+                "task"
+            ])),
+            "demo"
+        );
+        assert_eq!(
+            completion_project_name(args(&["opencode-kanban", "--project=other", "task"])),
+            "other"
+        );
+        assert_eq!(
+            completion_project_name(args(&["opencode-kanban", "-pshort"])),
+            "short"
+        );
+        assert_eq!(
+            completion_project_name(args(&["opencode-kanban", "task", "list"])),
+            projects::DEFAULT_PROJECT
+        );
+    }
+
+    #[test]
+    fn task_id_candidates_filter_by_short_id_prefix() {
+        let repo_dir = TempDir::new().expect("temp repo dir");
+        let db = Database::open(":memory:").expect("db should open");
+        let repo = db.add_repo(repo_dir.path()).expect("repo should save");
+        let category = db
+            .get_category_by_slug("todo")
+            .expect("lookup should succeed")
+            .expect("todo category should exist");
+        let task = db
+            .add_task(repo.id, "feature/complete", "complete me", category.id)
+            .expect("task should save");
+        let short = task.id.to_string().chars().take(8).collect::<String>();
+
+        let matches = task_id_candidates(&db, &short[..3]);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].get_value(), OsStr::new(&short));
+        assert_eq!(
+            matches[0].get_help().map(ToString::to_string).as_deref(),
+            Some("complete me")
+        );
+        assert!(task_id_candidates(&db, "zz").is_empty());
+    }
+
+    #[test]
+    fn write_completions_registers_env_completer() {
+        let mut output = Vec::new();
+        write_completions(CompletionShell::Bash, &mut output).expect("bash script should render");
+        let script = String::from_utf8(output).expect("script should be utf-8");
+
+        assert!(script.contains("COMPLETE"));
+        assert!(script.contains("opencode-kanban"));
+    }
+
     #[test]
     fn test_render_text_table_empty() {
         let result = render_text_table(&[], &[]);
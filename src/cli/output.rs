@@ -0,0 +1,330 @@
+use clap::ValueEnum;
+use serde_json::{Map, Value};
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Table,
+    Json,
+    Ndjson,
+    Csv,
+    #[value(alias = "md")]
+    Markdown,
+    Yaml,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct OutputOptions {
+    pub format: OutputFormat,
+    pub columns: Option<Vec<String>>,
+    pub no_header: bool,
+}
+
+impl OutputOptions {
+    pub fn json() -> Self {
+        Self {
+            format: OutputFormat::Json,
+            ..Self::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(super) struct ListColumn {
+    pub key: &'static str,
+    pub header: &'static str,
+}
+
+pub(super) const fn column(key: &'static str, header: &'static str) -> ListColumn {
+    ListColumn { key, header }
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Listing {
+    pub columns: &'static [ListColumn],
+    pub default_columns: &'static [&'static str],
+    pub rows: Vec<Vec<String>>,
+    pub records: Vec<Value>,
+    pub empty_text: &'static str,
+}
+
+impl Listing {
+    pub fn select_columns(&self, requested: Option<&[String]>) -> Result<Vec<usize>, String> {
+        let keys: Vec<&str> = match requested {
+            Some(requested) if !requested.is_empty() => {
+                requested.iter().map(|key| key.trim()).collect()
+            }
+            _ => self.default_columns.to_vec(),
+        };
+
+        keys.into_iter()
+            .map(|key| {
+                self.columns
+                    .iter()
+                    .position(|column| column.key.eq_ignore_ascii_case(key))
+                    .ok_or_else(|| {
+                        format!(
+                            "unknown column '{}'; available columns: {}",
+                            key,
+                            self.columns
+                                .iter()
+                                .map(|column| column.key)
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        )
+                    })
+            })
+            .collect()
+    }
+
+    pub fn headers(&self, selected: &[usize]) -> Vec<&'static str> {
+        selected
+            .iter()
+            .map(|index| self.columns[*index].header)
+            .collect()
+    }
+
+    pub fn selected_rows(&self, selected: &[usize]) -> Vec<Vec<String>> {
+        self.rows
+            .iter()
+            .map(|row| selected.iter().map(|index| row[*index].clone()).collect())
+            .collect()
+    }
+
+    // Structured formats keep the full JSON records unless the caller narrowed
+    // the columns, in which case they get the same cells as the table.
+    pub fn structured_records(&self, requested: Option<&[String]>) -> Result<Vec<Value>, String> {
+        if requested.is_none_or(|columns| columns.is_empty()) {
+            return Ok(self.records.clone());
+        }
+
+        let selected = self.select_columns(requested)?;
+        Ok(self
+            .rows
+            .iter()
+            .map(|row| {
+                let mut record = Map::new();
+                for index in &selected {
+                    record.insert(
+                        self.columns[*index].key.to_string(),
+                        Value::String(row[*index].clone()),
+                    );
+                }
+                Value::Object(record)
+            })
+            .collect())
+    }
+}
+
+pub(super) fn render_csv(headers: &[&str], rows: &[Vec<String>], include_header: bool) -> String {
+    let mut lines = Vec::with_capacity(rows.len() + 1);
+    if include_header {
+        lines.push(
+            headers
+                .iter()
+                .map(|header| csv_cell(header))
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+    }
+    for row in rows {
+        lines.push(
+            row.iter()
+                .map(|cell| csv_cell(cell))
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+    }
+    lines.join("\n")
+}
+
+fn csv_cell(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub(super) fn render_markdown_table(
+    headers: &[&str],
+    rows: &[Vec<String>],
+    include_header: bool,
+) -> String {
+    let mut lines = Vec::with_capacity(rows.len() + 2);
+    if include_header {
+        lines.push(markdown_row(
+            headers.iter().map(|header| header.to_string()),
+        ));
+        lines.push(markdown_row(headers.iter().map(|_| "---".to_string())));
+    }
+    for row in rows {
+        lines.push(markdown_row(row.iter().cloned()));
+    }
+    lines.join("\n")
+}
+
+fn markdown_row(cells: impl Iterator<Item = String>) -> String {
+    let cells = cells
+        .map(|cell| cell.replace('|', "\\|").replace(['\r', '\n'], " "))
+        .collect::<Vec<_>>();
+    format!("| {} |", cells.join(" | "))
+}
+
+pub(super) fn render_yaml(value: &Value) -> String {
+    let mut out = String::new();
+    match value {
+        Value::Object(map) if !map.is_empty() => write_yaml_map(map, 0, &mut out),
+        Value::Array(items) if !items.is_empty() => write_yaml_sequence(items, 0, &mut out),
+        scalar => {
+            out.push_str(&yaml_scalar(scalar));
+            out.push('\n');
+        }
+    }
+    out.trim_end().to_string()
+}
+
+fn write_yaml_map(map: &Map<String, Value>, indent: usize, out: &mut String) {
+    for (key, value) in map {
+        out.push_str(&" ".repeat(indent));
+        out.push_str(&yaml_key(key));
+        out.push(':');
+        match value {
+            Value::Object(child) if !child.is_empty() => {
+                out.push('\n');
+                write_yaml_map(child, indent + 2, out);
+            }
+            Value::Array(items) if !items.is_empty() => {
+                out.push('\n');
+                write_yaml_sequence(items, indent + 2, out);
+            }
+            scalar => {
+                out.push(' ');
+                out.push_str(&yaml_scalar(scalar));
+                out.push('\n');
+            }
+        }
+    }
+}
+
+fn write_yaml_sequence(items: &[Value], indent: usize, out: &mut String) {
+    for item in items {
+        match item {
+            Value::Object(child) if !child.is_empty() => {
+                let mut nested = String::new();
+                write_yaml_map(child, indent + 2, &mut nested);
+                out.push_str(&" ".repeat(indent));
+                out.push_str("- ");
+                out.push_str(&nested[indent + 2..]);
+            }
+            Value::Array(child) if !child.is_empty() => {
+                out.push_str(&" ".repeat(indent));
+                out.push_str("-\n");
+                write_yaml_sequence(child, indent + 2, out);
+            }
+            scalar => {
+                out.push_str(&" ".repeat(indent));
+                out.push_str("- ");
+                out.push_str(&yaml_scalar(scalar));
+                out.push('\n');
+            }
+        }
+    }
+}
+
+fn yaml_key(key: &str) -> String {
+    let plain = !key.is_empty()
+        && key
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-');
+    if plain {
+        key.to_string()
+    } else {
+        Value::String(key.to_string()).to_string()
+    }
+}
+
+// JSON string literals are valid YAML double-quoted scalars, which sidesteps
+// YAML's implicit typing of values like `yes`, `1e3` or `null`.
+fn yaml_scalar(value: &Value) -> String {
+    match value {
+        Value::Object(_) => "{}".to_string(),
+        Value::Array(_) => "[]".to_string(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    const COLUMNS: &[ListColumn] = &[column("id", "ID"), column("name", "Name")];
+
+    fn listing() -> Listing {
+        Listing {
+            columns: COLUMNS,
+            default_columns: &["id", "name"],
+            rows: vec![vec!["1".to_string(), "a, \"b\"".to_string()]],
+            records: vec![json!({ "id": 1, "name": "a, \"b\"", "extra": true })],
+            empty_text: "nothing",
+        }
+    }
+
+    #[test]
+    fn select_columns_rejects_unknown_keys() {
+        let err = listing()
+            .select_columns(Some(&["id".to_string(), "nope".to_string()]))
+            .expect_err("unknown column should fail");
+        assert!(err.contains("unknown column 'nope'"));
+        assert!(err.contains("id, name"));
+    }
+
+    #[test]
+    fn structured_records_project_selected_columns() {
+        let records = listing()
+            .structured_records(Some(&["name".to_string()]))
+            .expect("columns should resolve");
+        assert_eq!(records, vec![json!({ "name": "a, \"b\"" })]);
+
+        let full = listing()
+            .structured_records(None)
+            .expect("default records should resolve");
+        assert_eq!(full[0]["extra"], true);
+    }
+
+    #[test]
+    fn render_csv_quotes_special_characters() {
+        let listing = listing();
+        let output = render_csv(&["ID", "Name"], &listing.rows, true);
+        assert_eq!(output, "ID,Name\n1,\"a, \"\"b\"\"\"");
+
+        let headless = render_csv(&["ID", "Name"], &listing.rows, false);
+        assert_eq!(headless, "1,\"a, \"\"b\"\"\"");
+    }
+
+    #[test]
+    fn render_markdown_table_escapes_pipes() {
+        let rows = vec![vec!["1".to_string(), "a|b".to_string()]];
+        let output = render_markdown_table(&["ID", "Name"], &rows, true);
+        assert_eq!(output, "| ID | Name |\n| --- | --- |\n| 1 | a\\|b |");
+    }
+
+    #[test]
+    fn render_yaml_nests_maps_and_sequences() {
+        let value = json!({
+            "tasks": [
+                { "id": "abc", "tags": ["x"], "archived": false },
+                { "id": "def", "tags": [], "archived": null }
+            ],
+            "count": 2
+        });
+
+        let output = render_yaml(&value);
+        assert_eq!(
+            output,
+            "count: 2\ntasks:\n  - archived: false\n    id: \"abc\"\n    tags:\n      - \"x\"\n  - archived: null\n    id: \"def\"\n    tags: []"
+        );
+    }
+}
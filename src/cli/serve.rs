@@ -0,0 +1,893 @@
+//! `opencode-kanban serve`: a small local HTTP API over the project database.
+//!
+//! Read endpoints and mutations run the same handlers as the CLI and return
+//! the same JSON envelopes. `GET /api/events` streams board changes as
+//! server-sent events.
+//!
+//! Without a token only loopback `Host` headers are accepted, which keeps
+//! browser pages from reaching the API through DNS rebinding. With a token
+//! every request must send it as `Authorization: Bearer <token>`.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use serde_json::{Map, Value, json};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{Notify, broadcast};
+use tracing::{debug, warn};
+
+use crate::db::Database;
+use crate::opencode::ServerStatusProvider;
+
+use super::{
+    BatchLine, BoardCommand, CategoryCommand, CliError, CliResult, RootCommand, TaskCommand,
+    batch_command, error_envelope, execute_with_db, success_envelope, usage_error,
+};
+
+const SNAPSHOT_INTERVAL: Duration = Duration::from_secs(1);
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+const MAX_HEADER_BYTES: usize = 16 * 1024;
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+const MAX_HEADERS: usize = 64;
+const EVENT_CHANNEL_CAPACITY: usize = 64;
+const EVENT_KINDS: [&str; 4] = ["tasks", "categories", "repos", "status"];
+
+#[derive(Debug, Clone)]
+struct ApiEvent {
+    kind: &'static str,
+    data: Value,
+}
+
+struct ApiState {
+    db: Database,
+    project: String,
+    provider: ServerStatusProvider,
+    token: Option<String>,
+    snapshot: Mutex<BTreeMap<&'static str, Value>>,
+    events: broadcast::Sender<ApiEvent>,
+    refresh: Notify,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct Request {
+    method: String,
+    path: String,
+    query: Vec<(String, String)>,
+    host: Option<String>,
+    authorization: Option<String>,
+    content_type: Option<String>,
+    body: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Route {
+    Health,
+    Repos,
+    Status,
+    Events,
+    Command {
+        command: &'static str,
+        args: Value,
+        created: bool,
+    },
+    Generic(Value),
+}
+
+pub(super) async fn serve(
+    addr: SocketAddr,
+    db: Database,
+    project: String,
+    token: Option<String>,
+) -> io::Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    println!(
+        "serving project '{project}' on http://{}",
+        listener.local_addr()?
+    );
+    tokio::select! {
+        result = serve_listener(listener, db, project, token) => result,
+        _ = tokio::signal::ctrl_c() => Ok(()),
+    }
+}
+
+async fn serve_listener(
+    listener: TcpListener,
+    db: Database,
+    project: String,
+    token: Option<String>,
+) -> io::Result<()> {
+    let (events, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+    let state = Arc::new(ApiState {
+        db,
+        project,
+        provider: ServerStatusProvider::default(),
+        token,
+        snapshot: Mutex::new(BTreeMap::new()),
+        events,
+        refresh: Notify::new(),
+    });
+    tokio::spawn(snapshot_loop(Arc::clone(&state)));
+
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let state = Arc::clone(&state);
+        tokio::spawn(async move {
+            if let Err(err) = handle_connection(stream, state).await {
+                debug!(%peer, error = %err, "api connection closed with error");
+            }
+        });
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, state: Arc<ApiState>) -> io::Result<()> {
+    let request = match read_request(&mut stream).await {
+        Ok(Some(request)) => request,
+        Ok(None) => return Ok(()),
+        Err(err) => return write_json(&mut stream, http_status(&err), &error_envelope(&err)).await,
+    };
+    if let Err(err) = authorize(&request, state.token.as_deref()) {
+        return write_json(&mut stream, http_status(&err), &error_envelope(&err)).await;
+    }
+
+    let route = match resolve_route(&request) {
+        Ok(route) => route,
+        Err(err) => return write_json(&mut stream, http_status(&err), &error_envelope(&err)).await,
+    };
+
+    match route {
+        Route::Health => write_json(&mut stream, 200, &json!({ "ok": true })).await,
+        Route::Events => stream_events(stream, state).await,
+        Route::Repos => {
+            let (status, body) = match state.db.list_repos_async().await {
+                Ok(repos) => (
+                    200,
+                    data_envelope(&state, "repo list", json!({ "repos": repos })),
+                ),
+                Err(err) => {
+                    let err = super::runtime_error(err);
+                    (http_status(&err), error_envelope(&err))
+                }
+            };
+            write_json(&mut stream, status, &body).await
+        }
+        Route::Status => {
+            let body = data_envelope(&state, "status", session_statuses(&state).await);
+            write_json(&mut stream, 200, &body).await
+        }
+        Route::Command {
+            command,
+            args,
+            created,
+        } => {
+            let mutating = request.method != "GET";
+            let result = run_batch_line(
+                &state,
+                BatchLine {
+                    command: command.to_string(),
+                    args,
+                },
+                false,
+            )
+            .await;
+            respond_to_command(&mut stream, &state, result, mutating, created).await
+        }
+        Route::Generic(line) => {
+            let result = match serde_json::from_value::<BatchLine>(line) {
+                Ok(parsed) => run_batch_line(&state, parsed, true).await,
+                Err(err) => Err(usage_error(
+                    "API_INVALID_COMMAND",
+                    format!("expected {{\"command\": ..., \"args\": ...}} JSON: {err}"),
+                )),
+            };
+            respond_to_command(&mut stream, &state, result, false, false).await
+        }
+    }
+}
+
+async fn respond_to_command(
+    stream: &mut TcpStream,
+    state: &ApiState,
+    result: CliResult<Value>,
+    mutating: bool,
+    created: bool,
+) -> io::Result<()> {
+    match result {
+        Ok(envelope) => {
+            if mutating {
+                state.refresh.notify_one();
+            }
+            write_json(stream, if created { 201 } else { 200 }, &envelope).await
+        }
+        Err(err) => write_json(stream, http_status(&err), &error_envelope(&err)).await,
+    }
+}
+
+async fn run_command(state: &ApiState, command: String, args: Value) -> CliResult<Value> {
+    run_batch_line(state, BatchLine { command, args }, false).await
+}
+
+// CLI handlers use the blocking database wrappers, so they run off the
+// async workers just like the CLI runs them outside the TUI. `POST
+// /api/commands` passes `read_only`, limiting it to commands that only read.
+async fn run_batch_line(state: &ApiState, line: BatchLine, read_only: bool) -> CliResult<Value> {
+    let db = state.db.clone();
+    let project = state.project.clone();
+    tokio::task::spawn_blocking(move || {
+        let command = batch_command(line)?;
+        if read_only && !is_read_only_command(&command) {
+            return Err(CliError {
+                exit_code: 2,
+                code: "API_COMMAND_NOT_ALLOWED",
+                message: "/api/commands only runs read-only commands: task list, task show, \
+                          category list, board render and stats"
+                    .to_string(),
+                details: None,
+            });
+        }
+        execute_with_db(&db, &project, command).map(|output| success_envelope(&output))
+    })
+    .await
+    .map_err(|err| super::runtime_error(format!("API worker failed: {err}")))?
+}
+
+fn is_read_only_command(command: &RootCommand) -> bool {
+    matches!(
+        command,
+        RootCommand::Task {
+            command: TaskCommand::List(_) | TaskCommand::Show(_),
+            ..
+        } | RootCommand::Category {
+            command: CategoryCommand::List,
+            ..
+        } | RootCommand::Board {
+            command: BoardCommand::Render(_),
+            ..
+        } | RootCommand::Stats(_)
+    )
+}
+
+/// Checks the bearer token when one is configured, and otherwise that the
+/// request was addressed to a loopback host.
+fn authorize(request: &Request, token: Option<&str>) -> CliResult<()> {
+    match token {
+        Some(token) => {
+            let presented = request
+                .authorization
+                .as_deref()
+                .and_then(|value| value.strip_prefix("Bearer "))
+                .map(str::trim);
+            if presented.is_some_and(|presented| constant_time_eq(presented, token)) {
+                Ok(())
+            } else {
+                Err(usage_error(
+                    "API_UNAUTHORIZED",
+                    "missing or invalid Authorization: Bearer token",
+                ))
+            }
+        }
+        None if request.host.as_deref().is_some_and(is_loopback_host) => Ok(()),
+        None => Err(usage_error(
+            "API_FORBIDDEN_HOST",
+            "requests must be addressed to localhost, 127.0.0.1 or [::1]",
+        )),
+    }
+}
+
+fn is_loopback_host(host: &str) -> bool {
+    let host = host.trim();
+    let name = match host.strip_prefix('[') {
+        Some(bracketed) => bracketed.split(']').next().unwrap_or_default(),
+        None => match host.rsplit_once(':') {
+            Some((name, port)) if port.chars().all(|ch| ch.is_ascii_digit()) => name,
+            _ => host,
+        },
+    };
+    name.eq_ignore_ascii_case("localhost")
+        || name.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+fn constant_time_eq(left: &str, right: &str) -> bool {
+    left.len() == right.len()
+        && left
+            .bytes()
+            .zip(right.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn data_envelope(state: &ApiState, command: &'static str, data: Value) -> Value {
+    json!({
+        "schema_version": super::SCHEMA_VERSION,
+        "command": command,
+        "project": state.project,
+        "data": data,
+    })
+}
+
+fn resolve_route(request: &Request) -> CliResult<Route> {
+    let segments = request
+        .path
+        .trim_matches('/')
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>();
+    let Some(("api", segments)) = segments.split_first().map(|(first, rest)| (*first, rest)) else {
+        return Err(route_not_found(request));
+    };
+
+    let method = request.method.as_str();
+    if method != "GET" {
+        let is_json = request
+            .content_type
+            .as_deref()
+            .is_some_and(|value| value.trim().starts_with("application/json"));
+        if !is_json {
+            return Err(CliError {
+                exit_code: 2,
+                code: "API_JSON_REQUIRED",
+                message: "mutating requests must send Content-Type: application/json".to_string(),
+                details: None,
+            });
+        }
+    }
+
+    let route = match (method, segments) {
+        ("GET", ["health"]) => Route::Health,
+        ("GET", ["events"]) => Route::Events,
+        ("GET", ["repos"]) => Route::Repos,
+        ("GET", ["status"]) => Route::Status,
+        ("GET", ["tasks"]) => command_route("task list", query_args(&request.query), false),
+        ("POST", ["tasks"]) => command_route("task create", body_args(request)?, true),
+        ("GET", ["tasks", id]) => command_route("task show", with_id(Map::new(), id), false),
+        ("POST", ["tasks", id, "move"]) => {
+            command_route("task move", with_id(body_args(request)?, id), false)
+        }
+        ("POST", ["tasks", id, "archive"]) => {
+            command_route("task archive", with_id(body_args(request)?, id), false)
+        }
+        ("POST", ["tasks", id, "prompt"]) => {
+            command_route("task prompt", with_id(body_args(request)?, id), false)
+        }
+        ("POST", ["tasks", id, "abort"]) => {
+            command_route("task abort", with_id(body_args(request)?, id), false)
+        }
+        ("POST", ["tasks", id, "notes"]) => {
+            command_route("task note", with_id(body_args(request)?, id), true)
+        }
+        ("GET", ["categories"]) => command_route("category list", Map::new(), false),
+        ("POST", ["categories"]) => command_route("category create", body_args(request)?, true),
+        ("PATCH", ["categories", id]) => {
+            command_route("category update", with_id(body_args(request)?, id), false)
+        }
+        ("DELETE", ["categories", id]) => {
+            command_route("category delete", with_id(Map::new(), id), false)
+        }
+        ("POST", ["commands"]) => Route::Generic(Value::Object(body_args(request)?)),
+        _ => return Err(route_not_found(request)),
+    };
+    Ok(route)
+}
+
+fn command_route(command: &'static str, args: Map<String, Value>, created: bool) -> Route {
+    Route::Command {
+        command,
+        args: Value::Object(args),
+        created,
+    }
+}
+
+fn with_id(mut args: Map<String, Value>, id: &str) -> Map<String, Value> {
+    args.insert("id".to_string(), Value::String(id.to_string()));
+    args
+}
+
+fn query_args(query: &[(String, String)]) -> Map<String, Value> {
+    query
+        .iter()
+        .map(|(key, value)| {
+            let value = match value.as_str() {
+                "true" | "" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                other => Value::String(other.to_string()),
+            };
+            (key.clone(), value)
+        })
+        .collect()
+}
+
+fn body_args(request: &Request) -> CliResult<Map<String, Value>> {
+    if request.body.iter().all(u8::is_ascii_whitespace) {
+        return Ok(Map::new());
+    }
+    match serde_json::from_slice::<Value>(&request.body) {
+        Ok(Value::Object(map)) => Ok(map),
+        Ok(_) => Err(usage_error(
+            "API_INVALID_BODY",
+            "request body must be a JSON object",
+        )),
+        Err(err) => Err(usage_error(
+            "API_INVALID_BODY",
+            format!("request body is not valid JSON: {err}"),
+        )),
+    }
+}
+
+fn route_not_found(request: &Request) -> CliError {
+    super::not_found_error(
+        "API_ROUTE_NOT_FOUND",
+        format!("no route for {} {}", request.method, request.path),
+    )
+}
+
+fn http_status(err: &CliError) -> u16 {
+    match err.exit_code {
+        2 => match err.code {
+            "API_UNAUTHORIZED" => 401,
+            "API_FORBIDDEN_HOST" | "API_COMMAND_NOT_ALLOWED" => 403,
+            "API_JSON_REQUIRED" => 415,
+            "API_BODY_TOO_LARGE" => 413,
+            _ => 400,
+        },
+        3 => 404,
+        4 => 409,
+        _ => 500,
+    }
+}
+
+async fn read_request(stream: &mut TcpStream) -> CliResult<Option<Request>> {
+    let mut buffer = Vec::with_capacity(4096);
+    let mut chunk = [0u8; 4096];
+    let (header_len, content_length, mut request) = loop {
+        let read = stream
+            .read(&mut chunk)
+            .await
+            .map_err(|err| usage_error("API_READ_FAILED", err.to_string()))?;
+        if read == 0 {
+            return Ok(None);
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+        if let Some(parsed) = parse_head(&buffer)? {
+            break parsed;
+        }
+        if buffer.len() > MAX_HEADER_BYTES {
+            return Err(usage_error(
+                "API_HEADERS_TOO_LARGE",
+                "request headers are too large",
+            ));
+        }
+    };
+
+    if content_length > MAX_BODY_BYTES {
+        return Err(usage_error(
+            "API_BODY_TOO_LARGE",
+            format!("request body exceeds {MAX_BODY_BYTES} bytes"),
+        ));
+    }
+    request
+        .body
+        .extend_from_slice(&buffer[header_len..buffer.len().min(header_len + content_length)]);
+    while request.body.len() < content_length {
+        let read = stream
+            .read(&mut chunk)
+            .await
+            .map_err(|err| usage_error("API_READ_FAILED", err.to_string()))?;
+        if read == 0 {
+            return Err(usage_error(
+                "API_INVALID_REQUEST",
+                "connection closed before the request body was complete",
+            ));
+        }
+        let remaining = content_length - request.body.len();
+        request
+            .body
+            .extend_from_slice(&chunk[..read.min(remaining)]);
+    }
+    Ok(Some(request))
+}
+
+/// Parses the request line and headers, returning the header length and the
+/// declared `Content-Length` alongside a request with an empty body.
+fn parse_head(buffer: &[u8]) -> CliResult<Option<(usize, usize, Request)>> {
+    let mut headers = [httparse::EMPTY_HEADER; MAX_HEADERS];
+    let mut parsed = httparse::Request::new(&mut headers);
+    let header_len = match parsed.parse(buffer) {
+        Ok(httparse::Status::Complete(len)) => len,
+        Ok(httparse::Status::Partial) => return Ok(None),
+        Err(err) => {
+            return Err(usage_error(
+                "API_INVALID_REQUEST",
+                format!("malformed HTTP request: {err}"),
+            ));
+        }
+    };
+
+    let header = |name: &str| {
+        parsed
+            .headers
+            .iter()
+            .find(|header| header.name.eq_ignore_ascii_case(name))
+            .map(|header| String::from_utf8_lossy(header.value).trim().to_string())
+    };
+    let content_length = match header("content-length") {
+        Some(value) => value
+            .parse::<usize>()
+            .map_err(|_| usage_error("API_INVALID_REQUEST", "invalid Content-Length header"))?,
+        None => 0,
+    };
+
+    let target = parsed.path.unwrap_or("/");
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let query = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (decode_query(key), decode_query(value))
+        })
+        .collect();
+
+    Ok(Some((
+        header_len,
+        content_length,
+        Request {
+            method: parsed.method.unwrap_or("GET").to_ascii_uppercase(),
+            path: urlencoding::decode(path)
+                .map(|decoded| decoded.into_owned())
+                .unwrap_or_else(|_| path.to_string()),
+            query,
+            host: header("host"),
+            authorization: header("authorization"),
+            content_type: header("content-type"),
+            body: Vec::with_capacity(content_length.min(MAX_BODY_BYTES)),
+        },
+    )))
+}
+
+fn decode_query(value: &str) -> String {
+    let value = value.replace('+', " ");
+    urlencoding::decode(&value)
+        .map(|decoded| decoded.into_owned())
+        .unwrap_or(value)
+}
+
+async fn write_json(stream: &mut TcpStream, status: u16, body: &Value) -> io::Result<()> {
+    let body = serde_json::to_vec(body).unwrap_or_default();
+    let head = format!(
+        "HTTP/1.1 {status} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status_reason(status),
+        body.len()
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(&body).await?;
+    stream.shutdown().await
+}
+
+fn status_reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        409 => "Conflict",
+        413 => "Payload Too Large",
+        415 => "Unsupported Media Type",
+        _ => "Internal Server Error",
+    }
+}
+
+async fn stream_events(mut stream: TcpStream, state: Arc<ApiState>) -> io::Result<()> {
+    // Subscribe before reading the snapshot so no change falls in between.
+    let mut receiver = state.events.subscribe();
+    // Wake an idle snapshot loop; whatever it has not sent yet arrives as
+    // regular events.
+    state.refresh.notify_one();
+    let initial = state
+        .snapshot
+        .lock()
+        .map(|snapshot| snapshot.clone())
+        .unwrap_or_default();
+
+    stream
+        .write_all(
+            b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n",
+        )
+        .await?;
+    for (kind, data) in initial {
+        stream
+            .write_all(sse_frame(&ApiEvent { kind, data }).as_bytes())
+            .await?;
+    }
+
+    let mut keepalive = tokio::time::interval(KEEPALIVE_INTERVAL);
+    keepalive.tick().await;
+    loop {
+        tokio::select! {
+            event = receiver.recv() => match event {
+                Ok(event) => stream.write_all(sse_frame(&event).as_bytes()).await?,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!(skipped, "api event subscriber lagged");
+                }
+                Err(broadcast::error::RecvError::Closed) => return Ok(()),
+            },
+            _ = keepalive.tick() => stream.write_all(b": keepalive\n\n").await?,
+        }
+    }
+}
+
+fn sse_frame(event: &ApiEvent) -> String {
+    format!("event: {}\ndata: {}\n\n", event.kind, event.data)
+}
+
+async fn snapshot_loop(state: Arc<ApiState>) {
+    loop {
+        // Nobody is listening: skip the database and OpenCode requests and
+        // forget the snapshot so the next subscriber starts from fresh data.
+        if state.events.receiver_count() == 0 {
+            if let Ok(mut current) = state.snapshot.lock() {
+                current.clear();
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(SNAPSHOT_INTERVAL) => {}
+                _ = state.refresh.notified() => {}
+            }
+            continue;
+        }
+
+        let snapshot = take_snapshot(&state).await;
+        let changed = match state.snapshot.lock() {
+            Ok(mut current) => snapshot
+                .into_iter()
+                .filter_map(|(kind, data)| {
+                    let previous = current.insert(kind, data.clone());
+                    (previous.as_ref() != Some(&data)).then_some(ApiEvent { kind, data })
+                })
+                .collect::<Vec<_>>(),
+            Err(_) => Vec::new(),
+        };
+        for event in changed {
+            // No subscribers is not an error; the snapshot still advances.
+            let _ = state.events.send(event);
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(SNAPSHOT_INTERVAL) => {}
+            _ = state.refresh.notified() => {}
+        }
+    }
+}
+
+async fn take_snapshot(state: &ApiState) -> Vec<(&'static str, Value)> {
+    let mut snapshot = Vec::with_capacity(EVENT_KINDS.len());
+    for (kind, command) in [("tasks", "task list"), ("categories", "category list")] {
+        match run_command(state, command.to_string(), Value::Null).await {
+            Ok(envelope) => snapshot.push((kind, envelope["data"].clone())),
+            Err(err) => warn!(kind, error = %err.message, "failed to refresh api snapshot"),
+        }
+    }
+    match state.db.list_repos_async().await {
+        Ok(repos) => snapshot.push(("repos", json!({ "repos": repos }))),
+        Err(err) => warn!(error = %err, "failed to refresh api repo snapshot"),
+    }
+    snapshot.push(("status", session_statuses(state).await));
+    snapshot
+}
+
+// Each OpenCode instance serves one directory, so statuses are fetched per
+// distinct task worktree.
+async fn session_statuses(state: &ApiState) -> Value {
+    let tasks = match state.db.list_tasks_async().await {
+        Ok(tasks) => tasks,
+        Err(err) => return json!({ "sessions": [], "error": err.to_string() }),
+    };
+
+    let directories = tasks
+        .iter()
+        .filter_map(|task| task.worktree_path.clone())
+        .collect::<BTreeSet<_>>();
+    let mut live = BTreeMap::new();
+    let mut errors = BTreeMap::new();
+    for directory in directories {
+        match state
+            .provider
+            .fetch_all_statuses(SystemTime::now(), Some(&directory))
+            .await
+        {
+            Ok(statuses) => live.extend(statuses),
+            Err(err) => {
+                errors.insert(directory, err.code);
+            }
+        }
+    }
+
+    let sessions = tasks
+        .iter()
+        .map(|task| {
+            let live_status = task
+                .opencode_session_id
+                .as_ref()
+                .and_then(|session_id| live.get(session_id));
+            json!({
+                "task_id": task.id,
+                "session_id": task.opencode_session_id,
+                "state": live_status
+                    .map(|status| status.state.as_str().to_string())
+                    .unwrap_or_else(|| task.tmux_status.clone()),
+                "live": live_status.is_some(),
+                "error": task
+                    .worktree_path
+                    .as_ref()
+                    .and_then(|directory| errors.get(directory)),
+            })
+        })
+        .collect::<Vec<_>>();
+    json!({ "sessions": sessions })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: &str, target: &str, body: &str) -> Request {
+        let raw = format!(
+            "{method} {target} HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n",
+            body.len()
+        );
+        let (_, _, mut request) = parse_head(raw.as_bytes())
+            .expect("head should parse")
+            .expect("head should be complete");
+        request.body = body.as_bytes().to_vec();
+        request
+    }
+
+    #[test]
+    fn resolve_route_maps_rest_paths_to_cli_commands() {
+        let route = resolve_route(&request(
+            "POST",
+            "/api/tasks/abc123/move",
+            r#"{"category_slug":"done"}"#,
+        ))
+        .expect("route should resolve");
+        assert_eq!(
+            route,
+            Route::Command {
+                command: "task move",
+                args: json!({ "category_slug": "done", "id": "abc123" }),
+                created: false,
+            }
+        );
+
+        let route = resolve_route(&request("GET", "/api/tasks?archived&repo=my%20repo", ""))
+            .expect("route should resolve");
+        assert_eq!(
+            route,
+            Route::Command {
+                command: "task list",
+                args: json!({ "archived": true, "repo": "my repo" }),
+                created: false,
+            }
+        );
+
+        let err = resolve_route(&request("GET", "/api/nope", "")).expect_err("unknown route");
+        assert_eq!(http_status(&err), 404);
+    }
+
+    #[test]
+    fn resolve_route_requires_json_for_mutations() {
+        let mut post = request("POST", "/api/categories", r#"{"name":"Review"}"#);
+        post.content_type = Some("text/plain".to_string());
+        let err = resolve_route(&post).expect_err("text/plain should be rejected");
+        assert_eq!(http_status(&err), 415);
+    }
+
+    #[test]
+    fn authorize_requires_loopback_host_or_token() {
+        let mut request = request("GET", "/api/tasks", "");
+        assert!(authorize(&request, None).is_ok());
+        for host in ["127.0.0.1:4097", "[::1]:4097", "LOCALHOST"] {
+            request.host = Some(host.to_string());
+            assert!(authorize(&request, None).is_ok(), "{host}");
+        }
+
+        request.host = Some("attacker.example:4097".to_string());
+        let err = authorize(&request, None).expect_err("rebound host");
+        assert_eq!(http_status(&err), 403);
+        request.host = None;
+        assert!(authorize(&request, None).is_err());
+
+        request.host = Some("10.0.0.5:4097".to_string());
+        let err = authorize(&request, Some("s3cret")).expect_err("token required");
+        assert_eq!(http_status(&err), 401);
+        request.authorization = Some("Bearer wrong!".to_string());
+        assert!(authorize(&request, Some("s3cret")).is_err());
+        request.authorization = Some("Bearer s3cret".to_string());
+        assert!(authorize(&request, Some("s3cret")).is_ok());
+    }
+
+    #[test]
+    fn generic_commands_are_limited_to_read_only_ones() {
+        let command = |line: &str| {
+            batch_command(BatchLine {
+                command: line.to_string(),
+                args: Value::Null,
+            })
+            .expect("command should parse")
+        };
+        assert!(is_read_only_command(&command("task list")));
+        assert!(is_read_only_command(&command("category list")));
+        assert!(is_read_only_command(&command("stats")));
+        assert!(!is_read_only_command(&command("gc --apply")));
+        assert!(!is_read_only_command(&command("doctor")));
+        assert!(!is_read_only_command(&command("category create --name x")));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn serve_handles_requests_and_streams_events() {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener should bind");
+        let addr = listener.local_addr().expect("local addr");
+        let db = Database::open_async(":memory:")
+            .await
+            .expect("db should open");
+        let server = tokio::spawn(serve_listener(listener, db, "demo".to_string(), None));
+
+        let mut events = TcpStream::connect(addr).await.expect("connect events");
+        events
+            .write_all(b"GET /api/events HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .expect("write events request");
+
+        let body = r#"{"name":"Review"}"#;
+        let created = roundtrip(
+            addr,
+            &format!(
+                "POST /api/categories HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+                body.len()
+            ),
+        )
+        .await;
+        assert!(created.starts_with("HTTP/1.1 201 Created"), "{created}");
+        assert!(created.contains(r#""command":"category create""#));
+
+        let listed = roundtrip(
+            addr,
+            "GET /api/categories HTTP/1.1\r\nHost: localhost\r\n\r\n",
+        )
+        .await;
+        assert!(listed.starts_with("HTTP/1.1 200 OK"));
+        assert!(listed.contains("Review"));
+
+        let mut seen = String::new();
+        let mut chunk = [0u8; 4096];
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(10);
+        while !seen.contains("Review") && tokio::time::Instant::now() < deadline {
+            let read = tokio::time::timeout(Duration::from_secs(5), events.read(&mut chunk))
+                .await
+                .expect("event stream should produce data")
+                .expect("event read");
+            seen.push_str(&String::from_utf8_lossy(&chunk[..read]));
+        }
+        assert!(seen.starts_with("HTTP/1.1 200 OK"));
+        assert!(seen.contains("event: categories"));
+        assert!(seen.contains("Review"));
+
+        server.abort();
+    }
+
+    async fn roundtrip(addr: SocketAddr, raw: &str) -> String {
+        let mut stream = TcpStream::connect(addr).await.expect("connect");
+        stream.write_all(raw.as_bytes()).await.expect("write");
+        let mut response = String::new();
+        stream
+            .read_to_string(&mut response)
+            .await
+            .expect("read response");
+        response
+    }
+}
@@ -0,0 +1,746 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+use crate::db::{TASK_EVENT_ARCHIVED, TASK_EVENT_CREATED, TASK_EVENT_MOVED, TASK_EVENT_STATUS};
+use crate::opencode::Status;
+use crate::types::{Category, Repo, SessionUsageRecord, Task, TaskEvent, TokenUsage};
+
+use super::output::render_markdown_table;
+
+const BUSIEST_REPO_LIMIT: usize = 5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct CategoryStats {
+    pub id: Uuid,
+    pub slug: String,
+    pub name: String,
+    pub wip: usize,
+    pub created: usize,
+    pub moved_in: usize,
+    pub archived: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct RepoStats {
+    pub id: Uuid,
+    pub name: String,
+    pub created: usize,
+    pub moved: usize,
+    pub archived: usize,
+    pub running_seconds: i64,
+}
+
+impl RepoStats {
+    fn activity(&self) -> usize {
+        self.created + self.moved + self.archived
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct BoardStats {
+    pub since: Option<DateTime<Utc>>,
+    pub until: DateTime<Utc>,
+    pub created: usize,
+    pub archived: usize,
+    pub moved: usize,
+    pub average_cycle_seconds: Option<i64>,
+    pub running_seconds: i64,
+    pub categories: Vec<CategoryStats>,
+    pub repos: Vec<RepoStats>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct CostRow {
+    pub repo: String,
+    pub model: String,
+    pub tasks: usize,
+    pub sessions: usize,
+    pub usage: TokenUsage,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct CostReport {
+    pub total: TokenUsage,
+    pub tasks: usize,
+    pub rows: Vec<CostRow>,
+}
+
+#[derive(Default)]
+struct CostGroup<'a> {
+    tasks: HashSet<Uuid>,
+    sessions: HashSet<&'a str>,
+    usage: TokenUsage,
+}
+
+/// Accepts a relative window (`30m`, `12h`, `7d`, `2w`), an RFC 3339
+/// timestamp, or a `YYYY-MM-DD` date (midnight UTC).
+pub(super) fn parse_since(value: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+    let value = value.trim();
+    if let Some(split) = value.len().checked_sub(1).filter(|_| value.is_ascii())
+        && let Ok(amount) = value[..split].parse::<i64>()
+        && amount >= 0
+    {
+        let duration = match &value[split..] {
+            "m" => Some(Duration::minutes(amount)),
+            "h" => Some(Duration::hours(amount)),
+            "d" => Some(Duration::days(amount)),
+            "w" => Some(Duration::weeks(amount)),
+            _ => None,
+        };
+        if let Some(duration) = duration {
+            return Ok(now - duration);
+        }
+    }
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+    }
+    Err(format!(
+        "invalid --since value '{value}'; use a window like 7d, 12h or 2w, or a date like 2026-01-31"
+    ))
+}
+
+pub(super) fn compute_stats(
+    tasks: &[Task],
+    categories: &[Category],
+    repos: &[Repo],
+    events: &[TaskEvent],
+    since: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> BoardStats {
+    let in_window = |at: DateTime<Utc>| since.is_none_or(|since| at >= since) && at <= now;
+    let repo_by_task = tasks
+        .iter()
+        .map(|task| (task.id, task.repo_id))
+        .collect::<HashMap<_, _>>();
+
+    let mut stats = BoardStats {
+        since,
+        until: now,
+        created: 0,
+        archived: 0,
+        moved: 0,
+        average_cycle_seconds: None,
+        running_seconds: 0,
+        categories: categories
+            .iter()
+            .map(|category| CategoryStats {
+                id: category.id,
+                slug: category.slug.clone(),
+                name: category.name.clone(),
+                wip: tasks
+                    .iter()
+                    .filter(|task| !task.archived && task.category_id == category.id)
+                    .count(),
+                created: 0,
+                moved_in: 0,
+                archived: 0,
+            })
+            .collect(),
+        repos: repos
+            .iter()
+            .map(|repo| RepoStats {
+                id: repo.id,
+                name: repo.name.clone(),
+                created: 0,
+                moved: 0,
+                archived: 0,
+                running_seconds: 0,
+            })
+            .collect(),
+    };
+
+    let mut seen_created = HashSet::new();
+    let mut seen_archived = HashSet::new();
+    let mut running_since: HashMap<Uuid, DateTime<Utc>> = HashMap::new();
+
+    for event in events {
+        let Some(at) = parse_timestamp(&event.created_at) else {
+            continue;
+        };
+        let repo_id = repo_by_task.get(&event.task_id).copied();
+        match event.kind.as_str() {
+            TASK_EVENT_CREATED | TASK_EVENT_MOVED | TASK_EVENT_ARCHIVED => {
+                if event.kind == TASK_EVENT_CREATED {
+                    seen_created.insert(event.task_id);
+                }
+                if event.kind == TASK_EVENT_ARCHIVED {
+                    seen_archived.insert(event.task_id);
+                }
+                if in_window(at) {
+                    record_event(&mut stats, &event.kind, event.category_id, repo_id);
+                }
+            }
+            TASK_EVENT_STATUS => {
+                let running = event.status.as_deref() == Some(Status::Running.as_str());
+                match (running, running_since.get(&event.task_id).copied()) {
+                    (true, None) => {
+                        running_since.insert(event.task_id, at);
+                    }
+                    (false, Some(started)) => {
+                        running_since.remove(&event.task_id);
+                        let seconds = clipped_seconds(started, at, since, now);
+                        record_running(&mut stats, repo_id, seconds);
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Tasks created before the event log existed only have their own timestamps.
+    for task in tasks {
+        if !seen_created.contains(&task.id)
+            && parse_timestamp(&task.created_at).is_some_and(in_window)
+        {
+            record_event(
+                &mut stats,
+                TASK_EVENT_CREATED,
+                Some(task.category_id),
+                Some(task.repo_id),
+            );
+        }
+        if task.archived
+            && !seen_archived.contains(&task.id)
+            && task
+                .archived_at
+                .as_deref()
+                .and_then(parse_timestamp)
+                .is_some_and(in_window)
+        {
+            record_event(
+                &mut stats,
+                TASK_EVENT_ARCHIVED,
+                Some(task.category_id),
+                Some(task.repo_id),
+            );
+        }
+    }
+
+    for task in tasks {
+        if task.tmux_status == Status::Running.as_str()
+            && let Some(started) = running_since.get(&task.id)
+        {
+            let seconds = clipped_seconds(*started, now, since, now);
+            record_running(&mut stats, Some(task.repo_id), seconds);
+        }
+    }
+
+    let cycle_seconds = tasks
+        .iter()
+        .filter(|task| task.archived)
+        .filter_map(|task| {
+            let archived_at = parse_timestamp(task.archived_at.as_deref()?)?;
+            let created_at = parse_timestamp(&task.created_at)?;
+            in_window(archived_at).then(|| (archived_at - created_at).num_seconds().max(0))
+        })
+        .collect::<Vec<_>>();
+    stats.average_cycle_seconds = (!cycle_seconds.is_empty())
+        .then(|| cycle_seconds.iter().sum::<i64>() / cycle_seconds.len() as i64);
+
+    stats
+        .repos
+        .retain(|repo| repo.activity() > 0 || repo.running_seconds > 0);
+    stats.repos.sort_by(|left, right| {
+        right
+            .activity()
+            .cmp(&left.activity())
+            .then(right.running_seconds.cmp(&left.running_seconds))
+            .then_with(|| left.name.cmp(&right.name))
+    });
+    stats.repos.truncate(BUSIEST_REPO_LIMIT);
+    stats
+}
+
+fn record_event(
+    stats: &mut BoardStats,
+    kind: &str,
+    category_id: Option<Uuid>,
+    repo_id: Option<Uuid>,
+) {
+    let category = category_id.and_then(|id| stats.categories.iter_mut().find(|c| c.id == id));
+    let repo = repo_id.and_then(|id| stats.repos.iter_mut().find(|r| r.id == id));
+    match kind {
+        TASK_EVENT_CREATED => {
+            stats.created += 1;
+            if let Some(category) = category {
+                category.created += 1;
+            }
+            if let Some(repo) = repo {
+                repo.created += 1;
+            }
+        }
+        TASK_EVENT_MOVED => {
+            stats.moved += 1;
+            if let Some(category) = category {
+                category.moved_in += 1;
+            }
+            if let Some(repo) = repo {
+                repo.moved += 1;
+            }
+        }
+        _ => {
+            stats.archived += 1;
+            if let Some(category) = category {
+                category.archived += 1;
+            }
+            if let Some(repo) = repo {
+                repo.archived += 1;
+            }
+        }
+    }
+}
+
+fn record_running(stats: &mut BoardStats, repo_id: Option<Uuid>, seconds: i64) {
+    stats.running_seconds += seconds;
+    if let Some(repo) = repo_id.and_then(|id| stats.repos.iter_mut().find(|r| r.id == id)) {
+        repo.running_seconds += seconds;
+    }
+}
+
+fn clipped_seconds(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    since: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> i64 {
+    let start = since.map_or(start, |since| start.max(since));
+    let end = end.min(now);
+    (end - start).num_seconds().max(0)
+}
+
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+}
+
+fn hours(seconds: i64) -> f64 {
+    (seconds as f64 / 360.0).round() / 10.0
+}
+
+fn format_duration(seconds: i64) -> String {
+    let days = seconds / 86_400;
+    let hours = (seconds % 86_400) / 3_600;
+    let minutes = (seconds % 3_600) / 60;
+    if days > 0 {
+        format!("{days}d {hours}h")
+    } else if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else {
+        format!("{minutes}m")
+    }
+}
+
+pub(super) fn stats_json(stats: &BoardStats) -> Value {
+    json!({
+        "since": stats.since.map(|since| since.to_rfc3339()),
+        "until": stats.until.to_rfc3339(),
+        "summary": {
+            "created": stats.created,
+            "archived": stats.archived,
+            "moved": stats.moved,
+            "average_cycle_seconds": stats.average_cycle_seconds,
+            "agent_running_hours": hours(stats.running_seconds),
+        },
+        "categories": stats.categories.iter().map(|category| json!({
+            "id": category.id.to_string(),
+            "slug": category.slug,
+            "name": category.name,
+            "wip": category.wip,
+            "created": category.created,
+            "moved_in": category.moved_in,
+            "archived": category.archived,
+        })).collect::<Vec<_>>(),
+        "busiest_repos": stats.repos.iter().map(|repo| json!({
+            "id": repo.id.to_string(),
+            "name": repo.name,
+            "created": repo.created,
+            "moved": repo.moved,
+            "archived": repo.archived,
+            "agent_running_hours": hours(repo.running_seconds),
+        })).collect::<Vec<_>>(),
+    })
+}
+
+pub(super) fn render_stats_markdown(project: &str, stats: &BoardStats) -> String {
+    let window = match stats.since {
+        Some(since) => format!(
+            "{} to {}",
+            since.format("%Y-%m-%d %H:%M UTC"),
+            stats.until.format("%Y-%m-%d %H:%M UTC")
+        ),
+        None => format!("all time to {}", stats.until.format("%Y-%m-%d %H:%M UTC")),
+    };
+
+    let mut sections = vec![
+        format!("# Board stats: {project}"),
+        format!("_{window}_"),
+        [
+            format!("- Tasks created: {}", stats.created),
+            format!("- Tasks archived: {}", stats.archived),
+            format!("- Task moves: {}", stats.moved),
+            format!(
+                "- Average time to archive: {}",
+                stats
+                    .average_cycle_seconds
+                    .map_or_else(|| "n/a".to_string(), format_duration)
+            ),
+            format!("- Agent running time: {:.1}h", hours(stats.running_seconds)),
+        ]
+        .join("\n"),
+    ];
+
+    let category_rows = stats
+        .categories
+        .iter()
+        .map(|category| {
+            vec![
+                category.name.clone(),
+                category.wip.to_string(),
+                category.created.to_string(),
+                category.moved_in.to_string(),
+                category.archived.to_string(),
+            ]
+        })
+        .collect::<Vec<_>>();
+    sections.push(format!(
+        "## Categories\n\n{}",
+        render_markdown_table(
+            &["Category", "WIP", "Created", "Moved in", "Archived"],
+            &category_rows,
+            true
+        )
+    ));
+
+    let repo_section = if stats.repos.is_empty() {
+        "No repo activity in this window.".to_string()
+    } else {
+        let repo_rows = stats
+            .repos
+            .iter()
+            .map(|repo| {
+                vec![
+                    repo.name.clone(),
+                    repo.created.to_string(),
+                    repo.moved.to_string(),
+                    repo.archived.to_string(),
+                    format!("{:.1}", hours(repo.running_seconds)),
+                ]
+            })
+            .collect::<Vec<_>>();
+        render_markdown_table(
+            &["Repo", "Created", "Moves", "Archived", "Agent hours"],
+            &repo_rows,
+            true,
+        )
+    };
+    sections.push(format!("## Busiest repos\n\n{repo_section}"));
+
+    sections.join("\n\n")
+}
+
+/// Sums stored session usage (subagent sessions are recorded under their
+/// task) per repo and model, most expensive first.
+pub(super) fn compute_cost(
+    tasks: &[Task],
+    repos: &[Repo],
+    records: &[SessionUsageRecord],
+) -> CostReport {
+    let repo_names = repos
+        .iter()
+        .map(|repo| (repo.id, repo.name.as_str()))
+        .collect::<HashMap<_, _>>();
+    let repo_by_task = tasks
+        .iter()
+        .map(|task| {
+            let name = repo_names.get(&task.repo_id).copied().unwrap_or("unknown");
+            (task.id, name)
+        })
+        .collect::<HashMap<_, _>>();
+
+    let mut total = TokenUsage::default();
+    let mut all_tasks = HashSet::new();
+    let mut groups: HashMap<(String, String), CostGroup<'_>> = HashMap::new();
+    for record in records {
+        let repo = repo_by_task
+            .get(&record.task_id)
+            .copied()
+            .unwrap_or("unknown");
+        let group = groups
+            .entry((repo.to_string(), record.model.clone()))
+            .or_default();
+        group.tasks.insert(record.task_id);
+        group.sessions.insert(record.session_id.as_str());
+        group.usage.add(&record.usage);
+        total.add(&record.usage);
+        all_tasks.insert(record.task_id);
+    }
+
+    let mut rows = groups
+        .into_iter()
+        .map(|((repo, model), group)| CostRow {
+            repo,
+            model,
+            tasks: group.tasks.len(),
+            sessions: group.sessions.len(),
+            usage: group.usage,
+        })
+        .collect::<Vec<_>>();
+    rows.sort_by(|left, right| {
+        right
+            .usage
+            .cost
+            .total_cmp(&left.usage.cost)
+            .then(right.usage.total_tokens().cmp(&left.usage.total_tokens()))
+            .then_with(|| left.repo.cmp(&right.repo))
+            .then_with(|| left.model.cmp(&right.model))
+    });
+
+    CostReport {
+        total,
+        tasks: all_tasks.len(),
+        rows,
+    }
+}
+
+fn usage_json(usage: &TokenUsage) -> Value {
+    json!({
+        "input_tokens": usage.input_tokens,
+        "output_tokens": usage.output_tokens,
+        "reasoning_tokens": usage.reasoning_tokens,
+        "cache_read_tokens": usage.cache_read_tokens,
+        "cache_write_tokens": usage.cache_write_tokens,
+        "total_tokens": usage.total_tokens(),
+        "cost": usage.cost,
+    })
+}
+
+pub(super) fn cost_json(report: &CostReport) -> Value {
+    json!({
+        "tasks": report.tasks,
+        "total": usage_json(&report.total),
+        "by_repo_model": report.rows.iter().map(|row| json!({
+            "repo": row.repo,
+            "model": row.model,
+            "tasks": row.tasks,
+            "sessions": row.sessions,
+            "usage": usage_json(&row.usage),
+        })).collect::<Vec<_>>(),
+    })
+}
+
+pub(super) fn render_cost_markdown(project: &str, report: &CostReport) -> String {
+    let mut sections = vec![format!("# Token usage: {project}")];
+    if report.rows.is_empty() {
+        sections.push(
+            "No token usage recorded yet. Usage is collected while the board is open.".to_string(),
+        );
+        return sections.join("\n\n");
+    }
+
+    sections.push(
+        [
+            format!("- Total cost: ${:.2}", report.total.cost),
+            format!("- Total tokens: {}", report.total.total_tokens()),
+            format!("- Tasks with usage: {}", report.tasks),
+        ]
+        .join("\n"),
+    );
+
+    let rows = report
+        .rows
+        .iter()
+        .map(|row| {
+            vec![
+                row.repo.clone(),
+                row.model.clone(),
+                row.tasks.to_string(),
+                row.usage.input_tokens.to_string(),
+                (row.usage.output_tokens + row.usage.reasoning_tokens).to_string(),
+                (row.usage.cache_read_tokens + row.usage.cache_write_tokens).to_string(),
+                format!("${:.2}", row.usage.cost),
+            ]
+        })
+        .collect::<Vec<_>>();
+    sections.push(format!(
+        "## By repo and model\n\n{}",
+        render_markdown_table(
+            &["Repo", "Model", "Tasks", "Input", "Output", "Cache", "Cost"],
+            &rows,
+            true
+        )
+    ));
+
+    sections.join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(value: &str) -> DateTime<Utc> {
+        parse_timestamp(value).expect("valid timestamp")
+    }
+
+    fn event(
+        task_id: Uuid,
+        kind: &str,
+        category_id: Option<Uuid>,
+        status: Option<&str>,
+        when: &str,
+    ) -> TaskEvent {
+        TaskEvent {
+            id: 0,
+            task_id,
+            kind: kind.to_string(),
+            category_id,
+            status: status.map(str::to_string),
+            created_at: when.to_string(),
+        }
+    }
+
+    #[test]
+    fn parse_since_accepts_windows_and_dates() {
+        let now = at("2026-03-10T12:00:00Z");
+        assert_eq!(parse_since("7d", now), Ok(at("2026-03-03T12:00:00Z")));
+        assert_eq!(parse_since("12h", now), Ok(at("2026-03-10T00:00:00Z")));
+        assert_eq!(
+            parse_since("2026-03-01", now),
+            Ok(at("2026-03-01T00:00:00Z"))
+        );
+        assert!(parse_since("soon", now).is_err());
+    }
+
+    #[test]
+    fn compute_stats_counts_events_in_window_and_running_time() {
+        let now = at("2026-03-10T12:00:00Z");
+        let todo = Uuid::new_v4();
+        let done = Uuid::new_v4();
+        let repo_id = Uuid::new_v4();
+        let category = |id, slug: &str| Category {
+            id,
+            slug: slug.to_string(),
+            name: slug.to_uppercase(),
+            position: 0,
+            color: None,
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+        };
+        let task = |archived: bool, category_id, status: &str| Task {
+            id: Uuid::new_v4(),
+            title: "task".to_string(),
+            repo_id,
+            branch: "feature/x".to_string(),
+            category_id,
+            position: 0,
+            tmux_session_name: None,
+            worktree_path: None,
+            tmux_status: status.to_string(),
+            status_source: "none".to_string(),
+            status_fetched_at: None,
+            status_error: None,
+            opencode_session_id: None,
+            model: None,
+            agent: None,
+            forked_from: None,
+            backend: None,
+            attach_overlay_shown: false,
+            needs_inspection: false,
+            archived,
+            archived_at: archived.then(|| "2026-03-09T12:00:00Z".to_string()),
+            created_at: "2026-03-08T12:00:00Z".to_string(),
+            updated_at: "2026-03-09T12:00:00Z".to_string(),
+        };
+        let shipped = task(true, done, "idle");
+        let active = task(false, todo, "running");
+        let old = Task {
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            ..task(false, todo, "idle")
+        };
+        let repo = Repo {
+            id: repo_id,
+            path: "/tmp/repo".to_string(),
+            name: "repo".to_string(),
+            default_base: None,
+            remote_url: None,
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            updated_at: "2026-01-01T00:00:00Z".to_string(),
+        };
+        let events = vec![
+            event(
+                shipped.id,
+                TASK_EVENT_CREATED,
+                Some(todo),
+                None,
+                "2026-03-08T12:00:00Z",
+            ),
+            event(
+                shipped.id,
+                TASK_EVENT_STATUS,
+                None,
+                Some("running"),
+                "2026-03-08T13:00:00Z",
+            ),
+            event(
+                shipped.id,
+                TASK_EVENT_STATUS,
+                None,
+                Some("idle"),
+                "2026-03-08T15:00:00Z",
+            ),
+            event(
+                shipped.id,
+                TASK_EVENT_MOVED,
+                Some(done),
+                None,
+                "2026-03-09T10:00:00Z",
+            ),
+            event(
+                shipped.id,
+                TASK_EVENT_ARCHIVED,
+                Some(done),
+                None,
+                "2026-03-09T12:00:00Z",
+            ),
+            event(
+                active.id,
+                TASK_EVENT_STATUS,
+                None,
+                Some("running"),
+                "2026-03-10T11:00:00Z",
+            ),
+        ];
+
+        let stats = compute_stats(
+            &[shipped, active, old],
+            &[category(todo, "todo"), category(done, "done")],
+            &[repo],
+            &events,
+            Some(at("2026-03-03T12:00:00Z")),
+            now,
+        );
+
+        assert_eq!(stats.created, 2);
+        assert_eq!(stats.moved, 1);
+        assert_eq!(stats.archived, 1);
+        assert_eq!(stats.average_cycle_seconds, Some(86_400));
+        assert_eq!(stats.running_seconds, 3 * 3_600);
+        assert_eq!(stats.categories[0].wip, 2);
+        assert_eq!(stats.categories[0].created, 2);
+        assert_eq!(stats.categories[1].moved_in, 1);
+        assert_eq!(stats.categories[1].archived, 1);
+        assert_eq!(stats.repos.len(), 1);
+        assert_eq!(stats.repos[0].running_seconds, 3 * 3_600);
+
+        let markdown = render_stats_markdown("demo", &stats);
+        assert!(markdown.contains("- Average time to archive: 1d 0h"));
+        assert!(markdown.contains("- Agent running time: 3.0h"));
+        assert!(markdown.contains("| TODO | 2 | 2 | 0 | 0 |"));
+    }
+}
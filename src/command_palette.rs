@@ -205,6 +205,42 @@ pub fn all_commands() -> Vec<CommandDef> {
             keybinding: "o",
             message: Some(Message::OpenSelectedTaskInNewTerminal),
         },
+        CommandDef {
+            id: "quick_prompt",
+            display_name: "Send Prompt to Selected Task",
+            keybinding: "m",
+            message: Some(Message::OpenQuickPromptDialog),
+        },
+        CommandDef {
+            id: "interrupt_session",
+            display_name: "Interrupt Selected Task's Agent",
+            keybinding: "i",
+            message: Some(Message::OpenInterruptSessionDialog),
+        },
+        CommandDef {
+            id: "fork_task",
+            display_name: "Fork Selected Task",
+            keybinding: "F",
+            message: Some(Message::OpenForkTaskDialog),
+        },
+        CommandDef {
+            id: "revert_last_turn",
+            display_name: "Revert Last Agent Turn",
+            keybinding: "u",
+            message: Some(Message::RevertLastTurn),
+        },
+        CommandDef {
+            id: "unrevert_last_turn",
+            display_name: "Restore Reverted Agent Turn",
+            keybinding: "U",
+            message: Some(Message::UnrevertLastTurn),
+        },
+        CommandDef {
+            id: "approve_permission",
+            display_name: "Answer Selected Task's Permission Request",
+            keybinding: "y",
+            message: Some(Message::OpenPermissionApprovalDialog),
+        },
         CommandDef {
             id: "cycle_todo_visualization",
             display_name: "Cycle Todo Visualization",
@@ -235,6 +271,12 @@ pub fn all_commands() -> Vec<CommandDef> {
             keybinding: "D",
             message: Some(Message::OpenDeleteTaskDialog),
         },
+        CommandDef {
+            id: "gc",
+            display_name: "Clean Up Orphaned Worktrees/Sessions",
+            keybinding: "-",
+            message: Some(Message::OpenGcDialog),
+        },
         CommandDef {
             id: "move_task_left",
             display_name: "Move Task/Category Left",
@@ -351,8 +393,8 @@ mod tests {
         let commands = all_commands();
         assert_eq!(
             commands.len(),
-            25,
-            "Expected 25 commands, found {}",
+            32,
+            "Expected 32 commands, found {}",
             commands.len()
         );
     }
@@ -17,11 +17,22 @@ use sqlx::{Row, SqlitePool};
 use tokio::runtime::{Builder as RuntimeBuilder, Handle, RuntimeFlavor};
 use uuid::Uuid;
 
-use crate::types::{Category, CommandFrequency, Repo, Task};
+use crate::types::{
+    AgentSelection, Category, CommandFrequency, ModelUsage, Repo, SessionUsageRecord, Task,
+    TaskEvent, TaskNote, TokenUsage,
+};
 
 const DEFAULT_TMUX_STATUS: &str = "unknown";
 const DEFAULT_STATUS_SOURCE: &str = "none";
 
+pub const TASK_EVENT_CREATED: &str = "created";
+pub const TASK_EVENT_MOVED: &str = "moved";
+pub const TASK_EVENT_ARCHIVED: &str = "archived";
+/// Recorded only when a task starts or stops running; stats need nothing
+/// finer, and statuses flip on every poll.
+pub const TASK_EVENT_STATUS: &str = "status";
+const RUNNING_TMUX_STATUS: &str = "running";
+
 #[derive(Clone)]
 pub struct Database {
     pool: SqlitePool,
@@ -30,7 +41,27 @@ pub struct Database {
 impl Database {
     pub async fn open_async(path: impl AsRef<Path>) -> Result<Self> {
         let path_ref = path.as_ref();
+        let max_connections = if path_ref == Path::new(":memory:") {
+            1
+        } else {
+            5
+        };
+        Self::connect_async(path_ref, max_connections).await
+    }
+
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        block_on_db(Self::open_async(path))
+    }
 
+    pub async fn open_single_connection_async(path: impl AsRef<Path>) -> Result<Self> {
+        Self::connect_async(path.as_ref(), 1).await
+    }
+
+    pub fn open_single_connection(path: impl AsRef<Path>) -> Result<Self> {
+        block_on_db(Self::open_single_connection_async(path))
+    }
+
+    async fn connect_async(path_ref: &Path, max_connections: u32) -> Result<Self> {
         if path_ref != Path::new(":memory:")
             && let Some(parent) = path_ref.parent()
         {
@@ -43,12 +74,6 @@ impl Database {
         }
 
         let connect_options = sqlite_connect_options(path_ref)?;
-        let max_connections = if path_ref == Path::new(":memory:") {
-            1
-        } else {
-            5
-        };
-
         let pool = SqlitePoolOptions::new()
             .max_connections(max_connections)
             .connect_with(connect_options)
@@ -61,14 +86,48 @@ impl Database {
         Ok(db)
     }
 
-    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
-        block_on_db(Self::open_async(path))
-    }
-
     pub fn pool(&self) -> &SqlitePool {
         &self.pool
     }
 
+    // Transactions are issued as plain statements, so they only span multiple
+    // calls on a handle opened with `open_single_connection`.
+    pub async fn begin_transaction_async(&self) -> Result<()> {
+        sqlx::query("BEGIN IMMEDIATE")
+            .execute(&self.pool)
+            .await
+            .context("failed to begin transaction")?;
+        Ok(())
+    }
+
+    pub fn begin_transaction(&self) -> Result<()> {
+        block_on_db(self.begin_transaction_async())
+    }
+
+    pub async fn commit_transaction_async(&self) -> Result<()> {
+        sqlx::query("COMMIT")
+            .execute(&self.pool)
+            .await
+            .context("failed to commit transaction")?;
+        Ok(())
+    }
+
+    pub fn commit_transaction(&self) -> Result<()> {
+        block_on_db(self.commit_transaction_async())
+    }
+
+    pub async fn rollback_transaction_async(&self) -> Result<()> {
+        sqlx::query("ROLLBACK")
+            .execute(&self.pool)
+            .await
+            .context("failed to roll back transaction")?;
+        Ok(())
+    }
+
+    pub fn rollback_transaction(&self) -> Result<()> {
+        block_on_db(self.rollback_transaction_async())
+    }
+
     pub async fn add_repo_async(&self, path: PathBuf) -> Result<Repo> {
         let path_buf = fs::canonicalize(&path)
             .with_context(|| format!("failed to canonicalize repo path {}", path.display()))?;
@@ -215,11 +274,23 @@ impl Database {
         .bind(0)
         .bind(Option::<String>::None)
         .bind(now.clone())
-        .bind(now)
+        .bind(now.clone())
         .execute(&self.pool)
         .await
         .context("failed to insert task")?;
 
+        sqlx::query(
+            "INSERT INTO task_events (task_id, kind, category_id, status, created_at)
+             VALUES (?, ?, ?, NULL, ?)",
+        )
+        .bind(id.to_string())
+        .bind(TASK_EVENT_CREATED)
+        .bind(category_id.to_string())
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .context("failed to record task creation")?;
+
         self.get_task_async(id).await
     }
 
@@ -242,7 +313,7 @@ impl Database {
         let row = sqlx::query(
             "SELECT id, title, repo_id, branch, category_id, position, tmux_session_name,
                     worktree_path, tmux_status, status_source,
-                    status_fetched_at, status_error, opencode_session_id,
+                    status_fetched_at, status_error, opencode_session_id, model, agent, forked_from, backend,
                     attach_overlay_shown, needs_inspection, archived, archived_at,
                     created_at, updated_at
              FROM tasks WHERE id = ?",
@@ -263,7 +334,7 @@ impl Database {
         let rows = sqlx::query(
             "SELECT id, title, repo_id, branch, category_id, position, tmux_session_name,
                     worktree_path, tmux_status, status_source,
-                    status_fetched_at, status_error, opencode_session_id,
+                    status_fetched_at, status_error, opencode_session_id, model, agent, forked_from, backend,
                     attach_overlay_shown, needs_inspection, archived, archived_at,
                     created_at, updated_at
              FROM tasks WHERE archived = 0
@@ -284,7 +355,7 @@ impl Database {
         let rows = sqlx::query(
             "SELECT id, title, repo_id, branch, category_id, position, tmux_session_name,
                     worktree_path, tmux_status, status_source,
-                    status_fetched_at, status_error, opencode_session_id,
+                    status_fetched_at, status_error, opencode_session_id, model, agent, forked_from, backend,
                     attach_overlay_shown, needs_inspection, archived, archived_at,
                     created_at, updated_at
              FROM tasks WHERE archived = 1
@@ -303,6 +374,16 @@ impl Database {
 
     pub async fn archive_task_async(&self, id: Uuid) -> Result<()> {
         let now = now_iso();
+        sqlx::query(
+            "INSERT INTO task_events (task_id, kind, category_id, status, created_at)
+             SELECT id, ?, category_id, NULL, ? FROM tasks WHERE id = ? AND archived = 0",
+        )
+        .bind(TASK_EVENT_ARCHIVED)
+        .bind(now.clone())
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .context("failed to record task archive")?;
         sqlx::query(
             "UPDATE tasks
              SET archived = 1,
@@ -349,10 +430,23 @@ impl Database {
         category_id: Uuid,
         position: i64,
     ) -> Result<()> {
+        let now = now_iso();
+        sqlx::query(
+            "INSERT INTO task_events (task_id, kind, category_id, status, created_at)
+             SELECT id, ?, ?, NULL, ? FROM tasks WHERE id = ? AND category_id != ?",
+        )
+        .bind(TASK_EVENT_MOVED)
+        .bind(category_id.to_string())
+        .bind(now.clone())
+        .bind(id.to_string())
+        .bind(category_id.to_string())
+        .execute(&self.pool)
+        .await
+        .context("failed to record task move")?;
         sqlx::query("UPDATE tasks SET category_id = ?, position = ?, updated_at = ? WHERE id = ?")
             .bind(category_id.to_string())
             .bind(position)
-            .bind(now_iso())
+            .bind(now)
             .bind(id.to_string())
             .execute(&self.pool)
             .await
@@ -427,9 +521,25 @@ impl Database {
     }
 
     pub async fn update_task_status_async(&self, id: Uuid, status: impl AsRef<str>) -> Result<()> {
+        let now = now_iso();
+        sqlx::query(
+            "INSERT INTO task_events (task_id, kind, category_id, status, created_at)
+             SELECT id, ?, NULL, ?, ? FROM tasks
+             WHERE id = ? AND (tmux_status IS ?) <> (? = ?)",
+        )
+        .bind(TASK_EVENT_STATUS)
+        .bind(status.as_ref())
+        .bind(now.clone())
+        .bind(id.to_string())
+        .bind(RUNNING_TMUX_STATUS)
+        .bind(status.as_ref())
+        .bind(RUNNING_TMUX_STATUS)
+        .execute(&self.pool)
+        .await
+        .context("failed to record task status change")?;
         sqlx::query("UPDATE tasks SET tmux_status = ?, updated_at = ? WHERE id = ?")
             .bind(status.as_ref())
-            .bind(now_iso())
+            .bind(now)
             .bind(id.to_string())
             .execute(&self.pool)
             .await
@@ -510,6 +620,64 @@ impl Database {
         block_on_db(self.update_task_session_binding_async(id, opencode_session_id))
     }
 
+    pub async fn update_task_agent_selection_async(
+        &self,
+        id: Uuid,
+        selection: AgentSelection,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE tasks
+             SET model = ?,
+                 agent = ?,
+                 updated_at = ?
+             WHERE id = ?",
+        )
+        .bind(selection.model)
+        .bind(selection.agent)
+        .bind(now_iso())
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .context("failed to update task model and agent")?;
+        Ok(())
+    }
+
+    pub fn update_task_agent_selection(&self, id: Uuid, selection: AgentSelection) -> Result<()> {
+        block_on_db(self.update_task_agent_selection_async(id, selection))
+    }
+
+    /// Records the task this one was forked from.
+    pub async fn update_task_forked_from_async(&self, id: Uuid, source_id: Uuid) -> Result<()> {
+        sqlx::query("UPDATE tasks SET forked_from = ?, updated_at = ? WHERE id = ?")
+            .bind(source_id.to_string())
+            .bind(now_iso())
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .context("failed to update task fork source")?;
+        Ok(())
+    }
+
+    pub fn update_task_forked_from(&self, id: Uuid, source_id: Uuid) -> Result<()> {
+        block_on_db(self.update_task_forked_from_async(id, source_id))
+    }
+
+    /// Records which agent backend runs the task; `None` means OpenCode.
+    pub async fn update_task_backend_async(&self, id: Uuid, backend: Option<String>) -> Result<()> {
+        sqlx::query("UPDATE tasks SET backend = ?, updated_at = ? WHERE id = ?")
+            .bind(backend)
+            .bind(now_iso())
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .context("failed to update task backend")?;
+        Ok(())
+    }
+
+    pub fn update_task_backend(&self, id: Uuid, backend: Option<String>) -> Result<()> {
+        block_on_db(self.update_task_backend_async(id, backend))
+    }
+
     pub async fn update_task_attach_overlay_shown_async(
         &self,
         id: Uuid,
@@ -568,6 +736,21 @@ impl Database {
             .execute(&self.pool)
             .await
             .context("failed to delete task")?;
+        sqlx::query("DELETE FROM task_notes WHERE task_id = ?")
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .context("failed to delete task notes")?;
+        sqlx::query("DELETE FROM session_usage WHERE task_id = ?")
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .context("failed to delete task session usage")?;
+        sqlx::query("DELETE FROM task_events WHERE task_id = ?")
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await
+            .context("failed to delete task events")?;
         Ok(())
     }
 
@@ -802,6 +985,190 @@ impl Database {
         block_on_db(self.get_command_frequencies_async())
     }
 
+    pub async fn list_task_events_async(&self) -> Result<Vec<TaskEvent>> {
+        let rows = sqlx::query(
+            "SELECT id, task_id, kind, category_id, status, created_at
+             FROM task_events
+             ORDER BY created_at ASC, id ASC",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to list task events")?;
+
+        rows.iter().map(map_task_event_row).collect()
+    }
+
+    pub fn list_task_events(&self) -> Result<Vec<TaskEvent>> {
+        block_on_db(self.list_task_events_async())
+    }
+
+    pub async fn add_task_note_async(&self, task_id: Uuid, body: String) -> Result<TaskNote> {
+        let now = now_iso();
+        let result = sqlx::query(
+            "INSERT INTO task_notes (task_id, body, created_at)
+             SELECT id, ?, ? FROM tasks WHERE id = ?",
+        )
+        .bind(&body)
+        .bind(&now)
+        .bind(task_id.to_string())
+        .execute(&self.pool)
+        .await
+        .context("failed to add task note")?;
+        if result.rows_affected() == 0 {
+            bail!("task {task_id} not found");
+        }
+
+        Ok(TaskNote {
+            id: result.last_insert_rowid(),
+            task_id,
+            body,
+            created_at: now,
+        })
+    }
+
+    pub fn add_task_note(&self, task_id: Uuid, body: impl AsRef<str>) -> Result<TaskNote> {
+        block_on_db(self.add_task_note_async(task_id, body.as_ref().to_string()))
+    }
+
+    pub async fn list_task_notes_async(&self, task_id: Uuid) -> Result<Vec<TaskNote>> {
+        let rows = sqlx::query(
+            "SELECT id, task_id, body, created_at
+             FROM task_notes
+             WHERE task_id = ?
+             ORDER BY created_at ASC, id ASC",
+        )
+        .bind(task_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to list task notes")?;
+
+        rows.iter().map(map_task_note_row).collect()
+    }
+
+    pub fn list_task_notes(&self, task_id: Uuid) -> Result<Vec<TaskNote>> {
+        block_on_db(self.list_task_notes_async(task_id))
+    }
+
+    /// Replaces the stored per-model usage of one OpenCode session.
+    pub async fn replace_session_usage_async(
+        &self,
+        task_id: Uuid,
+        session_id: String,
+        usage: Vec<ModelUsage>,
+    ) -> Result<()> {
+        // Readers must never see the session between the delete and inserts.
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to begin session usage update")?;
+        sqlx::query("DELETE FROM session_usage WHERE session_id = ?")
+            .bind(&session_id)
+            .execute(&mut *tx)
+            .await
+            .context("failed to clear session usage")?;
+
+        let now = now_iso();
+        for entry in usage {
+            sqlx::query(
+                "INSERT INTO session_usage (
+                    task_id, session_id, model, input_tokens, output_tokens,
+                    reasoning_tokens, cache_read_tokens, cache_write_tokens, cost, updated_at
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(task_id.to_string())
+            .bind(&session_id)
+            .bind(entry.model)
+            .bind(entry.usage.input_tokens as i64)
+            .bind(entry.usage.output_tokens as i64)
+            .bind(entry.usage.reasoning_tokens as i64)
+            .bind(entry.usage.cache_read_tokens as i64)
+            .bind(entry.usage.cache_write_tokens as i64)
+            .bind(entry.usage.cost)
+            .bind(&now)
+            .execute(&mut *tx)
+            .await
+            .context("failed to record session usage")?;
+        }
+        tx.commit()
+            .await
+            .context("failed to commit session usage")?;
+        Ok(())
+    }
+
+    pub fn replace_session_usage(
+        &self,
+        task_id: Uuid,
+        session_id: impl AsRef<str>,
+        usage: Vec<ModelUsage>,
+    ) -> Result<()> {
+        block_on_db(self.replace_session_usage_async(
+            task_id,
+            session_id.as_ref().to_string(),
+            usage,
+        ))
+    }
+
+    pub async fn list_session_usage_async(&self) -> Result<Vec<SessionUsageRecord>> {
+        let rows = sqlx::query(
+            "SELECT task_id, session_id, model, input_tokens, output_tokens, reasoning_tokens,
+                    cache_read_tokens, cache_write_tokens, cost, updated_at
+             FROM session_usage
+             ORDER BY task_id ASC, session_id ASC, model ASC",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to list session usage")?;
+
+        rows.iter().map(map_session_usage_row).collect()
+    }
+
+    pub fn list_session_usage(&self) -> Result<Vec<SessionUsageRecord>> {
+        block_on_db(self.list_session_usage_async())
+    }
+
+    pub async fn record_prompt_history_async(&self, prompt: String) -> Result<()> {
+        let now = now_iso();
+        sqlx::query(
+            "INSERT INTO prompt_history (prompt, use_count, last_used)
+             VALUES (?, 1, ?)
+             ON CONFLICT(prompt) DO UPDATE SET
+                 use_count = use_count + 1,
+                 last_used = excluded.last_used",
+        )
+        .bind(prompt)
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .context("failed to record prompt history")?;
+        Ok(())
+    }
+
+    pub fn record_prompt_history(&self, prompt: impl AsRef<str>) -> Result<()> {
+        block_on_db(self.record_prompt_history_async(prompt.as_ref().to_string()))
+    }
+
+    /// Returns the most recently sent prompts, newest first.
+    pub async fn list_prompt_history_async(&self, limit: usize) -> Result<Vec<String>> {
+        let rows = sqlx::query(
+            "SELECT prompt FROM prompt_history
+             ORDER BY last_used DESC, rowid DESC
+             LIMIT ?",
+        )
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to list prompt history")?;
+
+        rows.iter()
+            .map(|row| row.try_get("prompt").map_err(Into::into))
+            .collect()
+    }
+
+    pub fn list_prompt_history(&self, limit: usize) -> Result<Vec<String>> {
+        block_on_db(self.list_prompt_history_async(limit))
+    }
+
     async fn run_migrations_async(&self) -> Result<()> {
         sqlx::query(
             "CREATE TABLE IF NOT EXISTS repos (
@@ -847,6 +1214,10 @@ impl Database {
                 status_fetched_at TEXT,
                 status_error TEXT,
                 opencode_session_id TEXT,
+                model TEXT,
+                agent TEXT,
+                forked_from TEXT,
+                backend TEXT,
                 attach_overlay_shown INTEGER NOT NULL DEFAULT 0,
                 needs_inspection INTEGER NOT NULL DEFAULT 0,
                 archived INTEGER NOT NULL DEFAULT 0,
@@ -871,6 +1242,68 @@ impl Database {
         .await
         .context("failed to create command_frequency table")?;
 
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS task_events (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                task_id TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                category_id TEXT,
+                status TEXT,
+                created_at TEXT NOT NULL
+            )",
+        )
+        .execute(&self.pool)
+        .await
+        .context("failed to create task_events table")?;
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_task_events_created_at ON task_events(created_at)",
+        )
+        .execute(&self.pool)
+        .await
+        .context("failed to create task_events index")?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS task_notes (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                task_id TEXT NOT NULL,
+                body TEXT NOT NULL,
+                created_at TEXT NOT NULL
+            )",
+        )
+        .execute(&self.pool)
+        .await
+        .context("failed to create task_notes table")?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS prompt_history (
+                prompt TEXT PRIMARY KEY,
+                use_count INTEGER NOT NULL DEFAULT 0,
+                last_used TEXT NOT NULL
+            )",
+        )
+        .execute(&self.pool)
+        .await
+        .context("failed to create prompt_history table")?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS session_usage (
+                task_id TEXT NOT NULL,
+                session_id TEXT NOT NULL,
+                model TEXT NOT NULL,
+                input_tokens INTEGER NOT NULL DEFAULT 0,
+                output_tokens INTEGER NOT NULL DEFAULT 0,
+                reasoning_tokens INTEGER NOT NULL DEFAULT 0,
+                cache_read_tokens INTEGER NOT NULL DEFAULT 0,
+                cache_write_tokens INTEGER NOT NULL DEFAULT 0,
+                cost REAL NOT NULL DEFAULT 0,
+                updated_at TEXT NOT NULL,
+                PRIMARY KEY (session_id, model)
+            )",
+        )
+        .execute(&self.pool)
+        .await
+        .context("failed to create session_usage table")?;
+
         execute_add_column_if_missing(
             &self.pool,
             "ALTER TABLE tasks ADD COLUMN status_source TEXT NOT NULL DEFAULT 'none'",
@@ -919,6 +1352,30 @@ impl Database {
             "failed to migrate tasks.archived_at",
         )
         .await?;
+        execute_add_column_if_missing(
+            &self.pool,
+            "ALTER TABLE tasks ADD COLUMN model TEXT",
+            "failed to migrate tasks.model",
+        )
+        .await?;
+        execute_add_column_if_missing(
+            &self.pool,
+            "ALTER TABLE tasks ADD COLUMN agent TEXT",
+            "failed to migrate tasks.agent",
+        )
+        .await?;
+        execute_add_column_if_missing(
+            &self.pool,
+            "ALTER TABLE tasks ADD COLUMN forked_from TEXT",
+            "failed to migrate tasks.forked_from",
+        )
+        .await?;
+        execute_add_column_if_missing(
+            &self.pool,
+            "ALTER TABLE tasks ADD COLUMN backend TEXT",
+            "failed to migrate tasks.backend",
+        )
+        .await?;
 
         sqlx::query("UPDATE tasks SET status_source = 'none' WHERE status_source IS NULL")
             .execute(&self.pool)
@@ -1147,6 +1604,13 @@ fn map_task_row(row: &SqliteRow) -> Result<Task> {
         status_fetched_at: row.try_get("status_fetched_at")?,
         status_error: row.try_get("status_error")?,
         opencode_session_id: row.try_get("opencode_session_id")?,
+        model: row.try_get("model")?,
+        agent: row.try_get("agent")?,
+        forked_from: row
+            .try_get::<Option<String>, _>("forked_from")?
+            .map(parse_uuid_column)
+            .transpose()?,
+        backend: row.try_get("backend")?,
         attach_overlay_shown: row.try_get::<i64, _>("attach_overlay_shown")? != 0,
         needs_inspection: row.try_get::<i64, _>("needs_inspection")? != 0,
         archived: row.try_get::<i64, _>("archived")? != 0,
@@ -1156,6 +1620,47 @@ fn map_task_row(row: &SqliteRow) -> Result<Task> {
     })
 }
 
+fn map_task_event_row(row: &SqliteRow) -> Result<TaskEvent> {
+    Ok(TaskEvent {
+        id: row.try_get("id")?,
+        task_id: parse_uuid_column(row.try_get::<String, _>("task_id")?)?,
+        kind: row.try_get("kind")?,
+        category_id: row
+            .try_get::<Option<String>, _>("category_id")?
+            .map(parse_uuid_column)
+            .transpose()?,
+        status: row.try_get("status")?,
+        created_at: row.try_get("created_at")?,
+    })
+}
+
+fn map_task_note_row(row: &SqliteRow) -> Result<TaskNote> {
+    Ok(TaskNote {
+        id: row.try_get("id")?,
+        task_id: parse_uuid_column(row.try_get::<String, _>("task_id")?)?,
+        body: row.try_get("body")?,
+        created_at: row.try_get("created_at")?,
+    })
+}
+
+fn map_session_usage_row(row: &SqliteRow) -> Result<SessionUsageRecord> {
+    let count = |column: &str| -> Result<u64> { Ok(row.try_get::<i64, _>(column)?.max(0) as u64) };
+    Ok(SessionUsageRecord {
+        task_id: parse_uuid_column(row.try_get::<String, _>("task_id")?)?,
+        session_id: row.try_get("session_id")?,
+        model: row.try_get("model")?,
+        usage: TokenUsage {
+            input_tokens: count("input_tokens")?,
+            output_tokens: count("output_tokens")?,
+            reasoning_tokens: count("reasoning_tokens")?,
+            cache_read_tokens: count("cache_read_tokens")?,
+            cache_write_tokens: count("cache_write_tokens")?,
+            cost: row.try_get("cost")?,
+        },
+        updated_at: row.try_get("updated_at")?,
+    })
+}
+
 fn is_duplicate_column_err(err: &sqlx::Error) -> bool {
     match err {
         sqlx::Error::Database(database_err) => {
@@ -1266,6 +1771,7 @@ mod tests {
     use uuid::Uuid;
 
     use super::Database;
+    use crate::types::{ModelUsage, TaskEvent, TokenUsage};
 
     #[test]
     fn test_db_creation_seeds_default_categories() -> Result<()> {
@@ -1344,6 +1850,209 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_task_events_record_moves_status_changes_and_archive() -> Result<()> {
+        let db = Database::open(":memory:")?;
+        let repo_dir = create_temp_git_repo("task-events")?;
+        let repo = db.add_repo(&repo_dir)?;
+        let categories = db.list_categories()?;
+
+        let task = db.add_task(repo.id, "feature/events", "Events", categories[0].id)?;
+        db.update_task_category(task.id, categories[0].id, 1)?;
+        db.update_task_category(task.id, categories[1].id, 0)?;
+        db.update_task_status(task.id, "running")?;
+        db.update_task_status(task.id, "running")?;
+        db.update_task_status(task.id, "awaiting_input")?;
+        db.update_task_status(task.id, "idle")?;
+        db.archive_task(task.id)?;
+        db.archive_task(task.id)?;
+
+        let events = db.list_task_events()?;
+        let kinds = events
+            .iter()
+            .map(|event| event.kind.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec!["created", "moved", "status", "status", "archived"]
+        );
+        assert_eq!(events[0].category_id, Some(categories[0].id));
+        assert_eq!(events[1].category_id, Some(categories[1].id));
+        assert_eq!(events[2].status.as_deref(), Some("running"));
+        assert_eq!(events[3].status.as_deref(), Some("awaiting_input"));
+        assert_eq!(events[4].category_id, Some(categories[1].id));
+
+        db.delete_task(task.id)?;
+        assert!(db.list_task_events()?.is_empty());
+
+        std::fs::remove_dir_all(&repo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_task_events_skip_idle_repeats_and_drop_only_the_deleted_task() -> Result<()> {
+        let db = Database::open(":memory:")?;
+        let repo_dir = create_temp_git_repo("task-events-idle")?;
+        let repo = db.add_repo(&repo_dir)?;
+        let category_id = db.list_categories()?[0].id;
+
+        let task = db.add_task(repo.id, "feature/idle", "Idle", category_id)?;
+        let other = db.add_task(repo.id, "feature/other", "Other", category_id)?;
+        db.update_task_status(task.id, "idle")?;
+        db.update_task_status(task.id, "idle")?;
+        db.update_task_status(other.id, "running")?;
+
+        let kinds_for = |events: &[TaskEvent], task_id: Uuid| {
+            events
+                .iter()
+                .filter(|event| event.task_id == task_id)
+                .map(|event| event.kind.clone())
+                .collect::<Vec<_>>()
+        };
+        let events = db.list_task_events()?;
+        assert_eq!(kinds_for(&events, task.id), vec!["created"]);
+        assert_eq!(kinds_for(&events, other.id), vec!["created", "status"]);
+
+        db.delete_task(task.id)?;
+        let events = db.list_task_events()?;
+        assert!(kinds_for(&events, task.id).is_empty());
+        assert_eq!(kinds_for(&events, other.id), vec!["created", "status"]);
+
+        std::fs::remove_dir_all(&repo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_prompt_history_lists_newest_first_without_duplicates() -> Result<()> {
+        let db = Database::open(":memory:")?;
+        db.record_prompt_history("run the tests")?;
+        db.record_prompt_history("fix clippy")?;
+        db.record_prompt_history("run the tests")?;
+
+        assert_eq!(
+            db.list_prompt_history(10)?,
+            vec!["run the tests".to_string(), "fix clippy".to_string()]
+        );
+        assert_eq!(
+            db.list_prompt_history(1)?,
+            vec!["run the tests".to_string()]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_task_notes_append_in_order_and_require_task() -> Result<()> {
+        let db = Database::open(":memory:")?;
+        let repo_dir = create_temp_git_repo("task-notes")?;
+        let repo = db.add_repo(&repo_dir)?;
+        let category_id = db.list_categories()?[0].id;
+
+        let task = db.add_task(repo.id, "feature/notes", "Notes", category_id)?;
+        db.add_task_note(task.id, "first")?;
+        db.add_task_note(task.id, "second")?;
+        assert!(db.add_task_note(Uuid::new_v4(), "orphan").is_err());
+
+        let notes = db.list_task_notes(task.id)?;
+        let bodies = notes
+            .iter()
+            .map(|note| note.body.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(bodies, vec!["first", "second"]);
+
+        db.delete_task(task.id)?;
+        assert!(db.list_task_notes(task.id)?.is_empty());
+
+        std::fs::remove_dir_all(&repo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_session_usage_is_replaced_per_session_and_dropped_with_task() -> Result<()> {
+        let db = Database::open(":memory:")?;
+        let repo_dir = create_temp_git_repo("session-usage")?;
+        let repo = db.add_repo(&repo_dir)?;
+        let category_id = db.list_categories()?[0].id;
+        let task = db.add_task(repo.id, "feature/usage", "Usage", category_id)?;
+
+        let usage = |model: &str, input_tokens: u64, cost: f64| ModelUsage {
+            model: model.to_string(),
+            usage: TokenUsage {
+                input_tokens,
+                cost,
+                ..TokenUsage::default()
+            },
+        };
+        db.replace_session_usage(task.id, "ses-root", vec![usage("anthropic/a", 10, 0.5)])?;
+        db.replace_session_usage(
+            task.id,
+            "ses-root",
+            vec![usage("anthropic/a", 40, 1.5), usage("openai/b", 5, 0.25)],
+        )?;
+        db.replace_session_usage(task.id, "ses-child", vec![usage("openai/b", 7, 0.0)])?;
+
+        let records = db.list_session_usage()?;
+        let summary = records
+            .iter()
+            .map(|record| {
+                (
+                    record.session_id.as_str(),
+                    record.model.as_str(),
+                    record.usage.input_tokens,
+                    record.usage.cost,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                ("ses-child", "openai/b", 7, 0.0),
+                ("ses-root", "anthropic/a", 40, 1.5),
+                ("ses-root", "openai/b", 5, 0.25),
+            ]
+        );
+
+        db.delete_task(task.id)?;
+        assert!(db.list_session_usage()?.is_empty());
+
+        std::fs::remove_dir_all(&repo_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_failed_session_usage_replace_keeps_previous_rows() -> Result<()> {
+        let db = Database::open(":memory:")?;
+        let repo_dir = create_temp_git_repo("session-usage-rollback")?;
+        let repo = db.add_repo(&repo_dir)?;
+        let category_id = db.list_categories()?[0].id;
+        let task = db.add_task(repo.id, "feature/usage", "Usage", category_id)?;
+
+        let usage = |model: &str, input_tokens: u64| ModelUsage {
+            model: model.to_string(),
+            usage: TokenUsage {
+                input_tokens,
+                ..TokenUsage::default()
+            },
+        };
+        db.replace_session_usage(task.id, "ses-root", vec![usage("anthropic/a", 10)])?;
+
+        // The duplicate model hits the primary key after the delete and the
+        // first insert have already run.
+        let result = db.replace_session_usage(
+            task.id,
+            "ses-root",
+            vec![usage("openai/b", 20), usage("openai/b", 30)],
+        );
+        assert!(result.is_err());
+
+        let records = db.list_session_usage()?;
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].model, "anthropic/a");
+        assert_eq!(records[0].usage.input_tokens, 10);
+
+        std::fs::remove_dir_all(&repo_dir)?;
+        Ok(())
+    }
+
     #[test]
     fn test_archive_and_unarchive_task_visibility() -> Result<()> {
         let db = Database::open(":memory:")?;
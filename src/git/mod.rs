@@ -19,6 +19,13 @@ pub struct GitChangeSummary {
     pub top_files: Vec<String>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitWorktree {
+    pub path: PathBuf,
+    pub branch: Option<String>,
+    pub is_main: bool,
+}
+
 pub fn git_detect_default_branch(repo_path: &Path) -> String {
     if let Ok(output) = run_git_output(repo_path, ["symbolic-ref", "refs/remotes/origin/HEAD"]) {
         let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
@@ -192,11 +199,108 @@ pub fn git_remove_worktree(repo_path: &Path, worktree_path: &Path) -> Result<()>
     .with_context(|| format!("failed to remove worktree `{}`", worktree_path.display()))
 }
 
+/// Removes a worktree only if it has no uncommitted or untracked changes;
+/// git refuses a dirty worktree and the error says so.
+pub fn git_remove_clean_worktree(repo_path: &Path, worktree_path: &Path) -> Result<()> {
+    let worktree_path_str = worktree_path.to_string_lossy().to_string();
+    run_git(repo_path, ["worktree", "remove", &worktree_path_str])
+        .with_context(|| format!("failed to remove worktree `{}`", worktree_path.display()))
+}
+
+pub fn git_list_worktrees(repo_path: &Path) -> Result<Vec<GitWorktree>> {
+    let output = run_git_output(repo_path, ["worktree", "list", "--porcelain"])
+        .context("failed to list worktrees")?;
+    Ok(parse_worktree_list(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+fn parse_worktree_list(porcelain: &str) -> Vec<GitWorktree> {
+    let mut worktrees = Vec::new();
+    for block in porcelain.split("\n\n") {
+        let mut path = None;
+        let mut branch = None;
+        for line in block.lines() {
+            if let Some(value) = line.strip_prefix("worktree ") {
+                path = Some(PathBuf::from(value));
+            } else if let Some(value) = line.strip_prefix("branch ") {
+                branch = Some(value.trim_start_matches("refs/heads/").to_string());
+            }
+        }
+        if let Some(path) = path {
+            worktrees.push(GitWorktree {
+                path,
+                branch,
+                is_main: worktrees.is_empty(),
+            });
+        }
+    }
+    worktrees
+}
+
+/// The commit `HEAD` points at in `worktree_path`.
+pub fn git_head_commit(worktree_path: &Path) -> Result<String> {
+    let output = run_git_output(worktree_path, ["rev-parse", "HEAD"])
+        .context("failed to resolve HEAD commit")?;
+    let commit = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if commit.is_empty() {
+        bail!("git returned an empty HEAD in {}", worktree_path.display());
+    }
+    Ok(commit)
+}
+
+/// Carries uncommitted work from one worktree of a repository into another:
+/// tracked changes through a stash commit that leaves `source` untouched, and
+/// untracked (non-ignored) files as plain copies.
+pub fn git_copy_uncommitted_changes(source: &Path, target: &Path) -> Result<()> {
+    let stash = run_git_output(source, ["stash", "create"])
+        .context("failed to snapshot uncommitted changes")?;
+    let stash_commit = String::from_utf8_lossy(&stash.stdout).trim().to_string();
+    if !stash_commit.is_empty() {
+        run_git(target, ["stash", "apply", stash_commit.as_str()])
+            .context("failed to apply uncommitted changes")?;
+    }
+
+    let untracked = run_git_output(source, ["ls-files", "--others", "--exclude-standard", "-z"])
+        .context("failed to list untracked files")?;
+    for relative in String::from_utf8_lossy(&untracked.stdout)
+        .split('\0')
+        .filter(|path| !path.is_empty())
+    {
+        let destination = target.join(relative);
+        if let Some(parent) = destination.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+        copy_untracked_file(&source.join(relative), &destination)
+            .with_context(|| format!("failed to copy untracked file {relative}"))?;
+    }
+
+    Ok(())
+}
+
+/// Copies one untracked path, recreating symlinks as links so a link that
+/// points outside the worktree is not dereferenced into a copy of its target.
+fn copy_untracked_file(source: &Path, destination: &Path) -> std::io::Result<()> {
+    if std::fs::symlink_metadata(source)?.file_type().is_symlink() {
+        let link_target = std::fs::read_link(source)?;
+        return std::os::unix::fs::symlink(link_target, destination);
+    }
+    std::fs::copy(source, destination).map(|_| ())
+}
+
 pub fn git_delete_branch(repo_path: &Path, branch_name: &str) -> Result<()> {
     run_git(repo_path, ["branch", "-d", branch_name])
         .with_context(|| format!("failed to delete branch `{branch_name}`"))
 }
 
+/// Deletes a branch whether or not it is merged; for rolling back a branch
+/// that was just created and holds no work of its own.
+pub fn git_force_delete_branch(repo_path: &Path, branch_name: &str) -> Result<()> {
+    run_git(repo_path, ["branch", "-D", branch_name])
+        .with_context(|| format!("failed to delete branch `{branch_name}`"))
+}
+
 pub fn git_is_valid_repo(path: &Path) -> bool {
     Command::new("git")
         .args(["rev-parse", "--git-dir"])
@@ -513,6 +617,44 @@ mod tests {
         assert!(worktrees.contains(worktree.to_string_lossy().as_ref()));
     }
 
+    #[test]
+    fn test_list_worktrees_reports_branches() {
+        let repo =
+            TestRepo::new_with_origin_main("list-worktrees").expect("repo should be created");
+        let worktree = repo.temp.path().join("wt-list");
+
+        git_create_worktree(repo.path(), &worktree, "feature/list", "origin/main")
+            .expect("worktree should be created");
+        let worktrees = git_list_worktrees(repo.path()).expect("worktrees should list");
+
+        assert_eq!(worktrees.len(), 2);
+        assert!(worktrees[0].is_main);
+        assert_eq!(worktrees[1].branch.as_deref(), Some("feature/list"));
+        assert!(!worktrees[1].is_main);
+    }
+
+    #[test]
+    fn test_parse_worktree_list_handles_detached_heads() {
+        let porcelain = "worktree /repo\nHEAD abc\nbranch refs/heads/main\n\nworktree /wt/detached\nHEAD def\ndetached\n";
+        let worktrees = parse_worktree_list(porcelain);
+
+        assert_eq!(
+            worktrees,
+            vec![
+                GitWorktree {
+                    path: PathBuf::from("/repo"),
+                    branch: Some("main".to_string()),
+                    is_main: true,
+                },
+                GitWorktree {
+                    path: PathBuf::from("/wt/detached"),
+                    branch: None,
+                    is_main: false,
+                },
+            ]
+        );
+    }
+
     #[test]
     fn test_remove_worktree_and_delete_branch() {
         let repo =
@@ -532,6 +674,24 @@ mod tests {
         assert!(!branches.lines().any(|line| line.trim() == "feature/remove"));
     }
 
+    #[test]
+    fn test_remove_clean_worktree_refuses_uncommitted_changes() {
+        let repo = TestRepo::new_with_origin_main("remove-clean").expect("repo should be created");
+        let worktree = repo.temp.path().join("wt-dirty");
+        git_create_worktree(repo.path(), &worktree, "feature/dirty", "origin/main")
+            .expect("worktree should be created");
+        fs::write(worktree.join("scratch.txt"), "work\n")
+            .expect("untracked file should be created");
+
+        assert!(git_remove_clean_worktree(repo.path(), &worktree).is_err());
+        assert!(worktree.join("scratch.txt").exists());
+
+        fs::remove_file(worktree.join("scratch.txt")).expect("untracked file should be removed");
+        git_remove_clean_worktree(repo.path(), &worktree)
+            .expect("clean worktree should be removed");
+        assert!(!worktree.exists());
+    }
+
     #[test]
     fn test_invalid_branch_name() {
         let repo =
@@ -640,6 +800,71 @@ mod tests {
         assert_eq!(summary.top_files, vec!["README.md".to_string()]);
     }
 
+    #[test]
+    fn test_copy_uncommitted_changes_into_fork_worktree() {
+        let repo = TestRepo::new_with_origin_main("fork-copy").expect("repo should be created");
+        fs::write(repo.path().join("README.md"), "initial\n").expect("readme should be created");
+        repo.git(["add", "README.md"])
+            .expect("readme should be staged");
+        repo.git(["commit", "-m", "add readme"])
+            .expect("readme commit should be created");
+
+        fs::write(repo.path().join("README.md"), "initial\nwork in progress\n")
+            .expect("readme should be modified");
+        fs::create_dir_all(repo.path().join("notes")).expect("notes dir should be created");
+        fs::write(repo.path().join("notes/todo.txt"), "untracked\n")
+            .expect("untracked file should be created");
+
+        let head = git_head_commit(repo.path()).expect("head should resolve");
+        let fork = repo.temp.path().join("wt-fork");
+        git_create_worktree(repo.path(), &fork, "feature/fork", &head)
+            .expect("fork worktree should be created");
+        git_copy_uncommitted_changes(repo.path(), &fork).expect("changes should be copied");
+
+        assert_eq!(
+            fs::read_to_string(fork.join("README.md")).expect("fork readme"),
+            "initial\nwork in progress\n"
+        );
+        assert_eq!(
+            fs::read_to_string(fork.join("notes/todo.txt")).expect("fork untracked file"),
+            "untracked\n"
+        );
+        assert_eq!(
+            fs::read_to_string(repo.path().join("README.md")).expect("source readme"),
+            "initial\nwork in progress\n"
+        );
+    }
+
+    #[test]
+    fn test_copy_uncommitted_changes_keeps_symlinks_as_links() {
+        let repo = TestRepo::new_with_origin_main("fork-symlink").expect("repo should be created");
+        let outside = repo.temp.path().join("outside.txt");
+        fs::write(&outside, "outside\n").expect("outside file should be created");
+        std::os::unix::fs::symlink(&outside, repo.path().join("link"))
+            .expect("untracked symlink should be created");
+        std::os::unix::fs::symlink("missing-target", repo.path().join("dangling"))
+            .expect("dangling symlink should be created");
+
+        let head = git_head_commit(repo.path()).expect("head should resolve");
+        let fork = repo.temp.path().join("wt-fork-symlink");
+        git_create_worktree(repo.path(), &fork, "feature/fork-symlink", &head)
+            .expect("fork worktree should be created");
+        git_copy_uncommitted_changes(repo.path(), &fork).expect("changes should be copied");
+
+        let link = fork.join("link");
+        assert!(
+            fs::symlink_metadata(&link)
+                .expect("fork link")
+                .file_type()
+                .is_symlink()
+        );
+        assert_eq!(fs::read_link(&link).expect("fork link target"), outside);
+        assert_eq!(
+            fs::read_link(fork.join("dangling")).expect("fork dangling link"),
+            PathBuf::from("missing-target")
+        );
+    }
+
     #[test]
     fn test_parse_shortstat_handles_missing_fields() {
         assert_eq!(parse_shortstat(""), (0, 0, 0));
@@ -753,16 +978,10 @@ mod tests {
             let seed = temp.path().join("seed");
             fs::create_dir_all(&seed).context("failed to create seed dir")?;
 
-            run_git_in(
-                &temp.path().to_path_buf(),
-                ["init", "--bare", "-b", "main", "origin.git"],
-            )?;
+            run_git_in(temp.path(), ["init", "--bare", "-b", "main", "origin.git"])?;
 
             let seed_path = seed.to_string_lossy().to_string();
-            run_git_in(
-                &temp.path().to_path_buf(),
-                ["init", "-b", "main", &seed_path],
-            )?;
+            run_git_in(temp.path(), ["init", "-b", "main", &seed_path])?;
             run_git_in(&seed, ["config", "user.name", "Test User"])?;
             run_git_in(&seed, ["config", "user.email", "test@example.com"])?;
             run_git_in(&seed, ["commit", "--allow-empty", "-m", "init"])?;
@@ -775,7 +994,7 @@ mod tests {
             let repo = temp.path().join("repo");
             let repo_path = repo.to_string_lossy().to_string();
             run_git_in(
-                &temp.path().to_path_buf(),
+                temp.path(),
                 ["clone", bare.to_string_lossy().as_ref(), &repo_path],
             )?;
             run_git_in(&repo, ["config", "user.name", "Test User"])?;
@@ -55,6 +55,12 @@ pub enum KeyAction {
     AttachTask,
     OpenInNewTerminal,
     OpenInWeb,
+    QuickPrompt,
+    InterruptSession,
+    ForkTask,
+    RevertLastTurn,
+    UnrevertLastTurn,
+    ApprovePermission,
     CycleTodoVisualization,
     Dismiss,
     ToggleCategoryEditMode,
@@ -406,6 +412,42 @@ const BOARD_DEFS: &[ActionDef] = &[
         description: "open selected task in web browser",
         defaults: &["w"],
     },
+    ActionDef {
+        id: "quick_prompt",
+        action: KeyAction::QuickPrompt,
+        description: "send a prompt to selected task's agent",
+        defaults: &["m"],
+    },
+    ActionDef {
+        id: "interrupt_session",
+        action: KeyAction::InterruptSession,
+        description: "interrupt selected task's running agent",
+        defaults: &["i"],
+    },
+    ActionDef {
+        id: "fork_task",
+        action: KeyAction::ForkTask,
+        description: "fork selected task into a new branch and session",
+        defaults: &["F"],
+    },
+    ActionDef {
+        id: "revert_last_turn",
+        action: KeyAction::RevertLastTurn,
+        description: "revert selected task's last agent turn",
+        defaults: &["u"],
+    },
+    ActionDef {
+        id: "unrevert_last_turn",
+        action: KeyAction::UnrevertLastTurn,
+        description: "restore selected task's reverted agent turn",
+        defaults: &["U"],
+    },
+    ActionDef {
+        id: "approve_permission",
+        action: KeyAction::ApprovePermission,
+        description: "answer selected task's pending permission request",
+        defaults: &["y"],
+    },
     ActionDef {
         id: "cycle_todo_visualization",
         action: KeyAction::CycleTodoVisualization,
@@ -462,6 +504,16 @@ impl Keybindings {
                 self.display_for(KeyContext::Board, KeyAction::OpenInNewTerminal)
             }
             "open_in_web" => self.display_for(KeyContext::Board, KeyAction::OpenInWeb),
+            "quick_prompt" => self.display_for(KeyContext::Board, KeyAction::QuickPrompt),
+            "interrupt_session" => self.display_for(KeyContext::Board, KeyAction::InterruptSession),
+            "fork_task" => self.display_for(KeyContext::Board, KeyAction::ForkTask),
+            "revert_last_turn" => self.display_for(KeyContext::Board, KeyAction::RevertLastTurn),
+            "unrevert_last_turn" => {
+                self.display_for(KeyContext::Board, KeyAction::UnrevertLastTurn)
+            }
+            "approve_permission" => {
+                self.display_for(KeyContext::Board, KeyAction::ApprovePermission)
+            }
             "add_category" => self.display_for(KeyContext::Board, KeyAction::AddCategory),
             "rename_category" => self.display_for(KeyContext::Board, KeyAction::RenameCategory),
             "delete_category" => self.display_for(KeyContext::Board, KeyAction::DeleteCategory),
@@ -620,6 +672,33 @@ impl Keybindings {
                 self.display_for(KeyContext::Board, KeyAction::OpenInWeb)
                     .unwrap_or_else(|| "-".to_string())
             ),
+            format!(
+                "  {}: send a prompt to selected task's agent",
+                self.display_for(KeyContext::Board, KeyAction::QuickPrompt)
+                    .unwrap_or_else(|| "-".to_string())
+            ),
+            format!(
+                "  {}: interrupt selected task's running agent",
+                self.display_for(KeyContext::Board, KeyAction::InterruptSession)
+                    .unwrap_or_else(|| "-".to_string())
+            ),
+            format!(
+                "  {}: fork selected task into a new branch and session",
+                self.display_for(KeyContext::Board, KeyAction::ForkTask)
+                    .unwrap_or_else(|| "-".to_string())
+            ),
+            format!(
+                "  {}/{}: revert/restore selected task's last agent turn",
+                self.display_for(KeyContext::Board, KeyAction::RevertLastTurn)
+                    .unwrap_or_else(|| "-".to_string()),
+                self.display_for(KeyContext::Board, KeyAction::UnrevertLastTurn)
+                    .unwrap_or_else(|| "-".to_string())
+            ),
+            format!(
+                "  {}: approve/deny selected task's permission request",
+                self.display_for(KeyContext::Board, KeyAction::ApprovePermission)
+                    .unwrap_or_else(|| "-".to_string())
+            ),
             format!(
                 "  {}: cycle todo visualization",
                 self.display_for(KeyContext::Board, KeyAction::CycleTodoVisualization)
@@ -922,7 +1001,7 @@ fn parse_binding(raw: &str) -> Option<KeyBinding> {
         "delete" => KeyCode::Delete,
         "insert" => KeyCode::Insert,
         "space" => KeyCode::Char(' '),
-        _ if lower.starts_with('f') && lower.len() <= 3 => {
+        _ if lower.starts_with('f') && (2..=3).contains(&lower.len()) => {
             let n = lower[1..].parse::<u8>().ok()?;
             KeyCode::F(n)
         }
@@ -957,6 +1036,17 @@ mod tests {
         assert!(!upper.matches(KeyEvent::new(KeyCode::Char('h'), KeyModifiers::empty())));
     }
 
+    #[test]
+    fn single_f_is_a_letter_not_a_function_key() {
+        let upper = parse_binding("F").expect("binding");
+        assert_eq!(upper.code, KeyCode::Char('F'));
+        assert_eq!(
+            parse_binding("f").expect("binding").code,
+            KeyCode::Char('f')
+        );
+        assert_eq!(parse_binding("F5").expect("binding").code, KeyCode::F(5));
+    }
+
     #[test]
     fn shifted_symbol_matches_without_shift_modifier() {
         let binding = parse_binding("?").expect("binding");
@@ -1079,3 +1169,66 @@ fn defaults_include_open_in_web() {
     );
     assert_eq!(action, Some(KeyAction::OpenInWeb));
 }
+
+#[test]
+fn defaults_include_quick_prompt() {
+    let keys = Keybindings::load();
+    let action = keys.action_for_key(
+        KeyContext::Board,
+        KeyEvent::new(KeyCode::Char('m'), KeyModifiers::empty()),
+    );
+    assert_eq!(action, Some(KeyAction::QuickPrompt));
+    assert_eq!(
+        keys.command_palette_keybinding("quick_prompt").as_deref(),
+        Some("m")
+    );
+}
+
+#[test]
+fn defaults_include_interrupt_session() {
+    let keys = Keybindings::load();
+    let action = keys.action_for_key(
+        KeyContext::Board,
+        KeyEvent::new(KeyCode::Char('i'), KeyModifiers::empty()),
+    );
+    assert_eq!(action, Some(KeyAction::InterruptSession));
+}
+
+#[test]
+fn defaults_include_fork_task() {
+    let keys = Keybindings::load();
+    let action = keys.action_for_key(
+        KeyContext::Board,
+        KeyEvent::new(KeyCode::Char('F'), KeyModifiers::SHIFT),
+    );
+    assert_eq!(action, Some(KeyAction::ForkTask));
+    assert_eq!(
+        keys.command_palette_keybinding("fork_task").as_deref(),
+        Some("F")
+    );
+}
+
+#[test]
+fn defaults_include_revert_last_turn() {
+    let keys = Keybindings::load();
+    let revert = keys.action_for_key(
+        KeyContext::Board,
+        KeyEvent::new(KeyCode::Char('u'), KeyModifiers::empty()),
+    );
+    let unrevert = keys.action_for_key(
+        KeyContext::Board,
+        KeyEvent::new(KeyCode::Char('U'), KeyModifiers::SHIFT),
+    );
+    assert_eq!(revert, Some(KeyAction::RevertLastTurn));
+    assert_eq!(unrevert, Some(KeyAction::UnrevertLastTurn));
+}
+
+#[test]
+fn defaults_include_approve_permission() {
+    let keys = Keybindings::load();
+    let action = keys.action_for_key(
+        KeyContext::Board,
+        KeyEvent::new(KeyCode::Char('y'), KeyModifiers::empty()),
+    );
+    assert_eq!(action, Some(KeyAction::ApprovePermission));
+}
@@ -1,4 +1,5 @@
 pub mod app;
+pub mod backend;
 pub mod cli;
 pub mod command_palette;
 pub mod db;
@@ -161,7 +161,7 @@ mod tests {
         let _ = fs::remove_dir_all(&temp_dir);
         fs::create_dir_all(&temp_dir).unwrap();
 
-        assert!(get_recent_log_path().is_none() || true);
+        let _ = get_recent_log_path();
 
         let _ = fs::remove_dir_all(&temp_dir);
     }
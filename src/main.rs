@@ -10,7 +10,8 @@ use std::{
 };
 
 use anyhow::{Context, Result, bail};
-use clap::Parser;
+use clap::{CommandFactory, Parser};
+use clap_complete::{ArgValueCompleter, CompleteEnv};
 use crossterm::{
     cursor::Show,
     event::DisableMouseCapture,
@@ -25,9 +26,13 @@ use tuirealm::{
 
 use opencode_kanban::{
     app::App,
-    cli::{self, RootCommand},
+    backend,
+    cli::{self, OutputFormat, OutputOptions, RootCommand},
     logging::{init_logging, print_log_location},
+    opencode::{endpoint, pane},
+    projects,
     realm::{RootId, apply_message, init_application, should_quit},
+    settings::Settings,
     theme::ThemePreset,
     tmux::{ensure_tmux_installed, tmux_session_exists},
 };
@@ -41,7 +46,13 @@ use opencode_kanban::{
     author
 )]
 struct Cli {
-    #[arg(short, long, global = true, value_name = "PROJECT")]
+    #[arg(
+        short,
+        long,
+        global = true,
+        value_name = "PROJECT",
+        add = ArgValueCompleter::new(cli::complete_project_names)
+    )]
     project: Option<String>,
 
     #[arg(long, value_name = "PRESET")]
@@ -50,6 +61,21 @@ struct Cli {
     #[arg(long, global = true)]
     json: bool,
 
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        value_name = "FORMAT",
+        conflicts_with = "json"
+    )]
+    format: Option<OutputFormat>,
+
+    #[arg(long, global = true, value_name = "COLUMNS", value_delimiter = ',')]
+    columns: Option<Vec<String>>,
+
+    #[arg(long = "no-header", global = true)]
+    no_header: bool,
+
     #[arg(long)]
     quiet: bool,
 
@@ -69,6 +95,8 @@ static TERMINAL_RESTORED: AtomicBool = AtomicBool::new(false);
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    CompleteEnv::with_factory(Cli::command).complete();
+
     let log_path = match init_logging() {
         Ok(path) => Some(path),
         Err(err) => {
@@ -101,20 +129,52 @@ async fn main() -> Result<()> {
 
 fn run_app() -> Result<RunOutcome> {
     let cli = Cli::parse();
+    let settings = Settings::load();
+    endpoint::configure(settings.server);
+    backend::configure(settings.backend);
+    pane::configure(settings.pane_status);
 
     if let Some(command) = cli.command {
-        let Some(project_name) = cli.project.as_deref() else {
-            eprintln!("error[PROJECT_REQUIRED]: --project is required for CLI commands");
-            return Ok(RunOutcome::Exit(2));
+        match &command {
+            RootCommand::Completions(args) => {
+                cli::write_completions(args.shell, &mut io::stdout())
+                    .context("failed to write shell completions")?;
+                return Ok(RunOutcome::Exit(0));
+            }
+            RootCommand::Man => {
+                clap_mangen::Man::new(Cli::command())
+                    .render(&mut io::stdout())
+                    .context("failed to render man page")?;
+                return Ok(RunOutcome::Exit(0));
+            }
+            _ => {}
+        }
+
+        // Doctor also diagnoses the default board, so it does not need --project.
+        // Other commands without it fall back to the task session or worktree
+        // they run in.
+        let project_name = match (cli.project.as_deref(), &command) {
+            (Some(project_name), _) => Some(project_name),
+            (None, RootCommand::Doctor) => Some(projects::DEFAULT_PROJECT),
+            (None, _) => None,
         };
 
-        if project_name.trim().is_empty() {
+        if project_name.is_some_and(|project_name| project_name.trim().is_empty()) {
             eprintln!("error[PROJECT_REQUIRED]: --project cannot be empty");
             return Ok(RunOutcome::Exit(2));
         }
 
         let _ = cli.no_color;
-        let code = cli::run(project_name, command, cli.json, cli.quiet);
+        let options = OutputOptions {
+            format: if cli.json {
+                OutputFormat::Json
+            } else {
+                cli.format.unwrap_or_default()
+            },
+            columns: cli.columns,
+            no_header: cli.no_header,
+        };
+        let code = cli::run(project_name, command, &options, cli.quiet);
         return Ok(RunOutcome::Exit(code));
     }
 
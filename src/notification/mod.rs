@@ -3,7 +3,7 @@
 pub mod sound;
 
 use crate::tmux::{tmux_broadcast_to_sessions, tmux_list_sessions};
-use crate::types::Task;
+use crate::types::{SessionState, Task};
 use std::str::FromStr;
 use tracing::{debug, warn};
 
@@ -83,6 +83,33 @@ impl FromStr for NotificationBackend {
 
 /// Send task completion notification via configured backend(s)
 pub fn notify_task_completion(task: &Task, config: TaskCompletionNotificationConfig) {
+    let message = format!("✓ Task completed | {}:{}", task.branch, task.title);
+    send_notification(task, &message, config);
+}
+
+/// Send a notification when a task stops on something only the user can
+/// fix: a failed run or a question. Retries resolve on their own and are
+/// not announced.
+pub fn notify_task_attention(
+    task: &Task,
+    state: SessionState,
+    config: TaskCompletionNotificationConfig,
+) {
+    if let Some(message) = attention_message(task, state) {
+        send_notification(task, &message, config);
+    }
+}
+
+fn attention_message(task: &Task, state: SessionState) -> Option<String> {
+    let label = match state {
+        SessionState::Error => "✗ Task failed",
+        SessionState::AwaitingInput => "? Task is waiting for input",
+        _ => return None,
+    };
+    Some(format!("{label} | {}:{}", task.branch, task.title))
+}
+
+fn send_notification(task: &Task, message: &str, config: TaskCompletionNotificationConfig) {
     sound::play_completion_sound(config.sound);
 
     let (send_tmux, send_system) = backend_targets(config.backend);
@@ -91,14 +118,12 @@ pub fn notify_task_completion(task: &Task, config: TaskCompletionNotificationCon
         return;
     }
 
-    let message = format!("✓ Task completed | {}:{}", task.branch, task.title);
-
     if send_tmux {
-        send_tmux_notification(task, &message, config.notification_display_duration_ms);
+        send_tmux_notification(task, message, config.notification_display_duration_ms);
     }
 
     if send_system {
-        send_system_notification(task, &message, config.notification_display_duration_ms);
+        send_system_notification(task, message, config.notification_display_duration_ms);
     }
 }
 
@@ -275,6 +300,46 @@ mod tests {
         assert_eq!(backend_targets(NotificationBackend::Both), (true, true));
     }
 
+    #[test]
+    fn test_attention_message_only_for_failures_and_questions() {
+        let task = Task {
+            id: uuid::Uuid::nil(),
+            title: "Fix login".to_string(),
+            repo_id: uuid::Uuid::nil(),
+            branch: "fix/login".to_string(),
+            category_id: uuid::Uuid::nil(),
+            position: 0,
+            tmux_session_name: None,
+            worktree_path: None,
+            tmux_status: "error".to_string(),
+            status_source: "server".to_string(),
+            status_fetched_at: None,
+            status_error: None,
+            opencode_session_id: None,
+            model: None,
+            agent: None,
+            forked_from: None,
+            backend: None,
+            attach_overlay_shown: false,
+            needs_inspection: false,
+            archived: false,
+            archived_at: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+        };
+
+        assert_eq!(
+            attention_message(&task, SessionState::Error).as_deref(),
+            Some("✗ Task failed | fix/login:Fix login")
+        );
+        assert_eq!(
+            attention_message(&task, SessionState::AwaitingInput).as_deref(),
+            Some("? Task is waiting for input | fix/login:Fix login")
+        );
+        assert_eq!(attention_message(&task, SessionState::Retrying), None);
+        assert_eq!(attention_message(&task, SessionState::Idle), None);
+    }
+
     #[test]
     fn test_notification_backend_roundtrip() {
         for backend in [
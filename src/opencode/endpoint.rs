@@ -0,0 +1,261 @@
+//! Where the OpenCode server listens and how to authenticate with it.
+//!
+//! The `[server]` table in `settings.toml` is registered once at startup with
+//! [`configure`]; environment variables override it so a single run can
+//! point somewhere else. Every client that talks to the server resolves its
+//! address and credentials through [`ServerEndpoint::current`].
+
+use std::sync::RwLock;
+use std::time::Duration;
+
+use base64::Engine;
+use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderValue};
+
+use crate::settings::ServerSettings;
+
+pub const DEFAULT_HOSTNAME: &str = "127.0.0.1";
+pub const DEFAULT_PORT: u16 = 4096;
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_millis(300);
+/// OpenCode's own default user name for `OPENCODE_SERVER_PASSWORD`.
+const DEFAULT_USERNAME: &str = "opencode";
+
+static CONFIGURED: RwLock<Option<ServerSettings>> = RwLock::new(None);
+
+/// Registers the `[server]` settings used by every later server request.
+pub fn configure(settings: ServerSettings) {
+    match CONFIGURED.write() {
+        Ok(mut configured) => *configured = Some(settings),
+        Err(poisoned) => *poisoned.into_inner() = Some(settings),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServerAuth {
+    Basic { username: String, password: String },
+    Bearer(String),
+}
+
+impl ServerAuth {
+    pub fn header_value(&self) -> String {
+        match self {
+            Self::Basic { username, password } => {
+                let encoded = base64::engine::general_purpose::STANDARD
+                    .encode(format!("{username}:{password}"));
+                format!("Basic {encoded}")
+            }
+            Self::Bearer(token) => format!("Bearer {token}"),
+        }
+    }
+
+    /// Variables that let `opencode` itself (`serve`, `attach`) use these
+    /// credentials. OpenCode has no bearer-token equivalent.
+    pub fn opencode_env(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Self::Basic { username, password } => vec![
+                ("OPENCODE_SERVER_USERNAME", username.clone()),
+                ("OPENCODE_SERVER_PASSWORD", password.clone()),
+            ],
+            Self::Bearer(_) => Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerEndpoint {
+    pub hostname: String,
+    pub port: u16,
+    pub request_timeout: Duration,
+    pub auth: Option<ServerAuth>,
+}
+
+impl Default for ServerEndpoint {
+    fn default() -> Self {
+        Self {
+            hostname: DEFAULT_HOSTNAME.to_string(),
+            port: DEFAULT_PORT,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            auth: None,
+        }
+    }
+}
+
+impl ServerEndpoint {
+    /// The configured settings with the process environment applied on top.
+    pub fn current() -> Self {
+        let settings = match CONFIGURED.read() {
+            Ok(configured) => configured.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        };
+        Self::resolve(&settings.unwrap_or_default(), |key| std::env::var(key).ok())
+    }
+
+    /// Environment variables win over settings:
+    /// `OPENCODE_KANBAN_SERVER_HOSTNAME`, `OPENCODE_KANBAN_SERVER_PORT` (or
+    /// the older `OPENCODE_KANBAN_STATUS_PORT`),
+    /// `OPENCODE_KANBAN_SERVER_TIMEOUT_MS`, `OPENCODE_KANBAN_SERVER_TOKEN`,
+    /// and OpenCode's own `OPENCODE_SERVER_USERNAME`/`OPENCODE_SERVER_PASSWORD`.
+    /// A bearer token takes precedence over basic-auth credentials.
+    pub fn resolve(settings: &ServerSettings, env: impl Fn(&str) -> Option<String>) -> Self {
+        let env = |key: &str| {
+            env(key)
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+
+        let hostname = env("OPENCODE_KANBAN_SERVER_HOSTNAME")
+            .or_else(|| settings.hostname.clone())
+            .unwrap_or_else(|| DEFAULT_HOSTNAME.to_string());
+        let port = env("OPENCODE_KANBAN_SERVER_PORT")
+            .or_else(|| env("OPENCODE_KANBAN_STATUS_PORT"))
+            .and_then(|value| value.parse::<u16>().ok())
+            .filter(|port| *port != 0)
+            .or(settings.port)
+            .unwrap_or(DEFAULT_PORT);
+        let request_timeout = env("OPENCODE_KANBAN_SERVER_TIMEOUT_MS")
+            .and_then(|value| value.parse::<u64>().ok())
+            .or(settings.request_timeout_ms)
+            .map(Duration::from_millis)
+            .unwrap_or(DEFAULT_REQUEST_TIMEOUT);
+
+        let token = env("OPENCODE_KANBAN_SERVER_TOKEN").or_else(|| settings.bearer_token.clone());
+        let password = env("OPENCODE_SERVER_PASSWORD").or_else(|| settings.password.clone());
+        let auth = match (token, password) {
+            (Some(token), _) => Some(ServerAuth::Bearer(token)),
+            (None, Some(password)) => Some(ServerAuth::Basic {
+                username: env("OPENCODE_SERVER_USERNAME")
+                    .or_else(|| settings.username.clone())
+                    .unwrap_or_else(|| DEFAULT_USERNAME.to_string()),
+                password,
+            }),
+            (None, None) => None,
+        };
+
+        Self {
+            hostname,
+            port,
+            request_timeout,
+            auth,
+        }
+    }
+
+    pub fn base_url(&self) -> String {
+        format!("http://{}:{}", self.hostname, self.port)
+    }
+
+    /// The command that starts a server this endpoint would reach.
+    pub fn serve_command(&self) -> String {
+        format!(
+            "opencode serve --port {} --hostname {}",
+            self.port, self.hostname
+        )
+    }
+}
+
+/// Headers every request to the server needs; empty without credentials.
+pub fn auth_headers(auth: Option<&ServerAuth>) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if let Some(auth) = auth
+        && let Ok(mut value) = HeaderValue::from_str(&auth.header_value())
+    {
+        value.set_sensitive(true);
+        headers.insert(AUTHORIZATION, value);
+    }
+    headers
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn resolve_with(settings: &ServerSettings, vars: &[(&str, &str)]) -> ServerEndpoint {
+        let vars = vars
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect::<HashMap<_, _>>();
+        ServerEndpoint::resolve(settings, |key| vars.get(key).cloned())
+    }
+
+    #[test]
+    fn resolve_defaults_to_local_server_without_auth() {
+        let endpoint = resolve_with(&ServerSettings::default(), &[]);
+        assert_eq!(endpoint, ServerEndpoint::default());
+        assert_eq!(endpoint.base_url(), "http://127.0.0.1:4096");
+        assert_eq!(
+            endpoint.serve_command(),
+            "opencode serve --port 4096 --hostname 127.0.0.1"
+        );
+    }
+
+    #[test]
+    fn resolve_prefers_environment_over_settings() {
+        let settings = ServerSettings {
+            hostname: Some("10.0.0.5".to_string()),
+            port: Some(5000),
+            request_timeout_ms: Some(1_000),
+            username: Some("kanban".to_string()),
+            password: Some("from-settings".to_string()),
+            bearer_token: None,
+        };
+
+        let endpoint = resolve_with(&settings, &[]);
+        assert_eq!(endpoint.base_url(), "http://10.0.0.5:5000");
+        assert_eq!(endpoint.request_timeout, Duration::from_secs(1));
+        assert_eq!(
+            endpoint.auth,
+            Some(ServerAuth::Basic {
+                username: "kanban".to_string(),
+                password: "from-settings".to_string(),
+            })
+        );
+
+        let endpoint = resolve_with(
+            &settings,
+            &[
+                ("OPENCODE_KANBAN_STATUS_PORT", "4200"),
+                ("OPENCODE_SERVER_PASSWORD", "from-env"),
+                ("OPENCODE_KANBAN_SERVER_TIMEOUT_MS", "750"),
+            ],
+        );
+        assert_eq!(endpoint.base_url(), "http://10.0.0.5:4200");
+        assert_eq!(endpoint.request_timeout, Duration::from_millis(750));
+        assert_eq!(
+            endpoint.auth,
+            Some(ServerAuth::Basic {
+                username: "kanban".to_string(),
+                password: "from-env".to_string(),
+            })
+        );
+
+        let endpoint = resolve_with(&settings, &[("OPENCODE_KANBAN_SERVER_TOKEN", "tok")]);
+        assert_eq!(endpoint.auth, Some(ServerAuth::Bearer("tok".to_string())));
+    }
+
+    #[test]
+    fn auth_header_values_match_http_schemes() {
+        let basic = ServerAuth::Basic {
+            username: "opencode".to_string(),
+            password: "secret".to_string(),
+        };
+        assert_eq!(basic.header_value(), "Basic b3BlbmNvZGU6c2VjcmV0");
+        assert_eq!(
+            ServerAuth::Bearer("tok".to_string()).header_value(),
+            "Bearer tok"
+        );
+        assert_eq!(
+            basic.opencode_env(),
+            vec![
+                ("OPENCODE_SERVER_USERNAME", "opencode".to_string()),
+                ("OPENCODE_SERVER_PASSWORD", "secret".to_string()),
+            ]
+        );
+        assert!(
+            ServerAuth::Bearer("tok".to_string())
+                .opencode_env()
+                .is_empty()
+        );
+        assert!(auth_headers(None).is_empty());
+        assert!(auth_headers(Some(&basic)).contains_key(AUTHORIZATION));
+    }
+}
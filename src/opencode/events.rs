@@ -0,0 +1,453 @@
+//! Server-sent events from the OpenCode server.
+//!
+//! Only the events the board reacts to are decoded; everything else is
+//! reported as [`ServerEvent::Other`] so the subscriber can ignore it.
+
+use std::collections::VecDeque;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::status_server::{parse_permission_value, parse_status_value, parse_todo_values};
+use crate::types::{SessionPermissionRequest, SessionState, SessionStatusError, SessionTodoItem};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ServerEvent {
+    Connected,
+    SessionStatus {
+        session_id: String,
+        state: SessionState,
+        /// Server's explanation for `Error` and `Retrying`.
+        detail: Option<String>,
+    },
+    SessionUpdated {
+        session_id: String,
+        title: Option<String>,
+    },
+    TodoUpdated {
+        session_id: String,
+        todos: Vec<SessionTodoItem>,
+    },
+    MessageUpdated {
+        session_id: String,
+    },
+    PermissionAsked(SessionPermissionRequest),
+    PermissionReplied {
+        session_id: String,
+        permission_id: String,
+    },
+    Other,
+}
+
+/// An event and the directory of the OpenCode instance that emitted it.
+/// Only `/global/event` reports the directory.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ScopedServerEvent {
+    pub directory: Option<String>,
+    pub event: ServerEvent,
+}
+
+impl ServerEvent {
+    pub fn session_id(&self) -> Option<&str> {
+        match self {
+            ServerEvent::SessionStatus { session_id, .. }
+            | ServerEvent::SessionUpdated { session_id, .. }
+            | ServerEvent::TodoUpdated { session_id, .. }
+            | ServerEvent::MessageUpdated { session_id }
+            | ServerEvent::PermissionReplied { session_id, .. } => Some(session_id),
+            ServerEvent::PermissionAsked(permission) => Some(&permission.session_id),
+            ServerEvent::Connected | ServerEvent::Other => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RawEvent {
+    #[serde(default, rename = "type")]
+    kind: Option<String>,
+    #[serde(default)]
+    properties: Value,
+    // `/global/event` wraps each instance event as `{directory, payload}`.
+    #[serde(default)]
+    directory: Option<String>,
+    #[serde(default)]
+    payload: Option<Box<RawEvent>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RawSessionInfo {
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    title: Option<String>,
+}
+
+/// Decodes one SSE `data` payload. Returns `None` for data that is not a
+/// recognizable OpenCode event.
+pub fn parse_server_event(data: &str) -> Option<ServerEvent> {
+    parse_scoped_server_event(data).map(|scoped| scoped.event)
+}
+
+/// Like [`parse_server_event`], keeping the directory of global events.
+pub fn parse_scoped_server_event(data: &str) -> Option<ScopedServerEvent> {
+    let mut raw = serde_json::from_str::<RawEvent>(data).ok()?;
+    let mut directory = None;
+    if raw.kind.is_none()
+        && let Some(payload) = raw.payload.take()
+    {
+        directory = raw
+            .directory
+            .take()
+            .filter(|directory| !directory.is_empty());
+        raw = *payload;
+    }
+    let event = decode_event(raw)?;
+    Some(ScopedServerEvent { directory, event })
+}
+
+fn decode_event(raw: RawEvent) -> Option<ServerEvent> {
+    let kind = raw.kind?;
+    let properties = raw.properties;
+
+    let event = match kind.as_str() {
+        "server.connected" => ServerEvent::Connected,
+        "session.status" => {
+            let (state, detail) = parse_status_value(properties.get("status")?.clone())?;
+            ServerEvent::SessionStatus {
+                session_id: session_id_property(&properties)?,
+                state,
+                detail,
+            }
+        }
+        "session.idle" => ServerEvent::SessionStatus {
+            session_id: session_id_property(&properties)?,
+            state: SessionState::Idle,
+            detail: None,
+        },
+        // Errors without a session (bad config, auth) are not a task's concern,
+        // and an aborted turn just leaves the session idle.
+        "session.error" => match session_id_property(&properties) {
+            Some(session_id) if is_abort_error(properties.get("error")) => {
+                ServerEvent::SessionStatus {
+                    session_id,
+                    state: SessionState::Idle,
+                    detail: None,
+                }
+            }
+            Some(session_id) => ServerEvent::SessionStatus {
+                session_id,
+                state: SessionState::Error,
+                detail: session_error_message(properties.get("error")),
+            },
+            None => ServerEvent::Other,
+        },
+        "question.asked" => ServerEvent::SessionStatus {
+            session_id: session_id_property(&properties)?,
+            state: SessionState::AwaitingInput,
+            detail: None,
+        },
+        "question.replied" | "question.rejected" => ServerEvent::SessionStatus {
+            session_id: session_id_property(&properties)?,
+            state: SessionState::Running,
+            detail: None,
+        },
+        "session.created" | "session.updated" => {
+            let info =
+                serde_json::from_value::<RawSessionInfo>(properties.get("info")?.clone()).ok()?;
+            ServerEvent::SessionUpdated {
+                session_id: info.id?,
+                title: info
+                    .title
+                    .map(|title| title.trim().to_string())
+                    .filter(|title| !title.is_empty()),
+            }
+        }
+        "todo.updated" => ServerEvent::TodoUpdated {
+            session_id: session_id_property(&properties)?,
+            todos: parse_todo_values(properties.get("todos")?.clone())?,
+        },
+        "message.updated" => ServerEvent::MessageUpdated {
+            session_id: session_id_property(properties.get("info")?)?,
+        },
+        "message.part.updated" => ServerEvent::MessageUpdated {
+            session_id: session_id_property(properties.get("part")?)?,
+        },
+        "message.removed" | "message.part.removed" => ServerEvent::MessageUpdated {
+            session_id: session_id_property(&properties)?,
+        },
+        "permission.updated" | "permission.asked" => {
+            ServerEvent::PermissionAsked(parse_permission_value(&properties)?)
+        }
+        "permission.replied" => ServerEvent::PermissionReplied {
+            session_id: session_id_property(&properties)?,
+            permission_id: properties
+                .get("permissionID")
+                .or_else(|| properties.get("requestID"))
+                .and_then(Value::as_str)?
+                .to_string(),
+        },
+        _ => ServerEvent::Other,
+    };
+    Some(event)
+}
+
+/// `session.error` carries `{name, data: {message}}`; fall back to the name
+/// for errors without a message.
+fn session_error_message(error: Option<&Value>) -> Option<String> {
+    let error = error?;
+    error
+        .get("data")
+        .and_then(|data| data.get("message"))
+        .or_else(|| error.get("message"))
+        .or_else(|| error.get("name"))
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|message| !message.is_empty())
+        .map(str::to_string)
+}
+
+/// OpenCode reports an aborted turn as a `MessageAbortedError`.
+fn is_abort_error(error: Option<&Value>) -> bool {
+    error
+        .and_then(|error| error.get("name"))
+        .and_then(Value::as_str)
+        == Some("MessageAbortedError")
+}
+
+fn session_id_property(value: &Value) -> Option<String> {
+    value
+        .get("sessionID")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+}
+
+/// Splits a byte stream into SSE events and yields their joined `data` lines.
+#[derive(Debug, Default)]
+pub struct SseDecoder {
+    // Raw bytes, so characters split across chunks decode intact.
+    buffer: Vec<u8>,
+    data: Vec<String>,
+}
+
+impl SseDecoder {
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(chunk);
+
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|byte| *byte == b'\n') {
+            let line = String::from_utf8_lossy(&self.buffer[..end])
+                .trim_end_matches('\r')
+                .to_string();
+            self.buffer.drain(..=end);
+
+            if line.is_empty() {
+                if !self.data.is_empty() {
+                    events.push(self.data.join("\n"));
+                    self.data.clear();
+                }
+            } else if let Some(value) = line.strip_prefix("data:") {
+                self.data
+                    .push(value.strip_prefix(' ').unwrap_or(value).to_string());
+            }
+        }
+        events
+    }
+}
+
+/// An open event stream response. Dropping it closes the connection.
+pub struct ServerEventStream {
+    response: reqwest::Response,
+    decoder: SseDecoder,
+    pending: VecDeque<ScopedServerEvent>,
+}
+
+impl ServerEventStream {
+    pub(super) fn new(response: reqwest::Response) -> Self {
+        Self {
+            response,
+            decoder: SseDecoder::default(),
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Waits for the next recognized event; `Ok(None)` once the server closes
+    /// the stream. Cancelling the returned future loses no events.
+    pub async fn next_event(&mut self) -> Result<Option<ScopedServerEvent>, SessionStatusError> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Ok(Some(event));
+            }
+
+            let chunk = self
+                .response
+                .chunk()
+                .await
+                .map_err(|err| SessionStatusError {
+                    code: "SERVER_READ_FAILED".to_string(),
+                    message: format!("event stream interrupted: {err}"),
+                })?;
+            let Some(chunk) = chunk else {
+                return Ok(None);
+            };
+            self.pending.extend(
+                self.decoder
+                    .push(&chunk)
+                    .iter()
+                    .filter_map(|data| parse_scoped_server_event(data)),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sse_decoder_joins_data_lines_across_chunks() {
+        let mut decoder = SseDecoder::default();
+        assert!(decoder.push(b"event: message\r\ndata: {\"a\":").is_empty());
+        assert!(decoder.push(b"1, \"b\": \"\xc3").is_empty());
+        assert_eq!(
+            decoder.push(b"\xa9\"}\n\n"),
+            vec!["{\"a\":1, \"b\": \"\u{e9}\"}"]
+        );
+        assert!(decoder.push(b"data: {\"a\":").is_empty());
+        assert_eq!(
+            decoder.push(b"1}\r\n\r\n: keepalive\n\n"),
+            vec!["{\"a\":1}"]
+        );
+        assert_eq!(
+            decoder.push(b"data: one\ndata: two\n\ndata:three\n\n"),
+            vec!["one\ntwo", "three"]
+        );
+    }
+
+    #[test]
+    fn parse_server_event_decodes_board_relevant_events() {
+        assert_eq!(
+            parse_server_event(
+                r#"{"type":"session.status","properties":{"sessionID":"ses_1","status":{"type":"busy"}}}"#
+            ),
+            Some(ServerEvent::SessionStatus {
+                session_id: "ses_1".to_string(),
+                state: SessionState::Running,
+                detail: None,
+            })
+        );
+        assert_eq!(
+            parse_scoped_server_event(
+                r#"{"directory":"/tmp/wt","payload":{"type":"session.idle","properties":{"sessionID":"ses_1"}}}"#
+            ),
+            Some(ScopedServerEvent {
+                directory: Some("/tmp/wt".to_string()),
+                event: ServerEvent::SessionStatus {
+                    session_id: "ses_1".to_string(),
+                    state: SessionState::Idle,
+                    detail: None,
+                },
+            })
+        );
+        assert_eq!(
+            parse_server_event(
+                r#"{"type":"session.status","properties":{"sessionID":"ses_1","status":{"type":"retry","attempt":1,"message":"Overloaded","next":0}}}"#
+            ),
+            Some(ServerEvent::SessionStatus {
+                session_id: "ses_1".to_string(),
+                state: SessionState::Retrying,
+                detail: Some("Overloaded".to_string()),
+            })
+        );
+        assert_eq!(
+            parse_server_event(
+                r#"{"type":"session.error","properties":{"sessionID":"ses_1","error":{"name":"APIError","data":{"message":"invalid api key"}}}}"#
+            ),
+            Some(ServerEvent::SessionStatus {
+                session_id: "ses_1".to_string(),
+                state: SessionState::Error,
+                detail: Some("invalid api key".to_string()),
+            })
+        );
+        assert_eq!(
+            parse_server_event(
+                r#"{"type":"question.asked","properties":{"id":"que_1","sessionID":"ses_1","questions":[]}}"#
+            ),
+            Some(ServerEvent::SessionStatus {
+                session_id: "ses_1".to_string(),
+                state: SessionState::AwaitingInput,
+                detail: None,
+            })
+        );
+        assert_eq!(
+            parse_server_event(r#"{"type":"session.error","properties":{"error":{"name":"X"}}}"#),
+            Some(ServerEvent::Other)
+        );
+        assert_eq!(
+            parse_server_event(
+                r#"{"type":"session.error","properties":{"sessionID":"ses_1","error":{"name":"MessageAbortedError","data":{"message":"Aborted"}}}}"#
+            ),
+            Some(ServerEvent::SessionStatus {
+                session_id: "ses_1".to_string(),
+                state: SessionState::Idle,
+                detail: None,
+            })
+        );
+        assert_eq!(
+            parse_server_event(
+                r#"{"type":"todo.updated","properties":{"sessionID":"ses_1","todos":[{"content":"write tests","status":"completed"}]}}"#
+            ),
+            Some(ServerEvent::TodoUpdated {
+                session_id: "ses_1".to_string(),
+                todos: vec![SessionTodoItem {
+                    content: "write tests".to_string(),
+                    completed: true,
+                }],
+            })
+        );
+        assert_eq!(
+            parse_server_event(
+                r#"{"type":"session.updated","properties":{"info":{"id":"ses_1","title":" Fix login "}}}"#
+            ),
+            Some(ServerEvent::SessionUpdated {
+                session_id: "ses_1".to_string(),
+                title: Some("Fix login".to_string()),
+            })
+        );
+        assert_eq!(
+            parse_server_event(
+                r#"{"type":"message.part.updated","properties":{"part":{"sessionID":"ses_1","type":"text"}}}"#
+            ),
+            Some(ServerEvent::MessageUpdated {
+                session_id: "ses_1".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_server_event(
+                r#"{"type":"permission.updated","properties":{"id":"per_1","sessionID":"ses_1","type":"bash","pattern":"rm -rf build","title":"rm -rf build","metadata":{}}}"#
+            ),
+            Some(ServerEvent::PermissionAsked(SessionPermissionRequest {
+                id: "per_1".to_string(),
+                session_id: "ses_1".to_string(),
+                tool: "bash".to_string(),
+                title: "rm -rf build".to_string(),
+                arguments: vec!["rm -rf build".to_string()],
+            }))
+        );
+        assert_eq!(
+            parse_server_event(
+                r#"{"type":"permission.replied","properties":{"sessionID":"ses_1","permissionID":"per_1","response":"once"}}"#
+            ),
+            Some(ServerEvent::PermissionReplied {
+                session_id: "ses_1".to_string(),
+                permission_id: "per_1".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_server_event(r#"{"type":"lsp.updated","properties":{}}"#),
+            Some(ServerEvent::Other)
+        );
+        assert_eq!(parse_server_event("not json"), None);
+    }
+}
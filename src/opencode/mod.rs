@@ -8,16 +8,25 @@ use regex::Regex;
 use reqwest::StatusCode;
 use reqwest::blocking::Client;
 use serde::Deserialize;
+use tokio::runtime::{Handle, RuntimeFlavor};
 use urlencoding::encode;
 
 use crate::tmux::tmux_get_pane_pid;
-use crate::types::SessionStatus;
+use crate::types::{AgentSelection, SessionStatus, SessionStatusError};
 
+pub mod endpoint;
+pub mod events;
+pub mod pane;
 pub mod server;
 pub mod status_server;
 
 pub use crate::types::SessionState as Status;
-pub use server::{OpenCodeServerManager, OpenCodeServerState, ensure_server_ready};
+pub use endpoint::ServerEndpoint;
+pub use pane::PaneStatusProvider;
+pub use server::{
+    OpenCodeServerManager, OpenCodeServerState, ensure_server_ready, server_is_healthy,
+    wait_for_server_ready,
+};
 pub use status_server::ServerStatusProvider;
 
 pub const DEFAULT_SERVER_URL: &str = "http://127.0.0.1:4096";
@@ -74,12 +83,22 @@ fn session_id_regex() -> Result<&'static Regex> {
         .map_err(|err| anyhow!("failed to compile OpenCode session-id matcher regex: {err}"))
 }
 
-pub fn opencode_launch(working_dir: &Path, session_id: Option<String>) -> Result<String> {
+pub fn opencode_launch(
+    working_dir: &Path,
+    session_id: Option<String>,
+    selection: &AgentSelection,
+) -> Result<String> {
     let binary = opencode_binary();
     ensure_opencode_available(&binary)?;
 
     let mut cmd = Command::new(&binary);
     cmd.current_dir(working_dir);
+    if let Some(model) = selection.model.as_deref() {
+        cmd.args(["-m", model]);
+    }
+    if let Some(agent) = selection.agent.as_deref() {
+        cmd.args(["--agent", agent]);
+    }
 
     if let Some(existing) = session_id {
         let output = cmd
@@ -179,11 +198,11 @@ pub fn opencode_is_running_in_session(tmux_session_name: &str) -> bool {
     false
 }
 
-fn opencode_binary() -> String {
+pub fn opencode_binary() -> String {
     env::var("OPENCODE_BIN").unwrap_or_else(|_| "opencode".to_string())
 }
 
-fn ensure_opencode_available(binary: &str) -> Result<()> {
+pub fn ensure_opencode_available(binary: &str) -> Result<()> {
     let output = Command::new(binary).arg("--version").output();
     match output {
         Ok(output) if output.status.success() => Ok(()),
@@ -199,23 +218,72 @@ fn ensure_opencode_available(binary: &str) -> Result<()> {
     }
 }
 
-pub fn opencode_attach_command(session_id: Option<&str>, worktree_dir: Option<&str>) -> String {
-    let mut parts = vec![format!("opencode attach {DEFAULT_SERVER_URL}")];
+/// Runs an OpenCode server request from synchronous code, reusing the
+/// surrounding multi-thread runtime when there is one.
+pub fn block_on_opencode<T>(
+    future: impl Future<Output = Result<T, SessionStatusError>>,
+) -> Result<T> {
+    let result = match Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(|| handle.block_on(future))
+        }
+        _ => tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .context("failed to start runtime for OpenCode request")?
+            .block_on(future),
+    };
+    result.map_err(|err| anyhow::anyhow!("{} ({})", err.message, err.code))
+}
+
+pub fn opencode_attach_command(
+    session_id: Option<&str>,
+    worktree_dir: Option<&str>,
+    selection: &AgentSelection,
+) -> String {
+    let mut parts = vec![format!(
+        "opencode attach {}",
+        ServerEndpoint::current().base_url()
+    )];
 
     if let Some(dir) = worktree_dir {
-        parts.push(format!("--dir {dir}"));
+        parts.push(format!("--dir {}", shell_quote(dir)));
     }
     if let Some(id) = session_id {
-        parts.push(format!("--session {id}"));
+        parts.push(format!("--session {}", shell_quote(id)));
+    }
+    if let Some(model) = selection.model.as_deref() {
+        parts.push(format!("--model {}", shell_quote(model)));
+    }
+    if let Some(agent) = selection.agent.as_deref() {
+        parts.push(format!("--agent {}", shell_quote(agent)));
     }
 
     parts.join(" ")
 }
 
+/// Single-quotes `value` for the shell tmux runs the command in, leaving
+/// plain words as they are.
+fn shell_quote(value: &str) -> String {
+    let plain = !value.is_empty()
+        && value.chars().all(|ch| {
+            ch.is_ascii_alphanumeric()
+                || matches!(ch, '.' | '_' | ':' | '/' | '-' | '=' | '@' | '+' | ',')
+        });
+    if plain {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
 pub fn opencode_open_in_web(session_id: &str, worktree_path: &str) -> Result<()> {
     let encoded_path =
         base64::Engine::encode(&base64::engine::general_purpose::STANDARD, worktree_path);
-    let url = format!("{DEFAULT_SERVER_URL}/{encoded_path}/session/{session_id}");
+    let url = format!(
+        "{}/{encoded_path}/session/{session_id}",
+        ServerEndpoint::current().base_url()
+    );
     open::that(&url).with_context(|| format!("failed to open browser for session {session_id}"))?;
     Ok(())
 }
@@ -227,19 +295,19 @@ pub fn opencode_query_session_by_dir(working_dir: &Path) -> Result<Option<String
         id: String,
     }
 
-    let config = status_server::ServerStatusConfig::default();
+    let endpoint = ServerEndpoint::current();
     let dir_str = working_dir.to_string_lossy();
     let session_url = format!(
-        "http://{}:{}/session?directory={}",
-        config.hostname,
-        config.port,
+        "{}/session?directory={}",
+        endpoint.base_url(),
         encode(&dir_str)
     );
 
     tracing::debug!("Querying OpenCode session API: {session_url}");
 
     let client = Client::builder()
-        .timeout(config.request_timeout)
+        .timeout(endpoint.request_timeout)
+        .default_headers(endpoint::auth_headers(endpoint.auth.as_ref()))
         .build()
         .context("failed to build OpenCode session lookup client")?;
 
@@ -496,7 +564,11 @@ mod tests {
         let fixture = FakeOpenCode::new("with-session")?;
         let session_id = Uuid::new_v4().to_string();
 
-        let returned = opencode_launch(fixture.temp.path(), Some(session_id.clone()))?;
+        let returned = opencode_launch(
+            fixture.temp.path(),
+            Some(session_id.clone()),
+            &AgentSelection::default(),
+        )?;
         assert_eq!(returned, session_id);
 
         let logged = fs::read_to_string(fixture.log_path())?;
@@ -509,11 +581,23 @@ mod tests {
     #[test]
     fn test_launch_without_session_extracts_uuid() -> Result<()> {
         let fixture = FakeOpenCode::new("new-session")?;
-        let generated = opencode_launch(fixture.temp.path(), None)?;
+        let generated = opencode_launch(fixture.temp.path(), None, &AgentSelection::default())?;
         assert!(Uuid::parse_str(&generated).is_ok());
         Ok(())
     }
 
+    #[test]
+    fn test_launch_passes_model_and_agent() -> Result<()> {
+        let fixture = FakeOpenCode::new("launch-model")?;
+        let selection = AgentSelection::parse("anthropic/claude-sonnet-4", "plan")
+            .map_err(|err| anyhow!(err))?;
+        opencode_launch(fixture.temp.path(), None, &selection)?;
+
+        let logged = fs::read_to_string(fixture.log_path())?;
+        assert!(logged.contains("-m anthropic/claude-sonnet-4 --agent plan"));
+        Ok(())
+    }
+
     #[test]
     fn test_resume_session_runs_with_context() -> Result<()> {
         let fixture = FakeOpenCode::new("resume")?;
@@ -529,7 +613,9 @@ mod tests {
 
     #[test]
     fn test_attach_command_without_session_includes_dir() {
-        let command = opencode_attach_command(None, Some("/tmp/worktree"));
+        let _guard = TEST_ENV_LOCK.lock().expect("test env mutex should lock");
+        let command =
+            opencode_attach_command(None, Some("/tmp/worktree"), &AgentSelection::default());
         assert_eq!(
             command,
             "opencode attach http://127.0.0.1:4096 --dir /tmp/worktree"
@@ -538,7 +624,12 @@ mod tests {
 
     #[test]
     fn test_attach_command_with_session_includes_dir() {
-        let command = opencode_attach_command(Some("sid-123"), Some("/tmp/worktree"));
+        let _guard = TEST_ENV_LOCK.lock().expect("test env mutex should lock");
+        let command = opencode_attach_command(
+            Some("sid-123"),
+            Some("/tmp/worktree"),
+            &AgentSelection::default(),
+        );
         assert_eq!(
             command,
             "opencode attach http://127.0.0.1:4096 --dir /tmp/worktree --session sid-123"
@@ -547,7 +638,8 @@ mod tests {
 
     #[test]
     fn test_attach_command_with_session_no_dir() {
-        let command = opencode_attach_command(Some("sid-123"), None);
+        let _guard = TEST_ENV_LOCK.lock().expect("test env mutex should lock");
+        let command = opencode_attach_command(Some("sid-123"), None, &AgentSelection::default());
         assert_eq!(
             command,
             "opencode attach http://127.0.0.1:4096 --session sid-123"
@@ -556,10 +648,38 @@ mod tests {
 
     #[test]
     fn test_attach_command_without_session_or_dir_uses_attach_base() {
-        let command = opencode_attach_command(None, None);
+        let _guard = TEST_ENV_LOCK.lock().expect("test env mutex should lock");
+        let command = opencode_attach_command(None, None, &AgentSelection::default());
         assert_eq!(command, "opencode attach http://127.0.0.1:4096");
     }
 
+    #[test]
+    fn test_attach_command_quotes_shell_metacharacters() {
+        let selection = AgentSelection {
+            model: Some("a/b;curl x|sh".to_string()),
+            agent: Some("$(id)".to_string()),
+        };
+        let command = opencode_attach_command(None, Some("/tmp/it's here"), &selection);
+
+        assert!(
+            command.ends_with("--dir '/tmp/it'\\''s here' --model 'a/b;curl x|sh' --agent '$(id)'")
+        );
+    }
+
+    #[test]
+    fn test_attach_command_appends_model_and_agent() {
+        let _guard = TEST_ENV_LOCK.lock().expect("test env mutex should lock");
+        let selection = AgentSelection {
+            model: Some("openai/gpt-5".to_string()),
+            agent: Some("build".to_string()),
+        };
+        let command = opencode_attach_command(Some("sid-123"), None, &selection);
+        assert_eq!(
+            command,
+            "opencode attach http://127.0.0.1:4096 --session sid-123 --model openai/gpt-5 --agent build"
+        );
+    }
+
     struct FakeOpenCode {
         temp: tempfile::TempDir,
         _guard: MutexGuard<'static, ()>,
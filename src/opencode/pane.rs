@@ -0,0 +1,384 @@
+//! Status fallback for when the OpenCode server cannot be reached.
+//!
+//! The task's tmux pane is read with `capture-pane` and its process tree is
+//! walked through `/proc`. If no agent process is left the task is idle;
+//! otherwise the bottom of the screen is matched against the `[pane_status]`
+//! regexes from `settings.toml`, registered at startup with [`configure`].
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Mutex, RwLock};
+use std::time::SystemTime;
+
+use regex::Regex;
+
+use super::StatusProvider;
+use crate::settings::PaneStatusSettings;
+use crate::tmux::{tmux_capture_pane, tmux_get_pane_pid};
+use crate::types::{SessionState, SessionStatus, SessionStatusError, SessionStatusSource};
+
+/// OpenCode's footer while a turn runs ("esc interrupt") and its spinners.
+pub const DEFAULT_RUNNING_PATTERN: &str =
+    r"(?i)esc\s+(to\s+)?interrupt|working\.\.\.|thinking\.\.\.|generating\.\.\.";
+/// Permission prompts and plain yes/no questions.
+pub const DEFAULT_AWAITING_INPUT_PATTERN: &str =
+    r"(?i)permission required|allow\s+(once|always)|\[y/n\]|press enter to continue";
+pub const DEFAULT_AGENT_PROCESS_PATTERN: &str = r"(?i)\bopencode\b";
+/// Only the bottom of the screen is matched, so stale scrollback text does
+/// not keep a finished task running.
+const PANE_TAIL_LINES: usize = 15;
+
+static CONFIGURED: RwLock<Option<PaneStatusSettings>> = RwLock::new(None);
+
+/// Registers the `[pane_status]` settings used by every later pane lookup.
+pub fn configure(settings: PaneStatusSettings) {
+    match CONFIGURED.write() {
+        Ok(mut configured) => *configured = Some(settings),
+        Err(poisoned) => *poisoned.into_inner() = Some(settings),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PanePatterns {
+    pub running: Regex,
+    pub awaiting_input: Regex,
+    pub agent_process: Regex,
+}
+
+impl Default for PanePatterns {
+    fn default() -> Self {
+        Self::from_settings(&PaneStatusSettings::default())
+    }
+}
+
+impl PanePatterns {
+    /// Configured patterns, falling back to the defaults for unset or
+    /// invalid ones.
+    pub fn from_settings(settings: &PaneStatusSettings) -> Self {
+        let compile = |configured: &Option<String>, default: &str| {
+            configured
+                .as_deref()
+                .and_then(|pattern| Regex::new(pattern).ok())
+                .unwrap_or_else(|| Regex::new(default).expect("default pane pattern compiles"))
+        };
+        Self {
+            running: compile(&settings.running_pattern, DEFAULT_RUNNING_PATTERN),
+            awaiting_input: compile(
+                &settings.awaiting_input_pattern,
+                DEFAULT_AWAITING_INPUT_PATTERN,
+            ),
+            agent_process: compile(
+                &settings.agent_process_pattern,
+                DEFAULT_AGENT_PROCESS_PATTERN,
+            ),
+        }
+    }
+
+    fn configured() -> Self {
+        let settings = match CONFIGURED.read() {
+            Ok(configured) => configured.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        };
+        Self::from_settings(&settings.unwrap_or_default())
+    }
+}
+
+/// Infers a task's status from its tmux pane; keyed by tmux session name.
+///
+/// Lookups block on `tmux` and `/proc`. The process table is read once and
+/// shared by every lookup until [`PaneStatusProvider::begin_cycle`].
+#[derive(Debug)]
+pub struct PaneStatusProvider {
+    patterns: PanePatterns,
+    capture: fn(&str) -> Option<String>,
+    pane_pid: fn(&str) -> Option<u32>,
+    read_processes: fn() -> Option<ProcessTable>,
+    /// `None` until the first lookup of the cycle reads the table.
+    processes: Mutex<Option<Option<ProcessTable>>>,
+}
+
+impl Default for PaneStatusProvider {
+    fn default() -> Self {
+        Self::new(
+            PanePatterns::configured(),
+            tmux_capture_pane,
+            tmux_get_pane_pid,
+            || ProcessTable::read(Path::new("/proc")),
+        )
+    }
+}
+
+impl PaneStatusProvider {
+    pub fn new(
+        patterns: PanePatterns,
+        capture: fn(&str) -> Option<String>,
+        pane_pid: fn(&str) -> Option<u32>,
+        read_processes: fn() -> Option<ProcessTable>,
+    ) -> Self {
+        Self {
+            patterns,
+            capture,
+            pane_pid,
+            read_processes,
+            processes: Mutex::new(None),
+        }
+    }
+
+    /// Forgets the process table so the next lookup reads a fresh one.
+    pub fn begin_cycle(&self) {
+        if let Ok(mut processes) = self.processes.lock() {
+            *processes = None;
+        }
+    }
+
+    fn process_commands(&self, tmux_session_name: &str) -> Option<Vec<String>> {
+        let pane_pid = (self.pane_pid)(tmux_session_name)?;
+        let mut processes = self.processes.lock().ok()?;
+        processes
+            .get_or_insert_with(self.read_processes)
+            .as_ref()
+            .map(|table| table.tree_commands(pane_pid))
+    }
+}
+
+impl StatusProvider for PaneStatusProvider {
+    fn get_status(&self, tmux_session_name: &str) -> SessionStatus {
+        let fetched_at = SystemTime::now();
+        let Some(screen) = (self.capture)(tmux_session_name) else {
+            return SessionStatus {
+                state: SessionState::Idle,
+                source: SessionStatusSource::Pane,
+                fetched_at,
+                error: Some(SessionStatusError {
+                    code: "PANE_UNAVAILABLE".to_string(),
+                    message: format!("tmux session {tmux_session_name} has no pane to read"),
+                }),
+            };
+        };
+        let commands = self.process_commands(tmux_session_name);
+
+        SessionStatus {
+            state: infer_pane_state(&self.patterns, &screen, commands.as_deref()),
+            source: SessionStatusSource::Pane,
+            fetched_at,
+            error: None,
+        }
+    }
+}
+
+/// `commands` is `None` when the process tree could not be read, in which
+/// case the screen alone decides.
+pub fn infer_pane_state(
+    patterns: &PanePatterns,
+    screen: &str,
+    commands: Option<&[String]>,
+) -> SessionState {
+    if let Some(commands) = commands
+        && !commands
+            .iter()
+            .any(|command| patterns.agent_process.is_match(command))
+    {
+        return SessionState::Idle;
+    }
+
+    let lines: Vec<&str> = screen
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    let tail = lines[lines.len().saturating_sub(PANE_TAIL_LINES)..].join("\n");
+
+    if patterns.awaiting_input.is_match(&tail) {
+        SessionState::AwaitingInput
+    } else if patterns.running.is_match(&tail) {
+        SessionState::Running
+    } else {
+        SessionState::Idle
+    }
+}
+
+/// Parent and command line of every process.
+#[derive(Debug, Clone, Default)]
+pub struct ProcessTable {
+    children: HashMap<u32, Vec<u32>>,
+    commands: HashMap<u32, String>,
+}
+
+impl ProcessTable {
+    /// Builds the table from `(pid, parent pid, command line)` entries.
+    pub fn new(processes: impl IntoIterator<Item = (u32, u32, String)>) -> Self {
+        let mut table = Self::default();
+        for (pid, parent, command) in processes {
+            table.children.entry(parent).or_default().push(pid);
+            if !command.is_empty() {
+                table.commands.insert(pid, command);
+            }
+        }
+        table
+    }
+
+    /// Reads a `/proc`-style directory. `None` when it cannot be listed.
+    pub fn read(proc_root: &Path) -> Option<Self> {
+        let processes = fs::read_dir(proc_root)
+            .ok()?
+            .flatten()
+            .filter_map(|entry| {
+                let pid = entry.file_name().to_str()?.parse::<u32>().ok()?;
+                let parent = parent_pid(&fs::read_to_string(entry.path().join("stat")).ok()?)?;
+                let command = fs::read(entry.path().join("cmdline"))
+                    .map(|raw| {
+                        raw.split(|byte| *byte == 0)
+                            .filter(|arg| !arg.is_empty())
+                            .map(|arg| String::from_utf8_lossy(arg).into_owned())
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    })
+                    .unwrap_or_default();
+                Some((pid, parent, command))
+            })
+            .collect::<Vec<_>>();
+        Some(Self::new(processes))
+    }
+
+    /// Command lines of `root_pid` and all of its descendants.
+    pub fn tree_commands(&self, root_pid: u32) -> Vec<String> {
+        let mut commands = Vec::new();
+        let mut pending = vec![root_pid];
+        while let Some(pid) = pending.pop() {
+            if let Some(command) = self.commands.get(&pid) {
+                commands.push(command.clone());
+            }
+            if let Some(descendants) = self.children.get(&pid) {
+                pending.extend(descendants);
+            }
+        }
+        commands
+    }
+}
+
+/// The parent pid from `/proc/<pid>/stat`. The command name in parentheses
+/// may itself contain spaces or parentheses, so fields are read after the
+/// last `)`.
+fn parent_pid(stat: &str) -> Option<u32> {
+    let (_, fields) = stat.rsplit_once(')')?;
+    fields.split_whitespace().nth(1)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::{PanePatterns, PaneStatusProvider, ProcessTable, infer_pane_state, parent_pid};
+    use crate::opencode::StatusProvider;
+    use crate::settings::PaneStatusSettings;
+    use crate::types::{SessionState, SessionStatusSource};
+
+    fn commands(commands: &[&str]) -> Vec<String> {
+        commands.iter().map(|command| command.to_string()).collect()
+    }
+
+    #[test]
+    fn infer_pane_state_matches_bottom_of_screen() {
+        let patterns = PanePatterns::default();
+        let agent = commands(&["zsh", "opencode attach http://127.0.0.1:4096"]);
+
+        let running = "> fix the build\n\n  Working...\n  esc interrupt\n";
+        assert_eq!(
+            infer_pane_state(&patterns, running, Some(&agent)),
+            SessionState::Running
+        );
+
+        let asking =
+            "  Working...\nPermission required: bash\n  Allow once  Allow always  Reject\n";
+        assert_eq!(
+            infer_pane_state(&patterns, asking, Some(&agent)),
+            SessionState::AwaitingInput
+        );
+
+        let stale = format!("  Working...\n{}", "done\n".repeat(20));
+        assert_eq!(
+            infer_pane_state(&patterns, &stale, None),
+            SessionState::Idle
+        );
+    }
+
+    #[test]
+    fn infer_pane_state_is_idle_once_the_agent_process_exits() {
+        let patterns = PanePatterns::default();
+        assert_eq!(
+            infer_pane_state(&patterns, "esc interrupt", Some(&commands(&["zsh"]))),
+            SessionState::Idle
+        );
+
+        let custom = PanePatterns::from_settings(&PaneStatusSettings {
+            running_pattern: Some("(?i)busy".to_string()),
+            awaiting_input_pattern: None,
+            agent_process_pattern: Some("aider".to_string()),
+        });
+        assert_eq!(
+            infer_pane_state(&custom, "BUSY", Some(&commands(&["python -m aider"]))),
+            SessionState::Running
+        );
+    }
+
+    static PROCESS_READS: AtomicUsize = AtomicUsize::new(0);
+
+    #[test]
+    fn pane_provider_reports_unreadable_panes_as_errors() {
+        let provider = PaneStatusProvider::new(
+            PanePatterns::default(),
+            |name| (name == "ok-live").then(|| "esc interrupt".to_string()),
+            |_| Some(1),
+            || {
+                PROCESS_READS.fetch_add(1, Ordering::SeqCst);
+                Some(ProcessTable::new([(1, 0, "opencode".to_string())]))
+            },
+        );
+
+        let live = provider.get_status("ok-live");
+        assert_eq!(live.state, SessionState::Running);
+        assert_eq!(live.source, SessionStatusSource::Pane);
+        assert_eq!(live.error, None);
+
+        let missing = provider.get_status("ok-gone");
+        assert_eq!(missing.state, SessionState::Idle);
+        assert_eq!(
+            missing.error.map(|error| error.code).as_deref(),
+            Some("PANE_UNAVAILABLE")
+        );
+
+        provider.get_status("ok-live");
+        assert_eq!(PROCESS_READS.load(Ordering::SeqCst), 1);
+        provider.begin_cycle();
+        provider.get_status("ok-live");
+        assert_eq!(PROCESS_READS.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn process_table_walks_descendants_of_the_pane() {
+        let proc_root = tempfile::TempDir::new().expect("temp proc dir");
+        let process = |pid: u32, stat: &str, cmdline: &[u8]| {
+            let dir = proc_root.path().join(pid.to_string());
+            fs::create_dir(&dir).unwrap();
+            fs::write(dir.join("stat"), stat).unwrap();
+            fs::write(dir.join("cmdline"), cmdline).unwrap();
+        };
+        process(100, "100 (zsh) S 1 100 100", b"-zsh\0");
+        process(
+            200,
+            "200 (node (main)) S 100 200 100",
+            b"node\0/usr/bin/opencode\0attach\0",
+        );
+        process(300, "300 (sleep) S 1 300 300", b"sleep\x0060\0");
+        fs::create_dir(proc_root.path().join("self")).unwrap();
+
+        let table = ProcessTable::read(proc_root.path()).expect("proc readable");
+        let mut tree = table.tree_commands(100);
+        tree.sort();
+        assert_eq!(tree, vec!["-zsh", "node /usr/bin/opencode attach"]);
+
+        assert_eq!(parent_pid("200 (node (main)) S 100 200"), Some(100));
+        assert!(ProcessTable::read(&proc_root.path().join("missing")).is_none());
+    }
+}
@@ -2,8 +2,11 @@ use reqwest::StatusCode;
 use reqwest::blocking::Client;
 use std::process::{Command, Stdio};
 use std::sync::{Arc, Mutex};
+use std::thread;
 use std::time::Duration;
 
+use super::endpoint::{ServerAuth, ServerEndpoint, auth_headers};
+
 #[derive(Debug, Clone, Default, Eq, PartialEq)]
 pub enum OpenCodeServerState {
     #[default]
@@ -45,6 +48,7 @@ pub struct ServerConfig {
     pub hostname: String,
     pub port: u16,
     pub request_timeout: Duration,
+    pub auth: Option<ServerAuth>,
     pub startup_timeout: Duration,
     pub initial_backoff: Duration,
     pub max_backoff: Duration,
@@ -52,10 +56,12 @@ pub struct ServerConfig {
 
 impl Default for ServerConfig {
     fn default() -> Self {
+        let endpoint = ServerEndpoint::current();
         Self {
-            hostname: "127.0.0.1".to_string(),
-            port: 4096,
-            request_timeout: Duration::from_millis(300),
+            hostname: endpoint.hostname,
+            port: endpoint.port,
+            request_timeout: endpoint.request_timeout,
+            auth: endpoint.auth,
             startup_timeout: Duration::from_secs(5),
             initial_backoff: Duration::from_millis(100),
             max_backoff: Duration::from_millis(800),
@@ -67,6 +73,30 @@ pub fn ensure_server_ready() -> OpenCodeServerManager {
     ensure_server_ready_with_config(ServerConfig::default())
 }
 
+/// Attaches to or starts the configured server and blocks until it answers
+/// its health check or the startup timeout passes.
+pub fn wait_for_server_ready() -> Result<(), String> {
+    let config = ServerConfig::default();
+    // The bootstrap uses blocking HTTP and its own sleep runtime, so it runs on
+    // a plain thread rather than on whatever async runtime the caller is in.
+    let state = thread::scope(|scope| {
+        scope
+            .spawn(|| bootstrap_server(&RealServerRuntime, &config))
+            .join()
+    })
+    .unwrap_or_else(|_| OpenCodeServerState::Failed("OpenCode server bootstrap panicked".into()));
+    match state {
+        OpenCodeServerState::ReadyAttached | OpenCodeServerState::ReadySpawned => Ok(()),
+        OpenCodeServerState::Failed(err) => Err(err),
+        OpenCodeServerState::Starting => Err("OpenCode server did not start".to_string()),
+    }
+}
+
+/// Whether the configured server answers its health check; never starts one.
+pub fn server_is_healthy() -> bool {
+    check_server_health(&ServerConfig::default())
+}
+
 fn ensure_server_ready_with_config(config: ServerConfig) -> OpenCodeServerManager {
     let manager = OpenCodeServerManager::new();
     let state = Arc::clone(&manager.state);
@@ -176,7 +206,11 @@ fn bootstrap_server(runtime: &impl ServerRuntime, config: &ServerConfig) -> Open
 }
 
 fn check_server_health(config: &ServerConfig) -> bool {
-    let client = match Client::builder().timeout(config.request_timeout).build() {
+    let client = match Client::builder()
+        .timeout(config.request_timeout)
+        .default_headers(auth_headers(config.auth.as_ref()))
+        .build()
+    {
         Ok(client) => client,
         Err(_) => return false,
     };
@@ -208,6 +242,11 @@ fn spawn_opencode_server(binary: &str, config: &ServerConfig) -> Result<(), Stri
     if let Some(home_dir) = dirs::home_dir() {
         cmd.current_dir(home_dir);
     }
+    // A server we start ourselves is protected by the same password we log
+    // in with.
+    if let Some(auth) = &config.auth {
+        cmd.envs(auth.opencode_env());
+    }
 
     cmd.args([
         "serve",
@@ -1,15 +1,19 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::time::{Duration, SystemTime};
 
 use reqwest::Client;
 use reqwest::StatusCode;
 use serde::Deserialize;
-use serde_json::Value;
+use serde_json::{Value, json};
 use urlencoding::encode;
 
+use super::endpoint::{ServerAuth, ServerEndpoint, auth_headers};
+use super::events::ServerEventStream;
+use super::{StatusProvider, block_on_opencode};
 use crate::types::{
-    SessionMessageItem, SessionState, SessionStatus, SessionStatusError, SessionStatusSource,
-    SessionTodoItem,
+    AgentSelection, ModelUsage, SessionMessageItem, SessionMessagePart, SessionPermissionRequest,
+    SessionState, SessionStatus, SessionStatusError, SessionStatusSource, SessionTodoItem,
+    SessionToolCall, TokenUsage, ToolCallStatus,
 };
 
 #[derive(Debug, Clone)]
@@ -34,6 +38,32 @@ pub struct SessionRecord {
     pub parent_session_id: Option<String>,
 }
 
+/// A session's messages plus the token usage its assistant messages
+/// reported, summed per model.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionMessageLog {
+    pub messages: Vec<SessionMessageItem>,
+    pub usage: Vec<ModelUsage>,
+}
+
+/// How a permission request is answered.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum PermissionReply {
+    Once,
+    Always,
+    Reject,
+}
+
+impl PermissionReply {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PermissionReply::Once => "once",
+            PermissionReply::Always => "always",
+            PermissionReply::Reject => "reject",
+        }
+    }
+}
+
 impl SessionStatusMatch {
     pub fn is_root_session(&self) -> bool {
         self.parent_session_id.is_none()
@@ -45,18 +75,17 @@ pub struct ServerStatusConfig {
     pub hostname: String,
     pub port: u16,
     pub request_timeout: Duration,
+    pub auth: Option<ServerAuth>,
 }
 
 impl Default for ServerStatusConfig {
     fn default() -> Self {
-        let port = std::env::var("OPENCODE_KANBAN_STATUS_PORT")
-            .ok()
-            .and_then(|v| v.parse::<u16>().ok())
-            .unwrap_or(4096);
+        let endpoint = ServerEndpoint::current();
         Self {
-            hostname: "127.0.0.1".to_string(),
-            port,
-            request_timeout: Duration::from_millis(300),
+            hostname: endpoint.hostname,
+            port: endpoint.port,
+            request_timeout: endpoint.request_timeout,
+            auth: endpoint.auth,
         }
     }
 }
@@ -67,9 +96,64 @@ impl Default for ServerStatusProvider {
     }
 }
 
+/// Blocking status lookups for callers outside the async poller. A session
+/// the server does not list as busy is idle.
+impl StatusProvider for ServerStatusProvider {
+    fn get_status(&self, session_id: &str) -> SessionStatus {
+        self.list_statuses(&[session_id.to_string()])
+            .pop()
+            .map(|(_, status)| status)
+            .unwrap_or_else(|| idle_server_status(SystemTime::now()))
+    }
+
+    fn list_statuses(&self, session_ids: &[String]) -> Vec<(String, SessionStatus)> {
+        let fetched_at = SystemTime::now();
+        let statuses =
+            block_on_opencode(async { Ok(self.fetch_all_statuses(fetched_at, None).await) })
+                .unwrap_or_else(|err| {
+                    Err(SessionStatusError {
+                        code: "SERVER_CLIENT_INIT_FAILED".to_string(),
+                        message: format!("{err:#}"),
+                    })
+                });
+
+        session_ids
+            .iter()
+            .map(|session_id| {
+                let status = match &statuses {
+                    Ok(statuses) => statuses
+                        .get(session_id)
+                        .cloned()
+                        .unwrap_or_else(|| idle_server_status(fetched_at)),
+                    Err(err) => SessionStatus {
+                        state: SessionState::Idle,
+                        source: SessionStatusSource::None,
+                        fetched_at,
+                        error: Some(err.clone()),
+                    },
+                };
+                (session_id.clone(), status)
+            })
+            .collect()
+    }
+}
+
+fn idle_server_status(fetched_at: SystemTime) -> SessionStatus {
+    SessionStatus {
+        state: SessionState::Idle,
+        source: SessionStatusSource::Server,
+        fetched_at,
+        error: None,
+    }
+}
+
 impl ServerStatusProvider {
     pub fn new(config: ServerStatusConfig) -> Self {
-        match Client::builder().timeout(config.request_timeout).build() {
+        match Client::builder()
+            .timeout(config.request_timeout)
+            .default_headers(auth_headers(config.auth.as_ref()))
+            .build()
+        {
             Ok(client) => Self {
                 config,
                 client: Some(client),
@@ -108,6 +192,10 @@ impl ServerStatusProvider {
         }
     }
 
+    fn session_id_url(&self, session_id: &str) -> String {
+        format!("{}/session/{}", self.base_url(), encode(session_id))
+    }
+
     fn session_status_url(&self) -> String {
         format!("{}/session/status", self.base_url())
     }
@@ -120,6 +208,37 @@ impl ServerStatusProvider {
         format!("{}/session/{}/message", self.base_url(), encode(session_id))
     }
 
+    fn session_fork_url(&self, session_id: &str, directory: &str) -> String {
+        format!(
+            "{}/session/{}/fork?directory={}",
+            self.base_url(),
+            encode(session_id),
+            encode(directory)
+        )
+    }
+
+    fn session_route_url(&self, session_id: &str, route: &str) -> String {
+        format!("{}/session/{}/{route}", self.base_url(), encode(session_id))
+    }
+
+    fn permission_url(&self, directory: Option<&str>) -> String {
+        let base = format!("{}/permission", self.base_url());
+        if let Some(directory) = directory {
+            format!("{base}?directory={}", encode(directory))
+        } else {
+            base
+        }
+    }
+
+    fn session_permission_url(&self, session_id: &str, permission_id: &str) -> String {
+        format!(
+            "{}/session/{}/permissions/{}",
+            self.base_url(),
+            encode(session_id),
+            encode(permission_id)
+        )
+    }
+
     pub async fn list_all_sessions(
         &self,
         directory: Option<&str>,
@@ -261,6 +380,17 @@ impl ServerStatusProvider {
         &self,
         session_id: &str,
     ) -> Result<Vec<SessionMessageItem>, SessionStatusError> {
+        self.fetch_session_message_log(session_id)
+            .await
+            .map(|log| log.messages)
+    }
+
+    /// Fetches a session's messages along with the token usage its assistant
+    /// messages reported.
+    pub async fn fetch_session_message_log(
+        &self,
+        session_id: &str,
+    ) -> Result<SessionMessageLog, SessionStatusError> {
         let response = self
             .client()?
             .get(self.session_message_url(session_id))
@@ -293,6 +423,290 @@ impl ServerStatusProvider {
 
         parse_session_message_body(&body)
     }
+
+    /// Lists the permission requests still waiting for an answer. Servers
+    /// without `/permission` only announce them as events; they answer
+    /// `Ok(None)` so callers keep whatever the events reported.
+    pub async fn list_pending_permissions(
+        &self,
+        directory: Option<&str>,
+    ) -> Result<Option<Vec<SessionPermissionRequest>>, SessionStatusError> {
+        let response = self
+            .client()?
+            .get(self.permission_url(directory))
+            .send()
+            .await
+            .map_err(|err| map_reqwest_error(err, "SERVER_CONNECT_FAILED"))?;
+
+        let status_code = response.status();
+        if status_code == StatusCode::UNAUTHORIZED {
+            return Err(SessionStatusError {
+                code: "SERVER_AUTH_ERROR".to_string(),
+                message: "OpenCode server rejected permission fetch with HTTP 401".to_string(),
+            });
+        }
+        if status_code == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if status_code != StatusCode::OK {
+            return Err(SessionStatusError {
+                code: "SERVER_HTTP_ERROR".to_string(),
+                message: format!("OpenCode server returned HTTP {status_code} for /permission"),
+            });
+        }
+
+        let body = response
+            .text()
+            .await
+            .map_err(|err| map_reqwest_error(err, "SERVER_READ_FAILED"))?;
+
+        parse_permission_body(&body).map(Some)
+    }
+
+    pub async fn reply_permission(
+        &self,
+        session_id: &str,
+        permission_id: &str,
+        reply: PermissionReply,
+    ) -> Result<(), SessionStatusError> {
+        let response = self
+            .client()?
+            .post(self.session_permission_url(session_id, permission_id))
+            .json(&json!({ "response": reply.as_str() }))
+            .send()
+            .await
+            .map_err(|err| map_reqwest_error(err, "SERVER_CONNECT_FAILED"))?;
+
+        check_request_status(
+            response.status(),
+            &format!("POST /session/{session_id}/permissions/{permission_id}"),
+            Some(SessionStatusError {
+                code: "PERMISSION_NOT_FOUND".to_string(),
+                message: format!(
+                    "permission request {permission_id} is no longer pending in session {session_id}"
+                ),
+            }),
+        )
+    }
+
+    /// Opens the server's event stream. Newer servers publish every
+    /// directory's events on `/global/event`; older ones only have `/event`.
+    pub async fn open_event_stream(&self) -> Result<ServerEventStream, SessionStatusError> {
+        let client = Client::builder()
+            .connect_timeout(self.config.request_timeout)
+            .default_headers(auth_headers(self.config.auth.as_ref()))
+            .build()
+            .map_err(|err| SessionStatusError {
+                code: "SERVER_CLIENT_INIT_FAILED".to_string(),
+                message: format!("failed to build event stream client: {err}"),
+            })?;
+
+        let mut last_status = StatusCode::NOT_FOUND;
+        for path in ["/global/event", "/event"] {
+            let response = client
+                .get(format!("{}{path}", self.base_url()))
+                .header(reqwest::header::ACCEPT, "text/event-stream")
+                .send()
+                .await
+                .map_err(|err| map_reqwest_error(err, "SERVER_CONNECT_FAILED"))?;
+
+            let status_code = response.status();
+            if status_code == StatusCode::OK {
+                return Ok(ServerEventStream::new(response));
+            }
+            if status_code == StatusCode::UNAUTHORIZED {
+                return Err(SessionStatusError {
+                    code: "SERVER_AUTH_ERROR".to_string(),
+                    message: "OpenCode server rejected event stream with HTTP 401".to_string(),
+                });
+            }
+            last_status = status_code;
+            if status_code != StatusCode::NOT_FOUND {
+                break;
+            }
+        }
+
+        Err(SessionStatusError {
+            code: "SERVER_HTTP_ERROR".to_string(),
+            message: format!("OpenCode server returned HTTP {last_status} for its event stream"),
+        })
+    }
+
+    pub async fn send_session_prompt(
+        &self,
+        session_id: &str,
+        text: &str,
+        selection: &AgentSelection,
+    ) -> Result<(), SessionStatusError> {
+        self.post_session_route(
+            session_id,
+            "prompt_async",
+            session_prompt_payload(text, selection),
+        )
+        .await
+    }
+
+    /// Adds `text` to the session's history without starting a run.
+    pub async fn seed_session_context(
+        &self,
+        session_id: &str,
+        text: &str,
+    ) -> Result<(), SessionStatusError> {
+        let mut payload = session_prompt_payload(text, &AgentSelection::default());
+        payload["noReply"] = json!(true);
+        self.post_session_route(session_id, "prompt_async", payload)
+            .await
+    }
+
+    /// Creates a new session rooted at `directory` and returns its id.
+    pub async fn create_session(
+        &self,
+        directory: &str,
+        title: &str,
+    ) -> Result<String, SessionStatusError> {
+        let response = self
+            .client()?
+            .post(self.session_url(Some(directory)))
+            .json(&json!({ "title": title }))
+            .send()
+            .await
+            .map_err(|err| map_reqwest_error(err, "SERVER_CONNECT_FAILED"))?;
+
+        check_request_status(response.status(), "POST /session", None)?;
+
+        let body = response
+            .text()
+            .await
+            .map_err(|err| map_reqwest_error(err, "SERVER_READ_FAILED"))?;
+        parse_session_id_body(&body)
+    }
+
+    /// Deletes the session and its history.
+    pub async fn delete_session(&self, session_id: &str) -> Result<(), SessionStatusError> {
+        let response = self
+            .client()?
+            .delete(self.session_id_url(session_id))
+            .send()
+            .await
+            .map_err(|err| map_reqwest_error(err, "SERVER_CONNECT_FAILED"))?;
+
+        check_request_status(
+            response.status(),
+            &format!("DELETE /session/{session_id}"),
+            Some(session_not_found(session_id)),
+        )
+    }
+
+    /// Copies the session's history into a new session rooted at `directory`
+    /// and returns its id. Servers without the fork route answer 404, which
+    /// is reported as `SESSION_FORK_UNSUPPORTED` so callers can fall back.
+    pub async fn fork_session(
+        &self,
+        session_id: &str,
+        directory: &str,
+    ) -> Result<String, SessionStatusError> {
+        let response = self
+            .client()?
+            .post(self.session_fork_url(session_id, directory))
+            .json(&json!({}))
+            .send()
+            .await
+            .map_err(|err| map_reqwest_error(err, "SERVER_CONNECT_FAILED"))?;
+
+        check_request_status(
+            response.status(),
+            &format!("POST /session/{session_id}/fork"),
+            Some(SessionStatusError {
+                code: "SESSION_FORK_UNSUPPORTED".to_string(),
+                message: format!("OpenCode server could not fork session {session_id} (HTTP 404)"),
+            }),
+        )?;
+
+        let body = response
+            .text()
+            .await
+            .map_err(|err| map_reqwest_error(err, "SERVER_READ_FAILED"))?;
+        parse_session_id_body(&body)
+    }
+
+    /// Interrupts whatever the session is currently running.
+    pub async fn abort_session(&self, session_id: &str) -> Result<(), SessionStatusError> {
+        self.post_session_route(session_id, "abort", json!({}))
+            .await
+    }
+
+    /// Rolls the session back to just before `message_id`, restoring the
+    /// files the agent changed from that message on.
+    pub async fn revert_session(
+        &self,
+        session_id: &str,
+        message_id: &str,
+    ) -> Result<(), SessionStatusError> {
+        self.post_session_route(session_id, "revert", json!({ "messageID": message_id }))
+            .await
+    }
+
+    /// Undoes the session's last revert.
+    pub async fn unrevert_session(&self, session_id: &str) -> Result<(), SessionStatusError> {
+        self.post_session_route(session_id, "unrevert", json!({}))
+            .await
+    }
+
+    async fn post_session_route(
+        &self,
+        session_id: &str,
+        route: &str,
+        payload: Value,
+    ) -> Result<(), SessionStatusError> {
+        let response = self
+            .client()?
+            .post(self.session_route_url(session_id, route))
+            .json(&payload)
+            .send()
+            .await
+            .map_err(|err| map_reqwest_error(err, "SERVER_CONNECT_FAILED"))?;
+
+        check_request_status(
+            response.status(),
+            &format!("POST /session/{session_id}/{route}"),
+            Some(session_not_found(session_id)),
+        )
+    }
+}
+
+fn session_not_found(session_id: &str) -> SessionStatusError {
+    SessionStatusError {
+        code: "SESSION_NOT_FOUND".to_string(),
+        message: format!("OpenCode session {session_id} was not found"),
+    }
+}
+
+/// Maps the status of a session `request` such as `POST /session`: 401 is an
+/// auth failure, 404 is `not_found` when the route gives 404 a meaning,
+/// anything else unsuccessful is a plain HTTP error.
+fn check_request_status(
+    status_code: StatusCode,
+    request: &str,
+    not_found: Option<SessionStatusError>,
+) -> Result<(), SessionStatusError> {
+    if status_code.is_success() {
+        return Ok(());
+    }
+    if status_code == StatusCode::UNAUTHORIZED {
+        return Err(SessionStatusError {
+            code: "SERVER_AUTH_ERROR".to_string(),
+            message: format!("OpenCode server rejected {request} with HTTP 401"),
+        });
+    }
+    if status_code == StatusCode::NOT_FOUND
+        && let Some(not_found) = not_found
+    {
+        return Err(not_found);
+    }
+    Err(SessionStatusError {
+        code: "SERVER_HTTP_ERROR".to_string(),
+        message: format!("OpenCode server returned HTTP {status_code} for {request}"),
+    })
 }
 
 #[derive(Debug, Deserialize)]
@@ -312,6 +726,9 @@ struct RawStatusObject {
     status: Option<String>,
     #[serde(default, rename = "parentID")]
     parent_session_id: Option<String>,
+    /// Why a `retry` (or `error`) entry is in that state.
+    #[serde(default)]
+    message: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -394,6 +811,7 @@ fn parse_status_matches_body(
             message: format!("invalid /session/status entry for session {session_id}: {err}"),
         })?;
         let parent_session_id = parse_parent_session_id(&entry);
+        let error = status_detail_error(state, parse_status_detail(&entry));
 
         statuses.push(SessionStatusMatch {
             session_id,
@@ -402,7 +820,7 @@ fn parse_status_matches_body(
                 state,
                 source: SessionStatusSource::Server,
                 fetched_at,
-                error: None,
+                error,
             },
         });
     }
@@ -443,6 +861,62 @@ fn parse_session_records_body(body: &str) -> Result<Vec<SessionRecord>, SessionS
     Ok(sessions)
 }
 
+fn parse_permission_body(body: &str) -> Result<Vec<SessionPermissionRequest>, SessionStatusError> {
+    let entries = serde_json::from_str::<Vec<Value>>(body).map_err(|err| SessionStatusError {
+        code: "SERVER_CONTRACT_PARSE_ERROR".to_string(),
+        message: format!("failed to parse /permission response: {err}"),
+    })?;
+    Ok(entries.iter().filter_map(parse_permission_value).collect())
+}
+
+/// Permission request from a `/permission` entry or a `permission.updated` /
+/// `permission.asked` event. Older servers name the tool `type` and carry a
+/// single `pattern`; newer ones use `permission` and a `patterns` list.
+pub(super) fn parse_permission_value(value: &Value) -> Option<SessionPermissionRequest> {
+    let text = |key: &str| normalize_optional_text(value.get(key).and_then(Value::as_str));
+    let id = text("id")?;
+    let session_id = text("sessionID")?;
+    let tool = text("permission")
+        .or_else(|| text("type"))
+        .unwrap_or_else(|| "unknown".to_string());
+    let title = text("title").unwrap_or_else(|| tool.clone());
+
+    let mut arguments = Vec::new();
+    for key in ["pattern", "patterns"] {
+        match value.get(key) {
+            Some(Value::String(pattern)) => arguments.push(pattern.clone()),
+            Some(Value::Array(patterns)) => arguments.extend(
+                patterns
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string),
+            ),
+            _ => {}
+        }
+    }
+    if let Some(metadata) = value.get("metadata").and_then(Value::as_object) {
+        for (key, entry) in metadata {
+            let rendered = match entry {
+                Value::String(text) => text.clone(),
+                Value::Number(_) | Value::Bool(_) => entry.to_string(),
+                _ => continue,
+            };
+            let line = format!("{key}: {rendered}");
+            if !arguments.contains(&rendered) {
+                arguments.push(line);
+            }
+        }
+    }
+
+    Some(SessionPermissionRequest {
+        id,
+        session_id,
+        tool,
+        title,
+        arguments,
+    })
+}
+
 fn parse_session_todo_body(body: &str) -> Result<Vec<SessionTodoItem>, SessionStatusError> {
     let entries: Vec<RawTodoEntry> =
         serde_json::from_str(body).map_err(|err| SessionStatusError {
@@ -462,7 +936,29 @@ fn parse_session_todo_body(body: &str) -> Result<Vec<SessionTodoItem>, SessionSt
     Ok(todos)
 }
 
-fn parse_session_message_body(body: &str) -> Result<Vec<SessionMessageItem>, SessionStatusError> {
+fn session_prompt_payload(text: &str, selection: &AgentSelection) -> Value {
+    let mut payload = json!({ "parts": [{ "type": "text", "text": text }] });
+    if let Some((provider_id, model_id)) = selection.provider_and_model() {
+        payload["model"] = json!({ "providerID": provider_id, "modelID": model_id });
+    }
+    if let Some(agent) = selection.agent.as_deref() {
+        payload["agent"] = json!(agent);
+    }
+    payload
+}
+
+fn parse_session_id_body(body: &str) -> Result<String, SessionStatusError> {
+    serde_json::from_str::<Value>(body)
+        .ok()
+        .and_then(|value| value.get("id").and_then(Value::as_str).map(str::to_string))
+        .filter(|id| !id.trim().is_empty())
+        .ok_or_else(|| SessionStatusError {
+            code: "SERVER_CONTRACT_PARSE_ERROR".to_string(),
+            message: "OpenCode server returned a session without an id".to_string(),
+        })
+}
+
+fn parse_session_message_body(body: &str) -> Result<SessionMessageLog, SessionStatusError> {
     let payload: RawMessagePayload =
         serde_json::from_str(body).map_err(|err| SessionStatusError {
             code: "SERVER_CONTRACT_PARSE_ERROR".to_string(),
@@ -480,13 +976,73 @@ fn parse_session_message_body(body: &str) -> Result<Vec<SessionMessageItem>, Ses
     };
 
     let mut messages = Vec::with_capacity(entries.len());
+    let mut usage_by_model: BTreeMap<String, TokenUsage> = BTreeMap::new();
     for entry in &entries {
         if let Ok(Some(parsed)) = parse_session_message_item(entry) {
             messages.push(parsed);
         }
+        if let RawMessageEntry::Object(value) = entry
+            && let Some((model, usage)) = parse_message_usage(value)
+        {
+            usage_by_model.entry(model).or_default().add(&usage);
+        }
+    }
+
+    Ok(SessionMessageLog {
+        messages,
+        usage: usage_by_model
+            .into_iter()
+            .map(|(model, usage)| ModelUsage { model, usage })
+            .collect(),
+    })
+}
+
+/// Reads the `tokens`/`cost` metadata OpenCode attaches to assistant
+/// messages, keyed by `provider/model`.
+fn parse_message_usage(value: &Value) -> Option<(String, TokenUsage)> {
+    let obj = value.as_object()?;
+    let info = obj.get("info").and_then(Value::as_object).unwrap_or(obj);
+    let tokens = info.get("tokens").and_then(Value::as_object);
+    let cache = tokens
+        .and_then(|tokens| tokens.get("cache"))
+        .and_then(Value::as_object);
+    let count = |source: Option<&serde_json::Map<String, Value>>, key: &str| {
+        source
+            .and_then(|source| source.get(key))
+            .and_then(|value| {
+                value
+                    .as_u64()
+                    .or_else(|| value.as_f64().map(|count| count.max(0.0) as u64))
+            })
+            .unwrap_or(0)
+    };
+
+    let usage = TokenUsage {
+        input_tokens: count(tokens, "input"),
+        output_tokens: count(tokens, "output"),
+        reasoning_tokens: count(tokens, "reasoning"),
+        cache_read_tokens: count(cache, "read"),
+        cache_write_tokens: count(cache, "write"),
+        cost: info.get("cost").and_then(Value::as_f64).unwrap_or(0.0),
+    };
+    if usage.is_empty() {
+        return None;
     }
 
-    Ok(messages)
+    let nested_model = info.get("model").and_then(Value::as_object);
+    let field = |key: &str| {
+        info.get(key)
+            .or_else(|| nested_model.and_then(|model| model.get(key)))
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    };
+    let model = match (field("providerID"), field("modelID")) {
+        (Some(provider), Some(model)) => format!("{provider}/{model}"),
+        (None, Some(model)) => model.to_string(),
+        _ => "unknown".to_string(),
+    };
+    Some((model, usage))
 }
 
 fn parse_session_message_item(
@@ -499,10 +1055,12 @@ fn parse_session_message_item(
                 return Ok(None);
             }
             Ok(Some(SessionMessageItem {
+                id: None,
                 message_type: Some("text".to_string()),
                 role: None,
                 content: content.to_string(),
                 timestamp: None,
+                parts: Vec::new(),
             }))
         }
         RawMessageEntry::Object(value) => parse_session_message_item_from_object(value),
@@ -537,25 +1095,221 @@ fn parse_session_message_item_from_object(
         .filter(|role| !role.is_empty())
         .map(str::to_string);
 
+    let id = obj
+        .get("id")
+        .or_else(|| {
+            obj.get("info")
+                .and_then(Value::as_object)
+                .and_then(|info| info.get("id"))
+        })
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(str::to_string);
+
     let timestamp = extract_message_timestamp(value);
 
     let message_type = extract_message_type(value);
 
-    let Some(content) = extract_message_content(value) else {
-        return Ok(None);
-    };
-    if content.trim().is_empty() {
+    let parts = extract_message_parts(value);
+    let content = extract_message_content(value).unwrap_or_default();
+    if content.trim().is_empty() && parts.is_empty() {
         return Ok(None);
     }
 
     Ok(Some(SessionMessageItem {
+        id,
         message_type,
         role,
         content,
         timestamp,
+        parts,
+    }))
+}
+
+const TOOL_ARGUMENT_MAX_CHARS: usize = 160;
+const TOOL_OUTPUT_MAX_LINES: usize = 20;
+const FILE_EDIT_DIFF_MAX_LINES: usize = 40;
+
+/// Reads OpenCode's typed `parts`, plus the error attached to the message
+/// itself. Bookkeeping parts such as step markers are dropped.
+fn extract_message_parts(value: &Value) -> Vec<SessionMessagePart> {
+    let Some(obj) = value.as_object() else {
+        return Vec::new();
+    };
+
+    let mut parts = obj
+        .get("parts")
+        .and_then(Value::as_array)
+        .map(|parts| parts.iter().filter_map(parse_message_part).collect())
+        .unwrap_or_else(Vec::new);
+
+    let info = obj.get("info").and_then(Value::as_object).unwrap_or(obj);
+    if let Some(message) = info.get("error").and_then(error_message) {
+        parts.push(SessionMessagePart::Error { message });
+    }
+
+    parts
+}
+
+fn parse_message_part(value: &Value) -> Option<SessionMessagePart> {
+    let obj = value.as_object()?;
+    let text = || {
+        obj.get("text")
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+            .map(str::to_string)
+    };
+
+    match obj.get("type").and_then(Value::as_str)? {
+        "text" => text().map(|text| SessionMessagePart::Text { text }),
+        "reasoning" => text().map(|text| SessionMessagePart::Reasoning { text }),
+        "tool" => parse_tool_part(obj),
+        _ => None,
+    }
+}
+
+fn parse_tool_part(obj: &serde_json::Map<String, Value>) -> Option<SessionMessagePart> {
+    let state = obj.get("state").and_then(Value::as_object)?;
+    let name = obj
+        .get("tool")
+        .and_then(Value::as_str)
+        .unwrap_or("tool")
+        .to_string();
+    let status = state
+        .get("status")
+        .and_then(Value::as_str)
+        .map(ToolCallStatus::parse)
+        .unwrap_or(ToolCallStatus::Pending);
+    let input = state.get("input").and_then(Value::as_object);
+
+    if status == ToolCallStatus::Completed
+        && let Some(diff) = state
+            .get("metadata")
+            .and_then(|metadata| metadata.get("diff"))
+            .and_then(Value::as_str)
+    {
+        let path = input
+            .and_then(|input| input.get("filePath").or_else(|| input.get("path")))
+            .and_then(Value::as_str)
+            .unwrap_or(name.as_str())
+            .to_string();
+        return Some(parse_file_edit(path, diff));
+    }
+
+    let arguments = input
+        .map(|input| {
+            input
+                .iter()
+                .map(|(key, value)| {
+                    let value = match value {
+                        Value::String(text) => text.clone(),
+                        other => other.to_string(),
+                    };
+                    (key.clone(), truncate_chars(&value, TOOL_ARGUMENT_MAX_CHARS))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let output = state
+        .get("output")
+        .and_then(Value::as_str)
+        .map(str::trim_end)
+        .filter(|output| !output.trim().is_empty())
+        .map(|output| truncate_lines(output, TOOL_OUTPUT_MAX_LINES));
+    let error = state
+        .get("error")
+        .and_then(error_message)
+        .filter(|_| status == ToolCallStatus::Failed);
+
+    Some(SessionMessagePart::Tool(SessionToolCall {
+        name,
+        status,
+        arguments,
+        output,
+        error,
     }))
 }
 
+fn parse_file_edit(path: String, diff: &str) -> SessionMessagePart {
+    let body = diff
+        .lines()
+        .filter(|line| {
+            !(line.starts_with("Index:")
+                || line.starts_with("===")
+                || line.starts_with("---")
+                || line.starts_with("+++")
+                || line.starts_with("diff "))
+        })
+        .collect::<Vec<_>>();
+    let additions = body.iter().filter(|line| line.starts_with('+')).count();
+    let deletions = body.iter().filter(|line| line.starts_with('-')).count();
+
+    let mut diff = body
+        .iter()
+        .take(FILE_EDIT_DIFF_MAX_LINES)
+        .map(|line| line.to_string())
+        .collect::<Vec<_>>();
+    if body.len() > FILE_EDIT_DIFF_MAX_LINES {
+        diff.push(format!(
+            "... {} more lines",
+            body.len() - FILE_EDIT_DIFF_MAX_LINES
+        ));
+    }
+
+    SessionMessagePart::FileEdit {
+        path,
+        additions,
+        deletions,
+        diff,
+    }
+}
+
+/// Accepts both plain error strings and OpenCode's `{ name, data: { message } }`.
+fn error_message(value: &Value) -> Option<String> {
+    let message = match value {
+        Value::String(text) => text.trim().to_string(),
+        Value::Object(obj) => {
+            let name = obj.get("name").and_then(Value::as_str);
+            let detail = obj
+                .get("data")
+                .and_then(|data| data.get("message"))
+                .or_else(|| obj.get("message"))
+                .and_then(Value::as_str);
+            match (name, detail) {
+                (Some(name), Some(detail)) => format!("{name}: {detail}"),
+                (Some(text), None) | (None, Some(text)) => text.to_string(),
+                (None, None) => return None,
+            }
+        }
+        _ => return None,
+    };
+    (!message.is_empty()).then_some(message)
+}
+
+fn truncate_chars(value: &str, max_chars: usize) -> String {
+    let single_line = value.split_whitespace().collect::<Vec<_>>().join(" ");
+    if single_line.chars().count() <= max_chars {
+        return single_line;
+    }
+    let mut shortened = single_line.chars().take(max_chars - 3).collect::<String>();
+    shortened.push_str("...");
+    shortened
+}
+
+fn truncate_lines(value: &str, max_lines: usize) -> String {
+    let lines = value.lines().collect::<Vec<_>>();
+    if lines.len() <= max_lines {
+        return value.to_string();
+    }
+    format!(
+        "{}\n... {} more lines",
+        lines[..max_lines].join("\n"),
+        lines.len() - max_lines
+    )
+}
+
 fn extract_message_type(value: &Value) -> Option<String> {
     let obj = value.as_object()?;
 
@@ -760,6 +1514,50 @@ fn scalar_timestamp(value: &Value) -> Option<String> {
     None
 }
 
+/// Session state, plus the server's explanation for retries and failures,
+/// from a status value as it appears in `/session/status` or in
+/// `session.status` events.
+pub(super) fn parse_status_value(value: Value) -> Option<(SessionState, Option<String>)> {
+    let entry = serde_json::from_value::<RawStatusEntry>(value).ok()?;
+    let state = parse_session_state(&entry).ok()?;
+    Some((state, parse_status_detail(&entry)))
+}
+
+fn parse_status_detail(entry: &RawStatusEntry) -> Option<String> {
+    match entry {
+        RawStatusEntry::Object(obj) => normalize_optional_text(obj.message.as_deref()),
+        RawStatusEntry::String(_) => None,
+    }
+}
+
+/// Carries the explanation for a retrying or failed session the same way
+/// fetch failures are carried, so it ends up in the task's `status_error`.
+pub fn status_detail_error(
+    state: SessionState,
+    detail: Option<String>,
+) -> Option<SessionStatusError> {
+    let code = match state {
+        SessionState::Error => "SESSION_ERROR",
+        SessionState::Retrying => "SESSION_RETRYING",
+        _ => return None,
+    };
+    Some(SessionStatusError {
+        code: code.to_string(),
+        message: detail.unwrap_or_else(|| state.as_str().to_string()),
+    })
+}
+
+/// Todo items from a todo array as it appears in `/session/:id/todo` or in
+/// `todo.updated` events.
+pub(super) fn parse_todo_values(value: Value) -> Option<Vec<SessionTodoItem>> {
+    let entries = serde_json::from_value::<Vec<RawTodoEntry>>(value).ok()?;
+    entries
+        .iter()
+        .map(parse_session_todo_item)
+        .collect::<Result<_, _>>()
+        .ok()
+}
+
 fn parse_session_todo_item(entry: &RawTodoEntry) -> Result<SessionTodoItem, &'static str> {
     match entry {
         RawTodoEntry::String(content) => Ok(SessionTodoItem {
@@ -800,7 +1598,8 @@ fn parse_session_state(entry: &RawStatusEntry) -> Result<SessionState, &'static
                 return match kind {
                     "idle" => Ok(SessionState::Idle),
                     "busy" => Ok(SessionState::Running),
-                    "retry" => Ok(SessionState::Idle),
+                    "retry" => Ok(SessionState::Retrying),
+                    "error" => Ok(SessionState::Error),
                     _ => Err("unrecognized session type value"),
                 };
             }
@@ -824,8 +1623,12 @@ fn normalize_optional_text(raw: Option<&str>) -> Option<String> {
 fn parse_state_str(state: &str) -> Result<SessionState, &'static str> {
     match state.trim().to_ascii_lowercase().as_str() {
         "running" | "active" | "thinking" | "processing" => Ok(SessionState::Running),
-        "waiting" | "blocked" | "prompt" | "paused" | "idle" | "ready" | "dead" | "stopped"
-        | "offline" | "completed" | "unknown" => Ok(SessionState::Idle),
+        "waiting" | "blocked" | "prompt" | "awaiting_input" => Ok(SessionState::AwaitingInput),
+        "error" | "failed" | "crashed" => Ok(SessionState::Error),
+        "retry" | "retrying" => Ok(SessionState::Retrying),
+        "paused" | "idle" | "ready" | "dead" | "stopped" | "offline" | "completed" | "unknown" => {
+            Ok(SessionState::Idle)
+        }
         _ => Err("unrecognized session state value"),
     }
 }
@@ -849,6 +1652,7 @@ mod tests {
     use tokio::net::TcpListener;
 
     use super::*;
+    use crate::opencode::events::{ScopedServerEvent, ServerEvent};
 
     #[tokio::test(flavor = "multi_thread")]
     async fn list_statuses_server_success_marks_server_source() {
@@ -876,6 +1680,40 @@ mod tests {
         assert!(results["sid-2"].error.is_none());
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn list_statuses_keeps_retry_and_error_details() {
+        let port = spawn_single_response_server(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nConnection: close\r\n\r\n{\"sid-1\":{\"type\":\"retry\",\"attempt\":2,\"message\":\"Rate limited\",\"next\":1700000000000},\"sid-2\":\"waiting\",\"sid-3\":\"failed\"}".to_string(),
+        )
+        .await;
+        let provider = ServerStatusProvider::new(ServerStatusConfig {
+            port,
+            request_timeout: Duration::from_millis(500),
+            ..ServerStatusConfig::default()
+        });
+
+        let results = provider
+            .fetch_all_statuses(SystemTime::UNIX_EPOCH, None)
+            .await
+            .expect("status fetch should succeed");
+
+        assert_eq!(results["sid-1"].state, SessionState::Retrying);
+        assert_eq!(
+            results["sid-1"].error,
+            Some(SessionStatusError {
+                code: "SESSION_RETRYING".to_string(),
+                message: "Rate limited".to_string(),
+            })
+        );
+        assert_eq!(results["sid-2"].state, SessionState::AwaitingInput);
+        assert!(results["sid-2"].error.is_none());
+        assert_eq!(results["sid-3"].state, SessionState::Error);
+        assert_eq!(
+            results["sid-3"].error.as_ref().map(|err| err.code.as_str()),
+            Some("SESSION_ERROR")
+        );
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn list_statuses_partial_response_marks_missing_entries() {
         let port = spawn_single_response_server(
@@ -1136,6 +1974,430 @@ mod tests {
         assert_eq!(messages[0].timestamp.as_deref(), Some("1735689600"));
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn fetch_session_messages_keeps_typed_parts() {
+        let body = json!([
+            {
+                "info": {
+                    "id": "msg_assistant",
+                    "role": "assistant",
+                    "error": {"name": "ProviderAuthError", "data": {"message": "bad key"}}
+                },
+                "parts": [
+                    {"type": "step-start"},
+                    {"type": "reasoning", "text": "check the file first"},
+                    {"type": "tool", "tool": "bash", "state": {
+                        "status": "completed",
+                        "input": {"command": "ls   -la"},
+                        "output": "a\nb\n"
+                    }},
+                    {"type": "tool", "tool": "read", "state": {
+                        "status": "error",
+                        "input": {"filePath": "/tmp/missing"},
+                        "error": "file not found"
+                    }},
+                    {"type": "tool", "tool": "edit", "state": {
+                        "status": "completed",
+                        "input": {"filePath": "src/lib.rs"},
+                        "metadata": {"diff": "Index: src/lib.rs\n===\n--- src/lib.rs\n+++ src/lib.rs\n@@ -1,2 +1,2 @@\n-old\n+new\n keep"}
+                    }},
+                    {"type": "text", "text": "done"}
+                ]
+            }
+        ]);
+        let port = spawn_single_response_server(format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nConnection: close\r\n\r\n{body}"
+        ))
+        .await;
+        let provider = ServerStatusProvider::new(ServerStatusConfig {
+            port,
+            request_timeout: Duration::from_millis(500),
+            ..ServerStatusConfig::default()
+        });
+
+        let messages = provider
+            .fetch_session_messages("sid-parts")
+            .await
+            .expect("message response should parse");
+
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].id.as_deref(), Some("msg_assistant"));
+        assert_eq!(
+            messages[0].parts,
+            vec![
+                SessionMessagePart::Reasoning {
+                    text: "check the file first".to_string(),
+                },
+                SessionMessagePart::Tool(SessionToolCall {
+                    name: "bash".to_string(),
+                    status: ToolCallStatus::Completed,
+                    arguments: vec![("command".to_string(), "ls -la".to_string())],
+                    output: Some("a\nb".to_string()),
+                    error: None,
+                }),
+                SessionMessagePart::Tool(SessionToolCall {
+                    name: "read".to_string(),
+                    status: ToolCallStatus::Failed,
+                    arguments: vec![("filePath".to_string(), "/tmp/missing".to_string())],
+                    output: None,
+                    error: Some("file not found".to_string()),
+                }),
+                SessionMessagePart::FileEdit {
+                    path: "src/lib.rs".to_string(),
+                    additions: 1,
+                    deletions: 1,
+                    diff: vec![
+                        "@@ -1,2 +1,2 @@".to_string(),
+                        "-old".to_string(),
+                        "+new".to_string(),
+                        " keep".to_string(),
+                    ],
+                },
+                SessionMessagePart::Text {
+                    text: "done".to_string(),
+                },
+                SessionMessagePart::Error {
+                    message: "ProviderAuthError: bad key".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn fetch_session_message_log_sums_usage_per_model() {
+        let port = spawn_single_response_server(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nConnection: close\r\n\r\n[{\"info\":{\"role\":\"user\"},\"parts\":[{\"type\":\"text\",\"text\":\"go\"}]},{\"info\":{\"role\":\"assistant\",\"providerID\":\"anthropic\",\"modelID\":\"claude\",\"cost\":0.25,\"tokens\":{\"input\":100,\"output\":20,\"reasoning\":5,\"cache\":{\"read\":300,\"write\":40}}},\"parts\":[{\"type\":\"tool\",\"tool\":\"bash\"}]},{\"info\":{\"role\":\"assistant\",\"providerID\":\"anthropic\",\"modelID\":\"claude\",\"cost\":0.5,\"tokens\":{\"input\":50,\"output\":10}},\"parts\":[{\"type\":\"text\",\"text\":\"done\"}]},{\"info\":{\"role\":\"assistant\",\"model\":{\"providerID\":\"openai\",\"modelID\":\"gpt\"},\"cost\":0,\"tokens\":{\"input\":7,\"output\":3}},\"parts\":[]}]".to_string(),
+        )
+        .await;
+        let provider = ServerStatusProvider::new(ServerStatusConfig {
+            port,
+            request_timeout: Duration::from_millis(500),
+            ..ServerStatusConfig::default()
+        });
+
+        let log = provider
+            .fetch_session_message_log("sid-usage")
+            .await
+            .expect("message response should parse");
+
+        assert_eq!(log.messages.len(), 2);
+        assert_eq!(
+            log.usage,
+            vec![
+                ModelUsage {
+                    model: "anthropic/claude".to_string(),
+                    usage: TokenUsage {
+                        input_tokens: 150,
+                        output_tokens: 30,
+                        reasoning_tokens: 5,
+                        cache_read_tokens: 300,
+                        cache_write_tokens: 40,
+                        cost: 0.75,
+                    },
+                },
+                ModelUsage {
+                    model: "openai/gpt".to_string(),
+                    usage: TokenUsage {
+                        input_tokens: 7,
+                        output_tokens: 3,
+                        ..TokenUsage::default()
+                    },
+                },
+            ]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn fork_session_returns_new_id_or_reports_unsupported() {
+        let port = spawn_single_response_server(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nConnection: close\r\n\r\n{\"id\":\"ses_forked\",\"parentID\":null}".to_string(),
+        )
+        .await;
+        let provider = ServerStatusProvider::new(ServerStatusConfig {
+            port,
+            request_timeout: Duration::from_millis(500),
+            ..ServerStatusConfig::default()
+        });
+        let forked = provider
+            .fork_session("ses_source", "/tmp/fork")
+            .await
+            .expect("fork should return the new session");
+        assert_eq!(forked, "ses_forked");
+
+        let port = spawn_single_response_server(
+            "HTTP/1.1 404 Not Found\r\nConnection: close\r\n\r\n".to_string(),
+        )
+        .await;
+        let provider = ServerStatusProvider::new(ServerStatusConfig {
+            port,
+            request_timeout: Duration::from_millis(500),
+            ..ServerStatusConfig::default()
+        });
+        let err = provider
+            .fork_session("ses_source", "/tmp/fork")
+            .await
+            .expect_err("404 should be reported");
+        assert_eq!(err.code, "SESSION_FORK_UNSUPPORTED");
+    }
+
+    #[test]
+    fn session_prompt_payload_carries_model_and_agent() {
+        let plain = session_prompt_payload("hi", &AgentSelection::default());
+        assert_eq!(
+            plain,
+            json!({ "parts": [{ "type": "text", "text": "hi" }] })
+        );
+
+        let selection = AgentSelection {
+            model: Some("anthropic/claude-sonnet-4".to_string()),
+            agent: Some("plan".to_string()),
+        };
+        let payload = session_prompt_payload("hi", &selection);
+        assert_eq!(
+            payload["model"],
+            json!({ "providerID": "anthropic", "modelID": "claude-sonnet-4" })
+        );
+        assert_eq!(payload["agent"], "plan");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn send_session_prompt_accepts_no_content_response() {
+        let port = spawn_single_response_server(
+            "HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n".to_string(),
+        )
+        .await;
+        let provider = ServerStatusProvider::new(ServerStatusConfig {
+            port,
+            request_timeout: Duration::from_millis(500),
+            ..ServerStatusConfig::default()
+        });
+
+        provider
+            .send_session_prompt("sid-1", "run the tests", &AgentSelection::default())
+            .await
+            .expect("prompt should be accepted");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn send_session_prompt_maps_missing_session() {
+        let port = spawn_single_response_server(
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string(),
+        )
+        .await;
+        let provider = ServerStatusProvider::new(ServerStatusConfig {
+            port,
+            request_timeout: Duration::from_millis(500),
+            ..ServerStatusConfig::default()
+        });
+
+        let err = provider
+            .send_session_prompt("sid-missing", "hello", &AgentSelection::default())
+            .await
+            .expect_err("missing session should fail");
+        assert_eq!(err.code, "SESSION_NOT_FOUND");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn create_session_returns_new_session_id() {
+        let port = spawn_single_response_server(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nConnection: close\r\n\r\n{\"id\":\"ses_new\",\"directory\":\"/repo\",\"title\":\"Fix login\"}".to_string(),
+        )
+        .await;
+        let provider = ServerStatusProvider::new(ServerStatusConfig {
+            port,
+            request_timeout: Duration::from_millis(500),
+            ..ServerStatusConfig::default()
+        });
+
+        let session_id = provider
+            .create_session("/repo", "Fix login")
+            .await
+            .expect("session should be created");
+        assert_eq!(session_id, "ses_new");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn abort_session_accepts_boolean_response() {
+        let port = spawn_single_response_server(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 4\r\nConnection: close\r\n\r\ntrue".to_string(),
+        )
+        .await;
+        let provider = ServerStatusProvider::new(ServerStatusConfig {
+            port,
+            request_timeout: Duration::from_millis(500),
+            ..ServerStatusConfig::default()
+        });
+
+        provider
+            .abort_session("sid-1")
+            .await
+            .expect("abort should be accepted");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn delete_session_reports_missing_sessions() {
+        let port = spawn_single_response_server(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 4\r\nConnection: close\r\n\r\ntrue".to_string(),
+        )
+        .await;
+        let provider = ServerStatusProvider::new(ServerStatusConfig {
+            port,
+            request_timeout: Duration::from_millis(500),
+            ..ServerStatusConfig::default()
+        });
+        provider
+            .delete_session("sid-1")
+            .await
+            .expect("delete should be accepted");
+
+        let port = spawn_single_response_server(
+            "HTTP/1.1 404 Not Found\r\nConnection: close\r\n\r\n".to_string(),
+        )
+        .await;
+        let provider = ServerStatusProvider::new(ServerStatusConfig {
+            port,
+            request_timeout: Duration::from_millis(500),
+            ..ServerStatusConfig::default()
+        });
+        let err = provider
+            .delete_session("sid-1")
+            .await
+            .expect_err("missing session should fail");
+        assert_eq!(err.code, "SESSION_NOT_FOUND");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn revert_and_unrevert_session_report_http_errors() {
+        let port = spawn_single_response_server(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{}".to_string(),
+        )
+        .await;
+        let provider = ServerStatusProvider::new(ServerStatusConfig {
+            port,
+            request_timeout: Duration::from_millis(500),
+            ..ServerStatusConfig::default()
+        });
+        provider
+            .revert_session("sid-1", "msg_2")
+            .await
+            .expect("revert should be accepted");
+
+        let port = spawn_single_response_server(
+            "HTTP/1.1 404 Not Found\r\nConnection: close\r\n\r\n".to_string(),
+        )
+        .await;
+        let provider = ServerStatusProvider::new(ServerStatusConfig {
+            port,
+            request_timeout: Duration::from_millis(500),
+            ..ServerStatusConfig::default()
+        });
+        let err = provider
+            .unrevert_session("sid-1")
+            .await
+            .expect_err("404 should be reported");
+        assert_eq!(err.code, "SESSION_NOT_FOUND");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn list_pending_permissions_parses_both_request_shapes() {
+        let body = r#"[{"id":"per_1","sessionID":"ses_1","type":"bash","pattern":"git push","title":"git push origin main","metadata":{"command":"git push origin main"}},{"id":"per_2","sessionID":"ses_2","permission":"edit","patterns":["src/lib.rs"],"metadata":{}}]"#;
+        let port = spawn_single_response_server(format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        ))
+        .await;
+        let provider = ServerStatusProvider::new(ServerStatusConfig {
+            port,
+            request_timeout: Duration::from_millis(500),
+            ..ServerStatusConfig::default()
+        });
+
+        let permissions = provider
+            .list_pending_permissions(Some("/repo"))
+            .await
+            .expect("permissions should parse");
+        assert_eq!(
+            permissions,
+            Some(vec![
+                SessionPermissionRequest {
+                    id: "per_1".to_string(),
+                    session_id: "ses_1".to_string(),
+                    tool: "bash".to_string(),
+                    title: "git push origin main".to_string(),
+                    arguments: vec![
+                        "git push".to_string(),
+                        "command: git push origin main".to_string(),
+                    ],
+                },
+                SessionPermissionRequest {
+                    id: "per_2".to_string(),
+                    session_id: "ses_2".to_string(),
+                    tool: "edit".to_string(),
+                    title: "edit".to_string(),
+                    arguments: vec!["src/lib.rs".to_string()],
+                },
+            ])
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn list_pending_permissions_reports_missing_endpoint_as_unknown() {
+        let port = spawn_single_response_server(
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string(),
+        )
+        .await;
+        let provider = ServerStatusProvider::new(ServerStatusConfig {
+            port,
+            request_timeout: Duration::from_millis(500),
+            ..ServerStatusConfig::default()
+        });
+
+        assert_eq!(
+            provider
+                .list_pending_permissions(None)
+                .await
+                .expect("404 should not be an error"),
+            None
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn event_stream_yields_parsed_events_until_closed() {
+        let port = spawn_single_response_server(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nConnection: close\r\n\r\ndata: {\"type\":\"server.connected\",\"properties\":{}}\n\ndata: {\"directory\":\"/tmp/wt\",\"payload\":{\"type\":\"session.idle\",\"properties\":{\"sessionID\":\"ses_1\"}}}\n\n".to_string(),
+        )
+        .await;
+        let provider = ServerStatusProvider::new(ServerStatusConfig {
+            port,
+            request_timeout: Duration::from_millis(500),
+            ..ServerStatusConfig::default()
+        });
+
+        let mut events = provider
+            .open_event_stream()
+            .await
+            .expect("event stream should open");
+        assert_eq!(
+            events.next_event().await.expect("event"),
+            Some(ScopedServerEvent {
+                directory: None,
+                event: ServerEvent::Connected,
+            })
+        );
+        assert_eq!(
+            events.next_event().await.expect("event"),
+            Some(ScopedServerEvent {
+                directory: Some("/tmp/wt".to_string()),
+                event: ServerEvent::SessionStatus {
+                    session_id: "ses_1".to_string(),
+                    state: SessionState::Idle,
+                    detail: None,
+                },
+            })
+        );
+        assert_eq!(events.next_event().await.expect("end of stream"), None);
+    }
+
     async fn spawn_single_response_server(response: String) -> u16 {
         let listener = TcpListener::bind(("127.0.0.1", 0))
             .await
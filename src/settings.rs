@@ -4,6 +4,7 @@ use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use anyhow::{Context, anyhow};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use tracing::warn;
 
@@ -27,6 +28,8 @@ const DEFAULT_SIDE_PANEL_WIDTH: u16 = 40;
 const MIN_SCROLL_COLUMN_WIDTH_CHARS: u16 = 24;
 const MAX_SCROLL_COLUMN_WIDTH_CHARS: u16 = 80;
 const DEFAULT_SCROLL_COLUMN_WIDTH_CHARS: u16 = 42;
+const MIN_SERVER_REQUEST_TIMEOUT_MS: u64 = 50;
+const MAX_SERVER_REQUEST_TIMEOUT_MS: u64 = 60_000;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(default)]
@@ -47,6 +50,53 @@ pub struct Settings {
     pub project_order: Vec<String>,
     pub archived_project_paths: Vec<String>,
     pub keybindings: KeybindingsConfig,
+    pub server: ServerSettings,
+    pub backend: BackendSettings,
+    pub pane_status: PaneStatusSettings,
+}
+
+/// Where the OpenCode server listens and how to log in to it. Unset fields
+/// fall back to the environment, then to a local server on port 4096.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct ServerSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_timeout_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bearer_token: Option<String>,
+}
+
+/// Which agent runs in new task sessions. `kind` is `opencode` (the default)
+/// or `shell`, which launches `shell_command` and tracks it by process.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct BackendSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shell_command: Option<String>,
+}
+
+/// Regexes the pane-scraping status fallback matches against a task's tmux
+/// pane and process tree while the OpenCode server is unreachable. Unset
+/// fields use the built-in OpenCode patterns.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct PaneStatusSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub running_pattern: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub awaiting_input_pattern: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent_process_pattern: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
@@ -76,6 +126,9 @@ impl Default for Settings {
             project_order: Vec::new(),
             archived_project_paths: Vec::new(),
             keybindings: KeybindingsConfig::default(),
+            server: ServerSettings::default(),
+            backend: BackendSettings::default(),
+            pane_status: PaneStatusSettings::default(),
         }
     }
 }
@@ -232,6 +285,62 @@ impl Settings {
         self.archived_project_paths.sort();
         self.archived_project_paths.dedup();
 
+        let trimmed = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        self.server.hostname = trimmed(&self.server.hostname);
+        self.server.username = trimmed(&self.server.username);
+        self.server.password = trimmed(&self.server.password);
+        self.server.bearer_token = trimmed(&self.server.bearer_token);
+        if self.server.port == Some(0) {
+            warn!("invalid server.port 0 in settings config; falling back to the default port");
+            self.server.port = None;
+        }
+        self.server.request_timeout_ms = self.server.request_timeout_ms.map(|timeout| {
+            timeout.clamp(MIN_SERVER_REQUEST_TIMEOUT_MS, MAX_SERVER_REQUEST_TIMEOUT_MS)
+        });
+
+        self.backend.shell_command = trimmed(&self.backend.shell_command);
+        self.backend.kind = match trimmed(&self.backend.kind)
+            .map(|kind| kind.to_ascii_lowercase())
+            .as_deref()
+        {
+            None => None,
+            Some(kind @ ("opencode" | "shell")) => Some(kind.to_string()),
+            Some(kind) => {
+                warn!("invalid backend.kind '{kind}' in settings config; falling back to opencode");
+                None
+            }
+        };
+        if self.backend.kind.as_deref() == Some("shell") && self.backend.shell_command.is_none() {
+            warn!("backend.kind 'shell' needs backend.shell_command; falling back to opencode");
+            self.backend.kind = None;
+        }
+
+        let pattern = |key: &str, value: &Option<String>| {
+            trimmed(value).filter(|pattern| match Regex::new(pattern) {
+                Ok(_) => true,
+                Err(error) => {
+                    warn!("invalid pane_status.{key} '{pattern}' in settings config: {error}");
+                    false
+                }
+            })
+        };
+        self.pane_status.running_pattern =
+            pattern("running_pattern", &self.pane_status.running_pattern);
+        self.pane_status.awaiting_input_pattern = pattern(
+            "awaiting_input_pattern",
+            &self.pane_status.awaiting_input_pattern,
+        );
+        self.pane_status.agent_process_pattern = pattern(
+            "agent_process_pattern",
+            &self.pane_status.agent_process_pattern,
+        );
+
         self.theme = match ThemePreset::from_str(&self.theme) {
             Ok(preset) => preset.as_str().to_string(),
             Err(()) => {
@@ -440,6 +549,23 @@ mod tests {
             project_order: vec!["/tmp/demo.sqlite".to_string()],
             archived_project_paths: vec!["/tmp/old.sqlite".to_string()],
             keybindings: KeybindingsConfig::default(),
+            server: ServerSettings {
+                hostname: Some("10.0.0.5".to_string()),
+                port: Some(4200),
+                request_timeout_ms: Some(1_500),
+                username: None,
+                password: Some("secret".to_string()),
+                bearer_token: None,
+            },
+            backend: BackendSettings {
+                kind: Some("shell".to_string()),
+                shell_command: Some("aider --yes".to_string()),
+            },
+            pane_status: PaneStatusSettings {
+                running_pattern: Some("(?i)busy".to_string()),
+                awaiting_input_pattern: None,
+                agent_process_pattern: Some("aider".to_string()),
+            },
         };
         expected.validate();
 
@@ -470,6 +596,9 @@ mod tests {
             project_order: Vec::new(),
             archived_project_paths: vec!["  /tmp/archived.sqlite  ".to_string(), " ".to_string()],
             keybindings: KeybindingsConfig::default(),
+            server: ServerSettings::default(),
+            backend: BackendSettings::default(),
+            pane_status: PaneStatusSettings::default(),
         };
 
         settings.validate();
@@ -512,6 +641,78 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_validate_server_settings() {
+        let mut settings = Settings {
+            server: ServerSettings {
+                hostname: Some("  ".to_string()),
+                port: Some(0),
+                request_timeout_ms: Some(5),
+                username: Some(" kanban ".to_string()),
+                password: Some("".to_string()),
+                bearer_token: Some(" tok ".to_string()),
+            },
+            ..Settings::default()
+        };
+        settings.validate();
+
+        assert_eq!(
+            settings.server,
+            ServerSettings {
+                hostname: None,
+                port: None,
+                request_timeout_ms: Some(MIN_SERVER_REQUEST_TIMEOUT_MS),
+                username: Some("kanban".to_string()),
+                password: None,
+                bearer_token: Some("tok".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_validate_backend_settings() {
+        let mut settings = Settings {
+            backend: BackendSettings {
+                kind: Some(" Shell ".to_string()),
+                shell_command: Some("  aider  ".to_string()),
+            },
+            ..Settings::default()
+        };
+        settings.validate();
+        assert_eq!(settings.backend.kind.as_deref(), Some("shell"));
+        assert_eq!(settings.backend.shell_command.as_deref(), Some("aider"));
+
+        settings.backend.shell_command = Some(" ".to_string());
+        settings.validate();
+        assert_eq!(settings.backend.kind, None);
+
+        settings.backend.kind = Some("claude".to_string());
+        settings.validate();
+        assert_eq!(settings.backend.kind, None);
+    }
+
+    #[test]
+    fn test_validate_pane_status_patterns() {
+        let mut settings = Settings {
+            pane_status: PaneStatusSettings {
+                running_pattern: Some(" (?i)working ".to_string()),
+                awaiting_input_pattern: Some("([unclosed".to_string()),
+                agent_process_pattern: Some("   ".to_string()),
+            },
+            ..Settings::default()
+        };
+        settings.validate();
+
+        assert_eq!(
+            settings.pane_status,
+            PaneStatusSettings {
+                running_pattern: Some("(?i)working".to_string()),
+                awaiting_input_pattern: None,
+                agent_process_pattern: None,
+            }
+        );
+    }
+
     #[test]
     fn test_validate_invalid_theme() {
         let mut settings = Settings {
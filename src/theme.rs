@@ -116,6 +116,9 @@ pub struct StatusPalette {
     pub dead: Color,
     pub broken: Color,
     pub unavailable: Color,
+    pub error: Color,
+    pub retrying: Color,
+    pub awaiting_input: Color,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -186,6 +189,9 @@ pub struct StatusPaletteOverride {
     pub dead: Option<String>,
     pub broken: Option<String>,
     pub unavailable: Option<String>,
+    pub error: Option<String>,
+    pub retrying: Option<String>,
+    pub awaiting_input: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
@@ -281,6 +287,9 @@ impl Theme {
                     dead: Color::Red,
                     broken: Color::LightRed,
                     unavailable: Color::Red,
+                    error: Color::LightRed,
+                    retrying: Color::LightMagenta,
+                    awaiting_input: Color::LightCyan,
                 },
                 tile: TilePalette {
                     repo: Color::LightCyan,
@@ -325,6 +334,9 @@ impl Theme {
                     dead: Color::Rgb(176, 46, 36),
                     broken: Color::Rgb(176, 46, 36),
                     unavailable: Color::Rgb(176, 46, 36),
+                    error: Color::Rgb(176, 46, 36),
+                    retrying: Color::Rgb(142, 68, 173),
+                    awaiting_input: Color::Rgb(8, 102, 120),
                 },
                 tile: TilePalette {
                     repo: Color::Rgb(8, 102, 120),
@@ -369,6 +381,9 @@ impl Theme {
                     dead: Color::LightRed,
                     broken: Color::LightRed,
                     unavailable: Color::LightRed,
+                    error: Color::LightRed,
+                    retrying: Color::LightMagenta,
+                    awaiting_input: Color::LightCyan,
                 },
                 tile: TilePalette {
                     repo: Color::LightCyan,
@@ -413,6 +428,9 @@ impl Theme {
                     dead: Color::White,
                     broken: Color::White,
                     unavailable: Color::White,
+                    error: Color::White,
+                    retrying: Color::Gray,
+                    awaiting_input: Color::White,
                 },
                 tile: TilePalette {
                     repo: Color::White,
@@ -457,11 +475,14 @@ impl Theme {
     pub fn status_color(&self, status: &str) -> Color {
         match status {
             "running" => self.status.running,
-            "waiting" => self.status.waiting,
+            "waiting" | "awaiting_approval" => self.status.waiting,
             "idle" => self.status.idle,
             "dead" => self.status.dead,
             "broken" => self.status.broken,
             "repo_unavailable" => self.status.unavailable,
+            "error" => self.status.error,
+            "retrying" => self.status.retrying,
+            "awaiting_input" => self.status.awaiting_input,
             _ => self.base.text,
         }
     }
@@ -601,6 +622,21 @@ impl CustomThemeConfig {
             self.status.unavailable.as_deref(),
             "custom_theme.status.unavailable"
         );
+        apply!(
+            theme.status.error,
+            self.status.error.as_deref(),
+            "custom_theme.status.error"
+        );
+        apply!(
+            theme.status.retrying,
+            self.status.retrying.as_deref(),
+            "custom_theme.status.retrying"
+        );
+        apply!(
+            theme.status.awaiting_input,
+            self.status.awaiting_input.as_deref(),
+            "custom_theme.status.awaiting_input"
+        );
 
         apply!(
             theme.tile.repo,
@@ -797,6 +833,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_status_color_covers_attention_states() {
+        let custom = CustomThemeConfig {
+            status: StatusPaletteOverride {
+                error: Some("#B02E24".to_string()),
+                ..StatusPaletteOverride::default()
+            },
+            ..CustomThemeConfig::default()
+        };
+
+        let theme = Theme::resolve(ThemePreset::Custom, &custom);
+        assert_eq!(theme.status_color("error"), Color::Rgb(176, 46, 36));
+        assert_eq!(theme.status_color("retrying"), theme.status.retrying);
+        assert_eq!(
+            theme.status_color("awaiting_input"),
+            theme.status.awaiting_input
+        );
+    }
+
     #[test]
     fn test_custom_theme_invalid_hex_falls_back_to_inherited_value() {
         let custom = CustomThemeConfig {
@@ -1,8 +1,9 @@
 #![allow(dead_code)]
 
 use std::env;
+use std::io::Write;
 use std::path::Path;
-use std::process::Command;
+use std::process::{Command, Stdio};
 
 use anyhow::{Context, Result, anyhow, bail};
 use termlauncher::{Application, CustomTerminal, Error as TermlauncherError, Terminal};
@@ -54,24 +55,43 @@ pub fn tmux_session_exists(session_name: &str) -> bool {
         .unwrap_or(false)
 }
 
+/// Creates a detached session. `env` may hold credentials, so when it is set
+/// the whole `new-session` command is fed to tmux on stdin rather than put on
+/// a command line every local user can read.
 pub fn tmux_create_session(
     session_name: &str,
     working_dir: &Path,
     command: Option<&str>,
+    env: &[(String, String)],
 ) -> Result<()> {
     let mut args = new_session_args(session_name, working_dir);
-    if let Some(command) = command {
-        args.push(command.to_string());
+    if env.is_empty() {
+        if let Some(command) = command {
+            args.push(command.to_string());
+        }
+        let output = tmux_command()
+            .args(args)
+            .output()
+            .context("failed to run tmux new-session")?;
+        return ensure_success(&output, "new-session");
     }
 
-    let output = tmux_command()
-        .args(args)
-        .output()
+    let mut child = tmux_command()
+        .args(source_stdin_args())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
         .context("failed to run tmux new-session")?;
-
-    ensure_success(&output, "new-session")?;
-
-    Ok(())
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(new_session_script(args, env, command).as_bytes())
+            .context("failed to pass new-session to tmux")?;
+    }
+    let output = child
+        .wait_with_output()
+        .context("failed to run tmux new-session")?;
+    ensure_success_with_output(&output, "new-session")
 }
 
 pub fn tmux_kill_session(session_name: &str) -> Result<()> {
@@ -251,7 +271,7 @@ pub fn tmux_display_message(
 }
 
 pub fn tmux_list_project_sessions(project_slug: &str) -> Vec<String> {
-    let prefix = format!("ok-{project_slug}-");
+    let prefix = project_session_prefix(Some(project_slug));
     let kanban_session = "opencode-kanban";
 
     tmux_list_sessions()
@@ -291,12 +311,22 @@ pub fn tmux_get_pane_pid(session_name: &str) -> Option<u32> {
         .ok()
 }
 
+/// The visible contents of the session's active pane as plain text.
+pub fn tmux_capture_pane(session_name: &str) -> Option<String> {
+    let output = tmux_command()
+        .args(capture_pane_args(session_name))
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
 pub fn sanitize_session_name(repo_name: &str, branch_name: &str) -> String {
-    let repo = sanitize_fragment(repo_name);
-    let branch = sanitize_fragment(branch_name);
-    let mut session_name = format!("ok-{repo}-{branch}");
-    session_name.truncate(200);
-    session_name
+    sanitize_session_name_for_project(None, repo_name, branch_name)
 }
 
 pub fn sanitize_session_name_for_project(
@@ -304,21 +334,32 @@ pub fn sanitize_session_name_for_project(
     repo_name: &str,
     branch_name: &str,
 ) -> String {
-    let Some(project_slug) = project_slug
-        .map(str::trim)
-        .filter(|value| !value.is_empty())
-    else {
-        return sanitize_session_name(repo_name, branch_name);
-    };
-
-    let project = sanitize_fragment(project_slug);
-    let repo = sanitize_fragment(repo_name);
     let branch = sanitize_fragment(branch_name);
-    let mut session_name = format!("ok-{project}-{repo}-{branch}");
+    let mut session_name = format!("{}{branch}", repo_session_prefix(project_slug, repo_name));
     session_name.truncate(200);
     session_name
 }
 
+/// `ok-<project>-` for a named project, `ok-` for the default one.
+pub fn project_session_prefix(project_slug: Option<&str>) -> String {
+    match project_slug
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    {
+        Some(project_slug) => format!("ok-{}-", sanitize_fragment(project_slug)),
+        None => "ok-".to_string(),
+    }
+}
+
+/// The prefix every session of `repo_name` in the project starts with.
+pub fn repo_session_prefix(project_slug: Option<&str>, repo_name: &str) -> String {
+    format!(
+        "{}{}-",
+        project_session_prefix(project_slug),
+        sanitize_fragment(repo_name)
+    )
+}
+
 fn sanitize_fragment(input: &str) -> String {
     input
         .chars()
@@ -364,6 +405,39 @@ fn new_session_args(session_name: &str, working_dir: &Path) -> Vec<String> {
     ]
 }
 
+/// Starts the server if needed and runs the commands read from stdin.
+fn source_stdin_args() -> Vec<String> {
+    vec![
+        "start-server".to_string(),
+        ";".to_string(),
+        "source-file".to_string(),
+        "-".to_string(),
+    ]
+}
+
+/// `new-session` as a line of tmux command syntax, with every argument
+/// single-quoted so nothing in it is expanded.
+fn new_session_script(
+    mut args: Vec<String>,
+    env: &[(String, String)],
+    command: Option<&str>,
+) -> String {
+    for (key, value) in env {
+        args.push("-e".to_string());
+        args.push(format!("{key}={value}"));
+    }
+    if let Some(command) = command {
+        args.push(command.to_string());
+    }
+    let mut script = args
+        .iter()
+        .map(|arg| format!("'{}'", arg.replace('\'', "'\\''")))
+        .collect::<Vec<_>>()
+        .join(" ");
+    script.push('\n');
+    script
+}
+
 fn kill_session_args(session_name: &str) -> Vec<String> {
     vec![
         "kill-session".to_string(),
@@ -588,6 +662,15 @@ fn list_panes_args(session_name: &str) -> Vec<String> {
     ]
 }
 
+fn capture_pane_args(session_name: &str) -> Vec<String> {
+    vec![
+        "capture-pane".to_string(),
+        "-p".to_string(),
+        "-t".to_string(),
+        session_name.to_string(),
+    ]
+}
+
 fn tmux_socket() -> String {
     if let Ok(socket) = env::var("OPENCODE_KANBAN_TMUX_SOCKET") {
         let trimmed = socket.trim();
@@ -711,6 +794,12 @@ mod tests {
             sanitize_session_name_for_project(Some("my project"), "my-repo", "feature/login"),
             "ok-my-project-my-repo-feature-login"
         );
+        assert_eq!(project_session_prefix(Some("my.project")), "ok-my-project-");
+        assert_eq!(project_session_prefix(None), "ok-");
+        assert_eq!(
+            repo_session_prefix(Some("my project"), "my.repo"),
+            "ok-my-project-my-repo-"
+        );
     }
 
     #[test]
@@ -735,6 +824,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_new_session_env_stays_off_the_command_line() {
+        let env = [
+            ("OPENCODE_KANBAN_TASK_ID".to_string(), "abc".to_string()),
+            (
+                "OPENCODE_SERVER_PASSWORD".to_string(),
+                "s3cr'et".to_string(),
+            ),
+        ];
+        let args = new_session_args("ok-test", Path::new("/tmp/worktree"));
+        let command_line = [args.clone(), source_stdin_args()].concat();
+        assert!(command_line.iter().all(|arg| !arg.contains("s3cr")));
+
+        let script = new_session_script(args, &env, Some("opencode attach"));
+        assert_eq!(
+            script,
+            "'new-session' '-d' '-s' 'ok-test' '-c' '/tmp/worktree' \
+             '-e' 'OPENCODE_KANBAN_TASK_ID=abc' '-e' 'OPENCODE_SERVER_PASSWORD=s3cr'\\''et' \
+             'opencode attach'\n"
+        );
+    }
+
     #[test]
     fn test_kill_session_args_builder() {
         assert_eq!(
@@ -894,6 +1005,46 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_capture_pane_args_builder() {
+        assert_eq!(
+            capture_pane_args("ok-test"),
+            vec!["capture-pane", "-p", "-t", "ok-test"]
+        );
+    }
+
+    #[test]
+    fn test_tmux_create_session_passes_env_through_stdin() {
+        if !tmux_available() {
+            return;
+        }
+        let session_name = unique_session_name("create-env");
+        let _cleanup = SessionCleanup::new(session_name.clone());
+        let output_dir = tempfile::TempDir::new().expect("temp dir should be created");
+        let output = output_dir.path().join("env.txt");
+
+        tmux_create_session(
+            &session_name,
+            Path::new("."),
+            Some(&format!(
+                "printf %s \"$OK_TEST_SECRET\" > '{}'; sleep 2",
+                output.display()
+            )),
+            &[("OK_TEST_SECRET".to_string(), "it's $HOME".to_string())],
+        )
+        .expect("create session should succeed");
+
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while !output.exists() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        std::thread::sleep(Duration::from_millis(100));
+        assert_eq!(
+            std::fs::read_to_string(&output).expect("env should be written"),
+            "it's $HOME"
+        );
+    }
+
     #[test]
     fn test_tmux_create_session() {
         if !tmux_available() {
@@ -906,6 +1057,7 @@ mod tests {
             &session_name,
             Path::new("."),
             Some("printf 'hello-from-create-test\\n'; sleep 2"),
+            &[],
         )
         .expect("create session should succeed");
 
@@ -39,6 +39,17 @@ pub struct Task {
     pub status_fetched_at: Option<String>,
     pub status_error: Option<String>,
     pub opencode_session_id: Option<String>,
+    /// `provider/model` the task's agent runs with; `None` uses OpenCode's default.
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub agent: Option<String>,
+    /// Task this one was forked from.
+    #[serde(default)]
+    pub forked_from: Option<Uuid>,
+    /// Agent backend the task's tmux session runs; `None` is OpenCode.
+    #[serde(default)]
+    pub backend: Option<String>,
     #[serde(default)]
     pub attach_overlay_shown: bool,
     #[serde(default)]
@@ -57,16 +68,220 @@ pub struct SessionTodoItem {
 
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
 pub struct SessionMessageItem {
+    /// Server-side message id; needed to revert the session to this message.
+    #[serde(default)]
+    pub id: Option<String>,
     pub message_type: Option<String>,
     pub role: Option<String>,
     pub content: String,
     pub timestamp: Option<String>,
+    /// Typed parts in the order the agent produced them; `content` keeps the
+    /// plain text for callers that only want the reply.
+    #[serde(default)]
+    pub parts: Vec<SessionMessagePart>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SessionMessagePart {
+    Text {
+        text: String,
+    },
+    Reasoning {
+        text: String,
+    },
+    Tool(SessionToolCall),
+    /// A file change with a shortened unified diff; the counts cover the
+    /// whole change.
+    FileEdit {
+        path: String,
+        additions: usize,
+        deletions: usize,
+        diff: Vec<String>,
+    },
+    Error {
+        message: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct SessionToolCall {
+    pub name: String,
+    pub status: ToolCallStatus,
+    pub arguments: Vec<(String, String)>,
+    pub output: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolCallStatus {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+}
+
+impl ToolCallStatus {
+    pub fn parse(raw: &str) -> Self {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "running" => Self::Running,
+            "completed" => Self::Completed,
+            "error" | "failed" => Self::Failed,
+            _ => Self::Pending,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Running => "running",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+impl Task {
+    pub fn agent_selection(&self) -> AgentSelection {
+        AgentSelection {
+            model: self.model.clone(),
+            agent: self.agent.clone(),
+        }
+    }
+}
+
+/// Model and agent to run a session with. Empty fields leave the choice to
+/// OpenCode's configuration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct AgentSelection {
+    pub model: Option<String>,
+    pub agent: Option<String>,
+}
+
+impl AgentSelection {
+    /// Trims both inputs and checks the model is written as `provider/model`.
+    /// Both end up on the command line tmux runs, so they are limited to
+    /// letters, digits and `._:/-`.
+    pub fn parse(model: &str, agent: &str) -> Result<Self, String> {
+        let model = model.trim();
+        let agent = agent.trim();
+        if !model.is_empty() && (split_model(model).is_none() || !is_selection_value(model)) {
+            return Err(format!(
+                "invalid model '{model}'; use provider/model, for example anthropic/claude-sonnet-4"
+            ));
+        }
+        if !agent.is_empty() && !is_selection_value(agent) {
+            return Err(format!(
+                "invalid agent '{agent}'; agent names use only letters, digits and ._:/-"
+            ));
+        }
+        Ok(Self {
+            model: (!model.is_empty()).then(|| model.to_string()),
+            agent: (!agent.is_empty()).then(|| agent.to_string()),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.model.is_none() && self.agent.is_none()
+    }
+
+    /// `(providerID, modelID)` as the OpenCode API expects them.
+    pub fn provider_and_model(&self) -> Option<(&str, &str)> {
+        self.model.as_deref().and_then(split_model)
+    }
+
+    /// Short label for cards: the model id without its provider, prefixed
+    /// by the agent when one is set.
+    pub fn label(&self) -> Option<String> {
+        let model = self
+            .provider_and_model()
+            .map(|(_, model)| model)
+            .or(self.model.as_deref());
+        match (self.agent.as_deref(), model) {
+            (Some(agent), Some(model)) => Some(format!("{agent}·{model}")),
+            (Some(agent), None) => Some(agent.to_string()),
+            (None, Some(model)) => Some(model.to_string()),
+            (None, None) => None,
+        }
+    }
+}
+
+fn is_selection_value(value: &str) -> bool {
+    value
+        .chars()
+        .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '.' | '_' | ':' | '/' | '-'))
+}
+
+fn split_model(model: &str) -> Option<(&str, &str)> {
+    let (provider, model) = model.split_once('/')?;
+    let valid = |part: &str| !part.is_empty() && !part.chars().any(char::is_whitespace);
+    (valid(provider) && valid(model)).then_some((provider, model))
+}
+
+/// Token counts and cost reported on OpenCode assistant messages.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct TokenUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub reasoning_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_write_tokens: u64,
+    pub cost: f64,
+}
+
+impl TokenUsage {
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens
+            + self.output_tokens
+            + self.reasoning_tokens
+            + self.cache_read_tokens
+            + self.cache_write_tokens
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total_tokens() == 0 && self.cost == 0.0
+    }
+
+    pub fn add(&mut self, other: &TokenUsage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.reasoning_tokens += other.reasoning_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+        self.cache_write_tokens += other.cache_write_tokens;
+        self.cost += other.cost;
+    }
+}
+
+/// Usage of one session summed per model (`provider/model`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ModelUsage {
+    pub model: String,
+    pub usage: TokenUsage,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SessionUsageRecord {
+    pub task_id: Uuid,
+    pub session_id: String,
+    pub model: String,
+    pub usage: TokenUsage,
+    pub updated_at: String,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
 pub enum SessionState {
     Running,
     Idle,
+    /// Blocked on a permission request that someone has to approve or deny.
+    AwaitingApproval,
+    /// Stopped to ask the user a question.
+    AwaitingInput,
+    /// The last run failed; the reason is kept in the task's `status_error`.
+    Error,
+    /// Backing off after a provider error (rate limit, overload) before
+    /// trying again.
+    Retrying,
 }
 
 impl SessionState {
@@ -74,21 +289,55 @@ impl SessionState {
         match self {
             SessionState::Running => "running",
             SessionState::Idle => "idle",
+            SessionState::AwaitingApproval => "awaiting_approval",
+            SessionState::AwaitingInput => "awaiting_input",
+            SessionState::Error => "error",
+            SessionState::Retrying => "retrying",
         }
     }
 
+    /// Whether the agent still has a run in flight, even if it is blocked.
+    pub fn is_active(self) -> bool {
+        matches!(
+            self,
+            SessionState::Running
+                | SessionState::AwaitingApproval
+                | SessionState::AwaitingInput
+                | SessionState::Retrying
+        )
+    }
+
     pub fn from_raw_status(raw: &str) -> Self {
         let normalized = raw.trim().to_ascii_lowercase();
         match normalized.as_str() {
             "running" | "active" | "thinking" | "processing" | "busy" => SessionState::Running,
+            "awaiting_approval" | "permission" => SessionState::AwaitingApproval,
+            "awaiting_input" | "waiting" | "question" => SessionState::AwaitingInput,
+            "error" | "failed" | "crashed" => SessionState::Error,
+            "retrying" | "retry" => SessionState::Retrying,
             _ => SessionState::Idle,
         }
     }
 }
 
+/// A tool call the agent is blocked on until the user answers it.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct SessionPermissionRequest {
+    pub id: String,
+    pub session_id: String,
+    pub tool: String,
+    pub title: String,
+    /// Requested arguments (command, file patterns, ...) rendered as text.
+    pub arguments: Vec<String>,
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
 pub enum SessionStatusSource {
     Server,
+    /// Liveness of the process running in the task's tmux pane.
+    Process,
+    /// Inferred from the task's tmux pane while the server is unreachable.
+    Pane,
     None,
 }
 
@@ -96,6 +345,8 @@ impl SessionStatusSource {
     pub fn as_str(self) -> &'static str {
         match self {
             SessionStatusSource::Server => "server",
+            SessionStatusSource::Process => "process",
+            SessionStatusSource::Pane => "pane",
             SessionStatusSource::None => "none",
         }
     }
@@ -115,6 +366,24 @@ pub struct SessionStatus {
     pub error: Option<SessionStatusError>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct TaskEvent {
+    pub id: i64,
+    pub task_id: Uuid,
+    pub kind: String,
+    pub category_id: Option<Uuid>,
+    pub status: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct TaskNote {
+    pub id: i64,
+    pub task_id: Uuid,
+    pub body: String,
+    pub created_at: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
 pub struct CommandFrequency {
     pub command_id: String,
@@ -130,6 +399,26 @@ mod tests {
     fn test_session_state_as_str() {
         assert_eq!(SessionState::Running.as_str(), "running");
         assert_eq!(SessionState::Idle.as_str(), "idle");
+        assert_eq!(SessionState::AwaitingApproval.as_str(), "awaiting_approval");
+        assert_eq!(
+            SessionState::from_raw_status("awaiting_approval"),
+            SessionState::AwaitingApproval
+        );
+    }
+
+    #[test]
+    fn test_session_state_roundtrips_attention_states() {
+        for state in [
+            SessionState::AwaitingInput,
+            SessionState::Error,
+            SessionState::Retrying,
+        ] {
+            assert_eq!(SessionState::from_raw_status(state.as_str()), state);
+        }
+        assert!(SessionState::Retrying.is_active());
+        assert!(SessionState::AwaitingInput.is_active());
+        assert!(!SessionState::Error.is_active());
+        assert!(!SessionState::Idle.is_active());
     }
 
     #[test]
@@ -223,6 +512,10 @@ mod tests {
             status_fetched_at: Some("2024-01-01".to_string()),
             status_error: None,
             opencode_session_id: Some("sess-123".to_string()),
+            model: None,
+            agent: None,
+            forked_from: None,
+            backend: None,
             attach_overlay_shown: false,
             needs_inspection: false,
             archived: false,
@@ -234,6 +527,36 @@ mod tests {
         assert!(!task.archived);
     }
 
+    #[test]
+    fn test_agent_selection_parses_and_labels() {
+        let selection =
+            AgentSelection::parse(" anthropic/claude-sonnet-4 ", "plan").expect("valid selection");
+        assert_eq!(
+            selection.provider_and_model(),
+            Some(("anthropic", "claude-sonnet-4"))
+        );
+        assert_eq!(selection.label().as_deref(), Some("plan·claude-sonnet-4"));
+
+        let empty = AgentSelection::parse("", " ").expect("empty selection is valid");
+        assert!(empty.is_empty());
+        assert_eq!(empty.label(), None);
+
+        assert!(AgentSelection::parse("claude-sonnet-4", "").is_err());
+        assert!(AgentSelection::parse("anthropic/", "").is_err());
+        assert!(AgentSelection::parse("", "two words").is_err());
+    }
+
+    #[test]
+    fn agent_selection_parse_rejects_shell_metacharacters() {
+        for model in ["a/b;curl x|sh", "a/$(id)", "a/b`id`", "a/b'c", "a/b&&c"] {
+            assert!(AgentSelection::parse(model, "").is_err(), "{model}");
+        }
+        for agent in ["build;rm", "$(id)", "a|b", "a>b", "a\"b"] {
+            assert!(AgentSelection::parse("", agent).is_err(), "{agent}");
+        }
+        assert!(AgentSelection::parse("openrouter/meta-llama/llama-3.1:free", "plan_v2").is_ok());
+    }
+
     #[test]
     fn test_session_todo_item_struct() {
         let item = SessionTodoItem {
@@ -247,10 +570,12 @@ mod tests {
     #[test]
     fn test_session_message_item_struct() {
         let item = SessionMessageItem {
+            id: None,
             message_type: Some("text".to_string()),
             role: Some("user".to_string()),
             content: "Hello".to_string(),
             timestamp: Some("2024-01-01".to_string()),
+            parts: Vec::new(),
         };
         assert_eq!(item.content, "Hello");
         assert_eq!(item.role, Some("user".to_string()));
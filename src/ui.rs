@@ -26,15 +26,16 @@ use crate::app::{
     ActiveDialog, App, ArchiveTaskDialogState, CATEGORY_COLOR_PALETTE, CategoryColorField,
     CategoryInputField, CategoryInputMode, ChangeSummaryState, ConfirmCancelField, ContextMenuItem,
     DeleteProjectDialogState, DeleteRepoDialogState, DeleteTaskField, DetailFocus, EditTaskField,
-    Message, NewProjectDialogState, NewProjectField, NewTaskField, ProjectDetailCache,
-    RenameProjectDialogState, RenameProjectField, RenameRepoDialogState, RenameRepoField,
-    RepoPickerTarget, SettingsSection, SidePanelRow, TaskSearchMode, TodoVisualizationMode, View,
-    ViewMode, category_color_label,
+    ForkTaskField, Message, NewProjectDialogState, NewProjectField, NewTaskField,
+    PermissionApprovalDialogState, PermissionReplyField, ProjectDetailCache,
+    QuickPromptDialogState, RenameProjectDialogState, RenameProjectField, RenameRepoDialogState,
+    RenameRepoField, RepoPickerTarget, SettingsSection, SidePanelRow, TaskSearchMode,
+    TodoVisualizationMode, View, ViewMode, category_color_label,
 };
 use crate::command_palette::all_commands;
 use crate::notification::CompletionSound;
 use crate::theme::{Theme, ThemePreset};
-use crate::types::{Category, SessionTodoItem, Task};
+use crate::types::{Category, SessionStatusSource, SessionTodoItem, Task, TokenUsage};
 
 #[derive(Clone, Copy)]
 pub enum OverlayAnchor {
@@ -922,7 +923,10 @@ fn render_side_panel_task_details(frame: &mut Frame<'_>, area: Rect, app: &mut A
         .find(|repo| repo.id == task.repo_id)
         .map(|repo| repo.name.clone())
         .unwrap_or_else(|| "unknown".to_string());
-    let runtime_status = task.tmux_status.to_ascii_uppercase();
+    let mut runtime_status = task.tmux_status.to_ascii_uppercase();
+    if task.status_source == SessionStatusSource::Pane.as_str() {
+        runtime_status.push_str(" (from pane)");
+    }
     let todo_summary = app
         .session_todo_summary(task.id)
         .map(|(done, total)| format!("{done}/{total}"))
@@ -946,16 +950,63 @@ fn render_side_panel_task_details(frame: &mut Frame<'_>, area: Rect, app: &mut A
             TextSpan::new(":").fg(theme.base.text_muted),
             TextSpan::new(task.branch.clone()).fg(theme.tile.branch),
         ],
+    ];
+    if let Some(source_id) = task.forked_from {
+        let source = app
+            .tasks
+            .iter()
+            .chain(app.archived_tasks.iter())
+            .find(|candidate| candidate.id == source_id)
+            .map(|source| source.title.clone())
+            .unwrap_or_else(|| "(deleted task)".to_string());
+        lines.push(vec![
+            TextSpan::new(detail_kv("Forked", &source)).fg(theme.base.text_muted),
+        ]);
+    }
+    lines.extend([
         vec![TextSpan::new("")],
         vec![TextSpan::new("RUNTIME").fg(theme.base.header).bold()],
         vec![
             TextSpan::new(detail_kv("Status", &runtime_status))
                 .fg(theme.status_color(task.tmux_status.as_str())),
         ],
+    ]);
+    if let Some(detail) = task_status_detail(task) {
+        lines.push(vec![
+            TextSpan::new(detail_kv("Reason", detail))
+                .fg(theme.status_color(task.tmux_status.as_str())),
+        ]);
+    }
+    lines.extend([
         vec![TextSpan::new(detail_kv("Todos", &todo_summary)).fg(theme.tile.todo)],
         vec![TextSpan::new(detail_kv("TodoView", todo_view)).fg(theme.base.text_muted)],
         vec![TextSpan::new(detail_kv("Session", &session)).fg(theme.base.text)],
-    ];
+        vec![
+            TextSpan::new(detail_kv(
+                "Model",
+                task.model.as_deref().unwrap_or("default"),
+            ))
+            .fg(theme.base.text),
+        ],
+        vec![
+            TextSpan::new(detail_kv(
+                "Agent",
+                task.agent.as_deref().unwrap_or("default"),
+            ))
+            .fg(theme.base.text),
+        ],
+    ]);
+    if let Some(backend) = task.backend.as_deref() {
+        lines.push(vec![
+            TextSpan::new(detail_kv("Backend", backend)).fg(theme.base.text_muted),
+        ]);
+    }
+    if let Some(usage) = app.session_usage(task.id) {
+        lines.extend([
+            vec![TextSpan::new(detail_kv("Tokens", &usage_detail(&usage))).fg(theme.base.text)],
+            vec![TextSpan::new(detail_kv("Cost", &format_cost(usage.cost))).fg(theme.base.text)],
+        ]);
+    }
 
     if app.todo_visualization_mode == TodoVisualizationMode::Checklist {
         let task_todos = app.session_todos(task.id);
@@ -1378,10 +1429,13 @@ fn render_dialog(frame: &mut Frame<'_>, app: &mut App) {
         ActiveDialog::CommandPalette(_) | ActiveDialog::TaskPalette(_) => {
             command_palette_overlay_size(app.viewport)
         }
-        ActiveDialog::NewTask(_) => (80, 72),
+        ActiveDialog::NewTask(_) => (80, 92),
         ActiveDialog::ArchiveTask(_) => (55, 35),
+        ActiveDialog::InterruptSession(_) => (55, 35),
+        ActiveDialog::PermissionApproval(_) => (70, 50),
         ActiveDialog::DeleteTask(_) => (60, 60),
         ActiveDialog::EditTask(_) => (70, 45),
+        ActiveDialog::ForkTask(_) => (70, 45),
         ActiveDialog::CategoryInput(_) => (60, 40),
         ActiveDialog::CategoryColor(_) => (60, 58),
         ActiveDialog::DeleteCategory(_) => (60, 40),
@@ -1390,6 +1444,8 @@ fn render_dialog(frame: &mut Frame<'_>, app: &mut App) {
         ActiveDialog::DeleteProject(_) => (60, 35),
         ActiveDialog::RenameRepo(_) => (60, 40),
         ActiveDialog::DeleteRepo(_) => (60, 35),
+        ActiveDialog::QuickPrompt(_) => (70, 50),
+        ActiveDialog::Gc(_) => (70, 60),
         _ => (60, 45),
     };
     let anchor = if matches!(
@@ -1415,9 +1471,33 @@ fn render_dialog(frame: &mut Frame<'_>, app: &mut App) {
             render_delete_task_dialog(frame, dialog_area, app, &state)
         }
         ActiveDialog::EditTask(state) => render_edit_task_dialog(frame, dialog_area, app, &state),
+        ActiveDialog::ForkTask(state) => render_fork_task_dialog(frame, dialog_area, app, &state),
         ActiveDialog::ArchiveTask(state) => {
             render_archive_task_dialog(frame, dialog_area, app, &state)
         }
+        ActiveDialog::InterruptSession(state) => {
+            let text = format!(
+                "Interrupt the running agent for '{}' ?\n\nThe session stays open; only the current run is stopped.",
+                state.task_title
+            );
+            render_confirm_cancel_dialog(
+                frame,
+                dialog_area,
+                app,
+                ConfirmCancelDialogSpec {
+                    title: "Interrupt Session",
+                    text: &text,
+                    confirm_label: "Interrupt",
+                    confirm_destructive: true,
+                    focused_field: state.focused_field,
+                    confirm_message: Message::ConfirmInterruptSession,
+                    cancel_message: Message::DismissDialog,
+                },
+            );
+        }
+        ActiveDialog::PermissionApproval(state) => {
+            render_permission_approval_dialog(frame, dialog_area, app, &state)
+        }
         ActiveDialog::CategoryInput(state) => {
             render_category_dialog(frame, dialog_area, app, &state)
         }
@@ -1448,6 +1528,7 @@ fn render_dialog(frame: &mut Frame<'_>, app: &mut App) {
         ActiveDialog::ConfirmQuit(state) => {
             render_confirm_quit_dialog(frame, dialog_area, app, &state);
         }
+        ActiveDialog::Gc(state) => render_gc_dialog(frame, dialog_area, app, &state),
         ActiveDialog::CommandPalette(state) => {
             render_command_palette_dialog(frame, dialog_area, app, &state)
         }
@@ -1469,10 +1550,15 @@ fn render_dialog(frame: &mut Frame<'_>, app: &mut App) {
         ActiveDialog::DeleteRepo(state) => {
             render_delete_repo_dialog(frame, dialog_area, app, &state)
         }
+        ActiveDialog::QuickPrompt(state) => {
+            render_quick_prompt_dialog(frame, dialog_area, app, &state)
+        }
         ActiveDialog::MoveTask(_) | ActiveDialog::None | ActiveDialog::Help => {}
     }
 }
 
+const NEW_TASK_PROMPT_HEIGHT: u16 = 5;
+
 fn render_new_task_dialog(
     frame: &mut Frame<'_>,
     area: Rect,
@@ -1495,6 +1581,8 @@ fn render_new_task_dialog(
                 Constraint::Length(3),
                 Constraint::Length(3),
                 Constraint::Length(3),
+                Constraint::Length(NEW_TASK_PROMPT_HEIGHT),
+                Constraint::Length(3),
                 Constraint::Length(2),
                 Constraint::Min(0),
             ])
@@ -1509,6 +1597,8 @@ fn render_new_task_dialog(
                 Constraint::Length(3),
                 Constraint::Length(3),
                 Constraint::Length(3),
+                Constraint::Length(NEW_TASK_PROMPT_HEIGHT),
+                Constraint::Length(3),
                 Constraint::Length(3),
                 Constraint::Length(2),
                 Constraint::Min(0),
@@ -1595,10 +1685,27 @@ fn render_new_task_dialog(
             Message::FocusNewTaskField(NewTaskField::Title),
         );
 
+        render_new_task_agent_row(frame, layout[3], app, state);
+
+        render_multiline_input_component(
+            frame,
+            layout[4],
+            "Initial prompt",
+            &state.prompt_input,
+            state.focused_field == NewTaskField::Prompt,
+            theme,
+            Some("optional; Alt+Enter for a new line"),
+        );
+        app.interaction_map.register_click(
+            InteractionLayer::Dialog,
+            layout[4],
+            Message::FocusNewTaskField(NewTaskField::Prompt),
+        );
+
         let actions = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-            .split(layout[3]);
+            .split(layout[5]);
 
         render_action_button(
             frame,
@@ -1624,6 +1731,9 @@ fn render_new_task_dialog(
                 set_text_input_cursor(frame, layout[1], &state.existing_dir_input)
             }
             NewTaskField::Title => set_text_input_cursor(frame, layout[2], &state.title_input),
+            NewTaskField::Prompt => {
+                set_multiline_input_cursor(frame, layout[4], &state.prompt_input)
+            }
             _ => {}
         }
     } else {
@@ -1699,6 +1809,23 @@ fn render_new_task_dialog(
             Message::FocusNewTaskField(NewTaskField::Title),
         );
 
+        render_new_task_agent_row(frame, layout[5], app, state);
+
+        render_multiline_input_component(
+            frame,
+            layout[6],
+            "Initial prompt",
+            &state.prompt_input,
+            state.focused_field == NewTaskField::Prompt,
+            theme,
+            Some("optional; Alt+Enter for a new line"),
+        );
+        app.interaction_map.register_click(
+            InteractionLayer::Dialog,
+            layout[6],
+            Message::FocusNewTaskField(NewTaskField::Prompt),
+        );
+
         let selected = if state.ensure_base_up_to_date {
             vec![0]
         } else {
@@ -1716,17 +1843,17 @@ fn render_new_task_dialog(
             .values(&selected)
             .rewind(false);
         checkbox.attr(Attribute::Focus, AttrValue::Flag(options_focused));
-        checkbox.view(frame, layout[5]);
+        checkbox.view(frame, layout[7]);
         app.interaction_map.register_click(
             InteractionLayer::Dialog,
-            layout[5],
+            layout[7],
             Message::ToggleNewTaskCheckbox,
         );
 
         let actions = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-            .split(layout[6]);
+            .split(layout[8]);
 
         render_action_button(
             frame,
@@ -1752,11 +1879,62 @@ fn render_new_task_dialog(
             NewTaskField::Branch => set_text_input_cursor(frame, layout[2], &state.branch_input),
             NewTaskField::Base => set_text_input_cursor(frame, layout[3], &state.base_input),
             NewTaskField::Title => set_text_input_cursor(frame, layout[4], &state.title_input),
+            NewTaskField::Prompt => {
+                set_multiline_input_cursor(frame, layout[6], &state.prompt_input)
+            }
             _ => {}
         }
     }
 }
 
+fn render_new_task_agent_row(
+    frame: &mut Frame<'_>,
+    area: Rect,
+    app: &mut App,
+    state: &crate::app::NewTaskDialogState,
+) {
+    let theme = app.theme;
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(area);
+
+    render_input_component(
+        frame,
+        columns[0],
+        "Model",
+        &state.model_input,
+        state.focused_field == NewTaskField::Model,
+        theme,
+        Some("provider/model, optional"),
+    );
+    app.interaction_map.register_click(
+        InteractionLayer::Dialog,
+        columns[0],
+        Message::FocusNewTaskField(NewTaskField::Model),
+    );
+    render_input_component(
+        frame,
+        columns[1],
+        "Agent",
+        &state.agent_input,
+        state.focused_field == NewTaskField::Agent,
+        theme,
+        Some("optional"),
+    );
+    app.interaction_map.register_click(
+        InteractionLayer::Dialog,
+        columns[1],
+        Message::FocusNewTaskField(NewTaskField::Agent),
+    );
+
+    match state.focused_field {
+        NewTaskField::Model => set_text_input_cursor(frame, columns[0], &state.model_input),
+        NewTaskField::Agent => set_text_input_cursor(frame, columns[1], &state.agent_input),
+        _ => {}
+    }
+}
+
 fn render_delete_task_dialog(
     frame: &mut Frame<'_>,
     area: Rect,
@@ -1989,6 +2167,109 @@ fn render_edit_task_dialog(
     }
 }
 
+fn render_fork_task_dialog(
+    frame: &mut Frame<'_>,
+    area: Rect,
+    app: &mut App,
+    state: &crate::app::ForkTaskDialogState,
+) {
+    let theme = app.theme;
+    let surface = dialog_surface(theme);
+
+    let mut panel =
+        dialog_panel("Fork Task", Alignment::Center, theme, surface).text([TextSpan::from("")]);
+    panel.view(frame, area);
+
+    let panel_inner = inset_rect(area, 1, 1);
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(2),
+            Constraint::Length(3),
+            Constraint::Length(2),
+            Constraint::Min(0),
+        ])
+        .split(panel_inner);
+
+    render_input_component(
+        frame,
+        layout[0],
+        "New branch",
+        &state.branch_input,
+        matches!(state.focused_field, ForkTaskField::Branch),
+        theme,
+        None,
+    );
+    app.interaction_map.register_click(
+        InteractionLayer::Dialog,
+        layout[0],
+        Message::FocusForkTaskField(ForkTaskField::Branch),
+    );
+    render_input_component(
+        frame,
+        layout[1],
+        "Title",
+        &state.title_input,
+        matches!(state.focused_field, ForkTaskField::Title),
+        theme,
+        None,
+    );
+    app.interaction_map.register_click(
+        InteractionLayer::Dialog,
+        layout[1],
+        Message::FocusForkTaskField(ForkTaskField::Title),
+    );
+
+    let mut source_hint = Label::default()
+        .text(format!(
+            "Starts from '{}' at HEAD with its uncommitted changes",
+            state.task_title
+        ))
+        .alignment(Alignment::Center)
+        .foreground(theme.base.text_muted)
+        .background(surface);
+    source_hint.view(frame, layout[2]);
+
+    let buttons = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(layout[3]);
+
+    render_action_button(
+        frame,
+        buttons[0],
+        "Fork",
+        matches!(state.focused_field, ForkTaskField::Fork),
+        false,
+        app,
+        Some(Message::ConfirmForkTask),
+    );
+    render_action_button(
+        frame,
+        buttons[1],
+        "Cancel",
+        matches!(state.focused_field, ForkTaskField::Cancel),
+        false,
+        app,
+        Some(Message::DismissDialog),
+    );
+
+    let mut hint = Label::default()
+        .text("Tab: next field  Enter: confirm  Esc: cancel")
+        .alignment(Alignment::Center)
+        .foreground(theme.base.text_muted)
+        .background(surface);
+    hint.view(frame, layout[4]);
+
+    match state.focused_field {
+        ForkTaskField::Branch => set_text_input_cursor(frame, layout[0], &state.branch_input),
+        ForkTaskField::Title => set_text_input_cursor(frame, layout[1], &state.title_input),
+        _ => {}
+    }
+}
+
 fn render_archive_task_dialog(
     frame: &mut Frame<'_>,
     area: Rect,
@@ -2154,6 +2435,109 @@ fn render_confirm_quit_dialog(
     );
 }
 
+fn render_gc_dialog(
+    frame: &mut Frame<'_>,
+    area: Rect,
+    app: &mut App,
+    state: &crate::app::GcDialogState,
+) {
+    let theme = app.theme;
+    let surface = dialog_surface(theme);
+
+    let mut panel = dialog_panel("Clean Up Orphans", Alignment::Center, theme, surface)
+        .text([TextSpan::from("")]);
+    panel.view(frame, area);
+
+    let panel_inner = inset_rect(area, 1, 1);
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(3),
+            Constraint::Length(1),
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ])
+        .split(panel_inner);
+
+    let report = &state.report;
+    let mut lines = Vec::new();
+    if !report.orphan_worktrees.is_empty() {
+        lines.push(TextSpan::from("Worktrees without a task:").bold());
+        for orphan in &report.orphan_worktrees {
+            lines.push(TextSpan::from(format!(
+                "  {} [{}] {}",
+                orphan.path.display(),
+                orphan.repo_name,
+                orphan.branch.as_deref().unwrap_or("(detached)")
+            )));
+        }
+    }
+    if !report.orphan_sessions.is_empty() {
+        lines.push(TextSpan::from("tmux sessions without a task:").bold());
+        for session in &report.orphan_sessions {
+            lines.push(TextSpan::from(format!("  {session}")));
+        }
+    }
+    if !report.missing_worktree_tasks.is_empty() {
+        lines.push(TextSpan::from("Tasks whose worktree is gone (left as they are):").bold());
+        for task in &report.missing_worktree_tasks {
+            lines.push(TextSpan::from(format!(
+                "  {} ({})",
+                task.title, task.worktree_path
+            )));
+        }
+    }
+    let mut summary = Paragraph::default()
+        .foreground(theme.base.text)
+        .background(surface)
+        .wrap(true)
+        .text(lines);
+    summary.view(frame, layout[0]);
+
+    let adopt_text = if state.adopt_worktrees {
+        "[x] Adopt orphan worktrees as tasks instead of removing them"
+    } else {
+        "[ ] Adopt orphan worktrees as tasks instead of removing them"
+    };
+    let mut adopt = Label::default()
+        .text(adopt_text)
+        .foreground(theme.base.text)
+        .background(surface);
+    adopt.view(frame, layout[1]);
+    app.interaction_map
+        .register_click(InteractionLayer::Dialog, layout[1], Message::ToggleGcAdopt);
+
+    let buttons = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(layout[2]);
+    render_action_button(
+        frame,
+        buttons[0],
+        "Apply",
+        matches!(state.focused_field, ConfirmCancelField::Confirm),
+        true,
+        app,
+        Some(Message::ConfirmGc),
+    );
+    render_action_button(
+        frame,
+        buttons[1],
+        "Cancel",
+        matches!(state.focused_field, ConfirmCancelField::Cancel),
+        false,
+        app,
+        Some(Message::DismissDialog),
+    );
+
+    let mut hint = Label::default()
+        .text("a: toggle adopt  Tab: switch  Enter: confirm  Esc: cancel")
+        .alignment(Alignment::Center)
+        .foreground(theme.base.text_muted)
+        .background(surface);
+    hint.view(frame, layout[3]);
+}
+
 struct ConfirmCancelDialogSpec<'a> {
     title: &'a str,
     text: &'a str,
@@ -2228,6 +2612,102 @@ fn render_confirm_cancel_dialog(
     hint.view(frame, layout[2]);
 }
 
+fn render_permission_approval_dialog(
+    frame: &mut Frame<'_>,
+    area: Rect,
+    app: &mut App,
+    state: &PermissionApprovalDialogState,
+) {
+    let theme = app.theme;
+    let surface = dialog_surface(theme);
+
+    let mut panel = dialog_panel("Permission Request", Alignment::Center, theme, surface)
+        .text([TextSpan::from("")]);
+    panel.view(frame, area);
+
+    let panel_inner = inset_rect(area, 1, 1);
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(3),
+            Constraint::Length(3),
+            Constraint::Length(2),
+        ])
+        .split(panel_inner);
+
+    let queued = if state.pending_count > 1 {
+        format!(" ({} pending)", state.pending_count)
+    } else {
+        String::new()
+    };
+    let mut summary = Paragraph::default()
+        .foreground(theme.base.text)
+        .background(surface)
+        .wrap(true)
+        .alignment(Alignment::Center)
+        .text([TextSpan::from(format!(
+            "'{}' wants to run {}{queued}:\n{}",
+            state.task_title, state.permission.tool, state.permission.title
+        ))]);
+    summary.view(frame, layout[0]);
+
+    let arguments = if state.permission.arguments.is_empty() {
+        vec![TextSpan::from("(no arguments)").fg(theme.base.text_muted)]
+    } else {
+        state
+            .permission
+            .arguments
+            .iter()
+            .map(|argument| TextSpan::from(argument.clone()).fg(theme.base.text))
+            .collect()
+    };
+    let mut details = Paragraph::default()
+        .title(" Arguments ", Alignment::Left)
+        .borders(rounded_borders(theme.interactive.border))
+        .foreground(theme.base.text)
+        .background(surface)
+        .wrap(true)
+        .text(arguments);
+    details.view(frame, layout[1]);
+
+    let buttons = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Ratio(1, 3),
+            Constraint::Ratio(1, 3),
+            Constraint::Ratio(1, 3),
+        ])
+        .split(layout[2]);
+    for (area, label, field, destructive) in [
+        (buttons[0], "Approve", PermissionReplyField::Approve, false),
+        (
+            buttons[1],
+            "Always Approve",
+            PermissionReplyField::AlwaysApprove,
+            false,
+        ),
+        (buttons[2], "Deny", PermissionReplyField::Deny, true),
+    ] {
+        render_action_button(
+            frame,
+            area,
+            label,
+            state.focused_field == field,
+            destructive,
+            app,
+            Some(Message::ReplyPermission(field)),
+        );
+    }
+
+    let mut hint = Label::default()
+        .text("y: approve  a: always  n: deny  Enter: confirm  Esc: later")
+        .alignment(Alignment::Center)
+        .foreground(theme.base.text_muted)
+        .background(surface);
+    hint.view(frame, layout[3]);
+}
+
 fn render_new_project_dialog(
     frame: &mut Frame<'_>,
     area: Rect,
@@ -2523,6 +3003,92 @@ fn render_rename_repo_dialog(
     }
 }
 
+fn render_quick_prompt_dialog(
+    frame: &mut Frame<'_>,
+    area: Rect,
+    app: &mut App,
+    state: &QuickPromptDialogState,
+) {
+    let theme = app.theme;
+    let surface = dialog_surface(theme);
+
+    let mut panel =
+        dialog_panel("Quick Prompt", Alignment::Center, theme, surface).text([TextSpan::from("")]);
+    panel.view(frame, area);
+
+    let panel_inner = inset_rect(area, 1, 1);
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .split(panel_inner);
+
+    render_input_component(
+        frame,
+        layout[0],
+        &format!("Message to '{}'", state.task_title),
+        &state.prompt_input,
+        true,
+        theme,
+        Some("Type a prompt for the agent"),
+    );
+
+    let history_title = if state.history.is_empty() {
+        "No recent prompts"
+    } else {
+        "Recent prompts (Up/Down to reuse)"
+    };
+    let mut heading = Label::default()
+        .text(history_title)
+        .foreground(theme.base.text_muted)
+        .background(surface);
+    heading.view(frame, layout[1]);
+
+    let history_area = layout[2];
+    for (index, prompt) in state
+        .history
+        .iter()
+        .take(history_area.height as usize)
+        .enumerate()
+    {
+        let row = Rect::new(
+            history_area.x,
+            history_area.y + index as u16,
+            history_area.width,
+            1,
+        );
+        let selected = state.history_index == Some(index);
+        let marker = if selected { ">" } else { " " };
+        let mut label = Label::default()
+            .text(format!("{marker} {}", prompt.replace('\n', " ")))
+            .foreground(if selected {
+                theme.interactive.focus
+            } else {
+                theme.base.text
+            })
+            .background(surface);
+        label.view(frame, row);
+        app.interaction_map.register_click(
+            InteractionLayer::Dialog,
+            row,
+            Message::UseQuickPromptHistory(index),
+        );
+    }
+
+    let mut hint = Label::default()
+        .text("Enter: send  Up/Down: history  Esc: cancel")
+        .alignment(Alignment::Center)
+        .foreground(theme.base.text_muted)
+        .background(surface);
+    hint.view(frame, layout[3]);
+
+    set_text_input_cursor(frame, layout[0], &state.prompt_input);
+}
+
 fn render_delete_repo_dialog(
     frame: &mut Frame<'_>,
     area: Rect,
@@ -3261,6 +3827,50 @@ fn resolve_input_display_value<'a>(
     (value, false)
 }
 
+/// Renders the last lines of `value` that fit, so the line being typed stays
+/// visible.
+fn render_multiline_input_component(
+    frame: &mut Frame<'_>,
+    area: Rect,
+    title: &str,
+    value: &str,
+    focused: bool,
+    theme: Theme,
+    placeholder: Option<&str>,
+) {
+    let (display_value, using_placeholder) = resolve_input_display_value(value, placeholder);
+    let visible_lines = area.height.saturating_sub(2) as usize;
+    let lines = display_value.split('\n').collect::<Vec<_>>();
+    let skipped = lines.len().saturating_sub(visible_lines);
+
+    let mut input = Paragraph::default()
+        .title(title, Alignment::Left)
+        .borders(rounded_borders(dialog_input_border(theme, focused)))
+        .foreground(if using_placeholder {
+            theme.base.text_muted
+        } else {
+            theme.base.text
+        })
+        .background(dialog_surface(theme))
+        .text(
+            lines[skipped..]
+                .iter()
+                .map(|line| TextSpan::from(line.to_string()))
+                .collect::<Vec<_>>(),
+        );
+    input.view(frame, area);
+}
+
+fn set_multiline_input_cursor(frame: &mut Frame<'_>, area: Rect, value: &str) {
+    let visible_lines = area.height.saturating_sub(2) as usize;
+    let line_count = value.split('\n').count();
+    let last_line = value.rsplit('\n').next().unwrap_or_default();
+    let row = line_count.min(visible_lines).saturating_sub(1) as u16;
+    if let Some((cursor_x, cursor_y)) = text_input_cursor_position(area, last_line) {
+        frame.set_cursor_position((cursor_x, cursor_y.saturating_add(row)));
+    }
+}
+
 fn set_text_input_cursor(frame: &mut Frame<'_>, area: Rect, value: &str) {
     if let Some((cursor_x, cursor_y)) = text_input_cursor_position(area, value) {
         frame.set_cursor_position((cursor_x, cursor_y));
@@ -3872,12 +4482,76 @@ fn slice_chars(value: &str, start: usize, end: usize) -> String {
 
 fn task_tile_status_line(app: &App, task: &Task) -> String {
     let spinner = task_tile_status_icon(task, app.pulse_phase);
+    if let Some(permission) = app.session_permissions(task.id).first() {
+        return format!("{spinner}  approve? {}", permission.tool);
+    }
+    if let Some(detail) = task_status_detail(task) {
+        return format!("{spinner}  {detail}");
+    }
+    let usage = app
+        .session_usage(task.id)
+        .map(|usage| format!("  {}", usage_badge(&usage)))
+        .unwrap_or_default();
+    let model = task
+        .agent_selection()
+        .label()
+        .map(|label| format!("  {label}"))
+        .unwrap_or_default();
     match app.session_todo_summary(task.id) {
-        Some((done, total)) => format!("{spinner}  todo {done}/{total}"),
-        None => spinner.to_string(),
+        Some((done, total)) => format!("{spinner}  todo {done}/{total}{usage}{model}"),
+        None => format!("{spinner}{usage}{model}"),
+    }
+}
+
+/// Compact spend shown on cards: the cost when the provider bills per
+/// token, otherwise the token count.
+fn usage_badge(usage: &TokenUsage) -> String {
+    if usage.cost >= 0.005 {
+        format_cost(usage.cost)
+    } else {
+        format!("{} tok", format_token_count(usage.total_tokens()))
+    }
+}
+
+fn usage_detail(usage: &TokenUsage) -> String {
+    format!(
+        "{} (in {}, out {}, cache {})",
+        format_token_count(usage.total_tokens()),
+        format_token_count(usage.input_tokens),
+        format_token_count(usage.output_tokens + usage.reasoning_tokens),
+        format_token_count(usage.cache_read_tokens + usage.cache_write_tokens),
+    )
+}
+
+fn format_cost(cost: f64) -> String {
+    format!("${cost:.2}")
+}
+
+fn format_token_count(count: u64) -> String {
+    match count {
+        0..1_000 => count.to_string(),
+        1_000..1_000_000 => format!("{:.1}k", count as f64 / 1_000.0),
+        _ => format!("{:.1}M", count as f64 / 1_000_000.0),
     }
 }
 
+/// Server explanation for a failed or retrying session, or why the server
+/// could not be asked when the pane stands in for it, without its code.
+fn task_status_detail(task: &Task) -> Option<&str> {
+    let error = task.status_error.as_deref()?;
+    if task.status_source == SessionStatusSource::Pane.as_str() {
+        return error
+            .split_once(':')
+            .map(|(_, detail)| detail.trim())
+            .filter(|detail| !detail.is_empty());
+    }
+    error
+        .strip_prefix("SESSION_ERROR:")
+        .or_else(|| error.strip_prefix("SESSION_RETRYING:"))
+        .map(str::trim)
+        .filter(|detail| !detail.is_empty())
+}
+
 fn task_needs_inspection_highlight(task: &Task) -> bool {
     task.needs_inspection && task.tmux_status == "idle"
 }
@@ -4072,7 +4746,13 @@ fn log_entries_to_spans(
                     if viewport > 0 && lines.len() >= viewport {
                         break;
                     }
-                    lines.push(TextSpan::new(format!("    {detail}")).fg(theme.base.text));
+                    lines.push(
+                        TextSpan::new(format!("    {detail}")).fg(structured_log_detail_color(
+                            theme,
+                            header_kind,
+                            detail,
+                        )),
+                    );
                 }
             }
         }
@@ -4100,13 +4780,37 @@ fn structured_log_kind_color(theme: Theme, kind: &str) -> Color {
         theme.tile.todo
     } else if kind.eq_ignore_ascii_case("RETRY") {
         theme.status.dead
-    } else if kind.eq_ignore_ascii_case("PATCH") {
+    } else if kind.eq_ignore_ascii_case("PATCH") || kind.eq_ignore_ascii_case("EDIT") {
         theme.interactive.focus
+    } else if kind.eq_ignore_ascii_case("TOOL!") || kind.eq_ignore_ascii_case("ERROR") {
+        theme.status.error
     } else {
         theme.base.header
     }
 }
 
+/// Colors diff lines of file edits and the error line of failed tool calls.
+fn structured_log_detail_color(theme: Theme, kind: &str, detail: &str) -> Color {
+    if kind.eq_ignore_ascii_case("EDIT") {
+        if detail.starts_with("@@") {
+            return theme.base.text_muted;
+        }
+        if detail.starts_with('+') {
+            return theme.category.success;
+        }
+        if detail.starts_with('-') {
+            return theme.category.danger;
+        }
+    }
+    if kind.eq_ignore_ascii_case("TOOL!") && detail.starts_with("error: ") {
+        return theme.status.error;
+    }
+    if kind.eq_ignore_ascii_case("THINK") {
+        return theme.base.text_muted;
+    }
+    theme.base.text
+}
+
 fn format_archive_time(iso_timestamp: &str) -> String {
     if let Ok(dt) = DateTime::parse_from_rfc3339(iso_timestamp) {
         let local = dt.with_timezone(&Utc);
@@ -4130,6 +4834,13 @@ fn clamp_text(value: &str, max_chars: usize) -> String {
 
 fn status_spinner_ascii(status: &str, pulse_phase: u8) -> &'static str {
     match status {
+        "awaiting_approval" => "??",
+        "awaiting_input" => ">_",
+        "error" => "xx",
+        "retrying" => match pulse_phase % 2 {
+            0 => "~.",
+            _ => ".~",
+        },
         "running" => match pulse_phase % 4 {
             0 => ".:",
             1 => "::",
@@ -5092,6 +5803,36 @@ mod tests {
     use crate::types::SessionTodoItem;
     use uuid::Uuid;
 
+    #[test]
+    fn structured_log_colors_diff_lines_and_failed_tools() {
+        let theme = Theme::default();
+        let entries = parse_structured_log_entries(
+            "> [EDIT] ASSISTANT 10:00:00 src/lib.rs +1 -1\n  @@ -1 +1 @@\n  -old\n  +new\n\n> [TOOL!] ASSISTANT 10:00:01 bash · failed\n  error: exit 1",
+        );
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].details, vec!["@@ -1 +1 @@", "-old", "+new"]);
+        assert_eq!(
+            structured_log_detail_color(theme, "EDIT", "+new"),
+            theme.category.success
+        );
+        assert_eq!(
+            structured_log_detail_color(theme, "EDIT", "-old"),
+            theme.category.danger
+        );
+        assert_eq!(
+            structured_log_detail_color(theme, "SAY", "- a list item"),
+            theme.base.text
+        );
+        assert_eq!(
+            structured_log_kind_color(
+                theme,
+                parse_structured_log_kind(&entries[1].header).unwrap()
+            ),
+            theme.status.error
+        );
+    }
+
     #[test]
     fn test_calculate_overlay_area_center() {
         let area = Rect::new(0, 0, 100, 100);
@@ -5426,6 +6167,44 @@ mod tests {
         assert_eq!(task_tile_status_icon(&task, 1), "::");
     }
 
+    #[test]
+    fn test_task_status_detail_strips_session_error_codes() {
+        let category_id = Uuid::new_v4();
+        let mut task = test_task(category_id, 0);
+        task.tmux_status = "error".to_string();
+        task.status_error = Some("SESSION_ERROR:invalid api key".to_string());
+        assert_eq!(task_status_detail(&task), Some("invalid api key"));
+        assert_eq!(task_tile_status_icon(&task, 0), "xx");
+
+        task.status_error = Some("SESSION_NOT_FOUND:ok-task".to_string());
+        assert_eq!(task_status_detail(&task), None);
+
+        task.tmux_status = "running".to_string();
+        task.status_source = SessionStatusSource::Pane.as_str().to_string();
+        task.status_error = Some("SERVER_CONNECT_FAILED:connection refused".to_string());
+        assert_eq!(task_status_detail(&task), Some("connection refused"));
+    }
+
+    #[test]
+    fn test_usage_badge_prefers_cost_and_falls_back_to_tokens() {
+        let billed = TokenUsage {
+            input_tokens: 12_000,
+            output_tokens: 3_400,
+            cost: 0.4271,
+            ..TokenUsage::default()
+        };
+        assert_eq!(usage_badge(&billed), "$0.43");
+        assert_eq!(usage_detail(&billed), "15.4k (in 12.0k, out 3.4k, cache 0)");
+
+        let free = TokenUsage {
+            input_tokens: 2_500_000,
+            cache_read_tokens: 400,
+            ..TokenUsage::default()
+        };
+        assert_eq!(usage_badge(&free), "2.5M tok");
+        assert_eq!(format_token_count(999), "999");
+    }
+
     fn test_task(category_id: Uuid, position: i64) -> Task {
         Task {
             id: Uuid::new_v4(),
@@ -5441,6 +6220,10 @@ mod tests {
             status_fetched_at: None,
             status_error: None,
             opencode_session_id: None,
+            model: None,
+            agent: None,
+            forked_from: None,
+            backend: None,
             attach_overlay_shown: false,
             needs_inspection: false,
             archived: false,
@@ -26,16 +26,15 @@ use opencode_kanban::types::{
     SessionState, SessionStatus, SessionStatusError, SessionStatusSource, Task,
 };
 
-static INTEGRATION_TEST_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+static INTEGRATION_TEST_LOCK: LazyLock<tokio::sync::Mutex<()>> =
+    LazyLock::new(|| tokio::sync::Mutex::new(()));
 
 #[tokio::test(flavor = "multi_thread")]
 async fn integration_test_full_lifecycle() -> Result<()> {
     if !tmux_available() {
         return Ok(());
     }
-    let _test_guard = INTEGRATION_TEST_LOCK
-        .lock()
-        .expect("integration test lock should not be poisoned");
+    let _test_guard = INTEGRATION_TEST_LOCK.lock().await;
 
     let socket = format!("ok-integration-{}", std::process::id());
     let _socket_guard = EnvVarGuard::set("OPENCODE_KANBAN_TMUX_SOCKET", &socket);
@@ -66,6 +65,7 @@ async fn integration_test_full_lifecycle() -> Result<()> {
         &session_name,
         &worktree_path,
         Some("printf \"I'm ready\\n\"; sleep 30"),
+        &[],
     )?;
     assert!(tmux_session_exists(&session_name));
 
@@ -114,9 +114,7 @@ async fn integration_test_server_first_lifecycle_with_stale_binding_transition()
     if !port_available(4096) {
         return Ok(());
     }
-    let _test_guard = INTEGRATION_TEST_LOCK
-        .lock()
-        .expect("integration test lock should not be poisoned");
+    let _test_guard = INTEGRATION_TEST_LOCK.lock().await;
 
     let fixture = GitFixture::new()?;
     let socket = format!("ok-server-first-{}", std::process::id());
@@ -163,6 +161,7 @@ async fn integration_test_server_first_lifecycle_with_stale_binding_transition()
         &session_name,
         fixture.repo_path(),
         Some("printf \"thinking...\\n\"; sleep 30"),
+        &[],
     )?;
 
     let task = db.add_task(
@@ -216,9 +215,7 @@ async fn integration_test_server_failure_falls_back_to_tmux_across_poll_cycles()
     if !port_available(4096) {
         return Ok(());
     }
-    let _test_guard = INTEGRATION_TEST_LOCK
-        .lock()
-        .expect("integration test lock should not be poisoned");
+    let _test_guard = INTEGRATION_TEST_LOCK.lock().await;
 
     let fixture = GitFixture::new()?;
     let socket = format!("ok-fallback-{}", std::process::id());
@@ -242,7 +239,7 @@ async fn integration_test_server_failure_falls_back_to_tmux_across_poll_cycles()
     let todo = db.list_categories()?[0].id;
 
     let session_name = sanitize_session_name(&repo.name, "feature/fallback-lifecycle");
-    tmux_create_session(&session_name, fixture.repo_path(), Some("sleep 30"))?;
+    tmux_create_session(&session_name, fixture.repo_path(), Some("sleep 30"), &[])?;
 
     let task = db.add_task(
         repo.id,
@@ -259,9 +256,11 @@ async fn integration_test_server_failure_falls_back_to_tmux_across_poll_cycles()
     {
         let _app = App::new(None)?;
 
+        // `sleep` is not an agent process, so the pane fallback reads idle,
+        // and the server failure stays on the task.
         wait_for_task(&db_path, task.id, Duration::from_secs(12), |current| {
             current.tmux_status == "idle"
-                && current.status_source == "none"
+                && current.status_source == "pane"
                 && current
                     .status_error
                     .as_deref()
@@ -426,10 +425,9 @@ async fn wait_for_task(
     while start.elapsed() <= timeout {
         if let Ok(db) = Database::open(db_path)
             && let Ok(task) = db.get_task(task_id)
+            && predicate(&task)
         {
-            if predicate(&task) {
-                return Ok(());
-            }
+            return Ok(());
         }
 
         tokio::time::sleep(Duration::from_millis(120)).await;